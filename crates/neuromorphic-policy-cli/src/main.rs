@@ -1,9 +1,10 @@
 use std::io::{Read, Write};
 
 use anyhow::Result;
+use neuromorphic_policy::rpc::{HttpAnchorResolver, HttpResolverConfig};
 use neuromorphic_policy::{
-    ConsentEnvelope, DidLedgerVerifier, LedgerAnchor, NeuromorphicNodeMetrics,
-    NeuromorphicPolicyAttestationSpec, PolicyDecision, SafetyCertificate,
+    LedgerBackedVerifier, NeuromorphicNodeMetrics, NeuromorphicPolicyAttestationSpec,
+    PolicyDecision,
 };
 use serde::{Deserialize, Serialize};
 
@@ -11,33 +12,10 @@ use serde::{Deserialize, Serialize};
 struct CliInput {
     spec: NeuromorphicPolicyAttestationSpec,
     metrics: NeuromorphicNodeMetrics,
-}
-
-/// Minimal, pluggable verifier; replace TODO sections with real DID/ledger checks.
-struct StubDidLedgerVerifier;
-
-impl DidLedgerVerifier for StubDidLedgerVerifier {
-    fn verify_consent_envelope(&self, env: &ConsentEnvelope) -> Result<()> {
-        if env.envelope_hash.is_empty() {
-            anyhow::bail!("envelope_hash missing");
-        }
-        if env.anchors.is_empty() {
-            anyhow::bail!("no ledger anchors on consent envelope");
-        }
-        // TODO: verify DID signatures and that tx contains envelope_hash.
-        Ok(())
-    }
-
-    fn verify_safety_certificate(&self, cert: &SafetyCertificate) -> Result<()> {
-        if cert.certificate_id.is_empty() {
-            anyhow::bail!("certificate_id missing");
-        }
-        if cert.anchors.is_empty() {
-            anyhow::bail!("no ledger anchors on safety certificate");
-        }
-        // TODO: verify anchors and link to your ALN/Googolswarm profile.
-        Ok(())
-    }
+    /// Where to look up the spec's ledger anchors; with no endpoint for a
+    /// chain, its anchors fail verification.
+    #[serde(default)]
+    ledger: HttpResolverConfig,
 }
 
 fn main() -> Result<()> {
@@ -46,7 +24,8 @@ fn main() -> Result<()> {
     std::io::stdin().read_to_string(&mut buf)?;
 
     let input: CliInput = serde_json::from_str(&buf)?;
-    let verifier = StubDidLedgerVerifier;
+    // Signatures by did:key, then anchors over the chains' REST endpoints.
+    let verifier = LedgerBackedVerifier::new(HttpAnchorResolver::new(input.ledger)?);
 
     let decision: PolicyDecision = neuromorphic_policy::evaluate_neuromorphic_transition(
         &input.spec,
@@ -54,6 +33,11 @@ fn main() -> Result<()> {
         &verifier,
     );
 
+    // One line per violation on stderr; stdout stays a single JSON decision.
+    for violation in &decision.violations {
+        eprintln!("violation: {violation}");
+    }
+
     let mut out = std::io::BufWriter::new(std::io::stdout());
     serde_json::to_writer(&mut out, &decision)?;
     out.write_all(b"\n")?;
@@ -0,0 +1,275 @@
+//! Checking `LedgerAnchor`s against the chain they name.
+//!
+//! A `LedgerAnchorResolver` fetches what a transaction recorded; an anchor
+//! holds when that contains the hash of the envelope or certificate it
+//! anchors. `HttpAnchorResolver` (feature `rpc-client`) asks Cosmos SDK
+//! chains such as Bostrom over their REST endpoint.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Context, Result};
+use sha2::{Digest, Sha256};
+
+use crate::did::canonical_payload_hash;
+use crate::{
+    DidKeyVerifier, DidLedgerVerifier, LedgerAnchor, SafetyCertificate, SignedConsentEnvelope,
+};
+
+pub trait LedgerAnchorResolver {
+    /// The memo of the transaction, followed by its event attribute values,
+    /// newline-separated. Fails if the transaction cannot be found.
+    fn fetch_tx_memo(&self, chain: &str, network: &str, tx_hash: &str) -> Result<Vec<u8>>;
+}
+
+/// In-memory transactions, for tests and offline replays.
+#[derive(Debug, Clone, Default)]
+pub struct MockAnchorResolver {
+    /// (chain, network, tx hash) -> memo
+    pub txs: HashMap<(String, String, String), Vec<u8>>,
+}
+
+impl MockAnchorResolver {
+    pub fn with_tx(mut self, chain: &str, network: &str, tx_hash: &str, memo: &[u8]) -> Self {
+        self.txs.insert(
+            (chain.to_string(), network.to_string(), tx_hash.to_string()),
+            memo.to_vec(),
+        );
+        self
+    }
+}
+
+impl LedgerAnchorResolver for MockAnchorResolver {
+    fn fetch_tx_memo(&self, chain: &str, network: &str, tx_hash: &str) -> Result<Vec<u8>> {
+        match self
+            .txs
+            .get(&(chain.to_string(), network.to_string(), tx_hash.to_string()))
+        {
+            Some(memo) => Ok(memo.clone()),
+            None => bail!("{chain}/{network}: tx {tx_hash} not found"),
+        }
+    }
+}
+
+/// Check that the anchor's transaction records `expected_hash`, a hex
+/// SHA-256 digest, in its memo or events. The memo and each event value are
+/// read line by line and split on anything that is not a letter or digit;
+/// one of the resulting words must equal the digest (case-insensitively), so
+/// a longer hex string that merely contains it does not count.
+pub fn verify_anchor_contains(
+    resolver: &dyn LedgerAnchorResolver,
+    anchor: &LedgerAnchor,
+    expected_hash: &str,
+) -> Result<()> {
+    let LedgerAnchor {
+        chain,
+        network,
+        tx_hash,
+        ..
+    } = anchor;
+    if expected_hash.len() != 64 || !expected_hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+        bail!("{chain}/{network} tx {tx_hash}: {expected_hash:?} is not a hex SHA-256 digest");
+    }
+    let memo = resolver
+        .fetch_tx_memo(chain, network, tx_hash)
+        .with_context(|| format!("fetching {chain}/{network} tx {tx_hash}"))?;
+    let memo = String::from_utf8_lossy(&memo);
+    let recorded = memo.lines().any(|line| {
+        line.split(|c: char| !c.is_ascii_alphanumeric())
+            .any(|word| word.eq_ignore_ascii_case(expected_hash))
+    });
+    if !recorded {
+        bail!("{chain}/{network} tx {tx_hash} does not record {expected_hash}");
+    }
+    Ok(())
+}
+
+/// Hex SHA-256 of the certificate's JSON without its anchors, which are
+/// only known once the hash has been recorded.
+pub fn certificate_hash(cert: &SafetyCertificate) -> String {
+    let unanchored = SafetyCertificate {
+        anchors: Vec::new(),
+        ..cert.clone()
+    };
+    // Serializing plain structs and vectors cannot fail.
+    let payload = serde_json::to_vec(&unanchored).expect("safety certificate serializes");
+    hex::encode(Sha256::digest(payload))
+}
+
+/// A `DidLedgerVerifier` that requires an envelope to be signed by its
+/// signers (checked by `DidKeyVerifier`) and every anchor of it to record
+/// its `canonical_payload_hash`, and every anchor of a certificate to record
+/// its `certificate_hash`. A stated `envelope_hash` must agree with the
+/// payload; it is never trusted in its place.
+pub struct LedgerBackedVerifier<R> {
+    pub resolver: R,
+}
+
+impl<R: LedgerAnchorResolver> LedgerBackedVerifier<R> {
+    pub fn new(resolver: R) -> Self {
+        Self { resolver }
+    }
+
+    fn verify_anchors(&self, anchors: &[LedgerAnchor], expected_hash: &str) -> Result<()> {
+        if anchors.is_empty() {
+            bail!("no ledger anchors");
+        }
+        for anchor in anchors {
+            verify_anchor_contains(&self.resolver, anchor, expected_hash)?;
+        }
+        Ok(())
+    }
+}
+
+impl<R: LedgerAnchorResolver> DidLedgerVerifier for LedgerBackedVerifier<R> {
+    fn verify_consent_envelope(&self, signed: &SignedConsentEnvelope) -> Result<()> {
+        DidKeyVerifier.verify(signed)?;
+        let env = &signed.envelope;
+        let payload_hash = canonical_payload_hash(env)?;
+        if !env.envelope_hash.is_empty() && !env.envelope_hash.eq_ignore_ascii_case(&payload_hash) {
+            bail!(
+                "envelope_hash {} does not match the payload hash {payload_hash}",
+                env.envelope_hash
+            );
+        }
+        self.verify_anchors(&env.anchors, &payload_hash)
+    }
+
+    fn verify_safety_certificate(&self, cert: &SafetyCertificate) -> Result<()> {
+        if cert.certificate_id.is_empty() {
+            bail!("certificate_id missing");
+        }
+        self.verify_anchors(&cert.anchors, &certificate_hash(cert))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::SigningKey;
+
+    use super::*;
+    use crate::did::did_key_for;
+    use crate::{ConsentEnvelope, DetachedSignature, SafetyCeilingParams};
+
+    fn anchor(tx_hash: &str) -> LedgerAnchor {
+        LedgerAnchor {
+            chain: "bostrom".into(),
+            network: "mainnet".into(),
+            tx_hash: tx_hash.into(),
+            source_id: "consent".into(),
+            eco_usage_commitment: None,
+        }
+    }
+
+    fn signed_envelope(anchors: Vec<LedgerAnchor>) -> SignedConsentEnvelope {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let did = did_key_for(&key.verifying_key());
+        let envelope = ConsentEnvelope {
+            transcript_root: "root".into(),
+            workspace_hash: "ws".into(),
+            fear_index_max: 0.3,
+            eco_fear_max: 0.2,
+            fairness_score: 0.9,
+            issuer_did: did.clone(),
+            additional_signers: Vec::new(),
+            envelope_hash: String::new(),
+            anchors,
+        };
+        let signature = DetachedSignature::sign(&envelope, &did, &key).unwrap();
+        SignedConsentEnvelope {
+            envelope,
+            signatures: vec![signature],
+        }
+    }
+
+    const HASH: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    #[test]
+    fn only_the_whole_digest_counts() {
+        let longer = format!("{HASH}00");
+        let resolver = MockAnchorResolver::default()
+            .with_tx(
+                "bostrom",
+                "mainnet",
+                "EXACT",
+                format!("anchor:{}", HASH.to_uppercase()).as_bytes(),
+            )
+            .with_tx(
+                "bostrom",
+                "mainnet",
+                "EVENT",
+                format!("memo\n{HASH}").as_bytes(),
+            )
+            .with_tx("bostrom", "mainnet", "OTHER", b"something else")
+            .with_tx("bostrom", "mainnet", "LONGER", longer.as_bytes())
+            .with_tx("bostrom", "mainnet", "PREFIX", &HASH.as_bytes()[..32]);
+        verify_anchor_contains(&resolver, &anchor("EXACT"), HASH).unwrap();
+        verify_anchor_contains(&resolver, &anchor("EVENT"), HASH).unwrap();
+        for tx in ["OTHER", "LONGER", "PREFIX"] {
+            let err = verify_anchor_contains(&resolver, &anchor(tx), HASH).unwrap_err();
+            assert!(err.to_string().contains("does not record"), "{tx}: {err}");
+        }
+        let err = verify_anchor_contains(&resolver, &anchor("MISSING"), HASH).unwrap_err();
+        assert!(format!("{err:#}").contains("not found"));
+        // A fragment of a digest is never looked for.
+        assert!(verify_anchor_contains(&resolver, &anchor("EXACT"), &HASH[..32]).is_err());
+    }
+
+    #[test]
+    fn envelopes_must_be_signed_and_anchored_by_their_payload_hash() {
+        let signed = signed_envelope(vec![anchor("T1")]);
+        let payload_hash = canonical_payload_hash(&signed.envelope).unwrap();
+        let resolver = MockAnchorResolver::default()
+            .with_tx("bostrom", "mainnet", "T1", payload_hash.as_bytes())
+            .with_tx("bostrom", "mainnet", "T2", HASH.as_bytes());
+        let verifier = LedgerBackedVerifier::new(resolver);
+        verifier.verify_consent_envelope(&signed).unwrap();
+
+        // The stated hash may repeat the payload hash but not replace it.
+        let mut stated = signed.clone();
+        stated.envelope.envelope_hash = payload_hash.to_uppercase();
+        verifier.verify_consent_envelope(&stated).unwrap();
+        stated.envelope.envelope_hash = HASH.into();
+        stated.envelope.anchors = vec![anchor("T2")];
+        assert!(verifier.verify_consent_envelope(&stated).is_err());
+
+        let mut unanchored = signed.clone();
+        unanchored.envelope.anchors.clear();
+        assert!(verifier.verify_consent_envelope(&unanchored).is_err());
+        let mut partly_anchored = signed.clone();
+        partly_anchored.envelope.anchors.push(anchor("T2"));
+        assert!(verifier.verify_consent_envelope(&partly_anchored).is_err());
+
+        let mut unsigned = signed;
+        unsigned.signatures.clear();
+        let err = verifier.verify_consent_envelope(&unsigned).unwrap_err();
+        assert!(err.to_string().contains("no signature"));
+    }
+
+    #[test]
+    fn certificates_are_anchored_by_their_unanchored_hash() {
+        let cert = SafetyCertificate {
+            certificate_id: "cert".into(),
+            ethical_ceiling: SafetyCeilingParams {
+                tau_p: 0.1,
+                tau_f: 0.2,
+                tau_e: 0.3,
+            },
+            anchors: vec![anchor("T1")],
+        };
+        let hash = certificate_hash(&cert);
+        let verifier = LedgerBackedVerifier::new(MockAnchorResolver::default().with_tx(
+            "bostrom",
+            "mainnet",
+            "T1",
+            format!("cert {hash}").as_bytes(),
+        ));
+        verifier.verify_safety_certificate(&cert).unwrap();
+
+        let mut changed = cert.clone();
+        changed.ethical_ceiling.tau_f = 0.5;
+        assert!(verifier.verify_safety_certificate(&changed).is_err());
+        let mut unnamed = cert;
+        unnamed.certificate_id.clear();
+        assert!(verifier.verify_safety_certificate(&unnamed).is_err());
+    }
+}
@@ -0,0 +1,412 @@
+//! Ed25519 signatures over consent envelopes by `did:key` identities.
+//!
+//! A `SignedConsentEnvelope` carries one detached signature per signer. Each
+//! signs the SHA-256 of the envelope's canonical payload, which is its JSON
+//! encoding with:
+//!
+//! - no `envelope_hash` (the hash of this payload), `anchors` (recorded
+//!   once it is signed) or signatures;
+//! - the keys of every object sorted by their UTF-8 bytes;
+//! - no whitespace, and strings and numbers as `serde_json` writes them;
+//!   non-finite numbers have no canonical form.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::ConsentEnvelope;
+
+pub const DID_KEY_PREFIX: &str = "did:key:";
+
+/// Multicodec prefix of an Ed25519 public key in a `did:key` identifier.
+const ED25519_PUB_MULTICODEC: [u8; 2] = [0xed, 0x01];
+
+/// `ConsentEnvelope` fields left out of its canonical payload.
+const UNSIGNED_FIELDS: [&str; 2] = ["envelope_hash", "anchors"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetachedSignature {
+    pub signer_did: String,
+    /// Standard base64 of the Ed25519 signature over the raw payload hash.
+    pub signature_b64: String,
+    /// Hex SHA-256 of the canonical payload the signer saw.
+    pub signed_payload_hash: String,
+}
+
+impl DetachedSignature {
+    pub fn sign(
+        envelope: &ConsentEnvelope,
+        signer_did: &str,
+        key: &SigningKey,
+    ) -> Result<Self, SignatureError> {
+        let digest = payload_digest(envelope)?;
+        Ok(DetachedSignature {
+            signer_did: signer_did.to_string(),
+            signature_b64: BASE64.encode(key.sign(&digest).to_bytes()),
+            signed_payload_hash: hex::encode(digest),
+        })
+    }
+}
+
+/// A consent envelope with the signatures of its issuer and additional
+/// signers; serialized as the envelope's fields plus `signatures`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedConsentEnvelope {
+    #[serde(flatten)]
+    pub envelope: ConsentEnvelope,
+    #[serde(default)]
+    pub signatures: Vec<DetachedSignature>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureError {
+    /// The identifier is not a DID, or its method is not `key`.
+    UnknownDidMethod { did: String },
+    /// The `did:key` identifier does not encode an Ed25519 public key.
+    BadKey { did: String, message: String },
+    /// The signature covers another payload than the envelope's.
+    HashMismatch {
+        did: String,
+        expected: String,
+        signed: String,
+    },
+    /// The signature is malformed or does not verify under the signer's key.
+    BadSignature { did: String, message: String },
+    /// A required signer has no signature on the envelope.
+    MissingSignature { did: String },
+    /// The envelope has no canonical payload to sign or check.
+    Unencodable { message: String },
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureError::UnknownDidMethod { did } => {
+                write!(f, "{did}: unknown DID method, only did:key is supported")
+            }
+            SignatureError::BadKey { did, message } => write!(f, "{did}: bad key: {message}"),
+            SignatureError::HashMismatch {
+                did,
+                expected,
+                signed,
+            } => write!(
+                f,
+                "{did}: payload hash mismatch: envelope hashes to {expected}, \
+                 signature covers {signed}"
+            ),
+            SignatureError::BadSignature { did, message } => {
+                write!(f, "{did}: bad signature: {message}")
+            }
+            SignatureError::MissingSignature { did } => write!(f, "{did}: no signature"),
+            SignatureError::Unencodable { message } => {
+                write!(f, "envelope has no canonical payload: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SignatureError {}
+
+/// Hex SHA-256 of the envelope's canonical payload.
+pub fn canonical_payload_hash(envelope: &ConsentEnvelope) -> Result<String, SignatureError> {
+    payload_digest(envelope).map(hex::encode)
+}
+
+/// The envelope's canonical payload, as described in the module docs.
+pub fn canonical_payload(envelope: &ConsentEnvelope) -> Result<Vec<u8>, SignatureError> {
+    let unencodable = |message: String| SignatureError::Unencodable { message };
+    for (field, value) in [
+        ("fear_index_max", envelope.fear_index_max),
+        ("eco_fear_max", envelope.eco_fear_max),
+        ("fairness_score", envelope.fairness_score),
+    ] {
+        if !value.is_finite() {
+            return Err(unencodable(format!("{field} is {value}")));
+        }
+    }
+    let mut payload = serde_json::to_value(envelope).map_err(|e| unencodable(e.to_string()))?;
+    if let Value::Object(fields) = &mut payload {
+        for field in UNSIGNED_FIELDS {
+            fields.remove(field);
+        }
+    }
+    serde_json::to_vec(&sort_keys(payload)).map_err(|e| unencodable(e.to_string()))
+}
+
+/// `value` with every object's keys in sorted order, whichever order
+/// `serde_json` maps keep.
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(fields) => {
+            let sorted: BTreeMap<String, Value> = fields
+                .into_iter()
+                .map(|(key, value)| (key, sort_keys(value)))
+                .collect();
+            Value::Object(sorted.into_iter().collect())
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(sort_keys).collect()),
+        scalar => scalar,
+    }
+}
+
+fn payload_digest(envelope: &ConsentEnvelope) -> Result<[u8; 32], SignatureError> {
+    Ok(Sha256::digest(canonical_payload(envelope)?).into())
+}
+
+/// The Ed25519 public key a `did:key:z...` identifier encodes.
+pub fn resolve_did_key(did: &str) -> Result<VerifyingKey, SignatureError> {
+    let bad_key = |message: &str| SignatureError::BadKey {
+        did: did.to_string(),
+        message: message.to_string(),
+    };
+    let Some(multibase) = did.strip_prefix(DID_KEY_PREFIX) else {
+        return Err(SignatureError::UnknownDidMethod {
+            did: did.to_string(),
+        });
+    };
+    let Some(base58) = multibase.strip_prefix('z') else {
+        return Err(bad_key("not base58btc multibase"));
+    };
+    let bytes = bs58::decode(base58)
+        .into_vec()
+        .map_err(|e| bad_key(&e.to_string()))?;
+    let Some(raw) = bytes.strip_prefix(&ED25519_PUB_MULTICODEC) else {
+        return Err(bad_key("not an Ed25519 public key"));
+    };
+    let raw: [u8; 32] = raw
+        .try_into()
+        .map_err(|_| bad_key("Ed25519 public key is not 32 bytes"))?;
+    VerifyingKey::from_bytes(&raw).map_err(|e| bad_key(&e.to_string()))
+}
+
+/// The `did:key` identifier of an Ed25519 public key.
+pub fn did_key_for(key: &VerifyingKey) -> String {
+    let mut bytes = ED25519_PUB_MULTICODEC.to_vec();
+    bytes.extend_from_slice(key.as_bytes());
+    format!("{DID_KEY_PREFIX}z{}", bs58::encode(bytes).into_string())
+}
+
+/// Checks envelope signatures by resolving `did:key` signers; other DID
+/// methods are rejected.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DidKeyVerifier;
+
+impl DidKeyVerifier {
+    /// Check that `issuer_did` and every additional signer signed the
+    /// envelope. Signatures by anyone else are ignored; of several by one
+    /// signer, the first is checked.
+    pub fn verify(&self, signed: &SignedConsentEnvelope) -> Result<(), SignatureError> {
+        let envelope = &signed.envelope;
+        let digest = payload_digest(envelope)?;
+        for did in std::iter::once(&envelope.issuer_did).chain(&envelope.additional_signers) {
+            let signature = signed
+                .signatures
+                .iter()
+                .find(|signature| signature.signer_did == *did)
+                .ok_or_else(|| SignatureError::MissingSignature { did: did.clone() })?;
+            check_signature(&digest, signature)?;
+        }
+        Ok(())
+    }
+}
+
+fn check_signature(digest: &[u8; 32], signature: &DetachedSignature) -> Result<(), SignatureError> {
+    let did = &signature.signer_did;
+    let key = resolve_did_key(did)?;
+    let expected = hex::encode(digest);
+    if !signature
+        .signed_payload_hash
+        .eq_ignore_ascii_case(&expected)
+    {
+        return Err(SignatureError::HashMismatch {
+            did: did.clone(),
+            expected,
+            signed: signature.signed_payload_hash.clone(),
+        });
+    }
+    let bad_signature = |message: String| SignatureError::BadSignature {
+        did: did.clone(),
+        message,
+    };
+    let bytes = BASE64
+        .decode(&signature.signature_b64)
+        .map_err(|e| bad_signature(format!("not base64: {e}")))?;
+    let sig = Signature::from_slice(&bytes).map_err(|e| bad_signature(e.to_string()))?;
+    key.verify_strict(digest, &sig)
+        .map_err(|_| bad_signature("does not verify under the signer's key".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LedgerAnchor;
+
+    /// RFC 8032 test 1 key.
+    const SECRET_KEY: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+    const DID: &str = "did:key:z6MktwupdmLXVVqTzCw4i46r4uGyosGXRnR3XjN4Zq7oMMsw";
+
+    fn rfc_key() -> SigningKey {
+        SigningKey::from_bytes(&hex::decode(SECRET_KEY).unwrap().try_into().unwrap())
+    }
+
+    fn key(byte: u8) -> SigningKey {
+        SigningKey::from_bytes(&[byte; 32])
+    }
+
+    fn envelope(issuer_did: &str, additional_signers: Vec<String>) -> ConsentEnvelope {
+        ConsentEnvelope {
+            transcript_root: "root".into(),
+            workspace_hash: "ws".into(),
+            fear_index_max: 0.3,
+            eco_fear_max: 0.2,
+            fairness_score: 0.9,
+            issuer_did: issuer_did.into(),
+            additional_signers,
+            envelope_hash: String::new(),
+            anchors: Vec::new(),
+        }
+    }
+
+    fn signed_by(
+        envelope: ConsentEnvelope,
+        signers: &[(&str, &SigningKey)],
+    ) -> SignedConsentEnvelope {
+        let signatures = signers
+            .iter()
+            .map(|(did, key)| DetachedSignature::sign(&envelope, did, key).unwrap())
+            .collect();
+        SignedConsentEnvelope {
+            envelope,
+            signatures,
+        }
+    }
+
+    #[test]
+    fn did_keys_round_trip() {
+        let key = rfc_key().verifying_key();
+        assert_eq!(
+            hex::encode(key.as_bytes()),
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"
+        );
+        assert_eq!(did_key_for(&key), DID);
+        assert_eq!(resolve_did_key(DID).unwrap(), key);
+    }
+
+    #[test]
+    fn the_canonical_payload_matches_its_test_vector() {
+        let mut envelope = envelope(DID, Vec::new());
+        let expected = [
+            r#"{"additional_signers":[],"eco_fear_max":0.2,"fairness_score":0.9,"#,
+            r#""fear_index_max":0.3,"issuer_did":""#,
+            DID,
+            r#"","transcript_root":"root","workspace_hash":"ws"}"#,
+        ]
+        .concat();
+        assert_eq!(canonical_payload(&envelope).unwrap(), expected.as_bytes());
+        let hash = "2d0dc64573907995a40ab817cd0a96b2cbfddc1613efcfee6e132e5b16f07b77";
+        assert_eq!(canonical_payload_hash(&envelope).unwrap(), hash);
+
+        let signature = DetachedSignature::sign(&envelope, DID, &rfc_key()).unwrap();
+        assert_eq!(signature.signed_payload_hash, hash);
+        let expected_signature = [
+            "cCEzsMSrGjTQio1nrsxxiJnCfxRqgFV1JnAEtnm5+Gf+",
+            "zRdz6PGexJIa+liPA88DgLLqKawUCi/p61bngNBiDw==",
+        ]
+        .concat();
+        assert_eq!(signature.signature_b64, expected_signature);
+
+        // Neither the hash nor the anchors are signed.
+        envelope.envelope_hash = hash.into();
+        envelope.anchors.push(LedgerAnchor {
+            chain: "bostrom".into(),
+            network: "mainnet".into(),
+            tx_hash: "ABC".into(),
+            source_id: "consent".into(),
+            eco_usage_commitment: None,
+        });
+        assert_eq!(canonical_payload_hash(&envelope).unwrap(), hash);
+    }
+
+    #[test]
+    fn non_finite_numbers_have_no_canonical_payload() {
+        let mut envelope = envelope(DID, Vec::new());
+        envelope.fairness_score = f64::NAN;
+        assert!(matches!(
+            canonical_payload_hash(&envelope),
+            Err(SignatureError::Unencodable { .. })
+        ));
+        assert!(DetachedSignature::sign(&envelope, DID, &rfc_key()).is_err());
+    }
+
+    #[test]
+    fn every_signer_must_sign() {
+        let (issuer, cosigner) = (key(1), key(2));
+        let issuer_did = did_key_for(&issuer.verifying_key());
+        let cosigner_did = did_key_for(&cosigner.verifying_key());
+        let envelope = envelope(&issuer_did, vec![cosigner_did.clone()]);
+        let signed = signed_by(
+            envelope,
+            &[(&issuer_did, &issuer), (&cosigner_did, &cosigner)],
+        );
+        DidKeyVerifier.verify(&signed).unwrap();
+        let json = serde_json::to_string(&signed).unwrap();
+        DidKeyVerifier
+            .verify(&serde_json::from_str(&json).unwrap())
+            .unwrap();
+
+        let mut missing = signed.clone();
+        missing.signatures.pop();
+        assert_eq!(
+            DidKeyVerifier.verify(&missing),
+            Err(SignatureError::MissingSignature {
+                did: cosigner_did.clone()
+            })
+        );
+
+        let mut tampered = signed.clone();
+        tampered.envelope.fear_index_max = 0.9;
+        assert!(matches!(
+            DidKeyVerifier.verify(&tampered),
+            Err(SignatureError::HashMismatch { .. })
+        ));
+
+        let mut forged = signed.clone();
+        forged.signatures[1] =
+            DetachedSignature::sign(&forged.envelope, &cosigner_did, &issuer).unwrap();
+        assert!(matches!(
+            DidKeyVerifier.verify(&forged),
+            Err(SignatureError::BadSignature { .. })
+        ));
+
+        let mut garbled = signed;
+        garbled.signatures[0].signature_b64 = "!!".into();
+        assert!(matches!(
+            DidKeyVerifier.verify(&garbled),
+            Err(SignatureError::BadSignature { .. })
+        ));
+    }
+
+    #[test]
+    fn only_ed25519_did_keys_are_resolved() {
+        let signer = key(1);
+        let web = "did:web:example.com";
+        let signed = signed_by(envelope(web, Vec::new()), &[(web, &signer)]);
+        assert!(matches!(
+            DidKeyVerifier.verify(&signed),
+            Err(SignatureError::UnknownDidMethod { .. })
+        ));
+
+        let truncated = &DID[..DID.len() - 3];
+        let signed = signed_by(envelope(truncated, Vec::new()), &[(truncated, &signer)]);
+        assert!(matches!(
+            DidKeyVerifier.verify(&signed),
+            Err(SignatureError::BadKey { .. })
+        ));
+    }
+}
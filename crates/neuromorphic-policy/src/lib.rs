@@ -1,6 +1,14 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+pub mod anchor;
+pub mod did;
+#[cfg(feature = "rpc-client")]
+pub mod rpc;
+
+pub use anchor::{verify_anchor_contains, LedgerAnchorResolver, LedgerBackedVerifier};
+pub use did::{DetachedSignature, DidKeyVerifier, SignatureError, SignedConsentEnvelope};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EthicalCeiling {
     pub max_fear_index_node: f64,
@@ -28,14 +36,6 @@ pub struct ConsentEnvelope {
     pub anchors: Vec<LedgerAnchor>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct LedgerAnchor {
-    pub chain: String,
-    pub network: String,
-    pub tx_hash: String,
-    pub source_id: String,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SafetyCertificate {
     pub certificate_id: String,
@@ -60,7 +60,8 @@ pub struct NeuromorphicPolicyAttestationSpec {
     pub bci_coupling: f64,
     pub eco_budget: EcoBudget,
     pub ethical_ceiling: EthicalCeiling,
-    pub consent_envelope: ConsentEnvelope,
+    /// The envelope's fields plus its signers' `signatures`.
+    pub consent_envelope: SignedConsentEnvelope,
     pub safety_certificate: SafetyCertificate,
 }
 
@@ -79,9 +80,55 @@ pub struct NeuromorphicNodeMetrics {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PolicyDecision {
     pub allowed: bool,
+    /// Human summary: the violations' messages joined by "; ".
     pub reason: String,
+    /// Every ceiling or check the transition failed, in evaluation order.
+    #[serde(default)]
+    pub violations: Vec<Violation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ViolationCode {
+    FearIndex,
+    EcoFear,
+    EcoBudgetFear,
+    Energy,
+    BciCoupling,
+    IrreversibleBio,
+    ConsentVerification,
+    CertificateVerification,
+}
+
+/// One failed check. Pass/fail checks (irreversible bio-risk and the
+/// verifications) report `observed` 1 against `limit` 0.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Violation {
+    pub code: ViolationCode,
+    pub observed: f64,
+    pub limit: f64,
+    pub message: String,
+}
+
+impl Violation {
+    fn failed(code: ViolationCode, message: String) -> Self {
+        Violation {
+            code,
+            observed: 1.0,
+            limit: 0.0,
+            message,
+        }
+    }
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}: {}", self.code, self.message)
+    }
 }
 
+/// Explicit hard clamp on bciCoupling under the current Phoenix profile.
+pub const MAX_BCI_COUPLING: f64 = 0.3;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EcoUsageCommitment {
     /// Commitment to eco-usage trajectory (e.g. Pedersen commitment).
@@ -106,88 +153,293 @@ pub struct LedgerAnchor {
 }
 
 pub trait DidLedgerVerifier {
-    fn verify_consent_envelope(&self, env: &ConsentEnvelope) -> anyhow::Result<()>;
+    fn verify_consent_envelope(&self, env: &SignedConsentEnvelope) -> anyhow::Result<()>;
     fn verify_safety_certificate(&self, cert: &SafetyCertificate) -> anyhow::Result<()>;
 }
 
-/// Core check: ethical ceiling as a hard machine-enforced predicate.
+/// Core check: ethical ceiling as a hard machine-enforced predicate. Every
+/// check runs, so the decision lists all violations at once.
 pub fn evaluate_neuromorphic_transition(
     spec: &NeuromorphicPolicyAttestationSpec,
     metrics: &NeuromorphicNodeMetrics,
     verifier: &dyn DidLedgerVerifier,
 ) -> PolicyDecision {
+    let mut violations = Vec::new();
+
     // 1. Ledger / DID checks (multi-sig, hash anchoring).
     if let Err(e) = verifier.verify_consent_envelope(&spec.consent_envelope) {
-        return PolicyDecision {
-            allowed: false,
-            reason: format!("consent envelope verification failed: {e}"),
-        };
+        violations.push(Violation::failed(
+            ViolationCode::ConsentVerification,
+            format!("consent envelope verification failed: {e}"),
+        ));
     }
     if let Err(e) = verifier.verify_safety_certificate(&spec.safety_certificate) {
-        return PolicyDecision {
-            allowed: false,
-            reason: format!("safety certificate verification failed: {e}"),
-        };
+        violations.push(Violation::failed(
+            ViolationCode::CertificateVerification,
+            format!("safety certificate verification failed: {e}"),
+        ));
     }
 
     // 2. Enforce BCI / irreversible bio ceilings.
     if spec.ethical_ceiling.forbid_irreversible_bio && metrics.irreversible_bio_risk {
-        return PolicyDecision {
-            allowed: false,
-            reason: "irreversible bio-risk detected for node; forbidden by ceiling".into(),
-        };
+        violations.push(Violation::failed(
+            ViolationCode::IrreversibleBio,
+            "irreversible bio-risk detected for node; forbidden by ceiling".into(),
+        ));
     }
-    // Explicit hard clamp: bciCoupling > 0.3 is denied under current Phoenix profile.
-    if spec.bci_coupling > 0.3 {
-        return PolicyDecision {
-            allowed: false,
-            reason: format!(
-                "bciCoupling {} exceeds Phoenix RoH/BCI ceiling of 0.3",
+    if spec.bci_coupling > MAX_BCI_COUPLING {
+        violations.push(Violation {
+            code: ViolationCode::BciCoupling,
+            observed: spec.bci_coupling,
+            limit: MAX_BCI_COUPLING,
+            message: format!(
+                "bciCoupling {} exceeds Phoenix RoH/BCI ceiling of {MAX_BCI_COUPLING}",
                 spec.bci_coupling
             ),
-        };
+        });
     }
 
     // 3. FearIndex and eco-fear ceilings (monotone, no rollbacks).
-    if metrics.fear_index_node > spec.ethical_ceiling.max_fear_index_node {
+    let mut exceeds = |code, observed: f64, limit: f64, message: String| {
+        if observed > limit {
+            violations.push(Violation {
+                code,
+                observed,
+                limit,
+                message,
+            });
+        }
+    };
+    let fear = metrics.fear_index_node;
+    let max_fear = spec.ethical_ceiling.max_fear_index_node;
+    exceeds(
+        ViolationCode::FearIndex,
+        fear,
+        max_fear,
+        format!("fearIndexNode {fear:.3} exceeds ceiling {max_fear:.3}"),
+    );
+    let eco_fear = metrics.eco_fear_node;
+    let max_eco_damage = spec.ethical_ceiling.max_eco_damage_node;
+    exceeds(
+        ViolationCode::EcoFear,
+        eco_fear,
+        max_eco_damage,
+        format!("ecoFearNode {eco_fear:.3} exceeds ceiling {max_eco_damage:.3}"),
+    );
+    let max_eco_fear = spec.eco_budget.max_eco_fear_node;
+    exceeds(
+        ViolationCode::EcoBudgetFear,
+        eco_fear,
+        max_eco_fear,
+        format!("ecoFearNode {eco_fear:.3} exceeds ecoBudget {max_eco_fear:.3}"),
+    );
+    let energy = metrics.energy_kwh_per_day;
+    let max_energy = spec.eco_budget.max_energy_kwh_per_day;
+    exceeds(
+        ViolationCode::Energy,
+        energy,
+        max_energy,
+        format!("energy {energy:.3} kWh/day exceeds ecoBudget {max_energy:.3} kWh/day"),
+    );
+
+    if violations.is_empty() {
         return PolicyDecision {
-            allowed: false,
-            reason: format!(
-                "fearIndexNode {:.3} exceeds ceiling {:.3}",
-                metrics.fear_index_node, spec.ethical_ceiling.max_fear_index_node
-            ),
+            allowed: true,
+            reason: "within neuromorphic ethical ceiling and eco budget".into(),
+            violations,
         };
     }
-    if metrics.eco_fear_node > spec.ethical_ceiling.max_eco_damage_node {
-        return PolicyDecision {
-            allowed: false,
-            reason: format!(
-                "ecoFearNode {:.3} exceeds ceiling {:.3}",
-                metrics.eco_fear_node, spec.ethical_ceiling.max_eco_damage_node
-            ),
+    PolicyDecision {
+        allowed: false,
+        reason: violations
+            .iter()
+            .map(|violation| violation.message.as_str())
+            .collect::<Vec<_>>()
+            .join("; "),
+        violations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::bail;
+
+    use super::*;
+
+    /// Passes or fails each verification as told.
+    struct FixedVerifier {
+        consent_ok: bool,
+        certificate_ok: bool,
+    }
+
+    impl DidLedgerVerifier for FixedVerifier {
+        fn verify_consent_envelope(&self, _: &SignedConsentEnvelope) -> anyhow::Result<()> {
+            if !self.consent_ok {
+                bail!("unsigned");
+            }
+            Ok(())
+        }
+
+        fn verify_safety_certificate(&self, _: &SafetyCertificate) -> anyhow::Result<()> {
+            if !self.certificate_ok {
+                bail!("unanchored");
+            }
+            Ok(())
+        }
+    }
+
+    const VERIFIED: FixedVerifier = FixedVerifier {
+        consent_ok: true,
+        certificate_ok: true,
+    };
+
+    fn spec() -> NeuromorphicPolicyAttestationSpec {
+        NeuromorphicPolicyAttestationSpec {
+            cluster_id: "cluster".into(),
+            namespace: "ns".into(),
+            helm_release: None,
+            node_class: "loihi".into(),
+            telemetry_contract_id: None,
+            bci_coupling: 0.1,
+            eco_budget: EcoBudget {
+                max_eco_fear_node: 0.2,
+                max_energy_kwh_per_day: 10.0,
+                region_profile_id: "region".into(),
+            },
+            ethical_ceiling: EthicalCeiling {
+                max_fear_index_node: 0.3,
+                max_eco_damage_node: 0.4,
+                forbid_irreversible_bio: true,
+            },
+            consent_envelope: SignedConsentEnvelope {
+                envelope: ConsentEnvelope {
+                    transcript_root: "root".into(),
+                    workspace_hash: "ws".into(),
+                    fear_index_max: 0.3,
+                    eco_fear_max: 0.2,
+                    fairness_score: 0.9,
+                    issuer_did: "did:key:z".into(),
+                    additional_signers: Vec::new(),
+                    envelope_hash: String::new(),
+                    anchors: Vec::new(),
+                },
+                signatures: Vec::new(),
+            },
+            safety_certificate: SafetyCertificate {
+                certificate_id: "cert".into(),
+                ethical_ceiling: SafetyCeilingParams {
+                    tau_p: 0.1,
+                    tau_f: 0.2,
+                    tau_e: 0.3,
+                },
+                anchors: Vec::new(),
+            },
+        }
+    }
+
+    fn metrics() -> NeuromorphicNodeMetrics {
+        NeuromorphicNodeMetrics {
+            fear_index_node: 0.1,
+            eco_fear_node: 0.1,
+            irreversible_bio_risk: false,
+            power_watts: 5.0,
+            energy_kwh_per_day: 1.0,
+            telemetry_flags: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn a_transition_within_every_ceiling_is_allowed() {
+        let decision = evaluate_neuromorphic_transition(&spec(), &metrics(), &VERIFIED);
+        assert!(decision.allowed);
+        assert!(decision.violations.is_empty());
+    }
+
+    #[test]
+    fn every_breach_is_reported_at_once() {
+        let spec = NeuromorphicPolicyAttestationSpec {
+            bci_coupling: 0.5,
+            ..spec()
+        };
+        let metrics = NeuromorphicNodeMetrics {
+            fear_index_node: 0.5,
+            eco_fear_node: 0.3,
+            irreversible_bio_risk: true,
+            energy_kwh_per_day: 12.0,
+            ..metrics()
         };
+        let verifier = FixedVerifier {
+            consent_ok: true,
+            certificate_ok: false,
+        };
+        let decision = evaluate_neuromorphic_transition(&spec, &metrics, &verifier);
+        assert!(!decision.allowed);
+        let codes: Vec<_> = decision.violations.iter().map(|v| v.code).collect();
+        assert_eq!(
+            codes,
+            [
+                ViolationCode::CertificateVerification,
+                ViolationCode::IrreversibleBio,
+                ViolationCode::BciCoupling,
+                ViolationCode::FearIndex,
+                ViolationCode::EcoBudgetFear,
+                ViolationCode::Energy,
+            ]
+        );
+        let fear = &decision.violations[3];
+        assert_eq!((fear.observed, fear.limit), (0.5, 0.3));
+        assert_eq!(decision.violations[1].observed, 1.0);
+        let messages: Vec<_> = decision
+            .violations
+            .iter()
+            .map(|v| v.message.as_str())
+            .collect();
+        assert_eq!(decision.reason, messages.join("; "));
+
+        let json = serde_json::to_value(&decision).unwrap();
+        assert_eq!(json["violations"][3]["code"], "FearIndex");
+        assert_eq!(json["violations"].as_array().unwrap().len(), 6);
     }
-    if metrics.eco_fear_node > spec.eco_budget.max_eco_fear_node {
-        return PolicyDecision {
-            allowed: false,
-            reason: format!(
-                "ecoFearNode {:.3} exceeds ecoBudget {:.3}",
-                metrics.eco_fear_node, spec.eco_budget.max_eco_fear_node
-            ),
+
+    #[test]
+    fn both_verifications_and_both_eco_ceilings_are_reported() {
+        let metrics = NeuromorphicNodeMetrics {
+            eco_fear_node: 0.5,
+            ..metrics()
+        };
+        let verifier = FixedVerifier {
+            consent_ok: false,
+            certificate_ok: false,
         };
+        let decision = evaluate_neuromorphic_transition(&spec(), &metrics, &verifier);
+        let codes: Vec<_> = decision.violations.iter().map(|v| v.code).collect();
+        assert_eq!(
+            codes,
+            [
+                ViolationCode::ConsentVerification,
+                ViolationCode::CertificateVerification,
+                ViolationCode::EcoFear,
+                ViolationCode::EcoBudgetFear,
+            ]
+        );
     }
-    if metrics.energy_kwh_per_day > spec.eco_budget.max_energy_kwh_per_day {
-        return PolicyDecision {
-            allowed: false,
-            reason: format!(
-                "energy {:.3} kWh/day exceeds ecoBudget {:.3} kWh/day",
-                metrics.energy_kwh_per_day, spec.eco_budget.max_energy_kwh_per_day
-            ),
+
+    #[test]
+    fn a_single_breach_keeps_its_reason() {
+        let metrics = NeuromorphicNodeMetrics {
+            fear_index_node: 0.5,
+            ..metrics()
         };
+        let decision = evaluate_neuromorphic_transition(&spec(), &metrics, &VERIFIED);
+        assert_eq!(decision.reason, "fearIndexNode 0.500 exceeds ceiling 0.300");
+        assert_eq!(decision.violations.len(), 1);
     }
 
-    PolicyDecision {
-        allowed: true,
-        reason: "within neuromorphic ethical ceiling and eco budget".into(),
+    #[test]
+    fn decisions_without_violations_still_deserialize() {
+        let decision: PolicyDecision =
+            serde_json::from_str(r#"{"allowed":true,"reason":"ok"}"#).unwrap();
+        assert!(decision.allowed);
+        assert!(decision.violations.is_empty());
     }
 }
@@ -0,0 +1,230 @@
+//! `LedgerAnchorResolver` over the Cosmos SDK REST API
+//! (`/cosmos/tx/v1beta1/txs/{hash}`), as served by Bostrom and other
+//! Cosmos chains.
+
+use std::collections::BTreeMap;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use reqwest::blocking::Client;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::anchor::LedgerAnchorResolver;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpResolverConfig {
+    /// chain -> network -> REST base URL, e.g.
+    /// `bostrom -> mainnet -> https://lcd.bostrom.cybernode.ai`
+    pub endpoints: BTreeMap<String, BTreeMap<String, String>>,
+    /// Per-request timeout, in milliseconds.
+    pub timeout_ms: u64,
+    /// Further attempts after a transport error, 429 or 5xx response.
+    pub retries: u32,
+    /// Wait before the first retry, doubled for each one after it.
+    pub retry_backoff_ms: u64,
+}
+
+impl Default for HttpResolverConfig {
+    fn default() -> Self {
+        Self {
+            endpoints: BTreeMap::new(),
+            timeout_ms: 10_000,
+            retries: 2,
+            retry_backoff_ms: 500,
+        }
+    }
+}
+
+pub struct HttpAnchorResolver {
+    config: HttpResolverConfig,
+    client: Client,
+}
+
+impl HttpAnchorResolver {
+    pub fn new(config: HttpResolverConfig) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()
+            .context("building HTTP client")?;
+        Ok(Self { config, client })
+    }
+
+    fn endpoint(&self, chain: &str, network: &str) -> Result<&str> {
+        self.config
+            .endpoints
+            .get(chain)
+            .and_then(|networks| networks.get(network))
+            .map(|url| url.trim_end_matches('/'))
+            .ok_or_else(|| anyhow!("no endpoint configured for {chain}/{network}"))
+    }
+
+    fn get(&self, url: &str) -> Result<TxResponse> {
+        let mut backoff = Duration::from_millis(self.config.retry_backoff_ms);
+        let mut attempt = 0;
+        loop {
+            let retryable = match self.client.get(url).send() {
+                Ok(response) if response.status().is_success() => {
+                    return response.json().with_context(|| format!("decoding {url}"));
+                }
+                Ok(response) if response.status() == StatusCode::NOT_FOUND => {
+                    bail!("{url}: tx not found");
+                }
+                Ok(response)
+                    if response.status() == StatusCode::TOO_MANY_REQUESTS
+                        || response.status().is_server_error() =>
+                {
+                    anyhow!("{url}: {}", response.status())
+                }
+                Ok(response) => bail!("{url}: {}", response.status()),
+                Err(e) => anyhow!(e).context(format!("requesting {url}")),
+            };
+            if attempt >= self.config.retries {
+                return Err(retryable);
+            }
+            attempt += 1;
+            thread::sleep(backoff);
+            backoff *= 2;
+        }
+    }
+}
+
+impl LedgerAnchorResolver for HttpAnchorResolver {
+    fn fetch_tx_memo(&self, chain: &str, network: &str, tx_hash: &str) -> Result<Vec<u8>> {
+        let url = format!(
+            "{}/cosmos/tx/v1beta1/txs/{tx_hash}",
+            self.endpoint(chain, network)?
+        );
+        let response = self.get(&url)?;
+        let mut memo = response.tx.body.memo.into_bytes();
+        for event in response.tx_response.events {
+            for attribute in event.attributes {
+                memo.push(b'\n');
+                memo.extend_from_slice(attribute.value.as_bytes());
+            }
+        }
+        Ok(memo)
+    }
+}
+
+#[derive(Deserialize)]
+struct TxResponse {
+    tx: Tx,
+    #[serde(default)]
+    tx_response: TxResult,
+}
+
+#[derive(Deserialize)]
+struct Tx {
+    body: TxBody,
+}
+
+#[derive(Deserialize)]
+struct TxBody {
+    #[serde(default)]
+    memo: String,
+}
+
+#[derive(Default, Deserialize)]
+struct TxResult {
+    #[serde(default)]
+    events: Vec<Event>,
+}
+
+#[derive(Deserialize)]
+struct Event {
+    #[serde(default)]
+    attributes: Vec<Attribute>,
+}
+
+#[derive(Deserialize)]
+struct Attribute {
+    #[serde(default)]
+    value: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    use super::*;
+    use crate::anchor::verify_anchor_contains;
+    use crate::LedgerAnchor;
+
+    const HASH: &str = "9F86D081884C7D659A2FEAA0C55AD015A3BF4F1B2B0B822CD15D6C15B0F00A08";
+
+    /// Serves one tx over HTTP: 404 for `MISSING`, and a 503 before the
+    /// first success.
+    fn serve() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || {
+            let mut failed_once = false;
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request = [0; 4096];
+                let len = stream.read(&mut request).unwrap();
+                let request = String::from_utf8_lossy(&request[..len]).into_owned();
+                let (status, body) = if request.contains("MISSING") {
+                    ("404 Not Found", "{}".to_string())
+                } else if !failed_once {
+                    failed_once = true;
+                    ("503 Service Unavailable", "{}".to_string())
+                } else {
+                    let body = serde_json::json!({
+                        "tx": {"body": {"memo": "consent"}},
+                        "tx_response": {"events": [{
+                            "type": "anchor",
+                            "attributes": [{"key": "hash", "value": HASH}],
+                        }]},
+                    });
+                    ("200 OK", body.to_string())
+                };
+                let response = format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        format!("http://127.0.0.1:{port}/")
+    }
+
+    #[test]
+    fn txs_are_fetched_with_retries() {
+        let mut config = HttpResolverConfig {
+            retry_backoff_ms: 10,
+            ..HttpResolverConfig::default()
+        };
+        config
+            .endpoints
+            .entry("bostrom".into())
+            .or_default()
+            .insert("mainnet".into(), serve());
+        let resolver = HttpAnchorResolver::new(config).unwrap();
+
+        let memo = resolver.fetch_tx_memo("bostrom", "mainnet", "T1").unwrap();
+        assert_eq!(memo, format!("consent\n{HASH}").into_bytes());
+        let anchor = LedgerAnchor {
+            chain: "bostrom".into(),
+            network: "mainnet".into(),
+            tx_hash: "T1".into(),
+            source_id: "consent".into(),
+            eco_usage_commitment: None,
+        };
+        verify_anchor_contains(&resolver, &anchor, &HASH.to_lowercase()).unwrap();
+
+        let err = resolver
+            .fetch_tx_memo("bostrom", "mainnet", "MISSING")
+            .unwrap_err();
+        assert!(err.to_string().contains("not found"));
+        let err = resolver
+            .fetch_tx_memo("bostrom", "testnet", "T1")
+            .unwrap_err();
+        assert!(err.to_string().contains("no endpoint"));
+    }
+}
@@ -0,0 +1,216 @@
+//! Resource budgets on transition costs (`PolicyEngine::transition_cost`).
+
+use crate::observer::TransitionObserver;
+use crate::{FearIndex, PolicyContext};
+
+/// Spending limits checked by `step_world` when set on `World`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ResourceBudget {
+    pub max_per_tick: f64,
+    /// Limit over the whole run, tracked in `World::resource_spent`.
+    pub max_cumulative: f64,
+}
+
+impl Default for ResourceBudget {
+    fn default() -> Self {
+        Self {
+            max_per_tick: f64::INFINITY,
+            max_cumulative: f64::INFINITY,
+        }
+    }
+}
+
+/// Wraps a tick's observer and defers transitions once the budget is
+/// exceeded. After the first transition that does not fit, every remaining
+/// transition of the tick is deferred, so what gets through depends only on
+/// the order transitions are considered in.
+pub struct BudgetedObserver<'a, O> {
+    pub budget: ResourceBudget,
+    spent_tick: f64,
+    spent_total: f64,
+    exhausted: bool,
+    inner: &'a mut O,
+}
+
+impl<'a, O: TransitionObserver> BudgetedObserver<'a, O> {
+    pub fn new(budget: ResourceBudget, spent_total: f64, inner: &'a mut O) -> Self {
+        Self {
+            budget,
+            spent_tick: 0.0,
+            spent_total,
+            exhausted: false,
+            inner,
+        }
+    }
+
+    pub fn spent_tick(&self) -> f64 {
+        self.spent_tick
+    }
+
+    pub fn spent_total(&self) -> f64 {
+        self.spent_total
+    }
+}
+
+impl<O: TransitionObserver> TransitionObserver for BudgetedObserver<'_, O> {
+    fn on_transition_applied(&mut self, ctx: &PolicyContext, fear: &FearIndex) {
+        self.inner.on_transition_applied(ctx, fear);
+    }
+
+    fn on_transition_forbidden(&mut self, ctx: &PolicyContext, reason: Option<&str>) {
+        self.inner.on_transition_forbidden(ctx, reason);
+    }
+
+    fn admit_cost(&mut self, ctx: &PolicyContext, cost: f64) -> bool {
+        if self.exhausted
+            || self.spent_tick + cost > self.budget.max_per_tick
+            || self.spent_total + cost > self.budget.max_cumulative
+        {
+            self.exhausted = true;
+            return false;
+        }
+        if !self.inner.admit_cost(ctx, cost) {
+            return false;
+        }
+        self.spent_tick += cost;
+        self.spent_total += cost;
+        true
+    }
+
+    fn on_transition_deferred(&mut self, ctx: &PolicyContext) {
+        self.inner.on_transition_deferred(ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decision_log::{DecisionOutcome, ZoneDecisionLog};
+    use crate::{
+        step_world, step_world_adaptive, step_world_observed, step_world_substeps,
+        AdaptiveSubsteps, AgentId, HumanAgent, Location, PolicyEngine, World,
+    };
+
+    /// Allows everything at no fear, for a fixed cost per transition.
+    struct Priced(f64);
+
+    impl PolicyEngine for Priced {
+        fn is_transition_forbidden(&self, _ctx: &PolicyContext) -> bool {
+            false
+        }
+
+        fn evaluate_transition(&self, _ctx: &PolicyContext) -> FearIndex {
+            FearIndex {
+                systemic_harm: 0.0,
+                regret: 0.0,
+                ecological_damage: 0.0,
+            }
+        }
+
+        fn transition_cost(&self, _ctx: &PolicyContext) -> f64 {
+            self.0
+        }
+    }
+
+    /// Six agents in one region offered `alpha`, under `budget`.
+    fn world(budget: ResourceBudget) -> World {
+        let mut world = World::default();
+        world.region_populations.insert("r1".to_string(), 0);
+        world
+            .concept_fields
+            .insert(("alpha".to_string(), "r1".to_string()), 0.5);
+        for id in 0..6 {
+            world
+                .agents
+                .push(HumanAgent::new(AgentId(id), Location::new(0.0, 0.0, "r1")));
+        }
+        world.resource_budget = Some(budget);
+        world
+    }
+
+    fn outcomes(log: &ZoneDecisionLog) -> Vec<(u64, DecisionOutcome)> {
+        log.entries
+            .iter()
+            .map(|e| (e.agent_id.0, e.outcome.clone()))
+            .collect()
+    }
+
+    fn adopters(world: &World) -> Vec<u64> {
+        world
+            .agents
+            .iter()
+            .filter(|a| a.beliefs.contains_key("alpha"))
+            .map(|a| a.id.0)
+            .collect()
+    }
+
+    #[test]
+    fn deferral_follows_agent_order_and_repeats() {
+        let budget = ResourceBudget {
+            max_per_tick: 3.5,
+            max_cumulative: f64::INFINITY,
+        };
+        let run = || {
+            let mut world = world(budget);
+            let mut log = ZoneDecisionLog::default();
+            step_world_observed(&mut world, &Priced(1.0), 1.0, &mut log);
+            (world, log)
+        };
+        let (world, log) = run();
+        let expected: Vec<(u64, DecisionOutcome)> = (0..6)
+            .map(|id| {
+                let outcome = if id < 3 {
+                    DecisionOutcome::Applied
+                } else {
+                    DecisionOutcome::BudgetDeferred
+                };
+                (id, outcome)
+            })
+            .collect();
+        assert_eq!(outcomes(&log), expected);
+        assert_eq!(adopters(&world), vec![0, 1, 2]);
+        assert_eq!(world.resource_spent, 3.0);
+
+        let (_, again) = run();
+        assert_eq!(outcomes(&again), outcomes(&log));
+    }
+
+    #[test]
+    fn cumulative_limit_spans_ticks() {
+        let mut world = world(ResourceBudget {
+            max_per_tick: 3.0,
+            max_cumulative: 5.0,
+        });
+        for _ in 0..4 {
+            step_world(&mut world, &Priced(1.0), 1.0);
+        }
+        assert_eq!(world.resource_spent, 5.0);
+        assert_eq!(adopters(&world), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn every_step_variant_is_budgeted() {
+        let budget = ResourceBudget {
+            max_per_tick: 2.5,
+            max_cumulative: f64::INFINITY,
+        };
+        let mut reference = world(budget);
+        step_world(&mut reference, &Priced(1.0), 1.0);
+
+        let mut substepped = world(budget);
+        step_world_substeps(&mut substepped, &Priced(1.0), 1.0, 4);
+        let mut adaptive = world(budget);
+        step_world_adaptive(
+            &mut adaptive,
+            &Priced(1.0),
+            1.0,
+            &mut AdaptiveSubsteps::new(0.1, 8),
+        );
+
+        for stepped in [&substepped, &adaptive] {
+            assert_eq!(stepped.resource_spent, 2.0);
+            assert_eq!(adopters(stepped), adopters(&reference));
+        }
+        assert_eq!(adopters(&reference), vec![0, 1]);
+    }
+}
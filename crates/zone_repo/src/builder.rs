@@ -0,0 +1,278 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::{AgentId, HumanAgent, Location, World};
+
+/// Reasons a `WorldBuilder` refuses to build.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WorldBuildError {
+    UnknownRegion { region_id: String, referenced_by: String },
+    UnknownConcept { concept_key: String, region_id: String },
+    DuplicateAgent { agent_id: AgentId },
+}
+
+impl fmt::Display for WorldBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorldBuildError::UnknownRegion {
+                region_id,
+                referenced_by,
+            } => write!(f, "{referenced_by} references undeclared region '{region_id}'"),
+            WorldBuildError::UnknownConcept {
+                concept_key,
+                region_id,
+            } => write!(
+                f,
+                "intensity in region '{region_id}' references undeclared concept '{concept_key}'"
+            ),
+            WorldBuildError::DuplicateAgent { agent_id } => {
+                write!(f, "agent id {} is used more than once", agent_id.0)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WorldBuildError {}
+
+/// Fluent construction of a `World` with reference checking.
+///
+/// Keys are always given in (concept, region) order, matching
+/// `World::concept_fields`.
+#[derive(Default)]
+pub struct WorldBuilder {
+    regions: Vec<(String, usize)>,
+    concepts: Vec<String>,
+    intensities: Vec<(String, String, f64)>,
+    neighbors: Vec<(String, Vec<String>)>,
+    agents: Vec<HumanAgent>,
+    populations_from_agents: bool,
+    seed: u64,
+}
+
+impl WorldBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn region(mut self, name: &str, population: usize) -> Self {
+        self.regions.push((name.to_string(), population));
+        self
+    }
+
+    pub fn concept(mut self, key: &str) -> Self {
+        self.concepts.push(key.to_string());
+        self
+    }
+
+    pub fn intensity(mut self, concept: &str, region: &str, value: f64) -> Self {
+        self.intensities
+            .push((concept.to_string(), region.to_string(), value));
+        self
+    }
+
+    pub fn neighbors(mut self, region: &str, adjacent: &[&str]) -> Self {
+        self.neighbors.push((
+            region.to_string(),
+            adjacent.iter().map(|r| r.to_string()).collect(),
+        ));
+        self
+    }
+
+    pub fn agent(mut self, agent: HumanAgent) -> Self {
+        self.agents.push(agent);
+        self
+    }
+
+    /// Add `count` belief-less agents at the origin of `region`, with ids
+    /// following the highest id added so far.
+    pub fn agents_in_region(mut self, count: usize, region: &str) -> Self {
+        let first_id = self.agents.iter().map(|a| a.id.0 + 1).max().unwrap_or(0);
+        for id in first_id..first_id + count as u64 {
+            self.agents.push(HumanAgent::new(
+                AgentId(id),
+                Location::new(0.0, 0.0, region),
+            ));
+        }
+        self
+    }
+
+    /// Seed for the world's RNG stream (defaults to 0).
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Overwrite declared populations with agent counts at build time.
+    pub fn populations_from_agents(mut self) -> Self {
+        self.populations_from_agents = true;
+        self
+    }
+
+    pub fn build(self) -> Result<World, WorldBuildError> {
+        let regions: HashSet<&str> = self.regions.iter().map(|(r, _)| r.as_str()).collect();
+        let concepts: HashSet<&str> = self.concepts.iter().map(String::as_str).collect();
+        let check_region = |region_id: &str, referenced_by: String| {
+            if regions.contains(region_id) {
+                Ok(())
+            } else {
+                Err(WorldBuildError::UnknownRegion {
+                    region_id: region_id.to_string(),
+                    referenced_by,
+                })
+            }
+        };
+
+        for (concept, region, _) in &self.intensities {
+            check_region(region, format!("intensity for concept '{concept}'"))?;
+            if !concepts.contains(concept.as_str()) {
+                return Err(WorldBuildError::UnknownConcept {
+                    concept_key: concept.clone(),
+                    region_id: region.clone(),
+                });
+            }
+        }
+        for (region, adjacent) in &self.neighbors {
+            check_region(region, "neighbor list".to_string())?;
+            for other in adjacent {
+                check_region(other, format!("neighbor list of '{region}'"))?;
+            }
+        }
+        let mut seen = HashSet::new();
+        for agent in &self.agents {
+            check_region(&agent.location.region_id, format!("agent {}", agent.id.0))?;
+            if !seen.insert(agent.id.0) {
+                return Err(WorldBuildError::DuplicateAgent {
+                    agent_id: agent.id.clone(),
+                });
+            }
+        }
+
+        let mut world = World {
+            agents: self.agents,
+            region_populations: self.regions.iter().cloned().collect(),
+            concept_fields: self
+                .intensities
+                .into_iter()
+                .map(|(concept, region, value)| ((concept, region), value))
+                .collect(),
+            region_neighbors: self.neighbors.into_iter().collect(),
+            ..World::seeded(self.seed)
+        };
+
+        if self.populations_from_agents {
+            world.recompute_populations();
+            for (region, _) in &self.regions {
+                world.region_populations.entry(region.clone()).or_insert(0);
+            }
+        }
+        world.rebuild_agent_index();
+
+        Ok(world)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Environment;
+
+    fn base() -> WorldBuilder {
+        WorldBuilder::new()
+            .region("r1", 100)
+            .region("r2", 50)
+            .concept("alpha")
+    }
+
+    #[test]
+    fn a_consistent_world_builds_and_validates() {
+        let world = base()
+            .intensity("alpha", "r1", 0.4)
+            .neighbors("r1", &["r2"])
+            .agents_in_region(2, "r1")
+            .seed(9)
+            .build()
+            .unwrap();
+        assert!(world.validate().is_empty());
+        assert_eq!(world.get_concept_intensity("alpha", "r1"), 0.4);
+        assert_eq!(world.region_neighbors["r1"], vec!["r2".to_string()]);
+        assert_eq!(world.get_region_population("r1"), 100);
+        assert_eq!(world.agent_index[&AgentId(1)], 1);
+    }
+
+    #[test]
+    fn undeclared_references_are_rejected() {
+        let err = base().intensity("alpha", "r3", 0.4).build().unwrap_err();
+        assert_eq!(
+            err,
+            WorldBuildError::UnknownRegion {
+                region_id: "r3".into(),
+                referenced_by: "intensity for concept 'alpha'".into(),
+            }
+        );
+        let err = base().neighbors("r1", &["r3"]).build().unwrap_err();
+        assert!(
+            matches!(err, WorldBuildError::UnknownRegion { region_id, .. } if region_id == "r3")
+        );
+        let err = base().agents_in_region(1, "r3").build().unwrap_err();
+        assert_eq!(err.to_string(), "agent 0 references undeclared region 'r3'");
+
+        let err = base().intensity("beta", "r1", 0.4).build().unwrap_err();
+        assert_eq!(
+            err,
+            WorldBuildError::UnknownConcept {
+                concept_key: "beta".into(),
+                region_id: "r1".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn agent_ids_must_be_unique() {
+        let err = base()
+            .agent(HumanAgent::new(AgentId(3), Location::new(0.0, 0.0, "r1")))
+            .agent(HumanAgent::new(AgentId(3), Location::new(1.0, 1.0, "r2")))
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            WorldBuildError::DuplicateAgent {
+                agent_id: AgentId(3)
+            }
+        );
+    }
+
+    #[test]
+    fn agents_in_region_continues_after_the_highest_id() {
+        let world = base()
+            .agent(HumanAgent::new(AgentId(7), Location::new(0.0, 0.0, "r2")))
+            .agents_in_region(3, "r1")
+            .agents_in_region(1, "r2")
+            .build()
+            .unwrap();
+        let ids: Vec<(u64, &str)> = world
+            .agents
+            .iter()
+            .map(|a| (a.id.0, a.location.region_id.as_str()))
+            .collect();
+        assert_eq!(
+            ids,
+            [(7, "r2"), (8, "r1"), (9, "r1"), (10, "r1"), (11, "r2")]
+        );
+        assert!(world.agents[1].beliefs.is_empty());
+    }
+
+    #[test]
+    fn populations_can_be_counted_from_agents() {
+        let world = base()
+            .region("empty", 30)
+            .agents_in_region(3, "r1")
+            .agents_in_region(1, "r2")
+            .populations_from_agents()
+            .build()
+            .unwrap();
+        assert_eq!(world.get_region_population("r1"), 3);
+        assert_eq!(world.get_region_population("r2"), 1);
+        assert_eq!(world.region_populations.get("empty"), Some(&0));
+        assert!(world.validate().is_empty());
+    }
+}
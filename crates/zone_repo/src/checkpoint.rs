@@ -0,0 +1,465 @@
+//! Periodic on-disk checkpoints so long runs survive being killed.
+//!
+//! A checkpoint file is a header line `zone_repo-checkpoint v<N> tick <T>`
+//! followed by a JSON `WorldSnapshot`, gzip-compressed when the `gzip`
+//! feature is enabled. Like `WorldSnapshot`, it holds only the mutable state
+//! of the world, so resuming restores it into a base world that carries the
+//! static configuration (neighbors, diffusion, point sources, ...).
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::snapshot::WorldSnapshot;
+use crate::{step_world, PolicyEngine, World};
+
+pub const CHECKPOINT_FORMAT_VERSION: u32 = 1;
+
+const HEADER_PREFIX: &str = "zone_repo-checkpoint v";
+const FILE_PREFIX: &str = "tick-";
+#[cfg(feature = "gzip")]
+const FILE_SUFFIX: &str = ".ckpt.gz";
+#[cfg(not(feature = "gzip"))]
+const FILE_SUFFIX: &str = ".ckpt";
+
+#[derive(Debug)]
+pub enum CheckpointError {
+    Io { path: PathBuf, source: io::Error },
+    /// The file exists but is truncated, corrupt, or from another format version.
+    Invalid { path: PathBuf, message: String },
+    /// No readable checkpoint in the directory; `skipped` holds why each
+    /// file present could not be read, newest first.
+    NoCheckpoint {
+        dir: PathBuf,
+        skipped: Vec<CheckpointError>,
+    },
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckpointError::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            CheckpointError::Invalid { path, message } => {
+                write!(f, "{}: invalid checkpoint: {message}", path.display())
+            }
+            CheckpointError::NoCheckpoint { dir, skipped } => {
+                write!(f, "{}: no readable checkpoint", dir.display())?;
+                if !skipped.is_empty() {
+                    write!(f, " ({} skipped)", skipped.len())?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CheckpointError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+fn io_error(path: &Path, source: io::Error) -> CheckpointError {
+    CheckpointError::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+fn invalid(path: &Path, message: impl Into<String>) -> CheckpointError {
+    CheckpointError::Invalid {
+        path: path.to_path_buf(),
+        message: message.into(),
+    }
+}
+
+/// A world restored from the newest readable checkpoint.
+#[derive(Debug)]
+pub struct Resumed {
+    pub world: World,
+    /// Simulation time of the checkpoint.
+    pub time: f64,
+    /// Newer checkpoints that could not be read, newest first.
+    pub skipped: Vec<CheckpointError>,
+}
+
+/// Writes a checkpoint of the world every `every_n_ticks` ticks and keeps
+/// the newest `keep_last` of them.
+#[derive(Clone, Debug)]
+pub struct Checkpointer {
+    pub dir: PathBuf,
+    pub every_n_ticks: u64,
+    pub keep_last: usize,
+    ticks: u64,
+}
+
+impl Checkpointer {
+    pub fn new(dir: impl Into<PathBuf>, every_n_ticks: u64) -> Self {
+        Self {
+            dir: dir.into(),
+            every_n_ticks,
+            keep_last: 3,
+            ticks: 0,
+        }
+    }
+
+    pub fn with_retention(mut self, keep_last: usize) -> Self {
+        self.keep_last = keep_last;
+        self
+    }
+
+    /// Ticks counted so far, including those before a resume.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Count a finished tick and write a checkpoint if one is due. Returns
+    /// the path written, if any.
+    pub fn record(&mut self, world: &World) -> Result<Option<PathBuf>, CheckpointError> {
+        self.ticks += 1;
+        if self.every_n_ticks == 0 || !self.ticks.is_multiple_of(self.every_n_ticks) {
+            return Ok(None);
+        }
+        let path = self.write(world)?;
+        self.prune()?;
+        Ok(Some(path))
+    }
+
+    /// Write a checkpoint for the current tick, regardless of the schedule.
+    pub fn write(&self, world: &World) -> Result<PathBuf, CheckpointError> {
+        fs::create_dir_all(&self.dir).map_err(|e| io_error(&self.dir, e))?;
+        let path = self
+            .dir
+            .join(format!("{FILE_PREFIX}{:012}{FILE_SUFFIX}", self.ticks));
+        let mut payload = format!(
+            "{HEADER_PREFIX}{CHECKPOINT_FORMAT_VERSION} tick {}\n",
+            self.ticks
+        )
+        .into_bytes();
+        serde_json::to_writer(&mut payload, &world.snapshot())
+            .map_err(|e| invalid(&path, e.to_string()))?;
+
+        // Write beside the target and rename, so a kill mid-write never
+        // leaves a half-written file under a checkpoint name.
+        let partial = path.with_extension("partial");
+        write_compressed(&partial, &payload).map_err(|e| io_error(&partial, e))?;
+        fs::rename(&partial, &path).map_err(|e| io_error(&path, e))?;
+        Ok(path)
+    }
+
+    /// Delete all but the newest `keep_last` checkpoints.
+    pub fn prune(&self) -> Result<(), CheckpointError> {
+        let files = list_checkpoints(&self.dir)?;
+        let excess = files.len().saturating_sub(self.keep_last.max(1));
+        for (_, path) in files.into_iter().take(excess) {
+            fs::remove_file(&path).map_err(|e| io_error(&path, e))?;
+        }
+        Ok(())
+    }
+
+    /// Restore the newest readable checkpoint in `dir` into `base` and
+    /// continue the tick count from it.
+    pub fn resume(&mut self, base: World) -> Result<Resumed, CheckpointError> {
+        let (tick, resumed) = load_latest(&self.dir, base)?;
+        self.ticks = tick;
+        Ok(resumed)
+    }
+}
+
+#[cfg(feature = "gzip")]
+fn write_compressed(path: &Path, payload: &[u8]) -> io::Result<()> {
+    let file = fs::File::create(path)?;
+    let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    encoder.write_all(payload)?;
+    encoder.finish()?.sync_all()
+}
+
+#[cfg(not(feature = "gzip"))]
+fn write_compressed(path: &Path, payload: &[u8]) -> io::Result<()> {
+    let mut file = fs::File::create(path)?;
+    file.write_all(payload)?;
+    file.sync_all()
+}
+
+#[cfg(feature = "gzip")]
+fn read_compressed(path: &Path) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    flate2::read::GzDecoder::new(fs::File::open(path)?).read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+#[cfg(not(feature = "gzip"))]
+fn read_compressed(path: &Path) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    fs::File::open(path)?.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Checkpoint files in `dir`, oldest first.
+fn list_checkpoints(dir: &Path) -> Result<Vec<(u64, PathBuf)>, CheckpointError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(io_error(dir, e)),
+    };
+    let mut files = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| io_error(dir, e))?.path();
+        let tick = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix(FILE_PREFIX))
+            .and_then(|name| name.strip_suffix(FILE_SUFFIX))
+            .and_then(|tick| tick.parse::<u64>().ok());
+        if let Some(tick) = tick {
+            files.push((tick, path));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn read_checkpoint(path: &Path) -> Result<(u64, WorldSnapshot), CheckpointError> {
+    let bytes = read_compressed(path).map_err(|e| invalid(path, e.to_string()))?;
+    let newline = bytes
+        .iter()
+        .position(|&b| b == b'\n')
+        .ok_or_else(|| invalid(path, "missing header"))?;
+    let header = std::str::from_utf8(&bytes[..newline])
+        .ok()
+        .and_then(|line| line.strip_prefix(HEADER_PREFIX))
+        .ok_or_else(|| invalid(path, "missing header"))?;
+    let (version, tick) = header
+        .split_once(" tick ")
+        .and_then(|(v, t)| Some((v.parse::<u32>().ok()?, t.parse::<u64>().ok()?)))
+        .ok_or_else(|| invalid(path, format!("malformed header '{header}'")))?;
+    if version != CHECKPOINT_FORMAT_VERSION {
+        return Err(invalid(
+            path,
+            format!("format version {version}, expected {CHECKPOINT_FORMAT_VERSION}"),
+        ));
+    }
+    let snapshot =
+        serde_json::from_slice(&bytes[newline + 1..]).map_err(|e| invalid(path, e.to_string()))?;
+    Ok((tick, snapshot))
+}
+
+/// Restore the newest checkpoint that reads back cleanly into `base`;
+/// unreadable ones are skipped and reported in `Resumed::skipped`.
+fn load_latest(dir: &Path, mut base: World) -> Result<(u64, Resumed), CheckpointError> {
+    let mut skipped = Vec::new();
+    for (_, path) in list_checkpoints(dir)?.into_iter().rev() {
+        match read_checkpoint(&path) {
+            Ok((tick, snapshot)) => {
+                base.restore(&snapshot);
+                let resumed = Resumed {
+                    world: base,
+                    time: snapshot.time,
+                    skipped,
+                };
+                return Ok((tick, resumed));
+            }
+            Err(e) => skipped.push(e),
+        }
+    }
+    Err(CheckpointError::NoCheckpoint {
+        dir: dir.to_path_buf(),
+        skipped,
+    })
+}
+
+/// Restore the newest readable checkpoint in `dir` into `base`, the world
+/// the run was started from (or one with the same static configuration).
+pub fn resume_latest(dir: impl AsRef<Path>, base: World) -> Result<Resumed, CheckpointError> {
+    load_latest(dir.as_ref(), base).map(|(_, resumed)| resumed)
+}
+
+/// `step_world`, then let `checkpointer` write a checkpoint if one is due.
+pub fn step_world_checkpointed<P: PolicyEngine>(
+    world: &mut World,
+    policies: &P,
+    dt: f64,
+    checkpointer: &mut Checkpointer,
+) -> Result<(), CheckpointError> {
+    step_world(world, policies, dt);
+    checkpointer.record(world)?;
+    Ok(())
+}
+
+/// Serde adapter for maps keyed by `(String, String)`, which JSON cannot
+/// use as object keys; stored as a list of `(a, b, value)` triples.
+pub(crate) mod pair_keyed {
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        map: &BTreeMap<(String, String), f64>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let entries: Vec<(&str, &str, f64)> = map
+            .iter()
+            .map(|((a, b), v)| (a.as_str(), b.as_str(), *v))
+            .collect();
+        entries.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<(String, String), f64>, D::Error> {
+        let entries = Vec::<(String, String, f64)>::deserialize(deserializer)?;
+        Ok(entries.into_iter().map(|(a, b, v)| ((a, b), v)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diffusion::DiffusionConfig;
+    use crate::injection::{Injection, InjectionShape};
+    use crate::{AdoptionNoise, AgentId, HumanAgent, Location, ZoneRepoPolicyEngine};
+
+    fn scenario() -> World {
+        let mut world = World::seeded(5);
+        for (region_id, neighbor) in [("a", "b"), ("b", "a")] {
+            world.region_populations.insert(region_id.to_string(), 0);
+            world
+                .region_neighbors
+                .insert(region_id.to_string(), vec![neighbor.to_string()]);
+        }
+        world.diffusion = Some(DiffusionConfig::default());
+        world.injections.push(Injection {
+            concept_key: "alpha".to_string(),
+            region_id: "a".to_string(),
+            start_time: 1.0,
+            duration: 8.0,
+            peak_intensity: 0.8,
+            shape: InjectionShape::Pulse,
+        });
+        for id in 0..6 {
+            let region_id = if id % 2 == 0 { "a" } else { "b" };
+            let mut agent = HumanAgent::new(AgentId(id), Location::new(0.0, 0.0, region_id));
+            agent.adoption_noise = Some(AdoptionNoise::default());
+            world.agents.push(agent);
+        }
+        world
+    }
+
+    /// A fresh directory under the system temp dir, removed on drop.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("zone_repo-{name}-{}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn state(world: &World) -> serde_json::Value {
+        serde_json::to_value(world.snapshot()).unwrap()
+    }
+
+    #[test]
+    fn resumed_run_matches_an_uninterrupted_one() {
+        let policy = ZoneRepoPolicyEngine::new(1.0);
+        let mut reference = scenario();
+        for _ in 0..10 {
+            step_world(&mut reference, &policy, 1.0);
+        }
+
+        let dir = TempDir::new("resume");
+        let mut checkpointer = Checkpointer::new(&dir.0, 2);
+        let mut world = scenario();
+        // Killed after tick 5: the last checkpoint is from tick 4.
+        for _ in 0..5 {
+            step_world_checkpointed(&mut world, &policy, 1.0, &mut checkpointer).unwrap();
+        }
+        drop(world);
+
+        let mut checkpointer = Checkpointer::new(&dir.0, 2);
+        let resumed = checkpointer.resume(scenario()).unwrap();
+        assert_eq!(checkpointer.ticks(), 4);
+        assert_eq!(resumed.time, 4.0);
+        assert!(resumed.skipped.is_empty());
+        let mut world = resumed.world;
+        for _ in 4..10 {
+            step_world_checkpointed(&mut world, &policy, 1.0, &mut checkpointer).unwrap();
+        }
+        assert_eq!(state(&world), state(&reference));
+    }
+
+    #[test]
+    fn unreadable_newest_checkpoint_is_skipped_and_reported() {
+        let policy = ZoneRepoPolicyEngine::new(1.0);
+        let dir = TempDir::new("corrupt");
+        let mut checkpointer = Checkpointer::new(&dir.0, 1);
+        let mut world = scenario();
+        let mut paths = Vec::new();
+        for _ in 0..3 {
+            step_world(&mut world, &policy, 1.0);
+            paths.extend(checkpointer.record(&world).unwrap());
+        }
+        let bytes = fs::read(&paths[2]).unwrap();
+        fs::write(&paths[2], &bytes[..bytes.len() / 2]).unwrap();
+
+        let resumed = resume_latest(&dir.0, scenario()).unwrap();
+        assert_eq!(resumed.time, 2.0);
+        assert_eq!(resumed.skipped.len(), 1);
+        assert!(matches!(
+            &resumed.skipped[0],
+            CheckpointError::Invalid { path, .. } | CheckpointError::Io { path, .. } if *path == paths[2]
+        ));
+    }
+
+    #[test]
+    fn other_format_version_is_rejected() {
+        let dir = TempDir::new("version");
+        let checkpointer = Checkpointer::new(&dir.0, 1);
+        let path = checkpointer.write(&scenario()).unwrap();
+        let payload = read_compressed(&path).unwrap();
+        let current = format!("{HEADER_PREFIX}{CHECKPOINT_FORMAT_VERSION} ");
+        let newer = format!("{HEADER_PREFIX}{} ", CHECKPOINT_FORMAT_VERSION + 1);
+        let payload = String::from_utf8(payload)
+            .unwrap()
+            .replacen(&current, &newer, 1);
+        write_compressed(&path, payload.as_bytes()).unwrap();
+
+        match resume_latest(&dir.0, scenario()) {
+            Err(CheckpointError::NoCheckpoint { skipped, .. }) => {
+                assert!(matches!(
+                    skipped.as_slice(),
+                    [CheckpointError::Invalid { .. }]
+                ));
+            }
+            other => panic!("expected NoCheckpoint, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn retention_keeps_the_newest_checkpoints() {
+        let policy = ZoneRepoPolicyEngine::new(1.0);
+        let dir = TempDir::new("retention");
+        let mut checkpointer = Checkpointer::new(&dir.0, 1).with_retention(2);
+        let mut world = scenario();
+        for _ in 0..5 {
+            step_world_checkpointed(&mut world, &policy, 1.0, &mut checkpointer).unwrap();
+        }
+        let ticks: Vec<u64> = list_checkpoints(&dir.0)
+            .unwrap()
+            .into_iter()
+            .map(|(tick, _)| tick)
+            .collect();
+        assert_eq!(ticks, vec![4, 5]);
+    }
+}
@@ -0,0 +1,219 @@
+use crate::{FearIndex, PolicyContext, PolicyEngine, PolicyVerdict};
+
+/// How member fear indices are folded into one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Aggregation {
+    Max,
+    Sum,
+    Mean,
+}
+
+pub struct CompositeMember {
+    pub name: String,
+    pub engine: Box<dyn PolicyEngine>,
+}
+
+/// Chains several engines: any member can veto, fear is aggregated.
+pub struct CompositePolicyEngine {
+    pub members: Vec<CompositeMember>,
+    pub aggregation: Aggregation,
+}
+
+/// A composite decision together with the member that vetoed it, if any.
+#[derive(Clone, Debug)]
+pub struct CompositeVerdict<'a> {
+    pub verdict: PolicyVerdict,
+    pub vetoed_by: Option<&'a str>,
+}
+
+impl CompositePolicyEngine {
+    pub fn new(aggregation: Aggregation) -> Self {
+        Self {
+            members: Vec::new(),
+            aggregation,
+        }
+    }
+
+    pub fn with_member(mut self, name: &str, engine: Box<dyn PolicyEngine>) -> Self {
+        self.push(name, engine);
+        self
+    }
+
+    pub fn push(&mut self, name: &str, engine: Box<dyn PolicyEngine>) {
+        self.members.push(CompositeMember {
+            name: name.to_string(),
+            engine,
+        });
+    }
+
+    /// Name of the first member (in insertion order) that forbids the context.
+    pub fn vetoing_member(&self, ctx: &PolicyContext) -> Option<&str> {
+        self.members
+            .iter()
+            .find(|m| m.engine.is_transition_forbidden(ctx))
+            .map(|m| m.name.as_str())
+    }
+
+    /// One `check_transition` per member: the first veto wins and is
+    /// reported with the vetoing member, otherwise member fears are
+    /// aggregated.
+    pub fn check_with_veto(&self, ctx: &PolicyContext) -> CompositeVerdict<'_> {
+        let mut fears = Vec::with_capacity(self.members.len());
+        for member in &self.members {
+            let verdict = member.engine.check_transition(ctx);
+            if verdict.forbidden {
+                let reason = match verdict.reason {
+                    Some(reason) => format!("vetoed by {}: {reason}", member.name),
+                    None => format!("vetoed by {}", member.name),
+                };
+                return CompositeVerdict {
+                    verdict: PolicyVerdict::forbid(Some(reason)),
+                    vetoed_by: Some(&member.name),
+                };
+            }
+            fears.push(
+                verdict
+                    .fear
+                    .unwrap_or_else(|| member.engine.evaluate_transition(ctx)),
+            );
+        }
+        CompositeVerdict {
+            verdict: PolicyVerdict::allow(self.aggregate(fears)),
+            vetoed_by: None,
+        }
+    }
+
+    fn aggregate(&self, fears: impl IntoIterator<Item = FearIndex>) -> FearIndex {
+        let mut acc = FearIndex::default();
+        for f in fears {
+            match self.aggregation {
+                Aggregation::Max => {
+                    acc.systemic_harm = acc.systemic_harm.max(f.systemic_harm);
+                    acc.regret = acc.regret.max(f.regret);
+                    acc.ecological_damage = acc.ecological_damage.max(f.ecological_damage);
+                }
+                Aggregation::Sum | Aggregation::Mean => acc = acc.add(&f),
+            }
+        }
+        if self.aggregation == Aggregation::Mean && !self.members.is_empty() {
+            acc = acc.scale(1.0 / self.members.len() as f64);
+        }
+        acc
+    }
+}
+
+impl PolicyEngine for CompositePolicyEngine {
+    fn is_transition_forbidden(
+        &self,
+        ctx: &PolicyContext,
+    ) -> bool {
+        self.vetoing_member(ctx).is_some()
+    }
+
+    fn forbid_reason(
+        &self,
+        ctx: &PolicyContext,
+    ) -> Option<String> {
+        self.check_with_veto(ctx).verdict.reason
+    }
+
+    fn evaluate_transition(
+        &self,
+        ctx: &PolicyContext,
+    ) -> FearIndex {
+        self.aggregate(self.members.iter().map(|m| m.engine.evaluate_transition(ctx)))
+    }
+
+    /// Members' costs add up.
+    fn transition_cost(
+        &self,
+        ctx: &PolicyContext,
+    ) -> f64 {
+        self.members
+            .iter()
+            .map(|m| m.engine.transition_cost(ctx))
+            .sum()
+    }
+
+    /// See `check_with_veto`.
+    fn check_transition(
+        &self,
+        ctx: &PolicyContext,
+    ) -> PolicyVerdict {
+        self.check_with_veto(ctx).verdict
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AgentId, BeliefStrength, TransitionKind, ZoneRepoPolicyEngine};
+
+    // Overload score 0.9 * 5000 / 10000 = 0.45.
+    fn ctx() -> PolicyContext<'static> {
+        PolicyContext {
+            agent_id: AgentId(1),
+            region_id: "r1",
+            concept_key: "alpha",
+            current_belief: None,
+            proposed_strength: BeliefStrength::Graded(0.9),
+            transition: TransitionKind::Adopt,
+            env_time: 0.0,
+            region_population: 5_000,
+            region_capacity: None,
+            concept_intensity: 0.9,
+            interaction: Default::default(),
+        }
+    }
+
+    fn permissive_and_strict(aggregation: Aggregation) -> CompositePolicyEngine {
+        CompositePolicyEngine::new(aggregation)
+            .with_member("permissive", Box::new(ZoneRepoPolicyEngine::new(10.0)))
+            .with_member("strict", Box::new(ZoneRepoPolicyEngine::new(0.1)))
+    }
+
+    #[test]
+    fn strict_member_vetoes_a_permissive_one() {
+        let composite = permissive_and_strict(Aggregation::Max);
+        let ctx = ctx();
+
+        assert!(!ZoneRepoPolicyEngine::new(10.0).is_transition_forbidden(&ctx));
+        assert!(composite.is_transition_forbidden(&ctx));
+        assert_eq!(composite.vetoing_member(&ctx), Some("strict"));
+
+        let decision = composite.check_with_veto(&ctx);
+        assert!(decision.verdict.forbidden);
+        assert_eq!(decision.vetoed_by, Some("strict"));
+        let reason = composite.forbid_reason(&ctx).unwrap();
+        assert!(
+            reason.starts_with("vetoed by strict: overload score"),
+            "{reason}"
+        );
+    }
+
+    #[test]
+    fn allowed_transitions_have_no_veto() {
+        let composite = CompositePolicyEngine::new(Aggregation::Max)
+            .with_member("a", Box::new(ZoneRepoPolicyEngine::new(10.0)))
+            .with_member("b", Box::new(ZoneRepoPolicyEngine::new(5.0)));
+        let decision = composite.check_with_veto(&ctx());
+        assert!(!decision.verdict.forbidden);
+        assert_eq!(decision.vetoed_by, None);
+        assert_eq!(composite.forbid_reason(&ctx()), None);
+    }
+
+    #[test]
+    fn fears_aggregate_per_mode() {
+        let member = ZoneRepoPolicyEngine::new(10.0).evaluate_transition(&ctx());
+        let doubled = |aggregation| {
+            CompositePolicyEngine::new(aggregation)
+                .with_member("a", Box::new(ZoneRepoPolicyEngine::new(10.0)))
+                .with_member("b", Box::new(ZoneRepoPolicyEngine::new(10.0)))
+                .evaluate_transition(&ctx())
+        };
+        let parts = |f: FearIndex| (f.systemic_harm, f.regret, f.ecological_damage);
+        assert_eq!(parts(doubled(Aggregation::Max)), parts(member.clone()));
+        assert_eq!(parts(doubled(Aggregation::Sum)), parts(member.add(&member)));
+        assert_eq!(parts(doubled(Aggregation::Mean)), parts(member));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::observer::TransitionObserver;
+use crate::{AgentId, FearIndex, PolicyContext};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecisionOutcome {
+    Applied,
+    Forbidden { reason: Option<String> },
+    /// Allowed, but skipped because the resource budget was spent.
+    BudgetDeferred,
+}
+
+/// One policy decision taken by an agent during a tick.
+#[derive(Clone, Debug)]
+pub struct ZoneDecisionEntry {
+    pub time: f64,
+    pub agent_id: AgentId,
+    pub region_id: String,
+    pub concept_key: String,
+    pub outcome: DecisionOutcome,
+    pub fear: Option<FearIndex>, // forbidden and deferred transitions carry none
+}
+
+/// Audit log of policy decisions, the zone_repo counterpart of `SimulationLog`.
+#[derive(Clone, Debug, Default)]
+pub struct ZoneDecisionLog {
+    pub entries: Vec<ZoneDecisionEntry>,
+}
+
+impl ZoneDecisionLog {
+    pub fn forbidden(&self) -> impl Iterator<Item = &ZoneDecisionEntry> {
+        self.entries
+            .iter()
+            .filter(|e| matches!(e.outcome, DecisionOutcome::Forbidden { .. }))
+    }
+}
+
+impl TransitionObserver for ZoneDecisionLog {
+    fn on_transition_applied(&mut self, ctx: &PolicyContext, fear: &FearIndex) {
+        self.entries.push(ZoneDecisionEntry {
+            time: ctx.env_time,
+            agent_id: ctx.agent_id.clone(),
+            region_id: ctx.region_id.to_string(),
+            concept_key: ctx.concept_key.to_string(),
+            outcome: DecisionOutcome::Applied,
+            fear: Some(fear.clone()),
+        });
+    }
+
+    fn on_transition_forbidden(&mut self, ctx: &PolicyContext, reason: Option<&str>) {
+        self.entries.push(ZoneDecisionEntry {
+            time: ctx.env_time,
+            agent_id: ctx.agent_id.clone(),
+            region_id: ctx.region_id.to_string(),
+            concept_key: ctx.concept_key.to_string(),
+            outcome: DecisionOutcome::Forbidden {
+                reason: reason.map(str::to_string),
+            },
+            fear: None,
+        });
+    }
+
+    fn on_transition_deferred(&mut self, ctx: &PolicyContext) {
+        self.entries.push(ZoneDecisionEntry {
+            time: ctx.env_time,
+            agent_id: ctx.agent_id.clone(),
+            region_id: ctx.region_id.to_string(),
+            concept_key: ctx.concept_key.to_string(),
+            outcome: DecisionOutcome::BudgetDeferred,
+            fear: None,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{step_world_logged, HumanAgent, Location, World, ZoneRepoPolicyEngine};
+
+    #[test]
+    fn forbidden_transitions_are_logged_with_agent_and_reason() {
+        let mut world = World::default();
+        world.region_populations.insert("calm".to_string(), 0);
+        world
+            .region_populations
+            .insert("crowded".to_string(), 20_000);
+        for (id, region_id) in [(1, "calm"), (7, "crowded"), (9, "crowded")] {
+            world.agents.push(HumanAgent::new(
+                AgentId(id),
+                Location::new(0.0, 0.0, region_id),
+            ));
+        }
+        for region_id in ["calm", "crowded"] {
+            world
+                .concept_fields
+                .insert(("alpha".to_string(), region_id.to_string()), 0.8);
+        }
+
+        let log = step_world_logged(&mut world, &ZoneRepoPolicyEngine::new(1.0), 1.0);
+
+        assert_eq!(log.entries.len(), 3);
+        assert_eq!(log.entries[0].agent_id, AgentId(1));
+        assert_eq!(log.entries[0].outcome, DecisionOutcome::Applied);
+        assert!(log.entries[0].fear.is_some());
+
+        let forbidden: Vec<_> = log.forbidden().collect();
+        assert_eq!(
+            forbidden
+                .iter()
+                .map(|e| e.agent_id.clone())
+                .collect::<Vec<_>>(),
+            vec![AgentId(7), AgentId(9)]
+        );
+        for entry in forbidden {
+            assert_eq!(entry.time, 1.0);
+            assert_eq!(entry.concept_key, "alpha");
+            assert!(entry.fear.is_none());
+            match &entry.outcome {
+                DecisionOutcome::Forbidden {
+                    reason: Some(reason),
+                } => {
+                    assert!(reason.contains("region 'crowded'"), "{reason}")
+                }
+                other => panic!("expected a forbid reason, got {other:?}"),
+            }
+        }
+    }
+}
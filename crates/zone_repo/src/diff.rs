@@ -0,0 +1,363 @@
+//! Structured comparison of two worlds, e.g. the same scenario run under
+//! two policy variants.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::{AgentId, BeliefStrength, World};
+
+/// Intensity differences at or below this are ignored by `diff_worlds`.
+pub const DEFAULT_INTENSITY_EPSILON: f64 = 1e-9;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PopulationDelta {
+    pub region_id: String,
+    /// Population in each world; a region missing from one counts as 0.
+    pub a: usize,
+    pub b: usize,
+}
+
+impl PopulationDelta {
+    pub fn delta(&self) -> i64 {
+        self.b as i64 - self.a as i64
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct IntensityDelta {
+    pub concept_key: String,
+    pub region_id: String,
+    /// Intensity in each world; a missing field counts as 0.
+    pub a: f64,
+    pub b: f64,
+}
+
+impl IntensityDelta {
+    pub fn delta(&self) -> f64 {
+        self.b - self.a
+    }
+}
+
+/// How one belief differs from world `a` to world `b`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub enum BeliefChange {
+    Added {
+        concept_key: String,
+        strength: BeliefStrength,
+    },
+    Removed {
+        concept_key: String,
+        strength: BeliefStrength,
+    },
+    StrengthChanged {
+        concept_key: String,
+        from: BeliefStrength,
+        to: BeliefStrength,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct AgentBeliefDiff {
+    pub agent_id: AgentId,
+    pub changes: Vec<BeliefChange>,
+}
+
+/// Differences from world `a` to world `b`, in key order.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct WorldDiff {
+    pub populations: Vec<PopulationDelta>,
+    pub intensities: Vec<IntensityDelta>,
+    pub beliefs: Vec<AgentBeliefDiff>,
+    pub agents_only_in_a: Vec<AgentId>,
+    pub agents_only_in_b: Vec<AgentId>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct DiffSummary {
+    pub population_changes: usize,
+    pub intensity_changes: usize,
+    pub beliefs_added: usize,
+    pub beliefs_removed: usize,
+    pub beliefs_changed: usize,
+    pub agents_only_in_a: usize,
+    pub agents_only_in_b: usize,
+}
+
+impl WorldDiff {
+    pub fn summary(&self) -> DiffSummary {
+        let mut summary = DiffSummary {
+            population_changes: self.populations.len(),
+            intensity_changes: self.intensities.len(),
+            agents_only_in_a: self.agents_only_in_a.len(),
+            agents_only_in_b: self.agents_only_in_b.len(),
+            ..DiffSummary::default()
+        };
+        for change in self.beliefs.iter().flat_map(|d| &d.changes) {
+            match change {
+                BeliefChange::Added { .. } => summary.beliefs_added += 1,
+                BeliefChange::Removed { .. } => summary.beliefs_removed += 1,
+                BeliefChange::StrengthChanged { .. } => summary.beliefs_changed += 1,
+            }
+        }
+        summary
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.summary() == DiffSummary::default()
+    }
+}
+
+/// `diff_worlds_with` at `DEFAULT_INTENSITY_EPSILON`.
+pub fn diff_worlds(a: &World, b: &World) -> WorldDiff {
+    diff_worlds_with(a, b, DEFAULT_INTENSITY_EPSILON)
+}
+
+/// Compare two worlds. Agents are matched by `AgentId`; ids present in only
+/// one world are listed rather than compared.
+pub fn diff_worlds_with(a: &World, b: &World, epsilon: f64) -> WorldDiff {
+    let mut diff = WorldDiff::default();
+
+    let regions: BTreeSet<&String> = a
+        .region_populations
+        .keys()
+        .chain(b.region_populations.keys())
+        .collect();
+    for region_id in regions {
+        let pa = a.region_populations.get(region_id).copied().unwrap_or(0);
+        let pb = b.region_populations.get(region_id).copied().unwrap_or(0);
+        if pa != pb {
+            diff.populations.push(PopulationDelta {
+                region_id: region_id.clone(),
+                a: pa,
+                b: pb,
+            });
+        }
+    }
+
+    let fields: BTreeSet<&(String, String)> = a
+        .concept_fields
+        .keys()
+        .chain(b.concept_fields.keys())
+        .collect();
+    for key in fields {
+        let ia = a.concept_fields.get(key).copied().unwrap_or(0.0);
+        let ib = b.concept_fields.get(key).copied().unwrap_or(0.0);
+        if (ib - ia).abs() > epsilon {
+            diff.intensities.push(IntensityDelta {
+                concept_key: key.0.clone(),
+                region_id: key.1.clone(),
+                a: ia,
+                b: ib,
+            });
+        }
+    }
+
+    let agents_a: BTreeMap<u64, _> = a.agents.iter().map(|agent| (agent.id.0, agent)).collect();
+    let agents_b: BTreeMap<u64, _> = b.agents.iter().map(|agent| (agent.id.0, agent)).collect();
+    for (id, agent_a) in &agents_a {
+        let Some(agent_b) = agents_b.get(id) else {
+            diff.agents_only_in_a.push(AgentId(*id));
+            continue;
+        };
+        let mut changes = Vec::new();
+        for (key, belief) in &agent_a.beliefs {
+            match agent_b.beliefs.get(key) {
+                None => changes.push(BeliefChange::Removed {
+                    concept_key: key.clone(),
+                    strength: belief.strength.clone(),
+                }),
+                Some(other) if other.strength != belief.strength => {
+                    changes.push(BeliefChange::StrengthChanged {
+                        concept_key: key.clone(),
+                        from: belief.strength.clone(),
+                        to: other.strength.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        for (key, belief) in &agent_b.beliefs {
+            if !agent_a.beliefs.contains_key(key) {
+                changes.push(BeliefChange::Added {
+                    concept_key: key.clone(),
+                    strength: belief.strength.clone(),
+                });
+            }
+        }
+        if !changes.is_empty() {
+            diff.beliefs.push(AgentBeliefDiff {
+                agent_id: AgentId(*id),
+                changes,
+            });
+        }
+    }
+    diff.agents_only_in_b = agents_b
+        .keys()
+        .filter(|id| !agents_a.contains_key(id))
+        .map(|id| AgentId(*id))
+        .collect();
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Belief, HumanAgent, Location};
+
+    fn agent(id: u64, beliefs: &[(&str, BeliefStrength)]) -> HumanAgent {
+        let mut agent = HumanAgent::new(AgentId(id), Location::new(0.0, 0.0, "r1"));
+        for (key, strength) in beliefs {
+            agent.beliefs.insert(
+                key.to_string(),
+                Belief {
+                    key: key.to_string(),
+                    strength: strength.clone(),
+                },
+            );
+        }
+        agent
+    }
+
+    fn world(
+        populations: &[(&str, usize)],
+        fields: &[(&str, &str, f64)],
+        agents: Vec<HumanAgent>,
+    ) -> World {
+        let mut world = World::default();
+        for (region_id, population) in populations {
+            world
+                .region_populations
+                .insert(region_id.to_string(), *population);
+        }
+        for (concept_key, region_id, intensity) in fields {
+            world
+                .concept_fields
+                .insert((concept_key.to_string(), region_id.to_string()), *intensity);
+        }
+        world.agents = agents;
+        world
+    }
+
+    #[test]
+    fn identical_worlds_have_an_empty_diff() {
+        let make = || {
+            world(
+                &[("r1", 10)],
+                &[("alpha", "r1", 0.5)],
+                vec![agent(1, &[("alpha", BeliefStrength::Strong)])],
+            )
+        };
+        let diff = diff_worlds(&make(), &make());
+        assert!(diff.is_empty());
+        assert_eq!(diff, WorldDiff::default());
+    }
+
+    #[test]
+    fn known_differences_are_reported_in_key_order() {
+        let a = world(
+            &[("r1", 10), ("r2", 5)],
+            &[
+                ("alpha", "r1", 0.5),
+                ("beta", "r1", 0.2),
+                ("gamma", "r2", 0.3),
+            ],
+            vec![
+                agent(
+                    1,
+                    &[
+                        ("alpha", BeliefStrength::Weak),
+                        ("beta", BeliefStrength::Moderate),
+                    ],
+                ),
+                agent(2, &[("alpha", BeliefStrength::Strong)]),
+                agent(3, &[]),
+            ],
+        );
+        let b = world(
+            &[("r1", 12), ("r3", 1)],
+            &[
+                ("alpha", "r1", 0.5 + 1e-12),
+                ("beta", "r1", 0.4),
+                ("gamma", "r2", 0.3),
+            ],
+            vec![
+                agent(4, &[]),
+                agent(2, &[("alpha", BeliefStrength::Strong)]),
+                agent(
+                    1,
+                    &[
+                        ("alpha", BeliefStrength::Strong),
+                        ("gamma", BeliefStrength::Weak),
+                    ],
+                ),
+            ],
+        );
+        let diff = diff_worlds(&a, &b);
+
+        let populations: Vec<_> = diff
+            .populations
+            .iter()
+            .map(|p| (p.region_id.as_str(), p.delta()))
+            .collect();
+        assert_eq!(populations, vec![("r1", 2), ("r2", -5), ("r3", 1)]);
+
+        // alpha moved by less than the epsilon.
+        assert_eq!(diff.intensities.len(), 1);
+        assert_eq!(diff.intensities[0].concept_key, "beta");
+        assert!((diff.intensities[0].delta() - 0.2).abs() < 1e-12);
+
+        assert_eq!(
+            diff.beliefs,
+            vec![AgentBeliefDiff {
+                agent_id: AgentId(1),
+                changes: vec![
+                    BeliefChange::StrengthChanged {
+                        concept_key: "alpha".to_string(),
+                        from: BeliefStrength::Weak,
+                        to: BeliefStrength::Strong,
+                    },
+                    BeliefChange::Removed {
+                        concept_key: "beta".to_string(),
+                        strength: BeliefStrength::Moderate,
+                    },
+                    BeliefChange::Added {
+                        concept_key: "gamma".to_string(),
+                        strength: BeliefStrength::Weak,
+                    },
+                ],
+            }]
+        );
+        assert_eq!(diff.agents_only_in_a, vec![AgentId(3)]);
+        assert_eq!(diff.agents_only_in_b, vec![AgentId(4)]);
+
+        assert_eq!(
+            diff.summary(),
+            DiffSummary {
+                population_changes: 3,
+                intensity_changes: 1,
+                beliefs_added: 1,
+                beliefs_removed: 1,
+                beliefs_changed: 1,
+                agents_only_in_a: 1,
+                agents_only_in_b: 1,
+            }
+        );
+
+        let json = serde_json::to_value(&diff).unwrap();
+        assert_eq!(json["agents_only_in_b"][0], serde_json::json!(4));
+        assert_eq!(
+            json["beliefs"][0]["changes"][1]["Removed"]["concept_key"],
+            "beta"
+        );
+    }
+
+    #[test]
+    fn custom_epsilon_hides_small_intensity_changes() {
+        let a = world(&[], &[("alpha", "r1", 0.5)], Vec::new());
+        let b = world(&[], &[("alpha", "r1", 0.55)], Vec::new());
+        assert_eq!(diff_worlds(&a, &b).intensities.len(), 1);
+        assert!(diff_worlds_with(&a, &b, 0.1).is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::BTreeMap;
+
+use crate::World;
+
+/// Parameters for spreading concept intensity across `World::region_neighbors`.
+#[derive(Clone, Debug)]
+pub struct DiffusionConfig {
+    /// Fraction of a region's intensity handed to its neighbors per unit time.
+    pub rate: f64,
+    /// Exponential decay rate applied to every field per unit time.
+    pub decay: f64,
+}
+
+impl Default for DiffusionConfig {
+    fn default() -> Self {
+        Self {
+            rate: 0.1,
+            decay: 0.0,
+        }
+    }
+}
+
+impl World {
+    /// Spread each concept's intensity to neighboring regions, then decay.
+    ///
+    /// Outflow is split evenly between neighbors, so with `decay == 0` the
+    /// total intensity per concept is conserved. Regions without neighbors
+    /// keep their intensity.
+    pub fn diffuse_concepts(&mut self, dt: f64, config: &DiffusionConfig) {
+        let share = (config.rate * dt).clamp(0.0, 1.0);
+        let retain = (-config.decay.max(0.0) * dt).exp();
+
+        let mut next: BTreeMap<(String, String), f64> = BTreeMap::new();
+        for ((concept_key, region_id), intensity) in &self.concept_fields {
+            let neighbors = self
+                .region_neighbors
+                .get(region_id)
+                .map(Vec::as_slice)
+                .unwrap_or(&[]);
+
+            let outflow = if neighbors.is_empty() {
+                0.0
+            } else {
+                intensity * share
+            };
+            *next
+                .entry((concept_key.clone(), region_id.clone()))
+                .or_insert(0.0) += intensity - outflow;
+
+            let per_neighbor = outflow / neighbors.len().max(1) as f64;
+            for neighbor in neighbors {
+                *next
+                    .entry((concept_key.clone(), neighbor.clone()))
+                    .or_insert(0.0) += per_neighbor;
+            }
+        }
+
+        for intensity in next.values_mut() {
+            *intensity *= retain;
+        }
+        self.concept_fields = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Environment;
+
+    fn line_world() -> World {
+        let mut world = World::default();
+        for (region_id, neighbors) in [("a", vec!["b"]), ("b", vec!["a", "c"]), ("c", vec!["b"])] {
+            world.region_populations.insert(region_id.to_string(), 0);
+            world.region_neighbors.insert(
+                region_id.to_string(),
+                neighbors.into_iter().map(str::to_string).collect(),
+            );
+        }
+        world
+            .concept_fields
+            .insert(("alpha".to_string(), "a".to_string()), 0.9);
+        world
+    }
+
+    fn total(world: &World) -> f64 {
+        world.concept_fields.values().sum()
+    }
+
+    #[test]
+    fn intensity_reaches_neighbors_and_mass_is_conserved() {
+        let mut world = line_world();
+        let config = DiffusionConfig::default();
+        for _ in 0..5 {
+            world.diffuse_concepts(1.0, &config);
+        }
+        assert!(world.get_concept_intensity("alpha", "b") > 0.0);
+        assert!(world.get_concept_intensity("alpha", "c") > 0.0);
+        assert!(world.get_concept_intensity("alpha", "a") < 0.9);
+        assert!((total(&world) - 0.9).abs() < 1e-12);
+    }
+
+    #[test]
+    fn decay_shrinks_total_mass() {
+        let mut world = line_world();
+        let config = DiffusionConfig {
+            rate: 0.1,
+            decay: 0.5,
+        };
+        world.diffuse_concepts(1.0, &config);
+        assert!((total(&world) - 0.9 * (-0.5f64).exp()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn step_world_diffuses_when_configured() {
+        let mut world = line_world();
+        world.diffusion = Some(DiffusionConfig::default());
+        crate::step_world(&mut world, &crate::ZoneRepoPolicyEngine::new(1.0), 1.0);
+        assert!((world.get_concept_intensity("alpha", "b") - 0.09).abs() < 1e-12);
+    }
+}
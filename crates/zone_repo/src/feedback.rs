@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use crate::World;
+
+/// Parameters for pulling concept fields toward local adoption.
+#[derive(Clone, Debug)]
+pub struct FeedbackConfig {
+    /// Fraction of the gap to the local belief share closed per tick.
+    pub gain: f64,
+    /// Fractional loss per tick where nobody holds the concept.
+    pub decay: f64,
+}
+
+impl Default for FeedbackConfig {
+    fn default() -> Self {
+        Self {
+            gain: 0.2,
+            decay: 0.1,
+        }
+    }
+}
+
+impl World {
+    /// Nudge each (concept, region) intensity toward the strength-weighted
+    /// share of local agents holding that belief; fields with no local
+    /// believers decay toward zero.
+    pub fn update_intensities_from_beliefs(&mut self, gain: f64, decay: f64) {
+        let mut residents: HashMap<&str, usize> = HashMap::new();
+        let mut held: HashMap<(String, String), f64> = HashMap::new();
+        for agent in &self.agents {
+            let region = agent.location.region_id.as_str();
+            *residents.entry(region).or_insert(0) += 1;
+            for (concept_key, belief) in &agent.beliefs {
+                *held
+                    .entry((concept_key.clone(), region.to_string()))
+                    .or_insert(0.0) += belief.strength.to_scalar();
+            }
+        }
+
+        for key in held.keys() {
+            self.concept_fields.entry(key.clone()).or_insert(0.0);
+        }
+
+        let gain = gain.clamp(0.0, 1.0);
+        let retain = (1.0 - decay).clamp(0.0, 1.0);
+        for (key, intensity) in self.concept_fields.iter_mut() {
+            match held.get(key) {
+                Some(weight) => {
+                    let share = weight / residents[key.1.as_str()] as f64;
+                    *intensity += gain * (share - *intensity);
+                }
+                None => *intensity *= retain,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monotone::MonotonePolicyWrapper;
+    use crate::{
+        step_world, AgentId, Belief, BeliefStrength, Environment, HumanAgent, Location,
+        ZoneRepoPolicyEngine,
+    };
+
+    #[test]
+    fn minority_belief_amplifies_over_ticks() {
+        let mut world = World::default();
+        world.region_populations.insert("r1".to_string(), 0);
+        world.feedback = Some(FeedbackConfig::default());
+        world
+            .concept_fields
+            .insert(("alpha".to_string(), "r1".to_string()), 0.05);
+        for id in 0..10 {
+            let mut agent = HumanAgent::new(AgentId(id), Location::new(0.0, 0.0, "r1"));
+            if id < 3 {
+                agent.beliefs.insert(
+                    "alpha".to_string(),
+                    Belief {
+                        key: "alpha".to_string(),
+                        strength: BeliefStrength::Strong,
+                    },
+                );
+            }
+            world.agents.push(agent);
+        }
+        // Believers keep their conviction while the field is still weak.
+        let policy = MonotonePolicyWrapper::new(ZoneRepoPolicyEngine::new(1.0));
+
+        let mut previous = world.get_concept_intensity("alpha", "r1");
+        for _ in 0..20 {
+            step_world(&mut world, &policy, 1.0);
+            let intensity = world.get_concept_intensity("alpha", "r1");
+            assert!(intensity > previous, "{intensity} after {previous}");
+            previous = intensity;
+        }
+        assert!(previous > 0.5);
+    }
+
+    #[test]
+    fn abandoned_concept_fades_to_zero() {
+        let mut world = World::default();
+        world
+            .concept_fields
+            .insert(("alpha".to_string(), "empty".to_string()), 0.8);
+        for _ in 0..200 {
+            world.update_intensities_from_beliefs(0.2, 0.1);
+        }
+        assert!(world.get_concept_intensity("alpha", "empty") < 1e-6);
+    }
+}
@@ -0,0 +1,289 @@
+//! Region assignment from GeoJSON boundaries. Enabled with the `geo` feature.
+//!
+//! Accepts a `FeatureCollection` whose features carry a `region_id` property
+//! and a `Polygon` or `MultiPolygon` geometry. The first ring of a polygon is
+//! its exterior, any further rings are holes.
+
+use serde_json::Value;
+
+use crate::loader::WorldLoadError;
+use crate::{AgentId, World};
+
+type Ring = Vec<(f64, f64)>;
+
+#[derive(Clone, Debug)]
+struct RegionShape {
+    region_id: String,
+    /// Every ring of every polygon; even-odd crossing handles holes.
+    rings: Vec<Ring>,
+    min: (f64, f64),
+    max: (f64, f64),
+}
+
+impl RegionShape {
+    fn contains(&self, x: f64, y: f64) -> bool {
+        if x < self.min.0 || x > self.max.0 || y < self.min.1 || y > self.max.1 {
+            return false;
+        }
+        let mut inside = false;
+        for ring in &self.rings {
+            if ring_crossings_odd(ring, x, y) {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+}
+
+/// Half-open ray casting: a point on an edge shared by two polygons is
+/// inside exactly one of them (the one to its right).
+fn ring_crossings_odd(ring: &[(f64, f64)], x: f64, y: f64) -> bool {
+    let mut odd = false;
+    let mut j = ring.len() - 1;
+    for i in 0..ring.len() {
+        let (xi, yi) = ring[i];
+        let (xj, yj) = ring[j];
+        if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+            odd = !odd;
+        }
+        j = i;
+    }
+    odd
+}
+
+/// Point-to-region lookup built from GeoJSON polygons.
+#[derive(Clone, Debug, Default)]
+pub struct RegionIndex {
+    shapes: Vec<RegionShape>,
+}
+
+fn malformed(at: &str, message: impl Into<String>) -> WorldLoadError {
+    WorldLoadError::Malformed {
+        at: at.to_string(),
+        message: message.into(),
+    }
+}
+
+fn parse_ring(at: &str, value: &Value) -> Result<Ring, WorldLoadError> {
+    let positions = value
+        .as_array()
+        .ok_or_else(|| malformed(at, "ring is not an array"))?;
+    let mut ring = Vec::with_capacity(positions.len());
+    for position in positions {
+        let coords = position.as_array().map(Vec::as_slice).unwrap_or(&[]);
+        match coords {
+            [x, y, ..] => match (x.as_f64(), y.as_f64()) {
+                (Some(x), Some(y)) => ring.push((x, y)),
+                _ => return Err(malformed(at, "non-numeric coordinate")),
+            },
+            _ => return Err(malformed(at, "position needs two coordinates")),
+        }
+    }
+    // GeoJSON rings repeat the first position at the end.
+    if ring.len() > 1 && ring.first() == ring.last() {
+        ring.pop();
+    }
+    if ring.len() < 3 {
+        return Err(malformed(at, "ring needs at least three distinct positions"));
+    }
+    Ok(ring)
+}
+
+fn parse_polygon(at: &str, value: &Value, rings: &mut Vec<Ring>) -> Result<(), WorldLoadError> {
+    let polygon = value
+        .as_array()
+        .ok_or_else(|| malformed(at, "polygon is not an array of rings"))?;
+    for ring in polygon {
+        rings.push(parse_ring(at, ring)?);
+    }
+    Ok(())
+}
+
+impl RegionIndex {
+    pub fn from_geojson(json: &str) -> Result<Self, WorldLoadError> {
+        let doc: Value =
+            serde_json::from_str(json).map_err(|e| WorldLoadError::Json(e.to_string()))?;
+        if doc.get("type").and_then(Value::as_str) != Some("FeatureCollection") {
+            return Err(malformed("geojson", "expected a FeatureCollection"));
+        }
+        let features = doc
+            .get("features")
+            .and_then(Value::as_array)
+            .ok_or_else(|| malformed("geojson", "missing features array"))?;
+
+        let mut shapes = Vec::with_capacity(features.len());
+        for (i, feature) in features.iter().enumerate() {
+            let at = format!("features[{i}]");
+            let region_id = feature
+                .pointer("/properties/region_id")
+                .and_then(Value::as_str)
+                .ok_or_else(|| malformed(&at, "missing string property region_id"))?;
+            let geometry = feature
+                .get("geometry")
+                .ok_or_else(|| malformed(&at, "missing geometry"))?;
+            let coordinates = geometry
+                .get("coordinates")
+                .ok_or_else(|| malformed(&at, "geometry has no coordinates"))?;
+
+            let mut rings = Vec::new();
+            match geometry.get("type").and_then(Value::as_str) {
+                Some("Polygon") => parse_polygon(&at, coordinates, &mut rings)?,
+                Some("MultiPolygon") => {
+                    let polygons = coordinates
+                        .as_array()
+                        .ok_or_else(|| malformed(&at, "MultiPolygon is not an array"))?;
+                    for polygon in polygons {
+                        parse_polygon(&at, polygon, &mut rings)?;
+                    }
+                }
+                other => {
+                    return Err(malformed(
+                        &at,
+                        format!("unsupported geometry type {:?}", other.unwrap_or("none")),
+                    ))
+                }
+            }
+
+            let mut min = (f64::INFINITY, f64::INFINITY);
+            let mut max = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+            for &(x, y) in rings.iter().flatten() {
+                min = (min.0.min(x), min.1.min(y));
+                max = (max.0.max(x), max.1.max(y));
+            }
+            shapes.push(RegionShape {
+                region_id: region_id.to_string(),
+                rings,
+                min,
+                max,
+            });
+        }
+        Ok(Self { shapes })
+    }
+
+    /// Region whose polygon contains the point. Overlapping polygons resolve
+    /// to the first one in the source document.
+    pub fn region_for_point(&self, x: f64, y: f64) -> Option<&str> {
+        self.shapes
+            .iter()
+            .find(|shape| shape.contains(x, y))
+            .map(|shape| shape.region_id.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.shapes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shapes.is_empty()
+    }
+}
+
+impl World {
+    /// Rewrite every agent's `region_id` from its coordinates. Agents outside
+    /// all polygons keep their current region and are returned.
+    pub fn assign_regions(&mut self, index: &RegionIndex) -> Vec<AgentId> {
+        let mut outside = Vec::new();
+        for agent in &mut self.agents {
+            match index.region_for_point(agent.location.x, agent.location.y) {
+                Some(region_id) => {
+                    if agent.location.region_id != region_id {
+                        agent.location.region_id = region_id.to_string();
+                    }
+                }
+                None => outside.push(agent.id.clone()),
+            }
+        }
+        outside
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HumanAgent, Location};
+
+    /// "west" is the unit square with a hole in the middle; "east" shares
+    /// its edge at x = 1.
+    const TWO_SQUARES: &str = r#"{
+        "type": "FeatureCollection",
+        "features": [
+            {
+                "type": "Feature",
+                "properties": {"region_id": "west"},
+                "geometry": {
+                    "type": "Polygon",
+                    "coordinates": [
+                        [[0, 0], [1, 0], [1, 1], [0, 1], [0, 0]],
+                        [[0.4, 0.4], [0.6, 0.4], [0.6, 0.6], [0.4, 0.6], [0.4, 0.4]]
+                    ]
+                }
+            },
+            {
+                "type": "Feature",
+                "properties": {"region_id": "east"},
+                "geometry": {
+                    "type": "MultiPolygon",
+                    "coordinates": [[[[1, 0], [2, 0], [2, 1], [1, 1], [1, 0]]]]
+                }
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn points_resolve_to_their_polygon() {
+        let index = RegionIndex::from_geojson(TWO_SQUARES).unwrap();
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.region_for_point(0.2, 0.2), Some("west"));
+        assert_eq!(index.region_for_point(1.5, 0.5), Some("east"));
+        assert_eq!(index.region_for_point(3.0, 0.5), None);
+    }
+
+    #[test]
+    fn hole_is_outside_its_polygon() {
+        let index = RegionIndex::from_geojson(TWO_SQUARES).unwrap();
+        assert_eq!(index.region_for_point(0.5, 0.5), None);
+        assert_eq!(index.region_for_point(0.3, 0.5), Some("west"));
+    }
+
+    #[test]
+    fn shared_edge_belongs_to_exactly_one_region() {
+        let index = RegionIndex::from_geojson(TWO_SQUARES).unwrap();
+        assert_eq!(index.region_for_point(1.0, 0.5), Some("east"));
+    }
+
+    #[test]
+    fn assign_regions_rewrites_agents_and_reports_strays() {
+        let index = RegionIndex::from_geojson(TWO_SQUARES).unwrap();
+        let mut world = World::default();
+        for (id, x) in [(1, 0.2), (2, 1.5), (3, 5.0)] {
+            world.agents.push(HumanAgent::new(
+                AgentId(id),
+                Location::new(x, 0.5, "unknown"),
+            ));
+        }
+        let outside = world.assign_regions(&index);
+        assert_eq!(outside, vec![AgentId(3)]);
+        let regions: Vec<_> = world
+            .agents
+            .iter()
+            .map(|a| a.location.region_id.as_str())
+            .collect();
+        assert_eq!(regions, vec!["west", "east", "unknown"]);
+    }
+
+    #[test]
+    fn malformed_features_are_rejected() {
+        let missing_id = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "properties": {}, "geometry": {"type": "Polygon", "coordinates": []}}
+        ]}"#;
+        assert!(matches!(
+            RegionIndex::from_geojson(missing_id),
+            Err(WorldLoadError::Malformed { at, .. }) if at == "features[0]"
+        ));
+        let point = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "properties": {"region_id": "p"},
+             "geometry": {"type": "Point", "coordinates": [0, 0]}}
+        ]}"#;
+        assert!(RegionIndex::from_geojson(point).is_err());
+    }
+}
@@ -0,0 +1,234 @@
+use std::collections::BTreeSet;
+
+use crate::observer::TransitionObserver;
+use crate::{
+    admit_transition, AgentId, BeliefStrength, PolicyEngine, TransitionProposal, World,
+};
+
+#[derive(Clone, Debug)]
+pub struct Household {
+    pub id: u64,
+    pub member_ids: Vec<AgentId>,
+}
+
+impl World {
+    /// Rebuild the `AgentId -> index` map from `agents`.
+    pub fn rebuild_agent_index(&mut self) {
+        self.agent_index = self
+            .agents
+            .iter()
+            .enumerate()
+            .map(|(i, a)| (a.id.clone(), i))
+            .collect();
+        let max = self.agents.iter().map(|a| a.id.0 + 1).max().unwrap_or(0);
+        self.next_free_agent_id = self.next_free_agent_id.max(max);
+    }
+
+    /// Position of an agent in `agents`, if the index entry is current.
+    pub fn index_of(&self, id: &AgentId) -> Option<usize> {
+        self.agent_index
+            .get(id)
+            .copied()
+            .filter(|&i| self.agents.get(i).map(|a| &a.id) == Some(id))
+    }
+
+    /// For each household, a concept held at `Strong` by a strict majority
+    /// of members is proposed at `Moderate` to members holding it below
+    /// `Moderate`, through the normal policy gate. Returns the number of
+    /// transitions applied.
+    pub fn propagate_within_households<P: PolicyEngine, O: TransitionObserver>(
+        &mut self,
+        policies: &P,
+        observer: &mut O,
+    ) -> usize {
+        if self.households.is_empty() {
+            return 0;
+        }
+        // One rebuild covers agents moved or pushed directly; members still
+        // missing after it are gone and skipped.
+        let stale = self.agents.len() != self.agent_index.len()
+            || self
+                .households
+                .iter()
+                .flat_map(|h| &h.member_ids)
+                .any(|id| self.index_of(id).is_none());
+        if stale {
+            self.rebuild_agent_index();
+        }
+
+        let households = std::mem::take(&mut self.households);
+        let mut applied = 0;
+        for household in &households {
+            let members: Vec<usize> = household
+                .member_ids
+                .iter()
+                .filter_map(|id| self.index_of(id))
+                .collect();
+            if members.is_empty() {
+                continue;
+            }
+
+            // Sorted so proposals happen in a stable order.
+            let concepts: BTreeSet<&String> = members
+                .iter()
+                .flat_map(|&i| self.agents[i].beliefs.keys())
+                .collect();
+            let majority: Vec<String> = concepts
+                .into_iter()
+                .filter(|key| {
+                    let strong = members
+                        .iter()
+                        .filter(|&&i| {
+                            self.agents[i]
+                                .beliefs
+                                .get(*key)
+                                .map(|b| b.strength.rank() == BeliefStrength::Strong.rank())
+                                .unwrap_or(false)
+                        })
+                        .count();
+                    strong * 2 > members.len()
+                })
+                .cloned()
+                .collect();
+
+            for concept_key in majority {
+                for &i in &members {
+                    let below = self.agents[i]
+                        .beliefs
+                        .get(&concept_key)
+                        .map(|b| b.strength < BeliefStrength::Moderate)
+                        .unwrap_or(true);
+                    if !below {
+                        continue;
+                    }
+                    let proposal = TransitionProposal {
+                        concept_intensity: self.agents[i].exposure(&*self, &concept_key),
+                        concept_key: concept_key.clone(),
+                        proposed_strength: BeliefStrength::Moderate,
+                    };
+                    let allowed = {
+                        let agent = &self.agents[i];
+                        let ctx = agent.context_for(&*self, &proposal);
+                        let verdict = policies.check_transition(&ctx);
+                        if verdict.forbidden {
+                            observer.on_transition_forbidden(&ctx, verdict.reason.as_deref());
+                            false
+                        } else {
+                            let fear = verdict
+                                .fear
+                                .unwrap_or_else(|| policies.evaluate_transition(&ctx));
+                            admit_transition(policies, observer, &ctx, &fear)
+                        }
+                    };
+                    if allowed {
+                        self.agents[i].apply_proposal(proposal);
+                        applied += 1;
+                    }
+                }
+            }
+        }
+        self.households = households;
+        applied
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Belief, HumanAgent, Location, ZoneRepoPolicyEngine};
+
+    /// `strong` members holding "alpha" at Strong, then `rest` without it.
+    fn household_world(strong: u64, rest: u64) -> World {
+        let mut world = World::default();
+        world.region_populations.insert("r1".to_string(), 0);
+        for id in 0..strong + rest {
+            let mut agent = HumanAgent::new(AgentId(id), Location::new(0.0, 0.0, "r1"));
+            if id < strong {
+                agent.beliefs.insert(
+                    "alpha".to_string(),
+                    Belief {
+                        key: "alpha".to_string(),
+                        strength: BeliefStrength::Strong,
+                    },
+                );
+            }
+            world.agents.push(agent);
+        }
+        world.households.push(Household {
+            id: 1,
+            member_ids: (0..strong + rest).map(AgentId).collect(),
+        });
+        world
+    }
+
+    #[test]
+    fn strong_majority_reaches_the_other_members_at_moderate() {
+        let mut world = household_world(2, 1);
+        let applied = world.propagate_within_households(&ZoneRepoPolicyEngine::new(1.0), &mut ());
+        assert_eq!(applied, 1);
+        let strength = &world.agents[2].beliefs["alpha"].strength;
+        assert_eq!(*strength, BeliefStrength::Moderate);
+        assert_eq!(
+            world.agents[0].beliefs["alpha"].strength,
+            BeliefStrength::Strong
+        );
+    }
+
+    #[test]
+    fn half_the_household_is_not_a_majority() {
+        let mut world = household_world(2, 2);
+        let applied = world.propagate_within_households(&ZoneRepoPolicyEngine::new(1.0), &mut ());
+        assert_eq!(applied, 0);
+        assert!(!world.agents[2].beliefs.contains_key("alpha"));
+    }
+
+    #[test]
+    fn policy_can_veto_inside_a_household() {
+        let mut world = household_world(2, 1);
+        world.region_populations.insert("r1".to_string(), 20_000);
+        world
+            .concept_fields
+            .insert(("alpha".to_string(), "r1".to_string()), 0.8);
+        let mut log = crate::decision_log::ZoneDecisionLog::default();
+        let applied = world.propagate_within_households(&ZoneRepoPolicyEngine::new(1.0), &mut log);
+        assert_eq!(applied, 0);
+        assert!(!world.agents[2].beliefs.contains_key("alpha"));
+        assert_eq!(log.forbidden().count(), 1);
+        assert_eq!(log.entries[0].agent_id, AgentId(2));
+    }
+
+    #[test]
+    fn members_are_found_after_agents_move_in_the_vector() {
+        let mut world = household_world(2, 1);
+        world.rebuild_agent_index();
+        world.agents.reverse();
+        let applied = world.propagate_within_households(&ZoneRepoPolicyEngine::new(1.0), &mut ());
+        assert_eq!(applied, 1);
+        assert_eq!(world.index_of(&AgentId(2)), Some(0));
+        assert!(world.agents[0].beliefs.contains_key("alpha"));
+    }
+
+    #[test]
+    fn removed_members_leave_their_household() {
+        let mut world = household_world(2, 2);
+        world.rebuild_agent_index();
+        world.remove_agent(&AgentId(3));
+        assert_eq!(
+            world.households[0].member_ids,
+            [AgentId(0), AgentId(1), AgentId(2)]
+        );
+        // Two of the three remaining members are now a majority.
+        let applied = world.propagate_within_households(&ZoneRepoPolicyEngine::new(1.0), &mut ());
+        assert_eq!(applied, 1);
+    }
+
+    #[test]
+    fn members_missing_from_agents_are_skipped() {
+        let mut world = household_world(2, 1);
+        world.households[0].member_ids.push(AgentId(99));
+        world.rebuild_agent_index();
+        let applied = world.propagate_within_households(&ZoneRepoPolicyEngine::new(1.0), &mut ());
+        assert_eq!(applied, 1);
+        assert_eq!(world.households[0].member_ids.len(), 4);
+    }
+}
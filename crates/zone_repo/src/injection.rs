@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::World;
+
+/// Time profile of an injection over `[start_time, start_time + duration)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InjectionShape {
+    /// Constant at peak for the whole window.
+    Step,
+    /// Starts at peak and falls linearly to zero at the end of the window.
+    Ramp,
+    /// Rises linearly to peak at mid-window, then falls back to zero.
+    Pulse,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Injection {
+    pub concept_key: String,
+    pub region_id: String,
+    pub start_time: f64,
+    pub duration: f64,
+    pub peak_intensity: f64,
+    pub shape: InjectionShape,
+}
+
+impl Injection {
+    /// Injected intensity at `time`, or `None` outside the window.
+    pub fn intensity_at(&self, time: f64) -> Option<f64> {
+        let elapsed = time - self.start_time;
+        if elapsed < 0.0 || elapsed >= self.duration {
+            return None;
+        }
+        let frac = elapsed / self.duration;
+        let scale = match self.shape {
+            InjectionShape::Step => 1.0,
+            InjectionShape::Ramp => 1.0 - frac,
+            InjectionShape::Pulse => 1.0 - (2.0 * frac - 1.0).abs(),
+        };
+        Some(self.peak_intensity * scale)
+    }
+}
+
+/// How overlapping injections on the same (concept, region) combine with
+/// each other and with the underlying field.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InjectionCombine {
+    /// Field is raised to the largest active injection.
+    #[default]
+    Max,
+    /// Active injections are added on top of the field.
+    Sum,
+}
+
+/// Campaign-style concept launches applied to `World::concept_fields`.
+///
+/// Injections are applied as an overlay: the amount added last tick is
+/// taken back before the next overlay is computed, so once every
+/// injection on a field has ended the field returns to its base value.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct InjectionSchedule {
+    pub injections: Vec<Injection>,
+    pub combine: InjectionCombine,
+    #[serde(with = "crate::checkpoint::pair_keyed")]
+    applied: BTreeMap<(String, String), f64>,
+}
+
+impl InjectionSchedule {
+    pub fn push(&mut self, injection: Injection) {
+        self.injections.push(injection);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.injections.is_empty()
+    }
+
+    /// Update `fields` for `time`, removing last tick's overlay first.
+    pub fn apply(&mut self, time: f64, fields: &mut BTreeMap<(String, String), f64>) {
+        for (key, delta) in std::mem::take(&mut self.applied) {
+            if let Some(value) = fields.get_mut(&key) {
+                *value = (*value - delta).max(0.0);
+            }
+        }
+
+        let mut active: BTreeMap<(String, String), f64> = BTreeMap::new();
+        for injection in &self.injections {
+            let Some(value) = injection.intensity_at(time) else {
+                continue;
+            };
+            let key = (injection.concept_key.clone(), injection.region_id.clone());
+            let slot = active.entry(key).or_insert(0.0);
+            *slot = match self.combine {
+                InjectionCombine::Max => slot.max(value),
+                InjectionCombine::Sum => *slot + value,
+            };
+        }
+
+        for (key, injected) in active {
+            let base = fields.entry(key.clone()).or_insert(0.0);
+            let delta = match self.combine {
+                InjectionCombine::Max => (injected - *base).max(0.0),
+                InjectionCombine::Sum => injected,
+            };
+            *base += delta;
+            self.applied.insert(key, delta);
+        }
+    }
+}
+
+impl World {
+    /// Apply `self.injections` for the current time.
+    pub fn apply_injections(&mut self) {
+        self.injections.apply(self.time, &mut self.concept_fields);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn injection(shape: InjectionShape, peak_intensity: f64) -> Injection {
+        Injection {
+            concept_key: "alpha".to_string(),
+            region_id: "r1".to_string(),
+            start_time: 10.0,
+            duration: 20.0,
+            peak_intensity,
+            shape,
+        }
+    }
+
+    fn key() -> (String, String) {
+        ("alpha".to_string(), "r1".to_string())
+    }
+
+    #[test]
+    fn ramp_interpolates_down_from_peak() {
+        let ramp = injection(InjectionShape::Ramp, 0.6);
+        assert_eq!(ramp.intensity_at(9.9), None);
+        assert_eq!(ramp.intensity_at(10.0), Some(0.6));
+        assert!((ramp.intensity_at(15.0).unwrap() - 0.45).abs() < 1e-12);
+        assert!((ramp.intensity_at(20.0).unwrap() - 0.3).abs() < 1e-12);
+        assert_eq!(ramp.intensity_at(30.0), None);
+    }
+
+    #[test]
+    fn pulse_peaks_mid_window() {
+        let pulse = injection(InjectionShape::Pulse, 0.8);
+        assert_eq!(pulse.intensity_at(10.0), Some(0.0));
+        assert_eq!(pulse.intensity_at(20.0), Some(0.8));
+        assert!((pulse.intensity_at(25.0).unwrap() - 0.4).abs() < 1e-12);
+    }
+
+    #[test]
+    fn field_returns_to_base_after_the_window() {
+        let mut schedule = InjectionSchedule::default();
+        schedule.push(injection(InjectionShape::Step, 0.6));
+        let mut fields = BTreeMap::from([(key(), 0.2)]);
+
+        schedule.apply(5.0, &mut fields);
+        assert_eq!(fields[&key()], 0.2);
+        schedule.apply(12.0, &mut fields);
+        assert_eq!(fields[&key()], 0.6);
+        schedule.apply(29.0, &mut fields);
+        assert_eq!(fields[&key()], 0.6);
+        schedule.apply(30.0, &mut fields);
+        assert!((fields[&key()] - 0.2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn overlapping_injections_combine_by_max_or_sum() {
+        let mut fields = BTreeMap::new();
+        let mut schedule = InjectionSchedule::default();
+        schedule.push(injection(InjectionShape::Step, 0.3));
+        schedule.push(injection(InjectionShape::Step, 0.5));
+        schedule.apply(12.0, &mut fields);
+        assert_eq!(fields[&key()], 0.5);
+
+        let mut schedule = InjectionSchedule {
+            combine: InjectionCombine::Sum,
+            ..InjectionSchedule::default()
+        };
+        schedule.push(injection(InjectionShape::Step, 0.3));
+        schedule.push(injection(InjectionShape::Step, 0.5));
+        let mut fields = BTreeMap::from([(key(), 0.1)]);
+        schedule.apply(12.0, &mut fields);
+        assert!((fields[&key()] - 0.9).abs() < 1e-12);
+        schedule.apply(40.0, &mut fields);
+        assert!((fields[&key()] - 0.1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn injections_apply_before_agents_in_step_world() {
+        let mut world = World::default();
+        world.region_populations.insert("r1".to_string(), 0);
+        world.agents.push(crate::HumanAgent::new(
+            crate::AgentId(1),
+            crate::Location::new(0.0, 0.0, "r1"),
+        ));
+        let mut launch = injection(InjectionShape::Step, 0.6);
+        launch.start_time = 1.0;
+        world.injections.push(launch);
+
+        crate::step_world(&mut world, &crate::ZoneRepoPolicyEngine::new(1.0), 1.0);
+
+        assert_eq!(world.agents[0].beliefs["alpha"].strength.to_scalar(), 0.6);
+    }
+}
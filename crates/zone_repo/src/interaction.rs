@@ -0,0 +1,230 @@
+//! Pairwise tension and reinforcement between concepts.
+
+use std::collections::HashMap;
+
+use crate::Belief;
+
+/// Symmetric `(concept_a, concept_b) -> [-1, 1]` weights. Negative entries
+/// mark incompatible concepts, positive ones concepts that reinforce each
+/// other; unlisted pairs are neutral.
+#[derive(Clone, Debug, Default)]
+pub struct BeliefInteractionMatrix {
+    entries: HashMap<(String, String), f64>,
+}
+
+fn pair_key(a: &str, b: &str) -> (String, String) {
+    if a <= b {
+        (a.to_string(), b.to_string())
+    } else {
+        (b.to_string(), a.to_string())
+    }
+}
+
+impl BeliefInteractionMatrix {
+    /// Set the interaction between two concepts, clamped to [-1, 1].
+    pub fn set(&mut self, a: &str, b: &str, weight: f64) {
+        self.entries.insert(pair_key(a, b), weight.clamp(-1.0, 1.0));
+    }
+
+    pub fn with(mut self, a: &str, b: &str, weight: f64) -> Self {
+        self.set(a, b, weight);
+        self
+    }
+
+    pub fn get(&self, a: &str, b: &str) -> f64 {
+        if a == b {
+            return 0.0;
+        }
+        self.entries.get(&pair_key(a, b)).copied().unwrap_or(0.0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// How an agent's other beliefs bear on one concept: each held belief
+/// contributes its interaction weight scaled by its strength. Both sides
+/// are capped at 1.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct InteractionSummary {
+    /// Pull away from the concept by incompatible beliefs, 0..1.
+    pub conflict: f64,
+    /// Push toward the concept by reinforcing beliefs, 0..1.
+    pub support: f64,
+}
+
+impl InteractionSummary {
+    /// Summarize `beliefs` against `concept_key`, with `weight(a, b)` giving
+    /// the pairwise interaction.
+    pub fn for_concept<'b>(
+        concept_key: &str,
+        beliefs: impl IntoIterator<Item = &'b Belief>,
+        weight: impl Fn(&str, &str) -> f64,
+    ) -> Self {
+        let mut summary = Self::default();
+        for belief in beliefs {
+            if belief.key == concept_key {
+                continue;
+            }
+            let w = weight(concept_key, &belief.key) * belief.strength.to_scalar();
+            if w < 0.0 {
+                summary.conflict -= w;
+            } else {
+                summary.support += w;
+            }
+        }
+        summary.conflict = summary.conflict.min(1.0);
+        summary.support = summary.support.min(1.0);
+        summary
+    }
+
+    /// Scale a proposed conviction: conflict pulls it toward zero, support
+    /// pushes it toward one.
+    pub fn adjust(&self, conviction: f64) -> f64 {
+        let damped = conviction * (1.0 - self.conflict);
+        (damped + (1.0 - damped) * self.support * damped).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        step_world, step_world_batched, AgentId, BeliefStrength, FearIndex, HumanAgent, Location,
+        PolicyContext, PolicyEngine, World, ZoneRepoPolicyEngine,
+    };
+
+    /// Agent 1 holds `beta` at `Strong`, agent 2 holds nothing; both sit in
+    /// a region where `alpha` is intense.
+    fn world(alpha_beta: f64) -> World {
+        let mut world = World::default();
+        world.region_populations.insert("r1".to_string(), 0);
+        world.belief_interactions.set("alpha", "beta", alpha_beta);
+        world
+            .concept_fields
+            .insert(("alpha".to_string(), "r1".to_string()), 0.6);
+        for id in [1, 2] {
+            let mut agent = HumanAgent::new(AgentId(id), Location::new(0.0, 0.0, "r1"));
+            if id == 1 {
+                agent.beliefs.insert(
+                    "beta".to_string(),
+                    Belief {
+                        key: "beta".to_string(),
+                        strength: BeliefStrength::Strong,
+                    },
+                );
+            }
+            world.agents.push(agent);
+        }
+        world
+    }
+
+    fn alpha_of(world: &World, agent: usize) -> f64 {
+        world.agents[agent].beliefs["alpha"].strength.to_scalar()
+    }
+
+    #[test]
+    fn matrix_is_symmetric_and_clamped() {
+        let matrix = BeliefInteractionMatrix::default()
+            .with("alpha", "beta", -1.5)
+            .with("gamma", "beta", 0.4);
+        assert_eq!(matrix.get("alpha", "beta"), -1.0);
+        assert_eq!(matrix.get("beta", "alpha"), -1.0);
+        assert_eq!(matrix.get("beta", "gamma"), 0.4);
+        assert_eq!(matrix.get("alpha", "gamma"), 0.0);
+        assert_eq!(matrix.get("alpha", "alpha"), 0.0);
+    }
+
+    #[test]
+    fn strong_incompatible_belief_resists_adoption() {
+        let mut world = world(-1.0);
+        world
+            .concept_fields
+            .insert(("alpha".to_string(), "r1".to_string()), 0.95);
+        step_world(&mut world, &ZoneRepoPolicyEngine::new(1.0), 1.0);
+        assert_eq!(
+            world.agents[1].beliefs["alpha"].strength.rank(),
+            BeliefStrength::Strong.rank()
+        );
+        assert!(alpha_of(&world, 0) < 0.1, "{}", alpha_of(&world, 0));
+        assert!(matches!(
+            world.agents[0].beliefs["beta"].strength,
+            BeliefStrength::Strong
+        ));
+    }
+
+    #[test]
+    fn reinforcing_belief_boosts_adoption() {
+        let mut world = world(1.0);
+        step_world(&mut world, &ZoneRepoPolicyEngine::new(1.0), 1.0);
+        assert!(alpha_of(&world, 0) > alpha_of(&world, 1));
+    }
+
+    /// Forbids conversions against a conflict above `max_conflict`.
+    struct NoForcedConversion {
+        max_conflict: f64,
+    }
+
+    impl PolicyEngine for NoForcedConversion {
+        fn is_transition_forbidden(&self, ctx: &PolicyContext) -> bool {
+            ctx.interaction.conflict > self.max_conflict
+        }
+
+        fn evaluate_transition(&self, _ctx: &PolicyContext) -> FearIndex {
+            FearIndex {
+                systemic_harm: 0.0,
+                regret: 0.0,
+                ecological_damage: 0.0,
+            }
+        }
+    }
+
+    #[test]
+    fn engines_see_the_interaction_summary() {
+        let mut world = world(-0.8);
+        step_world(&mut world, &NoForcedConversion { max_conflict: 0.5 }, 1.0);
+        assert!(!world.agents[0].beliefs.contains_key("alpha"));
+        assert!(world.agents[1].beliefs.contains_key("alpha"));
+
+        let summary = world.agents[0].interaction_summary(&world, "alpha");
+        assert_eq!(
+            summary,
+            InteractionSummary {
+                conflict: 0.8,
+                support: 0.0
+            }
+        );
+    }
+
+    #[test]
+    fn batched_stepping_judges_proposals_against_tick_start_beliefs() {
+        let world = || {
+            let mut world = World::default();
+            world.region_populations.insert("r1".to_string(), 0);
+            world.belief_interactions.set("alpha", "beta", -0.8);
+            for concept_key in ["alpha", "beta"] {
+                world
+                    .concept_fields
+                    .insert((concept_key.to_string(), "r1".to_string()), 0.6);
+            }
+            world
+                .agents
+                .push(HumanAgent::new(AgentId(1), Location::new(0.0, 0.0, "r1")));
+            world
+        };
+        let policy = NoForcedConversion { max_conflict: 0.3 };
+
+        // Sequentially, `alpha` is adopted first and then weighs on `beta`.
+        let mut sequential = world();
+        step_world(&mut sequential, &policy, 1.0);
+        assert!(sequential.agents[0].beliefs.contains_key("alpha"));
+        assert!(!sequential.agents[0].beliefs.contains_key("beta"));
+
+        // Batched, neither proposal sees the other.
+        let mut batched = world();
+        step_world_batched(&mut batched, &policy, 1.0);
+        assert!(batched.agents[0].beliefs.contains_key("alpha"));
+        assert!(batched.agents[0].beliefs.contains_key("beta"));
+    }
+}
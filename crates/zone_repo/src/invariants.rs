@@ -0,0 +1,231 @@
+//! Debug-mode check that forbidden transitions never reach belief maps.
+//! Enabled with the `strict-invariants` feature.
+
+use std::cell::RefCell;
+use std::fmt;
+
+use crate::{
+    AgentId, BeliefStrength, FearIndex, PolicyContext, PolicyEngine, PolicyVerdict,
+    TransitionKind, World,
+};
+
+/// A transition the inner engine refused.
+#[derive(Clone, Debug)]
+pub struct ForbiddenTransition {
+    pub agent_id: AgentId,
+    pub concept_key: String,
+    pub transition: TransitionKind,
+    pub previous: Option<BeliefStrength>,
+    pub proposed_strength: BeliefStrength,
+}
+
+#[derive(Clone, Debug)]
+pub struct InvariantViolation {
+    pub forbidden: ForbiddenTransition,
+    pub observed: Option<BeliefStrength>,
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "agent {} holds '{}' at {:?} although {:?} to {:?} was forbidden",
+            self.forbidden.agent_id.0,
+            self.forbidden.concept_key,
+            self.observed,
+            self.forbidden.transition,
+            self.forbidden.proposed_strength
+        )
+    }
+}
+
+impl std::error::Error for InvariantViolation {}
+
+/// Wraps an engine and remembers every transition it forbade.
+pub struct CheckedPolicyEngine<P> {
+    pub inner: P,
+    forbidden: RefCell<Vec<ForbiddenTransition>>,
+}
+
+impl<P: PolicyEngine> CheckedPolicyEngine<P> {
+    pub fn new(inner: P) -> Self {
+        Self {
+            inner,
+            forbidden: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn forbidden(&self) -> Vec<ForbiddenTransition> {
+        self.forbidden.borrow().clone()
+    }
+
+    /// Forget recorded decisions; call between ticks.
+    pub fn reset(&self) {
+        self.forbidden.borrow_mut().clear();
+    }
+
+    fn record(&self, ctx: &PolicyContext) {
+        self.forbidden.borrow_mut().push(ForbiddenTransition {
+            agent_id: ctx.agent_id.clone(),
+            concept_key: ctx.concept_key.to_string(),
+            transition: ctx.transition,
+            previous: ctx.current_belief.map(|b| b.strength.clone()),
+            proposed_strength: ctx.proposed_strength.clone(),
+        });
+    }
+}
+
+impl<P: PolicyEngine> PolicyEngine for CheckedPolicyEngine<P> {
+    fn is_transition_forbidden(
+        &self,
+        ctx: &PolicyContext,
+    ) -> bool {
+        let forbidden = self.inner.is_transition_forbidden(ctx);
+        if forbidden {
+            self.record(ctx);
+        }
+        forbidden
+    }
+
+    fn check_transition(
+        &self,
+        ctx: &PolicyContext,
+    ) -> PolicyVerdict {
+        let verdict = self.inner.check_transition(ctx);
+        if verdict.forbidden {
+            self.record(ctx);
+        }
+        verdict
+    }
+
+    fn evaluate_transition(
+        &self,
+        ctx: &PolicyContext,
+    ) -> FearIndex {
+        self.inner.evaluate_transition(ctx)
+    }
+
+    fn forbid_reason(
+        &self,
+        ctx: &PolicyContext,
+    ) -> Option<String> {
+        self.inner.forbid_reason(ctx)
+    }
+
+    fn transition_cost(
+        &self,
+        ctx: &PolicyContext,
+    ) -> f64 {
+        self.inner.transition_cost(ctx)
+    }
+
+    fn allows_downgrade(
+        &self,
+        ctx: &PolicyContext,
+    ) -> bool {
+        self.inner.allows_downgrade(ctx)
+    }
+}
+
+/// Check that no transition recorded by `engine` shows up in `world`: a
+/// forbidden change must leave the belief as it was, unless it already
+/// matched the proposal.
+pub fn verify_world_respects_decisions<P: PolicyEngine>(
+    world: &World,
+    engine: &CheckedPolicyEngine<P>,
+) -> Result<(), InvariantViolation> {
+    for forbidden in engine.forbidden.borrow().iter() {
+        let Some(agent) = world.agents.iter().find(|a| a.id == forbidden.agent_id) else {
+            continue;
+        };
+        let observed = agent
+            .beliefs
+            .get(&forbidden.concept_key)
+            .map(|b| &b.strength);
+        let target = match forbidden.transition {
+            TransitionKind::Remove => None,
+            _ => Some(&forbidden.proposed_strength),
+        };
+        let unchanged = observed == forbidden.previous.as_ref();
+        let was_target = forbidden.previous.as_ref() == target;
+        if !unchanged && !was_target && observed == target {
+            return Err(InvariantViolation {
+                forbidden: forbidden.clone(),
+                observed: observed.cloned(),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    use super::*;
+    use crate::{step_world, HumanAgent, Location, ZoneRepoPolicyEngine};
+
+    fn crowded_world() -> World {
+        let mut world = World::default();
+        world.region_populations.insert("calm".to_string(), 0);
+        world
+            .region_populations
+            .insert("crowded".to_string(), 20_000);
+        for (id, region_id) in [(1, "calm"), (2, "crowded")] {
+            world.agents.push(HumanAgent::new(
+                AgentId(id),
+                Location::new(0.0, 0.0, region_id),
+            ));
+        }
+        for region_id in ["calm", "crowded"] {
+            world
+                .concept_fields
+                .insert(("alpha".to_string(), region_id.to_string()), 0.8);
+        }
+        world
+    }
+
+    /// An agent step that asks the engine and then applies every proposal
+    /// regardless of the verdict.
+    fn misbehaving_step<P: PolicyEngine>(world: &mut World, engine: &P) {
+        let mut agents = std::mem::take(&mut world.agents);
+        let mut rng = ChaCha12Rng::seed_from_u64(0);
+        for agent in &mut agents {
+            for proposal in agent.propose_transitions(&*world, &mut rng) {
+                let _ignored = engine.check_transition(&agent.context_for(&*world, &proposal));
+                agent.apply_proposal(proposal);
+            }
+        }
+        world.agents = agents;
+    }
+
+    #[test]
+    fn well_behaved_step_respects_decisions() {
+        let mut world = crowded_world();
+        let engine = CheckedPolicyEngine::new(ZoneRepoPolicyEngine::new(1.0));
+        step_world(&mut world, &engine, 1.0);
+
+        let forbidden = engine.forbidden();
+        assert_eq!(forbidden.len(), 1);
+        assert_eq!(forbidden[0].agent_id, AgentId(2));
+        assert_eq!(forbidden[0].transition, TransitionKind::Adopt);
+        assert!(verify_world_respects_decisions(&world, &engine).is_ok());
+
+        engine.reset();
+        assert!(engine.forbidden().is_empty());
+    }
+
+    #[test]
+    fn misbehaving_agent_is_caught() {
+        let mut world = crowded_world();
+        let engine = CheckedPolicyEngine::new(ZoneRepoPolicyEngine::new(1.0));
+        misbehaving_step(&mut world, &engine);
+
+        let violation = verify_world_respects_decisions(&world, &engine).unwrap_err();
+        assert_eq!(violation.forbidden.agent_id, AgentId(2));
+        assert_eq!(violation.forbidden.concept_key, "alpha");
+        assert!(violation.observed.is_some());
+        assert!(violation.to_string().contains("was forbidden"));
+    }
+}
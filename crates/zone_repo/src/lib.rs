@@ -1,43 +1,305 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+
+pub mod budget;
+pub mod builder;
+pub mod checkpoint;
+pub mod composite;
+pub mod decision_log;
+pub mod diff;
+pub mod diffusion;
+pub mod feedback;
+#[cfg(feature = "geo")]
+pub mod geo;
+pub mod household;
+pub mod injection;
+pub mod interaction;
+#[cfg(feature = "strict-invariants")]
+pub mod invariants;
+pub mod loader;
 pub mod lua_policy;
+pub mod monotone;
+#[cfg(feature = "neuromorphic")]
+pub mod neuromorphic;
+pub mod observer;
+pub mod population;
+pub mod report;
+pub mod snapshot;
+#[cfg(feature = "sovereign-neuro")]
+pub mod sovereign;
+pub mod spatial;
+#[cfg(feature = "trace")]
+pub mod trace;
+pub mod validation;
+
+pub use diff::{diff_worlds, WorldDiff};
+
+use budget::{BudgetedObserver, ResourceBudget};
+use decision_log::ZoneDecisionLog;
+use diffusion::DiffusionConfig;
+use feedback::FeedbackConfig;
+use household::Household;
+use injection::InjectionSchedule;
+use interaction::{BeliefInteractionMatrix, InteractionSummary};
+use observer::TransitionObserver;
+use population::{PendingPopulationChanges, PopulationSchedule};
+use report::TickReport;
+use spatial::ConceptSources;
+use validation::{IntensityBounds, IntensityClamp};
 
 // ---------- Core domain types ----------
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct AgentId(pub u64);
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Location {
     pub x: f64,
     pub y: f64,
     pub region_id: String, // neighborhood, city, etc.
+    /// Workplace or other region the agent spends part of its time in.
+    #[serde(default)]
+    pub secondary_region: Option<String>,
+    /// Share of regional exposure drawn from `secondary_region`.
+    #[serde(default = "Location::default_secondary_weight")]
+    pub secondary_weight: f64,
 }
 
-#[derive(Clone, Debug)]
+impl Location {
+    pub const DEFAULT_SECONDARY_WEIGHT: f64 = 0.3;
+
+    pub fn new(x: f64, y: f64, region_id: &str) -> Self {
+        Self {
+            x,
+            y,
+            region_id: region_id.to_string(),
+            secondary_region: None,
+            secondary_weight: Self::DEFAULT_SECONDARY_WEIGHT,
+        }
+    }
+
+    /// Commute to `region_id` with the default 0.7 home / 0.3 secondary split.
+    pub fn with_secondary(mut self, region_id: &str) -> Self {
+        self.secondary_region = Some(region_id.to_string());
+        self
+    }
+
+    pub fn with_secondary_weight(mut self, weight: f64) -> Self {
+        self.secondary_weight = weight.clamp(0.0, 1.0);
+        self
+    }
+
+    fn default_secondary_weight() -> f64 {
+        Self::DEFAULT_SECONDARY_WEIGHT
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum BeliefStrength {
     Weak,
     Moderate,
     Strong,
+    /// Continuous conviction in 0..1.
+    Graded(f64),
 }
 
+// Strengths order by level (`rank`), then by scalar conviction, so
+// Weak < Moderate < Strong and graded values slot in between them. Distinct
+// variants with the same conviction (`Graded(1.0)` and `Strong`) are told
+// apart by variant, discrete first, so equality stays structural.
+impl PartialEq for BeliefStrength {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for BeliefStrength {}
+
+impl PartialOrd for BeliefStrength {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BeliefStrength {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.rank()
+            .cmp(&other.rank())
+            .then_with(|| self.to_scalar().total_cmp(&other.to_scalar()))
+            .then_with(|| self.variant_index().cmp(&other.variant_index()))
+            .then_with(|| match (self, other) {
+                (BeliefStrength::Graded(a), BeliefStrength::Graded(b)) => a.total_cmp(b),
+                _ => std::cmp::Ordering::Equal,
+            })
+    }
+}
+
+/// Scalar cut-offs used when quantizing a graded strength into levels.
 #[derive(Clone, Debug)]
+pub struct BeliefThresholds {
+    pub moderate: f64,
+    pub strong: f64,
+}
+
+impl Default for BeliefThresholds {
+    fn default() -> Self {
+        Self {
+            moderate: 0.4,
+            strong: 0.8,
+        }
+    }
+}
+
+impl BeliefStrength {
+    /// Scalar conviction in 0..1; discrete levels map to fixed anchors.
+    pub fn to_scalar(&self) -> f64 {
+        match self {
+            BeliefStrength::Weak => 0.2,
+            BeliefStrength::Moderate => 0.6,
+            BeliefStrength::Strong => 1.0,
+            BeliefStrength::Graded(v) => v.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Quantize a scalar into Weak/Moderate/Strong using the default thresholds.
+    pub fn from_scalar(value: f64) -> Self {
+        Self::from_scalar_with(value, &BeliefThresholds::default())
+    }
+
+    pub fn from_scalar_with(value: f64, thresholds: &BeliefThresholds) -> Self {
+        if value > thresholds.strong {
+            BeliefStrength::Strong
+        } else if value > thresholds.moderate {
+            BeliefStrength::Moderate
+        } else {
+            BeliefStrength::Weak
+        }
+    }
+
+    /// Ordinal level: Weak = 0, Moderate = 1, Strong = 2. Graded values
+    /// take the rank of their quantized level.
+    pub fn rank(&self) -> u8 {
+        match self {
+            BeliefStrength::Weak => 0,
+            BeliefStrength::Moderate => 1,
+            BeliefStrength::Strong => 2,
+            BeliefStrength::Graded(v) => BeliefStrength::from_scalar(*v).rank(),
+        }
+    }
+
+    fn variant_index(&self) -> u8 {
+        match self {
+            BeliefStrength::Weak => 0,
+            BeliefStrength::Moderate => 1,
+            BeliefStrength::Strong => 2,
+            BeliefStrength::Graded(_) => 3,
+        }
+    }
+
+    /// Discrete level name, quantizing graded values with the default thresholds.
+    pub fn label(&self) -> &'static str {
+        match self {
+            BeliefStrength::Weak => "Weak",
+            BeliefStrength::Moderate => "Moderate",
+            BeliefStrength::Strong => "Strong",
+            BeliefStrength::Graded(v) => BeliefStrength::from_scalar(*v).label(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Belief {
     pub key: String,          // e.g., "new_culture_X"
     pub strength: BeliefStrength,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct FearIndex {
     pub systemic_harm: f64,
     pub regret: f64,
     pub ecological_damage: f64,
 }
 
+/// Relative importance of each FearIndex component.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FearWeights {
+    pub systemic_harm: f64,
+    pub regret: f64,
+    pub ecological_damage: f64,
+}
+
+impl Default for FearWeights {
+    fn default() -> Self {
+        Self {
+            systemic_harm: 1.0,
+            regret: 1.0,
+            ecological_damage: 1.0,
+        }
+    }
+}
+
 impl FearIndex {
     pub fn total(&self) -> f64 {
         self.systemic_harm + self.regret + self.ecological_damage
     }
+
+    pub fn weighted_total(&self, weights: &FearWeights) -> f64 {
+        self.systemic_harm * weights.systemic_harm
+            + self.regret * weights.regret
+            + self.ecological_damage * weights.ecological_damage
+    }
+
+    /// Each component divided by its maximum and clamped to 0..1. A zero
+    /// (or negative) maximum maps any positive value to 1 and the rest to 0.
+    pub fn normalized(&self, maxes: &FearIndex) -> FearIndex {
+        fn norm(value: f64, max: f64) -> f64 {
+            if max > 0.0 {
+                (value / max).clamp(0.0, 1.0)
+            } else if value > 0.0 {
+                1.0
+            } else {
+                0.0
+            }
+        }
+        FearIndex {
+            systemic_harm: norm(self.systemic_harm, maxes.systemic_harm),
+            regret: norm(self.regret, maxes.regret),
+            ecological_damage: norm(self.ecological_damage, maxes.ecological_damage),
+        }
+    }
+
+    /// Name of the largest component; ties resolve in field order.
+    pub fn max_component(&self) -> &'static str {
+        let mut best = ("systemic_harm", self.systemic_harm);
+        for candidate in [
+            ("regret", self.regret),
+            ("ecological_damage", self.ecological_damage),
+        ] {
+            if candidate.1 > best.1 {
+                best = candidate;
+            }
+        }
+        best.0
+    }
+
+    pub fn add(&self, other: &FearIndex) -> FearIndex {
+        FearIndex {
+            systemic_harm: self.systemic_harm + other.systemic_harm,
+            regret: self.regret + other.regret,
+            ecological_damage: self.ecological_damage + other.ecological_damage,
+        }
+    }
+
+    pub fn scale(&self, factor: f64) -> FearIndex {
+        FearIndex {
+            systemic_harm: self.systemic_harm * factor,
+            regret: self.regret * factor,
+            ecological_damage: self.ecological_damage * factor,
+        }
+    }
 }
 
 // ---------- Traits for simulation ----------
@@ -45,23 +307,68 @@ impl FearIndex {
 pub trait Agent {
     fn id(&self) -> AgentId;
     fn location(&self) -> &Location;
-    fn beliefs(&self) -> &HashMap<String, Belief>;
-    fn beliefs_mut(&mut self) -> &mut HashMap<String, Belief>;
+    fn beliefs(&self) -> &BTreeMap<String, Belief>;
+    fn beliefs_mut(&mut self) -> &mut BTreeMap<String, Belief>;
 
     /// Called once per tick to let the agent update its state
     /// based on environment and policies.
-    fn step<E: Environment, P: PolicyEngine>(
+    fn step<E: Environment, P: PolicyEngine, R: rand::Rng>(
         &mut self,
         env: &E,
         policies: &P,
         dt: f64,
+        rng: &mut R,
     );
+
+    /// Like `step`, but reports each applied or forbidden transition to
+    /// `observer`. Agents that don't report fall back to plain `step`.
+    fn step_observed<E: Environment, P: PolicyEngine, R: rand::Rng, O: TransitionObserver>(
+        &mut self,
+        env: &E,
+        policies: &P,
+        dt: f64,
+        rng: &mut R,
+        observer: &mut O,
+    ) {
+        let _ = observer;
+        self.step(env, policies, dt, rng);
+    }
 }
 
 pub trait Environment {
     fn get_time(&self) -> f64;
     fn get_region_population(&self, region_id: &str) -> usize;
     fn get_concept_intensity(&self, concept_key: &str, region_id: &str) -> f64;
+
+    /// Concept keys with a field entry in the given region.
+    /// Defaults to none so existing environments keep compiling.
+    fn concepts_in_region(&self, _region_id: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Point-source intensity of a concept at a coordinate.
+    /// Defaults to zero for environments without spatial sources.
+    fn get_local_intensity(&self, _concept_key: &str, _x: f64, _y: f64) -> f64 {
+        0.0
+    }
+
+    /// Concept keys with a point source reaching the coordinate.
+    /// Defaults to none for environments without spatial sources.
+    fn concepts_near(&self, _x: f64, _y: f64) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Saturation capacity of a region, if it has one.
+    /// Defaults to unlimited.
+    fn get_region_capacity(&self, _region_id: &str) -> Option<usize> {
+        None
+    }
+
+    /// Interaction weight between two concepts in [-1, 1].
+    /// Defaults to neutral.
+    fn belief_interaction(&self, _concept_a: &str, _concept_b: &str) -> f64 {
+        0.0
+    }
 }
 
 pub trait PolicyEngine {
@@ -76,175 +383,1186 @@ pub trait PolicyEngine {
         &self,
         context: &PolicyContext,
     ) -> FearIndex;
+
+    /// Human-readable explanation for a forbidden transition, if the
+    /// engine can give one.
+    fn forbid_reason(
+        &self,
+        _context: &PolicyContext,
+    ) -> Option<String> {
+        None
+    }
+
+    /// Decide a transition in one call. The default combines
+    /// `is_transition_forbidden`, `forbid_reason` and `evaluate_transition`;
+    /// engines that learn all three at once should override it.
+    fn check_transition(
+        &self,
+        context: &PolicyContext,
+    ) -> PolicyVerdict {
+        if self.is_transition_forbidden(context) {
+            PolicyVerdict::forbid(self.forbid_reason(context))
+        } else {
+            PolicyVerdict::allow(self.evaluate_transition(context))
+        }
+    }
+
+    /// Decide a whole tick's worth of contexts at once, returning
+    /// `(forbidden, fear)` per context. Forbidden contexts are not scored and
+    /// carry a zero FearIndex. Engines with a costly call boundary (Lua,
+    /// remote) should override this with a single round trip.
+    fn evaluate_batch(
+        &self,
+        contexts: &[PolicyContext],
+    ) -> Vec<(bool, FearIndex)> {
+        contexts
+            .iter()
+            .map(|ctx| {
+                if self.is_transition_forbidden(ctx) {
+                    (true, FearIndex::default())
+                } else {
+                    (false, self.evaluate_transition(ctx))
+                }
+            })
+            .collect()
+    }
+
+    /// Resource cost of applying a transition (energy, staff time, ...),
+    /// checked against `World::resource_budget`. Defaults to free.
+    fn transition_cost(
+        &self,
+        _context: &PolicyContext,
+    ) -> f64 {
+        0.0
+    }
+
+    /// Whether this engine explicitly permits lowering a belief; consulted
+    /// by `MonotonePolicyWrapper`.
+    fn allows_downgrade(
+        &self,
+        _context: &PolicyContext,
+    ) -> bool {
+        false
+    }
+}
+
+/// Outcome of `PolicyEngine::check_transition`.
+#[derive(Clone, Debug, Default)]
+pub struct PolicyVerdict {
+    pub forbidden: bool,
+    pub reason: Option<String>,
+    /// Fear of an allowed transition, if the engine scored it.
+    pub fear: Option<FearIndex>,
+}
+
+impl PolicyVerdict {
+    pub fn allow(fear: FearIndex) -> Self {
+        Self {
+            forbidden: false,
+            reason: None,
+            fear: Some(fear),
+        }
+    }
+
+    pub fn forbid(reason: Option<String>) -> Self {
+        Self {
+            forbidden: true,
+            reason,
+            fear: None,
+        }
+    }
 }
 
 // ---------- Policy context ----------
 
+/// Direction of a proposed belief change.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransitionKind {
+    Adopt,
+    Strengthen,
+    Weaken,
+    Remove,
+}
+
+impl TransitionKind {
+    /// Classify a change from `current` to `proposed` (an unchanged value
+    /// counts as `Strengthen`).
+    pub fn between(current: Option<&BeliefStrength>, proposed: &BeliefStrength) -> Self {
+        match current {
+            None => TransitionKind::Adopt,
+            Some(c) if proposed < c => TransitionKind::Weaken,
+            Some(_) => TransitionKind::Strengthen,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct PolicyContext<'a> {
     pub agent_id: AgentId,
+    /// Region the transition is attributed to; for commuters this may be
+    /// their secondary region.
     pub region_id: &'a str,
     pub concept_key: &'a str,
     pub current_belief: Option<&'a Belief>,
     pub proposed_strength: BeliefStrength,
+    pub transition: TransitionKind,
     pub env_time: f64,
     pub region_population: usize,
+    pub region_capacity: Option<usize>,
     pub concept_intensity: f64,
+    /// The agent's other beliefs as they bear on this concept.
+    pub interaction: InteractionSummary,
 }
 
 // ---------- Concrete minimal types ----------
 
+/// A belief change an agent wants to make this tick, before policy review.
 #[derive(Clone, Debug)]
+pub struct TransitionProposal {
+    pub concept_key: String,
+    pub proposed_strength: BeliefStrength,
+    pub concept_intensity: f64,
+}
+
+/// Logistic sampling of proposed conviction around a concept's intensity.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AdoptionNoise {
+    pub steepness: f64, // higher = closer to the deterministic curve
+    pub midpoint: f64,  // intensity at which the median conviction is 0.5
+}
+
+impl Default for AdoptionNoise {
+    fn default() -> Self {
+        Self {
+            steepness: 8.0,
+            midpoint: 0.5,
+        }
+    }
+}
+
+impl AdoptionNoise {
+    /// Draw a conviction in 0..1: logistic in intensity, with logistic noise.
+    pub fn sample<R: rand::Rng>(&self, intensity: f64, rng: &mut R) -> f64 {
+        let u: f64 = rng.gen_range(f64::EPSILON..1.0);
+        let z = self.steepness * (intensity - self.midpoint) + (u / (1.0 - u)).ln();
+        1.0 / (1.0 + (-z).exp())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct HumanAgent {
     pub id: AgentId,
     pub location: Location,
-    pub beliefs: HashMap<String, Belief>,
+    pub beliefs: BTreeMap<String, Belief>,
+    pub adoption_noise: Option<AdoptionNoise>, // None = deterministic adoption
 }
 
-impl Agent for HumanAgent {
-    fn id(&self) -> AgentId {
-        self.id.clone()
+impl HumanAgent {
+    pub fn new(id: AgentId, location: Location) -> Self {
+        Self {
+            id,
+            location,
+            beliefs: BTreeMap::new(),
+            adoption_noise: None,
+        }
     }
 
-    fn location(&self) -> &Location {
-        &self.location
+    /// Home and secondary region intensities, each already scaled by its
+    /// share of the agent's time.
+    fn regional_exposures<E: Environment>(&self, env: &E, concept_key: &str) -> (f64, f64) {
+        let in_region = |region_id: &str| {
+            env.get_concept_intensity(concept_key, region_id) * saturation(env, region_id)
+        };
+        let home = in_region(&self.location.region_id);
+        match &self.location.secondary_region {
+            Some(secondary) => {
+                let weight = self.location.secondary_weight;
+                ((1.0 - weight) * home, weight * in_region(secondary))
+            }
+            None => (home, 0.0),
+        }
     }
 
-    fn beliefs(&self) -> &HashMap<String, Belief> {
-        &self.beliefs
+    /// Region a transition on `concept_key` is attributed to: the secondary
+    /// region when it contributes more of the agent's exposure, else home.
+    pub fn attributed_region<E: Environment>(&self, env: &E, concept_key: &str) -> &str {
+        match &self.location.secondary_region {
+            Some(secondary) => {
+                let (home, away) = self.regional_exposures(env, concept_key);
+                if away > home {
+                    secondary
+                } else {
+                    &self.location.region_id
+                }
+            }
+            None => &self.location.region_id,
+        }
     }
 
-    fn beliefs_mut(&mut self) -> &mut HashMap<String, Belief> {
-        &mut self.beliefs
+    /// Regional intensity (home and secondary blended) combined with nearby
+    /// point sources; either exposure can carry the concept, and this
+    /// reduces to the regional value when there are no sources.
+    fn exposure<E: Environment>(&self, env: &E, concept_key: &str) -> f64 {
+        let (home, away) = self.regional_exposures(env, concept_key);
+        let regional = home + away;
+        let local = env
+            .get_local_intensity(concept_key, self.location.x, self.location.y)
+            .clamp(0.0, 1.0);
+        1.0 - (1.0 - regional) * (1.0 - local)
     }
 
-    fn step<E: Environment, P: PolicyEngine>(
-        &mut self,
+    /// Tension and reinforcement from the agent's other beliefs on `concept_key`.
+    pub fn interaction_summary<E: Environment>(
+        &self,
         env: &E,
-        policies: &P,
-        dt: f64,
-    ) {
-        let _dt = dt;
+        concept_key: &str,
+    ) -> InteractionSummary {
+        InteractionSummary::for_concept(concept_key, self.beliefs.values(), |a, b| {
+            env.belief_interaction(a, b)
+        })
+    }
 
-        // Example: consider adopting or strengthening a belief in "new_concept"
-        let concept_key = "new_concept";
-        let region_population = env.get_region_population(&self.location.region_id);
-        let intensity = env.get_concept_intensity(concept_key, &self.location.region_id);
+    /// One proposal per concept present in the agent's home or secondary
+    /// region or reaching it from a point source, in sorted order. Draws
+    /// from `rng` only when the agent has adoption noise.
+    pub fn propose_transitions<E: Environment, R: rand::Rng>(
+        &self,
+        env: &E,
+        rng: &mut R,
+    ) -> Vec<TransitionProposal> {
+        let mut concepts = env.concepts_in_region(&self.location.region_id);
+        if let Some(secondary) = &self.location.secondary_region {
+            concepts.extend(env.concepts_in_region(secondary));
+        }
+        concepts.extend(env.concepts_near(self.location.x, self.location.y));
+        concepts.sort();
+        concepts.dedup();
 
-        // Proposed new strength based on intensity (very simplistic)
-        let proposed_strength = if intensity > 0.8 {
-            BeliefStrength::Strong
-        } else if intensity > 0.4 {
-            BeliefStrength::Moderate
-        } else {
-            BeliefStrength::Weak
-        };
+        let mut proposals = Vec::new();
+        for concept_key in concepts {
+            let intensity = self.exposure(env, &concept_key);
+            if intensity == 0.0 {
+                continue;
+            }
 
-        let current_belief = self.beliefs.get(concept_key);
+            // Proposed conviction tracks intensity smoothly, optionally sampled.
+            let proposed_value = match &self.adoption_noise {
+                Some(noise) => noise.sample(intensity, rng),
+                None => intensity.clamp(0.0, 1.0),
+            };
+            // Held beliefs resist incompatible concepts and amplify allied ones.
+            let proposed_value = self
+                .interaction_summary(env, &concept_key)
+                .adjust(proposed_value);
+            proposals.push(TransitionProposal {
+                concept_key,
+                proposed_strength: BeliefStrength::Graded(proposed_value),
+                concept_intensity: intensity,
+            });
+        }
+        proposals
+    }
+
+    /// Policy context for `proposal` against the agent's current beliefs.
+    pub fn context_for<'a, E: Environment>(
+        &'a self,
+        env: &E,
+        proposal: &'a TransitionProposal,
+    ) -> PolicyContext<'a> {
+        let current_belief = self.beliefs.get(&proposal.concept_key);
+        let region_id = self.attributed_region(env, &proposal.concept_key);
+        PolicyContext {
+            agent_id: self.id.clone(),
+            region_id,
+            concept_key: &proposal.concept_key,
+            current_belief,
+            transition: TransitionKind::between(
+                current_belief.map(|b| &b.strength),
+                &proposal.proposed_strength,
+            ),
+            proposed_strength: proposal.proposed_strength.clone(),
+            env_time: env.get_time(),
+            region_population: env.get_region_population(region_id),
+            region_capacity: env.get_region_capacity(region_id),
+            concept_intensity: proposal.concept_intensity,
+            interaction: self.interaction_summary(env, &proposal.concept_key),
+        }
+    }
+
+    /// Write an approved proposal into the belief map.
+    pub fn apply_proposal(&mut self, proposal: TransitionProposal) {
+        self.beliefs.insert(
+            proposal.concept_key.clone(),
+            Belief {
+                key: proposal.concept_key,
+                strength: proposal.proposed_strength,
+            },
+        );
+    }
 
+    /// Set a belief from outside the normal step, subject to the policy gate.
+    /// Returns the transition's fear index if it was applied.
+    pub fn try_set_belief<E: Environment, P: PolicyEngine>(
+        &mut self,
+        env: &E,
+        policies: &P,
+        concept_key: &str,
+        strength: BeliefStrength,
+    ) -> Option<FearIndex> {
+        let current_belief = self.beliefs.get(concept_key);
+        let region_id = self.attributed_region(env, concept_key);
         let ctx = PolicyContext {
             agent_id: self.id.clone(),
-            region_id: &self.location.region_id,
+            region_id,
             concept_key,
             current_belief,
-            proposed_strength: proposed_strength.clone(),
+            transition: TransitionKind::between(current_belief.map(|b| &b.strength), &strength),
+            proposed_strength: strength.clone(),
             env_time: env.get_time(),
-            region_population,
-            concept_intensity: intensity,
+            region_population: env.get_region_population(region_id),
+            region_capacity: env.get_region_capacity(region_id),
+            concept_intensity: self.exposure(env, concept_key),
+            interaction: self.interaction_summary(env, concept_key),
         };
-
-        // Check hard constraints
-        if policies.is_transition_forbidden(&ctx) {
-            return;
+        let verdict = policies.check_transition(&ctx);
+        if verdict.forbidden {
+            return None;
         }
-
-        // Evaluate fear index (can be logged or aggregated externally)
-        let _fear_index = policies.evaluate_transition(&ctx);
-
-        // Apply the belief change if not forbidden
+        let fear = verdict
+            .fear
+            .unwrap_or_else(|| policies.evaluate_transition(&ctx));
         self.beliefs.insert(
             concept_key.to_string(),
             Belief {
                 key: concept_key.to_string(),
-                strength: proposed_strength,
+                strength,
             },
         );
+        Some(fear)
     }
-}
 
-pub struct World {
-    pub time: f64,
-    pub agents: Vec<HumanAgent>,
-    pub region_populations: HashMap<String, usize>,
-    pub concept_fields: HashMap<(String, String), f64>, // (concept_key, region_id) -> intensity
+    /// Remove a belief, subject to the policy gate. Returns `None` if the
+    /// agent holds no such belief or removal is forbidden.
+    pub fn try_remove_belief<E: Environment, P: PolicyEngine>(
+        &mut self,
+        env: &E,
+        policies: &P,
+        concept_key: &str,
+    ) -> Option<FearIndex> {
+        let current_belief = self.beliefs.get(concept_key)?;
+        let region_id = self.attributed_region(env, concept_key);
+        let ctx = PolicyContext {
+            agent_id: self.id.clone(),
+            region_id,
+            concept_key,
+            current_belief: Some(current_belief),
+            proposed_strength: BeliefStrength::Graded(0.0),
+            transition: TransitionKind::Remove,
+            env_time: env.get_time(),
+            region_population: env.get_region_population(region_id),
+            region_capacity: env.get_region_capacity(region_id),
+            concept_intensity: self.exposure(env, concept_key),
+            interaction: self.interaction_summary(env, concept_key),
+        };
+        let verdict = policies.check_transition(&ctx);
+        if verdict.forbidden {
+            return None;
+        }
+        let fear = verdict
+            .fear
+            .unwrap_or_else(|| policies.evaluate_transition(&ctx));
+        self.beliefs.remove(concept_key);
+        Some(fear)
+    }
 }
 
-impl Environment for World {
-    fn get_time(&self) -> f64 {
-        self.time
+impl Agent for HumanAgent {
+    fn id(&self) -> AgentId {
+        self.id.clone()
     }
 
-    fn get_region_population(&self, region_id: &str) -> usize {
-        *self.region_populations.get(region_id).unwrap_or(&0)
+    fn location(&self) -> &Location {
+        &self.location
     }
 
-    fn get_concept_intensity(&self, concept_key: &str, region_id: &str) -> f64 {
-        *self
-            .concept_fields
-            .get(&(concept_key.to_string(), region_id.to_string()))
-            .unwrap_or(&0.0)
+    fn beliefs(&self) -> &BTreeMap<String, Belief> {
+        &self.beliefs
     }
-}
-
-// ---------- Simple policy engine skeleton ----------
 
-pub struct ZoneRepoPolicyEngine {
-    pub ethical_ceiling: f64,
-}
+    fn beliefs_mut(&mut self) -> &mut BTreeMap<String, Belief> {
+        &mut self.beliefs
+    }
 
-impl PolicyEngine for ZoneRepoPolicyEngine {
-    fn is_transition_forbidden(
-        &self,
-        ctx: &PolicyContext,
-    ) -> bool {
-        // Example: forbid if region is already overloaded with intensity + population.
-        let overload_score =
-            ctx.concept_intensity * (ctx.region_population as f64 / 10_000.0);
-        overload_score > self.ethical_ceiling
+    fn step<E: Environment, P: PolicyEngine, R: rand::Rng>(
+        &mut self,
+        env: &E,
+        policies: &P,
+        dt: f64,
+        rng: &mut R,
+    ) {
+        self.step_observed(env, policies, dt, rng, &mut ());
     }
 
-    fn evaluate_transition(
-        &self,
-        ctx: &PolicyContext,
-    ) -> FearIndex {
-        // Very simplified fear index model.
-        let systemic_harm = ctx.concept_intensity * 0.5;
-        let regret = match ctx.proposed_strength {
-            BeliefStrength::Strong => 0.4,
-            BeliefStrength::Moderate => 0.2,
-            BeliefStrength::Weak => 0.1,
-        };
-        let ecological_damage = (ctx.region_population as f64 / 1_000_000.0) * 0.3;
+    fn step_observed<E: Environment, P: PolicyEngine, R: rand::Rng, O: TransitionObserver>(
+        &mut self,
+        env: &E,
+        policies: &P,
+        dt: f64,
+        rng: &mut R,
+        observer: &mut O,
+    ) {
+        let _dt = dt;
+
+        for proposal in self.propose_transitions(env, rng) {
+            let ctx = self.context_for(env, &proposal);
+
+            // Check hard constraints; a refusal only affects this concept.
+            let verdict = policies.check_transition(&ctx);
+            if verdict.forbidden {
+                observer.on_transition_forbidden(&ctx, verdict.reason.as_deref());
+                continue;
+            }
+
+            // Evaluate fear index and hand it to the observer
+            let fear_index = verdict
+                .fear
+                .unwrap_or_else(|| policies.evaluate_transition(&ctx));
+            if !admit_transition(policies, observer, &ctx, &fear_index) {
+                continue;
+            }
+
+            // Apply the belief change if not forbidden
+            self.apply_proposal(proposal);
+        }
+    }
+}
+
+/// Offer an allowed transition's cost to `observer`, then report it as
+/// applied or budget-deferred. Returns whether to apply it.
+pub(crate) fn admit_transition<P: PolicyEngine, O: TransitionObserver>(
+    policies: &P,
+    observer: &mut O,
+    ctx: &PolicyContext,
+    fear: &FearIndex,
+) -> bool {
+    let cost = policies.transition_cost(ctx);
+    if !observer.admit_cost(ctx, cost) {
+        observer.on_transition_deferred(ctx);
+        return false;
+    }
+    observer.on_transition_applied(ctx, fear);
+    true
+}
+
+/// Attenuation of concept effects in a region past its capacity:
+/// `sqrt(capacity / population)` once over capacity, else 1. The square root
+/// keeps attenuation slower than the overload score grows.
+fn saturation<E: Environment>(env: &E, region_id: &str) -> f64 {
+    match env.get_region_capacity(region_id) {
+        Some(capacity) => {
+            let population = env.get_region_population(region_id);
+            if population > capacity {
+                (capacity as f64 / population as f64).sqrt()
+            } else {
+                1.0
+            }
+        }
+        None => 1.0,
+    }
+}
+
+/// Where `World::region_populations` comes from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PopulationMode {
+    /// Populations are set by the caller (aggregate modelling).
+    #[default]
+    Static,
+    /// Populations are recounted from agent locations every tick.
+    AgentDerived,
+}
+
+#[derive(Debug)]
+pub struct World {
+    pub time: f64,
+    pub agents: Vec<HumanAgent>,
+    pub region_populations: BTreeMap<String, usize>,
+    pub region_capacities: BTreeMap<String, usize>, // saturation point per region, if any
+    pub concept_fields: BTreeMap<(String, String), f64>, // (concept_key, region_id) -> intensity
+    pub region_neighbors: BTreeMap<String, Vec<String>>, // region_id -> adjacent region_ids
+    pub diffusion: Option<DiffusionConfig>,              // spread fields each tick when set
+    pub population_mode: PopulationMode,
+    pub rng: ChaCha12Rng, // `StdRng`'s generator, kept concrete so checkpoints can serialize it
+    pub concept_sources: ConceptSources,
+    pub injections: InjectionSchedule, // applied before agents each tick
+    pub feedback: Option<FeedbackConfig>, // beliefs feed back into fields when set
+    pub households: Vec<Household>,
+    pub agent_index: HashMap<AgentId, usize>, // AgentId -> position in `agents`
+    pub next_free_agent_id: u64, // above every id `agent_index` has held
+    pub population_schedule: PopulationSchedule, // spawns applied at the start of each tick
+    pub pending_population: PendingPopulationChanges,
+    pub belief_interactions: BeliefInteractionMatrix,
+    pub intensity_bounds: IntensityBounds, // fields are clamped into this range
+    pub intensity_warnings: Vec<IntensityClamp>, // clamps since last `take_intensity_warnings`
+    pub intensity_warnings_dropped: u64, // clamps not recorded because `intensity_warnings` was full
+    #[cfg(feature = "trace")]
+    pub trace: trace::TraceConfig, // sampling for `step_world` telemetry
+    pub resource_budget: Option<ResourceBudget>, // transition costs are capped when set
+    pub resource_spent: f64, // cumulative cost of applied transitions under a budget
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::seeded(0)
+    }
+}
+
+impl World {
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            time: 0.0,
+            agents: Vec::new(),
+            region_populations: BTreeMap::new(),
+            region_capacities: BTreeMap::new(),
+            concept_fields: BTreeMap::new(),
+            region_neighbors: BTreeMap::new(),
+            diffusion: None,
+            population_mode: PopulationMode::default(),
+            rng: ChaCha12Rng::seed_from_u64(seed),
+            concept_sources: ConceptSources::default(),
+            injections: InjectionSchedule::default(),
+            feedback: None,
+            households: Vec::new(),
+            agent_index: HashMap::new(),
+            next_free_agent_id: 0,
+            population_schedule: PopulationSchedule::default(),
+            pending_population: PendingPopulationChanges::default(),
+            belief_interactions: BeliefInteractionMatrix::default(),
+            intensity_bounds: IntensityBounds::default(),
+            intensity_warnings: Vec::new(),
+            intensity_warnings_dropped: 0,
+            #[cfg(feature = "trace")]
+            trace: trace::TraceConfig::default(),
+            resource_budget: None,
+            resource_spent: 0.0,
+        }
+    }
+
+    /// Replace `region_populations` with agent counts per `Location.region_id`.
+    /// Commuters count toward their home region only; known regions with
+    /// no residents stay listed at zero.
+    pub fn recompute_populations(&mut self) {
+        for count in self.region_populations.values_mut() {
+            *count = 0;
+        }
+        for agent in &self.agents {
+            *self
+                .region_populations
+                .entry(agent.location.region_id.clone())
+                .or_insert(0) += 1;
+        }
+    }
+}
+
+impl Environment for World {
+    fn get_time(&self) -> f64 {
+        self.time
+    }
+
+    fn get_region_population(&self, region_id: &str) -> usize {
+        *self.region_populations.get(region_id).unwrap_or(&0)
+    }
+
+    fn get_region_capacity(&self, region_id: &str) -> Option<usize> {
+        self.region_capacities.get(region_id).copied()
+    }
+
+    fn get_concept_intensity(&self, concept_key: &str, region_id: &str) -> f64 {
+        *self
+            .concept_fields
+            .get(&(concept_key.to_string(), region_id.to_string()))
+            .unwrap_or(&0.0)
+    }
+
+    fn concepts_in_region(&self, region_id: &str) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .concept_fields
+            .keys()
+            .filter(|(_, region)| region == region_id)
+            .map(|(concept_key, _)| concept_key.clone())
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    fn get_local_intensity(&self, concept_key: &str, x: f64, y: f64) -> f64 {
+        self.concept_sources.intensity_at(concept_key, x, y)
+    }
+
+    fn concepts_near(&self, x: f64, y: f64) -> Vec<String> {
+        self.concept_sources.concepts_near(x, y)
+    }
+
+    fn belief_interaction(&self, concept_a: &str, concept_b: &str) -> f64 {
+        self.belief_interactions.get(concept_a, concept_b)
+    }
+}
+
+// ---------- Simple policy engine skeleton ----------
+
+/// Tuning values for `ZoneRepoPolicyEngine`; defaults reproduce the
+/// original hardcoded model.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ZoneRepoPolicyConfig {
+    pub ethical_ceiling: f64,
+    pub overload_population_divisor: f64,
+    pub systemic_harm_per_intensity: f64,
+    pub regret_weak: f64,
+    pub regret_moderate: f64,
+    pub regret_strong: f64,
+    pub eco_damage_per_million_pop: f64,
+    /// Per-region overrides of `ethical_ceiling`.
+    pub region_ceilings: HashMap<String, f64>,
+    /// Per-region factors applied to every FearIndex component.
+    pub region_fear_multipliers: HashMap<String, f64>,
+}
+
+impl Default for ZoneRepoPolicyConfig {
+    fn default() -> Self {
+        Self {
+            ethical_ceiling: 1.0,
+            overload_population_divisor: 10_000.0,
+            systemic_harm_per_intensity: 0.5,
+            regret_weak: 0.1,
+            regret_moderate: 0.2,
+            regret_strong: 0.4,
+            eco_damage_per_million_pop: 0.3,
+            region_ceilings: HashMap::new(),
+            region_fear_multipliers: HashMap::new(),
+        }
+    }
+}
+
+impl ZoneRepoPolicyConfig {
+    /// All weights must be finite and non-negative; the divisor must be positive.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let weights = [
+            ("ethical_ceiling", self.ethical_ceiling),
+            ("overload_population_divisor", self.overload_population_divisor),
+            ("systemic_harm_per_intensity", self.systemic_harm_per_intensity),
+            ("regret_weak", self.regret_weak),
+            ("regret_moderate", self.regret_moderate),
+            ("regret_strong", self.regret_strong),
+            ("eco_damage_per_million_pop", self.eco_damage_per_million_pop),
+        ];
+        for (name, value) in weights {
+            if !value.is_finite() || value < 0.0 {
+                anyhow::bail!("{name} must be finite and non-negative, got {value}");
+            }
+        }
+        if self.overload_population_divisor == 0.0 {
+            anyhow::bail!("overload_population_divisor must be positive");
+        }
+        let regional = self
+            .region_ceilings
+            .iter()
+            .map(|(r, v)| ("region_ceilings", r, *v))
+            .chain(
+                self.region_fear_multipliers
+                    .iter()
+                    .map(|(r, v)| ("region_fear_multipliers", r, *v)),
+            );
+        for (table, region, value) in regional {
+            if !value.is_finite() || value < 0.0 {
+                anyhow::bail!("{table}['{region}'] must be finite and non-negative, got {value}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Ceiling for a region, falling back to the global one.
+    pub fn ceiling_for(&self, region_id: &str) -> f64 {
+        self.region_ceilings
+            .get(region_id)
+            .copied()
+            .unwrap_or(self.ethical_ceiling)
+    }
+
+    pub fn fear_multiplier_for(&self, region_id: &str) -> f64 {
+        self.region_fear_multipliers
+            .get(region_id)
+            .copied()
+            .unwrap_or(1.0)
+    }
+
+    /// Regret per strength; graded values interpolate linearly between the
+    /// Weak/Moderate/Strong anchors and clamp outside them.
+    pub fn regret_for(&self, strength: &BeliefStrength) -> f64 {
+        match strength {
+            BeliefStrength::Strong => self.regret_strong,
+            BeliefStrength::Moderate => self.regret_moderate,
+            BeliefStrength::Weak => self.regret_weak,
+            BeliefStrength::Graded(v) => {
+                let anchors = [
+                    (BeliefStrength::Weak.to_scalar(), self.regret_weak),
+                    (BeliefStrength::Moderate.to_scalar(), self.regret_moderate),
+                    (BeliefStrength::Strong.to_scalar(), self.regret_strong),
+                ];
+                let v = v.clamp(0.0, 1.0);
+                if v <= anchors[0].0 {
+                    return anchors[0].1;
+                }
+                for pair in anchors.windows(2) {
+                    let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+                    if v <= x1 {
+                        return y0 + (y1 - y0) * (v - x0) / (x1 - x0);
+                    }
+                }
+                anchors[2].1
+            }
+        }
+    }
+}
+
+pub struct ZoneRepoPolicyEngine {
+    pub config: ZoneRepoPolicyConfig,
+}
+
+impl ZoneRepoPolicyEngine {
+    /// Default weights with the given global ceiling.
+    pub fn new(ethical_ceiling: f64) -> Self {
+        Self {
+            config: ZoneRepoPolicyConfig {
+                ethical_ceiling,
+                ..ZoneRepoPolicyConfig::default()
+            },
+        }
+    }
+
+    pub fn from_config(config: ZoneRepoPolicyConfig) -> anyhow::Result<Self> {
+        config.validate()?;
+        Ok(Self { config })
+    }
+
+    /// Load a (possibly partial) config; missing fields take defaults.
+    pub fn from_json_str(json: &str) -> anyhow::Result<Self> {
+        Self::from_config(serde_json::from_str(json)?)
+    }
+
+    pub fn from_json_file(path: &str) -> anyhow::Result<Self> {
+        Self::from_json_str(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn with_region_ceiling(mut self, region_id: &str, ceiling: f64) -> Self {
+        self.config
+            .region_ceilings
+            .insert(region_id.to_string(), ceiling);
+        self
+    }
+
+    pub fn with_region_fear_multiplier(mut self, region_id: &str, multiplier: f64) -> Self {
+        self.config
+            .region_fear_multipliers
+            .insert(region_id.to_string(), multiplier);
+        self
+    }
+}
+
+impl ZoneRepoPolicyEngine {
+    /// The ceiling a context violates, if any.
+    fn violation(&self, ctx: &PolicyContext) -> Option<String> {
+        // Coerced de-conversion: never strip a strong belief while the
+        // concept is still intense in the region.
+        if ctx.transition == TransitionKind::Remove {
+            let strong = BeliefThresholds::default().strong;
+            let held_strongly = ctx
+                .current_belief
+                .map(|b| b.strength.to_scalar() > strong)
+                .unwrap_or(false);
+            if held_strongly && ctx.concept_intensity > strong {
+                return Some(format!(
+                    "coerced removal of strong belief '{}' at intensity {:.3}",
+                    ctx.concept_key, ctx.concept_intensity
+                ));
+            }
+        }
+
+        // Example: forbid if region is already overloaded with intensity + population.
+        // With a capacity, load is measured against it rather than the divisor.
+        let load = match ctx.region_capacity {
+            Some(capacity) => ctx.region_population as f64 / capacity.max(1) as f64,
+            None => ctx.region_population as f64 / self.config.overload_population_divisor,
+        };
+        let overload_score = ctx.concept_intensity * load;
+        let ceiling = self.config.ceiling_for(ctx.region_id);
+        if overload_score > ceiling {
+            return Some(format!(
+                "overload score {:.3} in region '{}' exceeds ethical ceiling {:.3}",
+                overload_score, ctx.region_id, ceiling
+            ));
+        }
+        None
+    }
+}
+
+impl PolicyEngine for ZoneRepoPolicyEngine {
+    fn is_transition_forbidden(
+        &self,
+        ctx: &PolicyContext,
+    ) -> bool {
+        self.violation(ctx).is_some()
+    }
+
+    fn forbid_reason(
+        &self,
+        ctx: &PolicyContext,
+    ) -> Option<String> {
+        self.violation(ctx)
+    }
+
+    fn check_transition(
+        &self,
+        ctx: &PolicyContext,
+    ) -> PolicyVerdict {
+        match self.violation(ctx) {
+            Some(reason) => PolicyVerdict::forbid(Some(reason)),
+            None => PolicyVerdict::allow(self.evaluate_transition(ctx)),
+        }
+    }
+
+    fn evaluate_transition(
+        &self,
+        ctx: &PolicyContext,
+    ) -> FearIndex {
+        // Very simplified fear index model.
+        let systemic_harm = ctx.concept_intensity * self.config.systemic_harm_per_intensity;
+        let regret = self.config.regret_for(&ctx.proposed_strength);
+        let ecological_damage = (ctx.region_population as f64 / 1_000_000.0)
+            * self.config.eco_damage_per_million_pop;
 
         FearIndex {
             systemic_harm,
             regret,
             ecological_damage,
         }
+        .scale(self.config.fear_multiplier_for(ctx.region_id))
     }
 }
 
 // ---------- Simulation loop helper ----------
 
+/// Advance `world` by one tick. With the `trace` feature the tick is
+/// reported through `tracing` (see `trace`).
+///
+/// Deterministic: two worlds built from the same inputs and seed reach
+/// bit-identical state after the same sequence of calls. Fields, regions
+/// and beliefs live in ordered maps and agents step in `agents` order, so
+/// no floating-point accumulation depends on hash iteration order. Custom
+/// `PolicyEngine`s must be deterministic themselves for this to hold.
 pub fn step_world<P: PolicyEngine>(
     world: &mut World,
     policies: &P,
     dt: f64,
 ) {
+    traced_tick(world, |world, observer| {
+        step_world_observed(world, policies, dt, observer)
+    });
+}
+
+/// Observer the unobserved `step_world*` entry points run ticks with.
+#[cfg(feature = "trace")]
+type TickObserver = trace::TracingObserver;
+#[cfg(not(feature = "trace"))]
+type TickObserver = ();
+
+/// Run one tick inside the `trace` feature's span and observer, or with no
+/// observer when the feature is off.
+fn traced_tick(world: &mut World, tick: impl FnOnce(&mut World, &mut TickObserver)) {
+    #[cfg(feature = "trace")]
+    {
+        let span = tracing::info_span!("tick", time = world.time, agents = world.agents.len());
+        let _entered = span.enter();
+        let mut observer = trace::TracingObserver::new(world.trace);
+        tick(world, &mut observer);
+        observer.finish(world.time);
+    }
+    #[cfg(not(feature = "trace"))]
+    tick(world, &mut ());
+}
+
+/// `step_world`, returning aggregated fear and transition counts for the tick.
+pub fn step_world_with_report<P: PolicyEngine>(
+    world: &mut World,
+    policies: &P,
+    dt: f64,
+) -> TickReport {
+    let mut report = TickReport::default();
+    step_world_observed(world, policies, dt, &mut report);
+    report.time = world.time;
+    report
+}
+
+/// `step_world`, returning a log entry for every transition considered.
+pub fn step_world_logged<P: PolicyEngine>(
+    world: &mut World,
+    policies: &P,
+    dt: f64,
+) -> ZoneDecisionLog {
+    let mut log = ZoneDecisionLog::default();
+    step_world_observed(world, policies, dt, &mut log);
+    log
+}
+
+/// `step_world`, threading `observer` through every agent's step.
+pub fn step_world_observed<P: PolicyEngine, O: TransitionObserver>(
+    world: &mut World,
+    policies: &P,
+    dt: f64,
+    observer: &mut O,
+) {
+    begin_tick(world);
+    advance_fields(world, dt);
+    with_budget(world, observer, |world, observer| {
+        step_agents(world, policies, dt, observer);
+        end_tick(world, policies, observer);
+    });
+}
+
+/// `step_world` with the tick split into `substeps` equal intervals for
+/// field dynamics (injections and diffusion). Agents still decide once per
+/// full tick, after the last sub-step, so policy call counts don't depend
+/// on `substeps`. As `substeps` grows the field update converges to the
+/// continuous-time result, so runs at 4 and 8 substeps should agree closely
+/// while dt = 1.0 with a single step may not.
+pub fn step_world_substeps<P: PolicyEngine>(
+    world: &mut World,
+    policies: &P,
+    dt: f64,
+    substeps: u32,
+) {
+    let substeps = substeps.max(1);
+    traced_tick(world, |world, observer| {
+        begin_tick(world);
+        let start = world.time;
+        let sub_dt = dt / substeps as f64;
+        for k in 1..=substeps {
+            advance_fields(world, sub_dt);
+            // Avoid drift from summing sub_dt repeatedly.
+            world.time = start + dt * k as f64 / substeps as f64;
+        }
+        with_budget(world, observer, |world, observer| {
+            step_agents(world, policies, dt, observer);
+            end_tick(world, policies, observer);
+        });
+    });
+}
+
+/// Picks a sub-step count from how fast fields moved during the previous
+/// tick: enough sub-steps that each one moves at most `max_delta_per_substep`.
+#[derive(Clone, Debug)]
+pub struct AdaptiveSubsteps {
+    pub max_delta_per_substep: f64,
+    pub max_substeps: u32,
+    /// Largest absolute intensity change seen in the last tick.
+    pub last_max_delta: f64,
+}
+
+impl AdaptiveSubsteps {
+    pub fn new(max_delta_per_substep: f64, max_substeps: u32) -> Self {
+        Self {
+            max_delta_per_substep,
+            max_substeps,
+            last_max_delta: 0.0,
+        }
+    }
+
+    pub fn substeps(&self) -> u32 {
+        if self.max_delta_per_substep <= 0.0 {
+            return self.max_substeps.max(1);
+        }
+        let needed = (self.last_max_delta / self.max_delta_per_substep).ceil();
+        (needed as u32).clamp(1, self.max_substeps.max(1))
+    }
+}
+
+/// `step_world_substeps` with the count chosen by `adaptive`, which is then
+/// updated with this tick's largest field change.
+pub fn step_world_adaptive<P: PolicyEngine>(
+    world: &mut World,
+    policies: &P,
+    dt: f64,
+    adaptive: &mut AdaptiveSubsteps,
+) -> u32 {
+    let substeps = adaptive.substeps();
+    let before = world.concept_fields.clone();
+    step_world_substeps(world, policies, dt, substeps);
+    adaptive.last_max_delta = world
+        .concept_fields
+        .iter()
+        .map(|(key, v)| (v - before.get(key).copied().unwrap_or(0.0)).abs())
+        .chain(
+            before
+                .iter()
+                .filter(|(key, _)| !world.concept_fields.contains_key(*key))
+                .map(|(_, v)| v.abs()),
+        )
+        .fold(0.0, f64::max);
+    substeps
+}
+
+/// `step_world` that gathers every agent's proposals for the tick and
+/// decides them with a single `PolicyEngine::evaluate_batch` call.
+///
+/// Every context is built from the beliefs agents held at the start of the
+/// tick. `step_world` instead applies an agent's transitions one at a time,
+/// so its later proposals see the earlier ones in `current_belief` and
+/// `interaction`. The two agree for engines whose batch and per-call paths
+/// agree, unless an agent gets several proposals that bear on each other
+/// through `belief_interactions`.
+pub fn step_world_batched<P: PolicyEngine>(
+    world: &mut World,
+    policies: &P,
+    dt: f64,
+) {
+    traced_tick(world, |world, observer| {
+        step_world_batched_observed(world, policies, dt, observer)
+    });
+}
+
+pub fn step_world_batched_observed<P: PolicyEngine, O: TransitionObserver>(
+    world: &mut World,
+    policies: &P,
+    dt: f64,
+    observer: &mut O,
+) {
+    begin_tick(world);
+    advance_fields(world, dt);
+    with_budget(world, observer, |world, observer| {
+        step_agents_batched(world, policies, observer);
+        end_tick(world, policies, observer);
+    });
+}
+
+fn step_agents_batched<P: PolicyEngine, O: TransitionObserver>(
+    world: &mut World,
+    policies: &P,
+    observer: &mut O,
+) {
+    let mut agents = std::mem::take(&mut world.agents);
+    let mut rng = std::mem::replace(&mut world.rng, ChaCha12Rng::seed_from_u64(0));
+    let env: &World = world;
+
+    let proposals: Vec<Vec<TransitionProposal>> = agents
+        .iter()
+        .map(|agent| agent.propose_transitions(env, &mut rng))
+        .collect();
+
+    let admitted: Vec<bool> = {
+        let contexts: Vec<PolicyContext> = agents
+            .iter()
+            .zip(&proposals)
+            .flat_map(|(agent, ps)| ps.iter().map(move |p| agent.context_for(env, p)))
+            .collect();
+        let verdicts = policies.evaluate_batch(&contexts);
+        contexts
+            .iter()
+            .zip(&verdicts)
+            .map(|(ctx, (forbidden, fear))| {
+                if *forbidden {
+                    let reason = policies.forbid_reason(ctx);
+                    observer.on_transition_forbidden(ctx, reason.as_deref());
+                    false
+                } else {
+                    admit_transition(policies, observer, ctx, fear)
+                }
+            })
+            .collect()
+    };
+
+    let mut admitted = admitted.into_iter();
+    for (agent, ps) in agents.iter_mut().zip(proposals) {
+        for proposal in ps {
+            if admitted.next().expect("one verdict per proposal") {
+                agent.apply_proposal(proposal);
+            }
+        }
+    }
+
+    world.agents = agents;
+    world.rng = rng;
+}
+
+/// Run the agent phase of a tick behind a `BudgetedObserver`. Without a
+/// `resource_budget` nothing is deferred and spending is not tracked.
+fn with_budget<O: TransitionObserver>(
+    world: &mut World,
+    observer: &mut O,
+    phase: impl FnOnce(&mut World, &mut BudgetedObserver<'_, O>),
+) {
+    let budget = world.resource_budget.unwrap_or_default();
+    let mut budgeted = BudgetedObserver::new(budget, world.resource_spent, observer);
+    phase(world, &mut budgeted);
+    if world.resource_budget.is_some() {
+        world.resource_spent = budgeted.spent_total();
+    }
+}
+
+/// Bookkeeping at the top of a tick, before time advances.
+fn begin_tick(world: &mut World) {
+    world.apply_population_changes();
+    if world.population_mode == PopulationMode::AgentDerived {
+        world.recompute_populations();
+    }
+    // Fields written straight into `concept_fields` are clamped first, so
+    // only what clamping cannot fix is reported.
+    world.clamp_intensities();
+    #[cfg(debug_assertions)]
+    {
+        let issues = world.validate();
+        debug_assert!(issues.is_empty(), "invalid world at tick start: {issues:?}");
+    }
+}
+
+/// Advance time and field dynamics by `dt`.
+fn advance_fields(world: &mut World, dt: f64) {
     world.time += dt;
-    for agent in world.agents.iter_mut() {
-        agent.step(world, policies, dt);
+    world.apply_injections();
+
+    if let Some(config) = world.diffusion.clone() {
+        world.diffuse_concepts(dt, &config);
+    }
+    world.clamp_intensities();
+}
+
+fn step_agents<P: PolicyEngine, O: TransitionObserver>(
+    world: &mut World,
+    policies: &P,
+    dt: f64,
+    observer: &mut O,
+) {
+    // Agents and the RNG are moved out while stepping so agents can read
+    // the world immutably.
+    let mut agents = std::mem::take(&mut world.agents);
+    let mut rng = std::mem::replace(&mut world.rng, ChaCha12Rng::seed_from_u64(0));
+    for agent in agents.iter_mut() {
+        agent.step_observed(&*world, policies, dt, &mut rng, observer);
+    }
+    world.agents = agents;
+    world.rng = rng;
+}
+
+/// World updates that run after agents act.
+fn end_tick<P: PolicyEngine, O: TransitionObserver>(
+    world: &mut World,
+    policies: &P,
+    observer: &mut O,
+) {
+    world.propagate_within_households(policies, observer);
+
+    if let Some(config) = world.feedback.clone() {
+        world.update_intensities_from_beliefs(config.gain, config.decay);
     }
+    world.clamp_intensities();
 }
 
 use lua_policy::LuaPolicyEngine;
@@ -254,3 +1572,906 @@ pub fn make_lua_policy_engine_from_file(path: &str) -> anyhow::Result<LuaPolicyE
     let script_source = std::fs::read_to_string(path)?;
     LuaPolicyEngine::new(&script_source)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Regions r1 and r2, empty, with one agent in `region_id`.
+    fn world_with_agent(region_id: &str) -> World {
+        let mut world = World::default();
+        world.region_populations.insert("r1".to_string(), 0);
+        world.region_populations.insert("r2".to_string(), 0);
+        world.agents.push(HumanAgent::new(
+            AgentId(1),
+            Location::new(0.0, 0.0, region_id),
+        ));
+        world
+    }
+
+    fn set_field(world: &mut World, concept_key: &str, region_id: &str, intensity: f64) {
+        world
+            .concept_fields
+            .insert((concept_key.to_string(), region_id.to_string()), intensity);
+    }
+
+    #[test]
+    fn every_concept_in_the_region_is_stepped() {
+        let mut world = world_with_agent("r1");
+        set_field(&mut world, "alpha", "r1", 0.5);
+        set_field(&mut world, "beta", "r1", 0.9);
+        set_field(&mut world, "gamma", "r2", 0.9);
+
+        step_world(&mut world, &ZoneRepoPolicyEngine::new(1.0), 1.0);
+
+        let beliefs = &world.agents[0].beliefs;
+        assert_eq!(
+            beliefs.keys().collect::<Vec<_>>(),
+            vec!["alpha", "beta"],
+            "both concepts of r1 adopted, none from r2"
+        );
+        assert_eq!(world.concepts_in_region("r1"), vec!["alpha", "beta"]);
+    }
+
+    #[test]
+    fn zero_intensity_concepts_are_skipped() {
+        let mut world = world_with_agent("r1");
+        set_field(&mut world, "alpha", "r1", 0.0);
+        set_field(&mut world, "beta", "r1", 0.3);
+
+        step_world(&mut world, &ZoneRepoPolicyEngine::new(1.0), 1.0);
+
+        assert!(!world.agents[0].beliefs.contains_key("alpha"));
+        assert!(world.agents[0].beliefs.contains_key("beta"));
+    }
+
+    #[test]
+    fn from_scalar_boundaries_are_exclusive() {
+        assert!(matches!(
+            BeliefStrength::from_scalar(0.0),
+            BeliefStrength::Weak
+        ));
+        assert!(matches!(
+            BeliefStrength::from_scalar(0.4),
+            BeliefStrength::Weak
+        ));
+        assert!(matches!(
+            BeliefStrength::from_scalar(0.41),
+            BeliefStrength::Moderate
+        ));
+        assert!(matches!(
+            BeliefStrength::from_scalar(0.8),
+            BeliefStrength::Moderate
+        ));
+        assert!(matches!(
+            BeliefStrength::from_scalar(0.81),
+            BeliefStrength::Strong
+        ));
+        assert!(matches!(
+            BeliefStrength::from_scalar(1.0),
+            BeliefStrength::Strong
+        ));
+    }
+
+    #[test]
+    fn scalar_roundtrip_keeps_discrete_levels() {
+        for level in [
+            BeliefStrength::Weak,
+            BeliefStrength::Moderate,
+            BeliefStrength::Strong,
+        ] {
+            assert_eq!(
+                BeliefStrength::from_scalar(level.to_scalar()).rank(),
+                level.rank()
+            );
+        }
+        assert_eq!(BeliefStrength::Graded(1.7).to_scalar(), 1.0);
+        assert_eq!(BeliefStrength::Graded(-0.2).to_scalar(), 0.0);
+    }
+
+    #[test]
+    fn custom_thresholds_move_the_cut_offs() {
+        let thresholds = BeliefThresholds {
+            moderate: 0.1,
+            strong: 0.5,
+        };
+        let level = BeliefStrength::from_scalar_with(0.3, &thresholds);
+        assert!(matches!(level, BeliefStrength::Moderate));
+        let level = BeliefStrength::from_scalar_with(0.6, &thresholds);
+        assert!(matches!(level, BeliefStrength::Strong));
+    }
+
+    #[test]
+    fn graded_regret_interpolates_between_anchors() {
+        let config = ZoneRepoPolicyConfig::default();
+        let regret = |v| config.regret_for(&BeliefStrength::Graded(v));
+        assert_eq!(regret(0.0), config.regret_weak);
+        assert_eq!(regret(0.2), config.regret_weak);
+        assert!((regret(0.4) - 0.15).abs() < 1e-12);
+        assert_eq!(regret(0.6), config.regret_moderate);
+        assert!((regret(0.8) - 0.3).abs() < 1e-12);
+        assert_eq!(regret(1.0), config.regret_strong);
+    }
+
+    #[test]
+    fn proposed_conviction_is_smooth_in_intensity() {
+        let proposed = |intensity| {
+            let mut world = world_with_agent("r1");
+            set_field(&mut world, "alpha", "r1", intensity);
+            step_world(&mut world, &ZoneRepoPolicyEngine::new(1.0), 1.0);
+            world.agents[0].beliefs["alpha"].strength.to_scalar()
+        };
+        // No cliff at the old 0.4 / 0.8 cut-offs.
+        assert!((proposed(0.41) - proposed(0.39) - 0.02).abs() < 1e-9);
+        assert!((proposed(0.81) - proposed(0.79) - 0.02).abs() < 1e-9);
+    }
+
+    /// Region population seen by every applied or forbidden transition.
+    #[derive(Default)]
+    struct PopulationProbe(Vec<usize>);
+
+    impl TransitionObserver for PopulationProbe {
+        fn on_transition_applied(&mut self, ctx: &PolicyContext, _fear: &FearIndex) {
+            self.0.push(ctx.region_population);
+        }
+
+        fn on_transition_forbidden(&mut self, ctx: &PolicyContext, _reason: Option<&str>) {
+            self.0.push(ctx.region_population);
+        }
+    }
+
+    #[test]
+    fn agent_derived_populations_follow_moves() {
+        let mut world = world_with_agent("r1");
+        world.population_mode = PopulationMode::AgentDerived;
+        world
+            .agents
+            .push(HumanAgent::new(AgentId(2), Location::new(0.0, 0.0, "r1")));
+        set_field(&mut world, "alpha", "r2", 0.5);
+        let policy = ZoneRepoPolicyEngine::new(1.0);
+
+        step_world(&mut world, &policy, 1.0);
+        assert_eq!(world.region_populations["r1"], 2);
+        assert_eq!(world.region_populations["r2"], 0);
+
+        world.agents[0].location.region_id = "r2".to_string();
+        let mut probe = PopulationProbe::default();
+        step_world_observed(&mut world, &policy, 1.0, &mut probe);
+        assert_eq!(probe.0, vec![1]);
+        assert_eq!(world.region_populations["r1"], 1);
+        assert_eq!(world.region_populations["r2"], 1);
+    }
+
+    #[test]
+    fn static_populations_are_left_alone() {
+        let mut world = world_with_agent("r1");
+        world.region_populations.insert("r1".to_string(), 500);
+        step_world(&mut world, &ZoneRepoPolicyEngine::new(1.0), 1.0);
+        assert_eq!(world.region_populations["r1"], 500);
+    }
+
+    fn noisy_world(seed: u64) -> World {
+        let mut world = World::seeded(seed);
+        world.region_populations.insert("r1".to_string(), 0);
+        for id in 0..20 {
+            let mut agent = HumanAgent::new(AgentId(id), Location::new(0.0, 0.0, "r1"));
+            agent.adoption_noise = Some(AdoptionNoise::default());
+            world.agents.push(agent);
+        }
+        set_field(&mut world, "alpha", "r1", 0.5);
+        set_field(&mut world, "beta", "r1", 0.7);
+        world
+    }
+
+    fn belief_bytes(world: &World) -> Vec<u8> {
+        let beliefs: Vec<_> = world.agents.iter().map(|a| &a.beliefs).collect();
+        serde_json::to_vec(&beliefs).unwrap()
+    }
+
+    #[test]
+    fn same_seed_gives_identical_beliefs() {
+        let policy = ZoneRepoPolicyEngine::new(1.0);
+        let mut a = noisy_world(7);
+        let mut b = noisy_world(7);
+        for _ in 0..3 {
+            step_world(&mut a, &policy, 1.0);
+            step_world(&mut b, &policy, 1.0);
+        }
+        assert_eq!(belief_bytes(&a), belief_bytes(&b));
+    }
+
+    #[test]
+    fn different_seeds_give_different_beliefs() {
+        let policy = ZoneRepoPolicyEngine::new(1.0);
+        let mut a = noisy_world(7);
+        let mut b = noisy_world(8);
+        step_world(&mut a, &policy, 1.0);
+        step_world(&mut b, &policy, 1.0);
+        assert_ne!(belief_bytes(&a), belief_bytes(&b));
+    }
+
+    #[test]
+    fn adoption_noise_stays_in_unit_range() {
+        let noise = AdoptionNoise::default();
+        let mut rng = ChaCha12Rng::seed_from_u64(3);
+        for intensity in [0.0, 0.5, 1.0] {
+            for _ in 0..100 {
+                let v = noise.sample(intensity, &mut rng);
+                assert!((0.0..=1.0).contains(&v), "{v}");
+            }
+        }
+    }
+
+    #[test]
+    fn transition_kind_follows_the_direction_of_change() {
+        let moderate = BeliefStrength::Moderate;
+        assert_eq!(
+            TransitionKind::between(None, &moderate),
+            TransitionKind::Adopt
+        );
+        assert_eq!(
+            TransitionKind::between(Some(&moderate), &BeliefStrength::Strong),
+            TransitionKind::Strengthen
+        );
+        assert_eq!(
+            TransitionKind::between(Some(&moderate), &moderate),
+            TransitionKind::Strengthen
+        );
+        assert_eq!(
+            TransitionKind::between(Some(&moderate), &BeliefStrength::Weak),
+            TransitionKind::Weaken
+        );
+    }
+
+    fn holder_of_strong_alpha(intensity: f64) -> (World, HumanAgent) {
+        let mut world = world_with_agent("r1");
+        set_field(&mut world, "alpha", "r1", intensity);
+        let mut agent = world.agents.pop().unwrap();
+        agent.beliefs.insert(
+            "alpha".to_string(),
+            Belief {
+                key: "alpha".to_string(),
+                strength: BeliefStrength::Strong,
+            },
+        );
+        (world, agent)
+    }
+
+    #[test]
+    fn coerced_removal_of_a_strong_belief_is_forbidden() {
+        let (world, mut agent) = holder_of_strong_alpha(0.9);
+        let policy = ZoneRepoPolicyEngine::new(1.0);
+        assert!(agent.try_remove_belief(&world, &policy, "alpha").is_none());
+        assert!(agent.beliefs.contains_key("alpha"));
+    }
+
+    #[test]
+    fn removal_is_allowed_once_intensity_fades() {
+        let (world, mut agent) = holder_of_strong_alpha(0.3);
+        let policy = ZoneRepoPolicyEngine::new(1.0);
+        assert!(agent.try_remove_belief(&world, &policy, "alpha").is_some());
+        assert!(!agent.beliefs.contains_key("alpha"));
+        assert!(agent.try_remove_belief(&world, &policy, "alpha").is_none());
+    }
+
+    #[test]
+    fn try_set_belief_adopts_weakens_and_strengthens() {
+        let (world, mut agent) = holder_of_strong_alpha(0.9);
+        let policy = ZoneRepoPolicyEngine::new(1.0);
+        assert!(agent
+            .try_set_belief(&world, &policy, "alpha", BeliefStrength::Weak)
+            .is_some());
+        assert_eq!(agent.beliefs["alpha"].strength.rank(), 0);
+        assert!(agent
+            .try_set_belief(&world, &policy, "alpha", BeliefStrength::Moderate)
+            .is_some());
+        assert_eq!(agent.beliefs["alpha"].strength.rank(), 1);
+        assert!(agent
+            .try_set_belief(&world, &policy, "beta", BeliefStrength::Strong)
+            .is_some());
+        assert!(agent.beliefs.contains_key("beta"));
+    }
+
+    #[test]
+    fn try_set_belief_respects_the_ceiling() {
+        let (mut world, mut agent) = holder_of_strong_alpha(0.9);
+        world.region_populations.insert("r1".to_string(), 20_000);
+        let policy = ZoneRepoPolicyEngine::new(1.0);
+        assert!(agent
+            .try_set_belief(&world, &policy, "alpha", BeliefStrength::Weak)
+            .is_none());
+        assert_eq!(agent.beliefs["alpha"].strength.rank(), 2);
+    }
+
+    #[test]
+    fn default_config_reproduces_the_original_numbers() {
+        let ctx = PolicyContext {
+            agent_id: AgentId(1),
+            region_id: "r1",
+            concept_key: "alpha",
+            current_belief: None,
+            proposed_strength: BeliefStrength::Moderate,
+            transition: TransitionKind::Adopt,
+            env_time: 0.0,
+            region_population: 2_000_000,
+            region_capacity: None,
+            concept_intensity: 0.0004,
+            interaction: InteractionSummary::default(),
+        };
+        let engine = ZoneRepoPolicyEngine::new(1.0);
+        let fear = engine.evaluate_transition(&ctx);
+        assert_eq!(fear.systemic_harm, 0.0004 * 0.5);
+        assert_eq!(fear.regret, 0.2);
+        assert_eq!(fear.ecological_damage, 2.0 * 0.3);
+        // 0.0004 * 2_000_000 / 10_000 = 0.08, under the ceiling.
+        assert!(!engine.is_transition_forbidden(&ctx));
+        let strict = ZoneRepoPolicyEngine::new(0.05);
+        assert!(strict.is_transition_forbidden(&ctx));
+
+        for (strength, regret) in [
+            (BeliefStrength::Weak, 0.1),
+            (BeliefStrength::Moderate, 0.2),
+            (BeliefStrength::Strong, 0.4),
+        ] {
+            assert_eq!(engine.config.regret_for(&strength), regret);
+        }
+    }
+
+    #[test]
+    fn partial_json_config_takes_defaults() {
+        let engine = ZoneRepoPolicyEngine::from_json_str(
+            r#"{"ethical_ceiling": 2.5, "regret_strong": 0.9}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            engine.config,
+            ZoneRepoPolicyConfig {
+                ethical_ceiling: 2.5,
+                regret_strong: 0.9,
+                ..ZoneRepoPolicyConfig::default()
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_weights_are_rejected() {
+        for json in [
+            r#"{"regret_weak": -0.1}"#,
+            r#"{"overload_population_divisor": 0.0}"#,
+            r#"{"region_ceilings": {"r1": -1.0}}"#,
+        ] {
+            assert!(ZoneRepoPolicyEngine::from_json_str(json).is_err(), "{json}");
+        }
+        let config = ZoneRepoPolicyConfig {
+            systemic_harm_per_intensity: f64::NAN,
+            ..ZoneRepoPolicyConfig::default()
+        };
+        assert!(ZoneRepoPolicyEngine::from_config(config).is_err());
+    }
+
+    fn fear(systemic_harm: f64, regret: f64, ecological_damage: f64) -> FearIndex {
+        FearIndex {
+            systemic_harm,
+            regret,
+            ecological_damage,
+        }
+    }
+
+    fn components(f: &FearIndex) -> [f64; 3] {
+        [f.systemic_harm, f.regret, f.ecological_damage]
+    }
+
+    #[test]
+    fn weighted_total_weights_each_component() {
+        let f = fear(0.2, 0.5, 0.1);
+        assert_eq!(f.weighted_total(&FearWeights::default()), f.total());
+        let eco_heavy = FearWeights {
+            systemic_harm: 1.0,
+            regret: 0.0,
+            ecological_damage: 10.0,
+        };
+        assert!((f.weighted_total(&eco_heavy) - 1.2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn normalized_clamps_and_handles_zero_maxima() {
+        let f = fear(0.5, 3.0, 0.2);
+        let maxes = fear(1.0, 2.0, 0.0);
+        assert_eq!(components(&f.normalized(&maxes)), [0.5, 1.0, 1.0]);
+        assert_eq!(
+            components(&fear(0.0, -1.0, 0.0).normalized(&fear(0.0, 1.0, -1.0))),
+            [0.0, 0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn max_component_names_the_dominant_one() {
+        assert_eq!(fear(0.1, 0.5, 0.2).max_component(), "regret");
+        assert_eq!(fear(0.1, 0.2, 0.5).max_component(), "ecological_damage");
+        assert_eq!(fear(0.3, 0.3, 0.3).max_component(), "systemic_harm");
+        assert_eq!(FearIndex::default().max_component(), "systemic_harm");
+    }
+
+    #[test]
+    fn add_and_scale_are_component_wise() {
+        let sum = fear(0.1, 0.2, 0.3).add(&fear(1.0, 2.0, 3.0));
+        assert_eq!(components(&sum), [1.1, 2.2, 3.3]);
+        assert_eq!(components(&sum.scale(0.0)), [0.0, 0.0, 0.0]);
+        assert_eq!(components(&fear(1.0, 2.0, 4.0).scale(0.5)), [0.5, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn strengths_order_by_level_then_conviction() {
+        use BeliefStrength::*;
+        let ordered = [
+            Graded(0.0),
+            Weak,
+            Graded(0.3),
+            Graded(0.45),
+            Moderate,
+            Graded(0.7),
+            Graded(0.9),
+            Strong,
+            Graded(1.0),
+        ];
+        for pair in ordered.windows(2) {
+            assert!(pair[0] < pair[1], "{:?} < {:?}", pair[0], pair[1]);
+        }
+        assert!(Graded(f64::NAN) < Moderate, "NaN ranks as Weak");
+    }
+
+    #[test]
+    fn equality_is_structural() {
+        use BeliefStrength::*;
+        assert_ne!(Graded(1.0), Strong);
+        assert_ne!(Graded(0.6), Moderate);
+        assert_ne!(Graded(1.0), Graded(1.5));
+        assert_eq!(Graded(0.25), Graded(0.25));
+        assert_eq!(Moderate, Moderate);
+        assert_eq!(Strong.rank(), Graded(1.0).rank());
+    }
+
+    fn overload_ctx(region_id: &str) -> PolicyContext<'_> {
+        // Overload score 0.6 * 5_000 / 10_000 = 0.3.
+        PolicyContext {
+            agent_id: AgentId(1),
+            region_id,
+            concept_key: "alpha",
+            current_belief: None,
+            proposed_strength: BeliefStrength::Moderate,
+            transition: TransitionKind::Adopt,
+            env_time: 0.0,
+            region_population: 5_000,
+            region_capacity: None,
+            concept_intensity: 0.6,
+            interaction: InteractionSummary::default(),
+        }
+    }
+
+    #[test]
+    fn region_ceilings_override_the_global_one() {
+        let engine = ZoneRepoPolicyEngine::new(1.0).with_region_ceiling("wetland", 0.2);
+        assert!(engine.is_transition_forbidden(&overload_ctx("wetland")));
+        assert!(!engine.is_transition_forbidden(&overload_ctx("downtown")));
+        let reason = engine.forbid_reason(&overload_ctx("wetland")).unwrap();
+        assert!(reason.contains("ethical ceiling 0.200"), "{reason}");
+    }
+
+    #[test]
+    fn region_fear_multipliers_scale_every_component() {
+        let engine = ZoneRepoPolicyEngine::new(1.0).with_region_fear_multiplier("wetland", 3.0);
+        let base = engine.evaluate_transition(&overload_ctx("downtown"));
+        let scaled = engine.evaluate_transition(&overload_ctx("wetland"));
+        assert_eq!(components(&scaled), components(&base.scale(3.0)));
+    }
+
+    #[test]
+    fn region_tables_load_from_json() {
+        let engine = ZoneRepoPolicyEngine::from_json_str(
+            r#"{"region_ceilings": {"wetland": 0.2}, "region_fear_multipliers": {"wetland": 2.0}}"#,
+        )
+        .unwrap();
+        assert_eq!(engine.config.ceiling_for("wetland"), 0.2);
+        assert_eq!(engine.config.ceiling_for("downtown"), 1.0);
+        assert_eq!(engine.config.fear_multiplier_for("wetland"), 2.0);
+        assert_eq!(engine.config.fear_multiplier_for("downtown"), 1.0);
+        assert!(engine.is_transition_forbidden(&overload_ctx("wetland")));
+    }
+
+    /// Counts `evaluate_batch` and `check_transition` calls on the inner engine.
+    struct CallCounter<P> {
+        inner: P,
+        batches: std::cell::Cell<usize>,
+        checks: std::cell::Cell<usize>,
+    }
+
+    impl<P: PolicyEngine> PolicyEngine for CallCounter<P> {
+        fn is_transition_forbidden(&self, ctx: &PolicyContext) -> bool {
+            self.inner.is_transition_forbidden(ctx)
+        }
+
+        fn evaluate_transition(&self, ctx: &PolicyContext) -> FearIndex {
+            self.inner.evaluate_transition(ctx)
+        }
+
+        fn forbid_reason(&self, ctx: &PolicyContext) -> Option<String> {
+            self.inner.forbid_reason(ctx)
+        }
+
+        fn check_transition(&self, ctx: &PolicyContext) -> PolicyVerdict {
+            self.checks.set(self.checks.get() + 1);
+            self.inner.check_transition(ctx)
+        }
+
+        fn evaluate_batch(&self, contexts: &[PolicyContext]) -> Vec<(bool, FearIndex)> {
+            self.batches.set(self.batches.get() + 1);
+            self.inner.evaluate_batch(contexts)
+        }
+    }
+
+    fn mixed_world() -> World {
+        let mut world = noisy_world(5);
+        world.region_populations.insert("r2".to_string(), 20_000);
+        for id in 20..30 {
+            world
+                .agents
+                .push(HumanAgent::new(AgentId(id), Location::new(0.0, 0.0, "r2")));
+        }
+        set_field(&mut world, "alpha", "r2", 0.3);
+        set_field(&mut world, "beta", "r2", 0.8);
+        world
+    }
+
+    #[test]
+    fn batched_and_per_call_stepping_agree() {
+        let policy = ZoneRepoPolicyEngine::new(1.0);
+        let mut per_call = mixed_world();
+        let mut batched = mixed_world();
+        for _ in 0..3 {
+            let mut expected = observer::VecObserver::default();
+            let mut actual = observer::VecObserver::default();
+            step_world_observed(&mut per_call, &policy, 1.0, &mut expected);
+            step_world_batched_observed(&mut batched, &policy, 1.0, &mut actual);
+            assert_eq!(
+                format!("{:?}", expected.events),
+                format!("{:?}", actual.events)
+            );
+        }
+        assert_eq!(belief_bytes(&per_call), belief_bytes(&batched));
+    }
+
+    #[test]
+    fn batched_stepping_makes_one_batch_call_per_tick() {
+        let policy = CallCounter {
+            inner: ZoneRepoPolicyEngine::new(1.0),
+            batches: Default::default(),
+            checks: Default::default(),
+        };
+        let mut world = mixed_world();
+        step_world_batched(&mut world, &policy, 1.0);
+        step_world_batched(&mut world, &policy, 1.0);
+        assert_eq!(policy.batches.get(), 2);
+        assert_eq!(policy.checks.get(), 0);
+    }
+
+    /// Two regions exchanging "alpha" fast enough that one step per tick
+    /// overshoots.
+    fn diffusing_world() -> World {
+        let mut world = world_with_agent("r1");
+        world
+            .region_neighbors
+            .insert("r1".to_string(), vec!["r2".to_string()]);
+        world
+            .region_neighbors
+            .insert("r2".to_string(), vec!["r1".to_string()]);
+        world.diffusion = Some(DiffusionConfig {
+            rate: 0.8,
+            decay: 0.3,
+        });
+        set_field(&mut world, "alpha", "r1", 1.0);
+        world
+    }
+
+    fn field_after(substeps: u32) -> f64 {
+        let mut world = diffusing_world();
+        let policy = ZoneRepoPolicyEngine::new(1.0);
+        for _ in 0..3 {
+            step_world_substeps(&mut world, &policy, 1.0, substeps);
+        }
+        assert_eq!(world.time, 3.0);
+        world.get_concept_intensity("alpha", "r1")
+    }
+
+    #[test]
+    fn substeps_converge() {
+        let coarse = (field_after(1) - field_after(8)).abs();
+        let fine = (field_after(4) - field_after(8)).abs();
+        assert!(fine < 0.02, "4 vs 8 substeps differ by {fine}");
+        assert!(fine < coarse / 4.0, "{fine} vs {coarse}");
+    }
+
+    #[test]
+    fn substeps_decide_once_per_tick() {
+        let policy = CallCounter {
+            inner: ZoneRepoPolicyEngine::new(1.0),
+            batches: Default::default(),
+            checks: Default::default(),
+        };
+        let mut world = diffusing_world();
+        step_world_substeps(&mut world, &policy, 1.0, 8);
+        assert_eq!(policy.checks.get(), 1);
+    }
+
+    #[test]
+    fn adaptive_substeps_follow_the_last_delta() {
+        let mut adaptive = AdaptiveSubsteps::new(0.05, 16);
+        assert_eq!(adaptive.substeps(), 1);
+        let mut world = diffusing_world();
+        let policy = ZoneRepoPolicyEngine::new(1.0);
+        assert_eq!(
+            step_world_adaptive(&mut world, &policy, 1.0, &mut adaptive),
+            1
+        );
+        assert!(adaptive.last_max_delta > 0.05);
+        let next = adaptive.substeps();
+        assert!(next > 1 && next <= 16, "{next}");
+        assert_eq!(
+            step_world_adaptive(&mut world, &policy, 1.0, &mut adaptive),
+            next
+        );
+
+        adaptive.last_max_delta = 100.0;
+        assert_eq!(adaptive.substeps(), 16);
+    }
+
+    fn commuter(id: u64, weight: f64) -> HumanAgent {
+        let location = Location::new(0.0, 0.0, "r1")
+            .with_secondary("r2")
+            .with_secondary_weight(weight);
+        HumanAgent::new(AgentId(id), location)
+    }
+
+    #[test]
+    fn commuter_exposure_blends_home_and_secondary() {
+        let mut world = world_with_agent("r1");
+        set_field(&mut world, "alpha", "r1", 1.0);
+        set_field(&mut world, "beta", "r2", 1.0);
+        let agent = commuter(2, Location::DEFAULT_SECONDARY_WEIGHT);
+        let mut rng = world.rng.clone();
+        let proposals = agent.propose_transitions(&world, &mut rng);
+        let exposures: Vec<(&str, f64)> = proposals
+            .iter()
+            .map(|p| (p.concept_key.as_str(), p.concept_intensity))
+            .collect();
+        assert_eq!(exposures.len(), 2);
+        assert_eq!(exposures[0].0, "alpha");
+        assert!((exposures[0].1 - 0.7).abs() < 1e-12);
+        assert_eq!(exposures[1].0, "beta");
+        assert!((exposures[1].1 - 0.3).abs() < 1e-12);
+
+        // A stay-at-home agent sees none of the secondary region.
+        let mut rng = world.rng.clone();
+        let proposals = world.agents[0].propose_transitions(&world, &mut rng);
+        assert_eq!(proposals.len(), 1);
+        assert_eq!(proposals[0].concept_intensity, 1.0);
+    }
+
+    #[test]
+    fn transitions_are_attributed_to_the_dominant_region() {
+        let mut world = world_with_agent("r1");
+        world.region_populations.insert("r2".to_string(), 500);
+        set_field(&mut world, "alpha", "r1", 0.5);
+        set_field(&mut world, "alpha", "r2", 0.5);
+
+        let mostly_home = commuter(2, 0.3);
+        assert_eq!(mostly_home.attributed_region(&world, "alpha"), "r1");
+        let mostly_away = commuter(3, 0.8);
+        assert_eq!(mostly_away.attributed_region(&world, "alpha"), "r2");
+
+        let mut rng = world.rng.clone();
+        let proposals = mostly_away.propose_transitions(&world, &mut rng);
+        let ctx = mostly_away.context_for(&world, &proposals[0]);
+        assert_eq!(ctx.region_id, "r2");
+        assert_eq!(ctx.region_population, 500);
+    }
+
+    #[test]
+    fn populations_count_home_regions_only() {
+        let mut world = world_with_agent("r1");
+        world.agents.push(commuter(2, 0.9));
+        world.agents.push(commuter(3, 0.5));
+        world.population_mode = PopulationMode::AgentDerived;
+        world.recompute_populations();
+        assert_eq!(world.region_populations["r1"], 3);
+        assert_eq!(world.region_populations["r2"], 0);
+    }
+
+    #[test]
+    fn capacity_replaces_the_overload_divisor() {
+        let engine = ZoneRepoPolicyEngine::new(1.0);
+        let mut ctx = overload_ctx("r1");
+        assert!(!engine.is_transition_forbidden(&ctx));
+
+        // 0.6 * 5_000 / 2_000 = 1.5 over the ceiling.
+        ctx.region_capacity = Some(2_000);
+        assert!(engine.is_transition_forbidden(&ctx));
+        // 0.6 * 5_000 / 10_000 = 0.3 again, now against the capacity.
+        ctx.region_capacity = Some(10_000);
+        assert!(!engine.is_transition_forbidden(&ctx));
+    }
+
+    #[test]
+    fn crowding_past_capacity_attenuates_exposure() {
+        let exposure = |capacity: Option<usize>| {
+            let mut world = world_with_agent("r1");
+            world.region_populations.insert("r1".to_string(), 400);
+            if let Some(capacity) = capacity {
+                world.region_capacities.insert("r1".to_string(), capacity);
+            }
+            set_field(&mut world, "alpha", "r1", 0.8);
+            let mut rng = world.rng.clone();
+            let proposals = world.agents[0].propose_transitions(&world, &mut rng);
+            let ctx = world.agents[0].context_for(&world, &proposals[0]);
+            assert_eq!(ctx.region_capacity, capacity);
+            proposals[0].concept_intensity
+        };
+        assert_eq!(exposure(None), 0.8);
+        assert_eq!(exposure(Some(400)), 0.8);
+        // Four times over capacity: scaled by sqrt(100 / 400).
+        assert!((exposure(Some(100)) - 0.4).abs() < 1e-12);
+    }
+
+    /// Three regions in a line with diffusion, feedback, an injection and
+    /// noisy agents. `reverse` builds every table in the opposite order.
+    fn determinism_scenario(reverse: bool) -> World {
+        let mut world = World::seeded(42);
+        let mut regions = vec![
+            ("r1", vec!["r2"]),
+            ("r2", vec!["r1", "r3"]),
+            ("r3", vec!["r2"]),
+        ];
+        if reverse {
+            regions.reverse();
+        }
+        for (region_id, neighbors) in &regions {
+            world.region_populations.insert(region_id.to_string(), 0);
+            world.region_neighbors.insert(
+                region_id.to_string(),
+                neighbors.iter().map(|n| n.to_string()).collect(),
+            );
+        }
+        world.population_mode = PopulationMode::AgentDerived;
+        world.diffusion = Some(DiffusionConfig::default());
+        world.feedback = Some(FeedbackConfig::default());
+        world.injections.push(injection::Injection {
+            concept_key: "beta".to_string(),
+            region_id: "r3".to_string(),
+            start_time: 2.0,
+            duration: 5.0,
+            peak_intensity: 0.9,
+            shape: injection::InjectionShape::Pulse,
+        });
+        let mut fields = vec![
+            ("alpha", "r1", 0.7),
+            ("beta", "r2", 0.2),
+            ("gamma", "r3", 0.4),
+        ];
+        if reverse {
+            fields.reverse();
+        }
+        for (concept_key, region_id, intensity) in fields {
+            set_field(&mut world, concept_key, region_id, intensity);
+        }
+        let mut ids: Vec<u64> = (0..30).collect();
+        if reverse {
+            ids.reverse();
+        }
+        let mut agents: Vec<HumanAgent> = ids
+            .into_iter()
+            .map(|id| {
+                let region_id = ["r1", "r2", "r3"][id as usize % 3];
+                let mut agent = HumanAgent::new(AgentId(id), Location::new(0.0, 0.0, region_id));
+                agent.adoption_noise = Some(AdoptionNoise::default());
+                agent
+            })
+            .collect();
+        // Agents are stepped in `agents` order, which is part of the scenario.
+        agents.sort_by_key(|a| a.id.0);
+        for agent in agents {
+            world.spawn_agent(agent);
+        }
+        world
+    }
+
+    #[test]
+    fn seeded_runs_serialize_identically() {
+        let policy = ZoneRepoPolicyEngine::new(1.0);
+        let mut a = determinism_scenario(false);
+        let mut b = determinism_scenario(true);
+        let mut reports = (Vec::new(), Vec::new());
+        for _ in 0..15 {
+            let report = step_world_with_report(&mut a, &policy, 1.0);
+            reports.0.push(format!("{report:?}"));
+            let report = step_world_with_report(&mut b, &policy, 1.0);
+            reports.1.push(format!("{report:?}"));
+        }
+        assert_eq!(reports.0, reports.1);
+        let a = serde_json::to_vec(&a.snapshot()).unwrap();
+        let b = serde_json::to_vec(&b.snapshot()).unwrap();
+        assert!(a == b, "snapshots differ");
+    }
+
+    /// An engine written before `check_transition`: forbids `banned`
+    /// without a reason and scores fear from the intensity.
+    struct LegacyEngine;
+
+    impl PolicyEngine for LegacyEngine {
+        fn is_transition_forbidden(&self, ctx: &PolicyContext) -> bool {
+            ctx.concept_key == "banned"
+        }
+
+        fn evaluate_transition(&self, ctx: &PolicyContext) -> FearIndex {
+            FearIndex {
+                systemic_harm: ctx.concept_intensity,
+                regret: 0.0,
+                ecological_damage: 0.0,
+            }
+        }
+    }
+
+    /// `LegacyEngine` that also explains itself.
+    struct ExplainingEngine;
+
+    impl PolicyEngine for ExplainingEngine {
+        fn is_transition_forbidden(&self, ctx: &PolicyContext) -> bool {
+            LegacyEngine.is_transition_forbidden(ctx)
+        }
+
+        fn evaluate_transition(&self, ctx: &PolicyContext) -> FearIndex {
+            LegacyEngine.evaluate_transition(ctx)
+        }
+
+        fn forbid_reason(&self, ctx: &PolicyContext) -> Option<String> {
+            Some(format!("'{}' is banned", ctx.concept_key))
+        }
+    }
+
+    #[test]
+    fn default_verdict_is_built_from_the_old_methods() {
+        let mut ctx = overload_ctx("r1");
+        let verdict = LegacyEngine.check_transition(&ctx);
+        assert!(!verdict.forbidden);
+        assert!(verdict.reason.is_none());
+        assert_eq!(verdict.fear.map(|f| f.systemic_harm), Some(0.6));
+
+        ctx.concept_key = "banned";
+        let verdict = LegacyEngine.check_transition(&ctx);
+        assert!(verdict.forbidden);
+        assert!(verdict.reason.is_none());
+        assert!(verdict.fear.is_none());
+
+        let verdict = ExplainingEngine.check_transition(&ctx);
+        assert!(verdict.forbidden);
+        assert_eq!(verdict.reason.as_deref(), Some("'banned' is banned"));
+    }
+
+    #[test]
+    fn legacy_engines_still_gate_step_world() {
+        let mut world = world_with_agent("r1");
+        set_field(&mut world, "alpha", "r1", 0.5);
+        set_field(&mut world, "banned", "r1", 0.5);
+        let mut log = decision_log::ZoneDecisionLog::default();
+        step_world_observed(&mut world, &ExplainingEngine, 1.0, &mut log);
+
+        let beliefs = &world.agents[0].beliefs;
+        assert!(beliefs.contains_key("alpha"));
+        assert!(!beliefs.contains_key("banned"));
+        let reasons: Vec<_> = log.forbidden().map(|entry| entry.outcome.clone()).collect();
+        assert_eq!(
+            reasons,
+            vec![decision_log::DecisionOutcome::Forbidden {
+                reason: Some("'banned' is banned".to_string())
+            }]
+        );
+    }
+}
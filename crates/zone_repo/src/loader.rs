@@ -0,0 +1,313 @@
+//! Scenario loading from spreadsheet exports (CSV) or a single JSON document.
+//!
+//! CSV layouts (with a header row):
+//! - regions: `name,population`
+//! - agents: `id,x,y,region`
+//! - intensities: `concept,region,value`
+
+use std::collections::HashSet;
+use std::fmt;
+use std::io::Read;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::{AgentId, HumanAgent, Location, World};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WorldLoadError {
+    /// The file could not be read or a row has the wrong shape.
+    Malformed { at: String, message: String },
+    /// A field could not be parsed.
+    InvalidField {
+        at: String,
+        field: &'static str,
+        value: String,
+    },
+    /// An agent or intensity refers to a region missing from the regions data.
+    UnknownRegion { at: String, region_id: String },
+    Json(String),
+}
+
+impl fmt::Display for WorldLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorldLoadError::Malformed { at, message } => write!(f, "{at}: {message}"),
+            WorldLoadError::InvalidField { at, field, value } => {
+                write!(f, "{at}: invalid {field} '{value}'")
+            }
+            WorldLoadError::UnknownRegion { at, region_id } => {
+                write!(f, "{at}: unknown region '{region_id}'")
+            }
+            WorldLoadError::Json(message) => write!(f, "scenario JSON: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for WorldLoadError {}
+
+fn read_rows<R: Read>(
+    file: &str,
+    reader: R,
+    columns: usize,
+) -> Result<Vec<(String, Vec<String>)>, WorldLoadError> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(reader);
+    let mut rows = Vec::new();
+    for record in rdr.records() {
+        let record = record.map_err(|e| WorldLoadError::Malformed {
+            at: format!(
+                "{file} line {}",
+                e.position().map(|p| p.line()).unwrap_or(0)
+            ),
+            message: e.to_string(),
+        })?;
+        let at = format!(
+            "{file} line {}",
+            record.position().map(|p| p.line()).unwrap_or(0)
+        );
+        if record.len() != columns {
+            return Err(WorldLoadError::Malformed {
+                at,
+                message: format!("expected {columns} fields, found {}", record.len()),
+            });
+        }
+        rows.push((at, record.iter().map(str::to_string).collect()));
+    }
+    Ok(rows)
+}
+
+fn parse_field<T: FromStr>(at: &str, field: &'static str, value: &str) -> Result<T, WorldLoadError> {
+    value.parse().map_err(|_| WorldLoadError::InvalidField {
+        at: at.to_string(),
+        field,
+        value: value.to_string(),
+    })
+}
+
+fn check_region(
+    regions: &HashSet<String>,
+    at: &str,
+    region_id: &str,
+) -> Result<(), WorldLoadError> {
+    if regions.contains(region_id) {
+        Ok(())
+    } else {
+        Err(WorldLoadError::UnknownRegion {
+            at: at.to_string(),
+            region_id: region_id.to_string(),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct ScenarioRegion {
+    name: String,
+    population: usize,
+}
+
+#[derive(Deserialize)]
+struct ScenarioAgent {
+    id: u64,
+    x: f64,
+    y: f64,
+    region: String,
+}
+
+#[derive(Deserialize)]
+struct ScenarioIntensity {
+    concept: String,
+    region: String,
+    value: f64,
+}
+
+/// Single-document scenario: the three CSV tables as JSON arrays.
+#[derive(Deserialize)]
+struct ScenarioDocument {
+    regions: Vec<ScenarioRegion>,
+    #[serde(default)]
+    agents: Vec<ScenarioAgent>,
+    #[serde(default)]
+    intensities: Vec<ScenarioIntensity>,
+}
+
+impl World {
+    pub fn from_csv_readers(
+        regions: impl Read,
+        agents: impl Read,
+        intensities: impl Read,
+    ) -> Result<World, WorldLoadError> {
+        let mut world = World::default();
+
+        for (at, row) in read_rows("regions", regions, 2)? {
+            let population = parse_field(&at, "population", &row[1])?;
+            world.region_populations.insert(row[0].clone(), population);
+        }
+        let known: HashSet<String> = world.region_populations.keys().cloned().collect();
+
+        for (at, row) in read_rows("agents", agents, 4)? {
+            let id = parse_field(&at, "id", &row[0])?;
+            let x = parse_field(&at, "x", &row[1])?;
+            let y = parse_field(&at, "y", &row[2])?;
+            check_region(&known, &at, &row[3])?;
+            world.agents.push(HumanAgent::new(
+                AgentId(id),
+                Location::new(x, y, &row[3]),
+            ));
+        }
+
+        for (at, row) in read_rows("intensities", intensities, 3)? {
+            check_region(&known, &at, &row[1])?;
+            let value = parse_field(&at, "value", &row[2])?;
+            world
+                .concept_fields
+                .insert((row[0].clone(), row[1].clone()), value);
+        }
+
+        Ok(world)
+    }
+
+    pub fn from_json_str(json: &str) -> Result<World, WorldLoadError> {
+        let doc: ScenarioDocument =
+            serde_json::from_str(json).map_err(|e| WorldLoadError::Json(e.to_string()))?;
+        let mut world = World::default();
+
+        for region in doc.regions {
+            world
+                .region_populations
+                .insert(region.name, region.population);
+        }
+        let known: HashSet<String> = world.region_populations.keys().cloned().collect();
+
+        for (i, agent) in doc.agents.into_iter().enumerate() {
+            check_region(&known, &format!("agents[{i}]"), &agent.region)?;
+            world.agents.push(HumanAgent::new(
+                AgentId(agent.id),
+                Location::new(agent.x, agent.y, &agent.region),
+            ));
+        }
+
+        for (i, entry) in doc.intensities.into_iter().enumerate() {
+            check_region(&known, &format!("intensities[{i}]"), &entry.region)?;
+            world
+                .concept_fields
+                .insert((entry.concept, entry.region), entry.value);
+        }
+
+        Ok(world)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Environment;
+
+    const REGIONS: &str = "name,population\nnorth,120\nsouth,80\n";
+    const AGENTS: &str = "id,x,y,region\n1,0.5,1.5,north\n2,3.0,4.0,south\n";
+    const INTENSITIES: &str = "concept,region,value\nalpha,north,0.4\nbeta,south,0.7\n";
+
+    #[test]
+    fn csv_tables_build_a_world() {
+        let world = World::from_csv_readers(
+            REGIONS.as_bytes(),
+            AGENTS.as_bytes(),
+            INTENSITIES.as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(world.region_populations["north"], 120);
+        assert_eq!(world.agents.len(), 2);
+        assert_eq!(world.agents[1].id, AgentId(2));
+        assert_eq!(world.agents[1].location.region_id, "south");
+        assert_eq!(world.agents[0].location.y, 1.5);
+        assert_eq!(world.get_concept_intensity("beta", "south"), 0.7);
+        assert!(world.validate().is_empty());
+    }
+
+    #[test]
+    fn malformed_row_reports_its_line() {
+        let agents = "id,x,y,region\n1,0.5,1.5,north\n2,3.0,south\n";
+        let err = World::from_csv_readers(
+            REGIONS.as_bytes(),
+            agents.as_bytes(),
+            INTENSITIES.as_bytes(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            WorldLoadError::Malformed {
+                at: "agents line 3".to_string(),
+                message: "expected 4 fields, found 3".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn unparsable_field_is_named() {
+        let intensities = "concept,region,value\nalpha,north,lots\n";
+        let err = World::from_csv_readers(
+            REGIONS.as_bytes(),
+            AGENTS.as_bytes(),
+            intensities.as_bytes(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            WorldLoadError::InvalidField {
+                at: "intensities line 2".to_string(),
+                field: "value",
+                value: "lots".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_regions_are_reported() {
+        let agents = "id,x,y,region\n1,0.5,1.5,east\n";
+        let err = World::from_csv_readers(
+            REGIONS.as_bytes(),
+            agents.as_bytes(),
+            INTENSITIES.as_bytes(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            WorldLoadError::UnknownRegion {
+                at: "agents line 2".to_string(),
+                region_id: "east".to_string(),
+            }
+        );
+
+        let json = r#"{
+            "regions": [{"name": "north", "population": 10}],
+            "intensities": [{"concept": "alpha", "region": "west", "value": 0.2}]
+        }"#;
+        assert_eq!(
+            World::from_json_str(json).unwrap_err(),
+            WorldLoadError::UnknownRegion {
+                at: "intensities[0]".to_string(),
+                region_id: "west".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn json_document_builds_the_same_world() {
+        let json = r#"{
+            "regions": [{"name": "north", "population": 120}, {"name": "south", "population": 80}],
+            "agents": [{"id": 1, "x": 0.5, "y": 1.5, "region": "north"}],
+            "intensities": [{"concept": "alpha", "region": "north", "value": 0.4}]
+        }"#;
+        let world = World::from_json_str(json).unwrap();
+        assert_eq!(world.region_populations.len(), 2);
+        assert_eq!(world.agents[0].location.x, 0.5);
+        assert_eq!(world.get_concept_intensity("alpha", "north"), 0.4);
+        assert!(matches!(
+            World::from_json_str("{\"regions\": 3}"),
+            Err(WorldLoadError::Json(_))
+        ));
+    }
+}
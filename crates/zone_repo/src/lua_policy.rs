@@ -0,0 +1,109 @@
+use crate::{FearIndex, PolicyContext, PolicyEngine};
+use mlua::{Function, Lua, RegistryKey, Result as LuaResult, Table, Value};
+
+/// Fear scored when the script fails: fail closed.
+const FAIL_CLOSED_FEAR: FearIndex = FearIndex {
+    systemic_harm: 1.0,
+    regret: 1.0,
+    ecological_damage: 1.0,
+};
+
+pub struct LuaPolicyEngine {
+    lua: Lua,
+    is_forbidden_fn: RegistryKey,
+    eval_transition_fn: RegistryKey,
+}
+
+impl LuaPolicyEngine {
+    pub fn new(script_source: &str) -> anyhow::Result<Self> {
+        let lua = Lua::new();
+
+        // Optional: sandbox for safety.
+        lua.sandbox(true)?;
+
+        // Load the script (behaviors.lua contents).
+        lua.load(script_source).exec()?;
+
+        let globals = lua.globals();
+        let module: Table = globals
+            .get("M")
+            .or_else(|_| globals.get("behaviors"))
+            .unwrap_or(globals);
+
+        let is_forbidden_fn: Function = module.get("is_transition_forbidden")?;
+        let eval_transition_fn: Function = module.get("evaluate_transition")?;
+
+        // Functions borrow the interpreter, so keep them in its registry and
+        // fetch them per call.
+        let is_forbidden_fn = lua.create_registry_value(is_forbidden_fn)?;
+        let eval_transition_fn = lua.create_registry_value(eval_transition_fn)?;
+        drop(module);
+
+        Ok(Self {
+            lua,
+            is_forbidden_fn,
+            eval_transition_fn,
+        })
+    }
+
+    fn ctx_to_lua_table(&self, ctx: &PolicyContext) -> LuaResult<Table<'_>> {
+        let tbl = self.lua.create_table()?;
+        tbl.set("agent_id", ctx.agent_id.0)?;
+        tbl.set("region_id", ctx.region_id)?;
+        tbl.set("concept_key", ctx.concept_key)?;
+        tbl.set("proposed_strength", ctx.proposed_strength.label())?;
+        tbl.set("proposed_strength_value", ctx.proposed_strength.to_scalar())?;
+        tbl.set("transition", format!("{:?}", ctx.transition))?;
+        tbl.set("env_time", ctx.env_time)?;
+        tbl.set("region_population", ctx.region_population)?;
+        tbl.set("region_capacity", ctx.region_capacity)?;
+        tbl.set("concept_intensity", ctx.concept_intensity)?;
+        tbl.set("interaction_conflict", ctx.interaction.conflict)?;
+        tbl.set("interaction_support", ctx.interaction.support)?;
+
+        match ctx.current_belief {
+            Some(b) => {
+                let b_tbl = self.lua.create_table()?;
+                b_tbl.set("key", b.key.as_str())?;
+                b_tbl.set("strength", b.strength.label())?;
+                b_tbl.set("strength_value", b.strength.to_scalar())?;
+                tbl.set("current_belief", b_tbl)?;
+            }
+            None => tbl.set("current_belief", Value::Nil)?,
+        }
+
+        Ok(tbl)
+    }
+
+    fn call<'lua, R: mlua::FromLuaMulti<'lua>>(
+        &'lua self,
+        function: &RegistryKey,
+        ctx: &PolicyContext,
+    ) -> LuaResult<R> {
+        let function: Function = self.lua.registry_value(function)?;
+        function.call(self.ctx_to_lua_table(ctx)?)
+    }
+}
+
+impl PolicyEngine for LuaPolicyEngine {
+    fn is_transition_forbidden(
+        &self,
+        ctx: &PolicyContext,
+    ) -> bool {
+        self.call(&self.is_forbidden_fn, ctx).unwrap_or(true) // fail-closed
+    }
+
+    fn evaluate_transition(
+        &self,
+        ctx: &PolicyContext,
+    ) -> FearIndex {
+        match self.call::<Table>(&self.eval_transition_fn, ctx) {
+            Ok(t) => FearIndex {
+                systemic_harm: t.get("systemic_harm").unwrap_or(1.0),
+                regret: t.get("regret").unwrap_or(1.0),
+                ecological_damage: t.get("ecological_damage").unwrap_or(1.0),
+            },
+            Err(_) => FAIL_CLOSED_FEAR,
+        }
+    }
+}
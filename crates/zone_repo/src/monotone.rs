@@ -0,0 +1,166 @@
+use crate::{FearIndex, PolicyContext, PolicyEngine, PolicyVerdict, TransitionKind};
+
+/// Forbids lowering or removing a belief unless the inner engine's
+/// `allows_downgrade` says otherwise. Adoptions and upgrades are left to
+/// the inner engine.
+pub struct MonotonePolicyWrapper<P> {
+    pub inner: P,
+}
+
+impl<P: PolicyEngine> MonotonePolicyWrapper<P> {
+    pub fn new(inner: P) -> Self {
+        Self { inner }
+    }
+
+    fn is_downgrade(ctx: &PolicyContext) -> bool {
+        match ctx.current_belief {
+            None => false,
+            Some(_) if ctx.transition == TransitionKind::Remove => true,
+            Some(current) => ctx.proposed_strength < current.strength,
+        }
+    }
+}
+
+impl<P: PolicyEngine> PolicyEngine for MonotonePolicyWrapper<P> {
+    fn is_transition_forbidden(
+        &self,
+        ctx: &PolicyContext,
+    ) -> bool {
+        if Self::is_downgrade(ctx) && !self.inner.allows_downgrade(ctx) {
+            return true;
+        }
+        self.inner.is_transition_forbidden(ctx)
+    }
+
+    fn evaluate_transition(
+        &self,
+        ctx: &PolicyContext,
+    ) -> FearIndex {
+        self.inner.evaluate_transition(ctx)
+    }
+
+    fn forbid_reason(
+        &self,
+        ctx: &PolicyContext,
+    ) -> Option<String> {
+        if Self::is_downgrade(ctx) && !self.inner.allows_downgrade(ctx) {
+            return Some(format!(
+                "downgrade of '{}' not permitted by inner engine",
+                ctx.concept_key
+            ));
+        }
+        self.inner.forbid_reason(ctx)
+    }
+
+    fn check_transition(
+        &self,
+        ctx: &PolicyContext,
+    ) -> PolicyVerdict {
+        if Self::is_downgrade(ctx) && !self.inner.allows_downgrade(ctx) {
+            return PolicyVerdict::forbid(self.forbid_reason(ctx));
+        }
+        self.inner.check_transition(ctx)
+    }
+
+    fn transition_cost(
+        &self,
+        ctx: &PolicyContext,
+    ) -> f64 {
+        self.inner.transition_cost(ctx)
+    }
+
+    fn allows_downgrade(
+        &self,
+        ctx: &PolicyContext,
+    ) -> bool {
+        self.inner.allows_downgrade(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AgentId, Belief, BeliefStrength, ZoneRepoPolicyEngine};
+
+    fn ctx<'a>(current: Option<&'a Belief>, proposed: BeliefStrength) -> PolicyContext<'a> {
+        PolicyContext {
+            agent_id: AgentId(1),
+            region_id: "r1",
+            concept_key: "alpha",
+            current_belief: current,
+            transition: TransitionKind::between(current.map(|b| &b.strength), &proposed),
+            proposed_strength: proposed,
+            env_time: 0.0,
+            region_population: 0,
+            region_capacity: None,
+            concept_intensity: 0.5,
+            interaction: Default::default(),
+        }
+    }
+
+    fn belief(strength: BeliefStrength) -> Belief {
+        Belief {
+            key: "alpha".to_string(),
+            strength,
+        }
+    }
+
+    /// Permits every downgrade.
+    struct Lenient;
+
+    impl PolicyEngine for Lenient {
+        fn is_transition_forbidden(&self, _ctx: &PolicyContext) -> bool {
+            false
+        }
+
+        fn evaluate_transition(&self, _ctx: &PolicyContext) -> FearIndex {
+            FearIndex::default()
+        }
+
+        fn allows_downgrade(&self, _ctx: &PolicyContext) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn adoption_without_a_current_belief_passes() {
+        let wrapper = MonotonePolicyWrapper::new(ZoneRepoPolicyEngine::new(1.0));
+        assert!(!wrapper.is_transition_forbidden(&ctx(None, BeliefStrength::Weak)));
+    }
+
+    #[test]
+    fn equal_rank_and_upgrades_pass() {
+        let wrapper = MonotonePolicyWrapper::new(ZoneRepoPolicyEngine::new(1.0));
+        let moderate = belief(BeliefStrength::Moderate);
+        assert!(!wrapper.is_transition_forbidden(&ctx(Some(&moderate), BeliefStrength::Moderate)));
+        assert!(!wrapper.is_transition_forbidden(&ctx(Some(&moderate), BeliefStrength::Strong)));
+        assert!(
+            !wrapper
+                .check_transition(&ctx(Some(&moderate), BeliefStrength::Graded(0.7)))
+                .forbidden
+        );
+    }
+
+    #[test]
+    fn downgrades_are_forbidden_unless_the_inner_engine_allows_them() {
+        let strong = belief(BeliefStrength::Strong);
+        let weaker = ctx(Some(&strong), BeliefStrength::Graded(0.9));
+
+        let strict = MonotonePolicyWrapper::new(ZoneRepoPolicyEngine::new(1.0));
+        assert!(strict.is_transition_forbidden(&weaker));
+        let verdict = strict.check_transition(&weaker);
+        assert!(verdict.forbidden);
+        assert_eq!(
+            verdict.reason.as_deref(),
+            Some("downgrade of 'alpha' not permitted by inner engine")
+        );
+
+        let mut removal = ctx(Some(&strong), BeliefStrength::Graded(0.0));
+        removal.transition = TransitionKind::Remove;
+        assert!(strict.is_transition_forbidden(&removal));
+
+        let lenient = MonotonePolicyWrapper::new(Lenient);
+        assert!(!lenient.is_transition_forbidden(&weaker));
+        assert!(!lenient.is_transition_forbidden(&removal));
+    }
+}
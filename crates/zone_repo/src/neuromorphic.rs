@@ -0,0 +1,336 @@
+//! `PolicyEngine` adapter that enforces a `NeuromorphicPolicyAttestationSpec`
+//! on agent transitions. Enabled with the `neuromorphic` feature.
+//!
+//! The spec's consent envelope and safety certificate do not depend on the
+//! transition, so they are verified once when the adapter is built and the
+//! outcome is reused for every decision.
+
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+use neuromorphic_policy::{
+    evaluate_neuromorphic_transition, DidLedgerVerifier, NeuromorphicNodeMetrics,
+    NeuromorphicPolicyAttestationSpec, PolicyDecision, SafetyCertificate, SignedConsentEnvelope,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{FearIndex, PolicyContext, PolicyEngine, PolicyVerdict};
+
+/// How a `PolicyContext` is projected onto node metrics.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NeuromorphicMappingWeights {
+    /// concept_intensity -> fear_index_node
+    pub fear_per_intensity: f64,
+    /// region_population / 1e6 -> eco_fear_node
+    pub eco_per_million_pop: f64,
+    /// proposed strength scalar -> FearIndex::regret
+    pub regret_per_strength: f64,
+    /// region_population * concept_intensity -> energy_kwh_per_day; power
+    /// is the same energy spread evenly over the day.
+    pub energy_kwh_per_exposed_agent: f64,
+    /// Concepts whose adoption carries irreversible bio-risk.
+    pub irreversible_bio_concepts: Vec<String>,
+}
+
+impl Default for NeuromorphicMappingWeights {
+    fn default() -> Self {
+        Self {
+            fear_per_intensity: 1.0,
+            eco_per_million_pop: 0.3,
+            regret_per_strength: 0.4,
+            energy_kwh_per_exposed_agent: 0.0,
+            irreversible_bio_concepts: Vec::new(),
+        }
+    }
+}
+
+/// Outcome of verifying a spec's envelope and certificate, replayed as a
+/// `DidLedgerVerifier`.
+#[derive(Clone, Debug)]
+struct CachedVerification {
+    consent: Result<(), String>,
+    certificate: Result<(), String>,
+}
+
+impl CachedVerification {
+    fn of(spec: &NeuromorphicPolicyAttestationSpec, verifier: &dyn DidLedgerVerifier) -> Self {
+        Self {
+            consent: verifier
+                .verify_consent_envelope(&spec.consent_envelope)
+                .map_err(|e| format!("{e:#}")),
+            certificate: verifier
+                .verify_safety_certificate(&spec.safety_certificate)
+                .map_err(|e| format!("{e:#}")),
+        }
+    }
+}
+
+impl DidLedgerVerifier for CachedVerification {
+    fn verify_consent_envelope(&self, _env: &SignedConsentEnvelope) -> anyhow::Result<()> {
+        self.consent.clone().map_err(|e| anyhow!(e))
+    }
+
+    fn verify_safety_certificate(&self, _cert: &SafetyCertificate) -> anyhow::Result<()> {
+        self.certificate.clone().map_err(|e| anyhow!(e))
+    }
+}
+
+pub struct NeuromorphicPolicyAdapter {
+    spec: NeuromorphicPolicyAttestationSpec,
+    pub weights: NeuromorphicMappingWeights,
+    verification: CachedVerification,
+}
+
+impl NeuromorphicPolicyAdapter {
+    /// Verifies `spec` with `verifier` once; the result applies to every
+    /// transition the adapter decides.
+    pub fn new(
+        spec: NeuromorphicPolicyAttestationSpec,
+        weights: NeuromorphicMappingWeights,
+        verifier: &dyn DidLedgerVerifier,
+    ) -> Self {
+        let verification = CachedVerification::of(&spec, verifier);
+        Self {
+            spec,
+            weights,
+            verification,
+        }
+    }
+
+    /// The enforced spec; read-only, since its verification is cached.
+    pub fn spec(&self) -> &NeuromorphicPolicyAttestationSpec {
+        &self.spec
+    }
+
+    pub fn metrics_for(&self, ctx: &PolicyContext) -> NeuromorphicNodeMetrics {
+        let energy_kwh_per_day = ctx.region_population as f64
+            * ctx.concept_intensity
+            * self.weights.energy_kwh_per_exposed_agent;
+        let telemetry_flags = HashMap::from([
+            ("concept_intensity".to_string(), ctx.concept_intensity),
+            (
+                "proposed_strength".to_string(),
+                ctx.proposed_strength.to_scalar(),
+            ),
+            ("region_population".to_string(), ctx.region_population as f64),
+        ]);
+        NeuromorphicNodeMetrics {
+            fear_index_node: ctx.concept_intensity * self.weights.fear_per_intensity,
+            eco_fear_node: (ctx.region_population as f64 / 1_000_000.0)
+                * self.weights.eco_per_million_pop,
+            irreversible_bio_risk: self
+                .weights
+                .irreversible_bio_concepts
+                .iter()
+                .any(|concept| concept == ctx.concept_key),
+            power_watts: energy_kwh_per_day * 1000.0 / 24.0,
+            energy_kwh_per_day,
+            telemetry_flags,
+        }
+    }
+
+    pub fn decide(&self, ctx: &PolicyContext) -> PolicyDecision {
+        let metrics = self.metrics_for(ctx);
+        evaluate_neuromorphic_transition(&self.spec, &metrics, &self.verification)
+    }
+}
+
+impl PolicyEngine for NeuromorphicPolicyAdapter {
+    fn is_transition_forbidden(
+        &self,
+        ctx: &PolicyContext,
+    ) -> bool {
+        !self.decide(ctx).allowed
+    }
+
+    fn forbid_reason(
+        &self,
+        ctx: &PolicyContext,
+    ) -> Option<String> {
+        let decision = self.decide(ctx);
+        (!decision.allowed).then_some(decision.reason)
+    }
+
+    fn check_transition(
+        &self,
+        ctx: &PolicyContext,
+    ) -> PolicyVerdict {
+        let decision = self.decide(ctx);
+        if decision.allowed {
+            PolicyVerdict::allow(self.evaluate_transition(ctx))
+        } else {
+            PolicyVerdict::forbid(Some(decision.reason))
+        }
+    }
+
+    fn evaluate_transition(
+        &self,
+        ctx: &PolicyContext,
+    ) -> FearIndex {
+        let metrics = self.metrics_for(ctx);
+        FearIndex {
+            systemic_harm: metrics.fear_index_node,
+            regret: ctx.proposed_strength.to_scalar() * self.weights.regret_per_strength,
+            ecological_damage: metrics.eco_fear_node,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use neuromorphic_policy::{ConsentEnvelope, EcoBudget, EthicalCeiling, SafetyCeilingParams};
+
+    use super::*;
+    use crate::{step_world, AgentId, HumanAgent, Location, World};
+
+    /// Accepts everything, counting how often it is asked.
+    #[derive(Default)]
+    struct CountingVerifier {
+        calls: Cell<usize>,
+    }
+
+    impl DidLedgerVerifier for CountingVerifier {
+        fn verify_consent_envelope(&self, _env: &SignedConsentEnvelope) -> anyhow::Result<()> {
+            self.calls.set(self.calls.get() + 1);
+            Ok(())
+        }
+
+        fn verify_safety_certificate(&self, _cert: &SafetyCertificate) -> anyhow::Result<()> {
+            self.calls.set(self.calls.get() + 1);
+            Ok(())
+        }
+    }
+
+    fn spec(max_fear_index_node: f64) -> NeuromorphicPolicyAttestationSpec {
+        NeuromorphicPolicyAttestationSpec {
+            cluster_id: "test".to_string(),
+            namespace: "default".to_string(),
+            helm_release: None,
+            node_class: "loihi".to_string(),
+            telemetry_contract_id: None,
+            bci_coupling: 0.0,
+            eco_budget: EcoBudget {
+                max_eco_fear_node: 1.0,
+                max_energy_kwh_per_day: 100.0,
+                region_profile_id: "test".to_string(),
+            },
+            ethical_ceiling: EthicalCeiling {
+                max_fear_index_node,
+                max_eco_damage_node: 1.0,
+                forbid_irreversible_bio: true,
+            },
+            consent_envelope: SignedConsentEnvelope {
+                envelope: ConsentEnvelope {
+                    transcript_root: String::new(),
+                    workspace_hash: String::new(),
+                    fear_index_max: 1.0,
+                    eco_fear_max: 1.0,
+                    fairness_score: 1.0,
+                    issuer_did: String::new(),
+                    additional_signers: Vec::new(),
+                    envelope_hash: String::new(),
+                    anchors: Vec::new(),
+                },
+                signatures: Vec::new(),
+            },
+            safety_certificate: SafetyCertificate {
+                certificate_id: "cert".to_string(),
+                ethical_ceiling: SafetyCeilingParams {
+                    tau_p: 1.0,
+                    tau_f: 1.0,
+                    tau_e: 1.0,
+                },
+                anchors: Vec::new(),
+            },
+        }
+    }
+
+    fn world_with(intensities: &[(&str, f64)]) -> World {
+        let mut world = World::default();
+        world.region_populations.insert("r1".to_string(), 100);
+        world
+            .agents
+            .push(HumanAgent::new(AgentId(1), Location::new(0.0, 0.0, "r1")));
+        for (concept_key, intensity) in intensities {
+            world
+                .concept_fields
+                .insert((concept_key.to_string(), "r1".to_string()), *intensity);
+        }
+        world
+    }
+
+    #[test]
+    fn low_fear_ceiling_blocks_high_intensity_adoptions() {
+        let verifier = CountingVerifier::default();
+        let adapter = NeuromorphicPolicyAdapter::new(
+            spec(0.5),
+            NeuromorphicMappingWeights::default(),
+            &verifier,
+        );
+        let mut world = world_with(&[("calm", 0.3), ("hot", 0.9)]);
+        for _ in 0..3 {
+            step_world(&mut world, &adapter, 1.0);
+        }
+        let beliefs = &world.agents[0].beliefs;
+        assert!(beliefs.contains_key("calm"));
+        assert!(!beliefs.contains_key("hot"));
+        assert_eq!(verifier.calls.get(), 2, "spec verified once");
+    }
+
+    #[test]
+    fn bio_risk_and_energy_come_from_the_context() {
+        let weights = NeuromorphicMappingWeights {
+            energy_kwh_per_exposed_agent: 1.0,
+            irreversible_bio_concepts: vec!["gene_drive".to_string()],
+            ..NeuromorphicMappingWeights::default()
+        };
+        let adapter =
+            NeuromorphicPolicyAdapter::new(spec(1.0), weights, &CountingVerifier::default());
+        // 100 agents * 0.5 intensity = 50 kWh/day, under the 100 budget.
+        let mut world = world_with(&[("gene_drive", 0.3), ("solar", 0.5)]);
+        step_world(&mut world, &adapter, 1.0);
+        let beliefs = &world.agents[0].beliefs;
+        assert!(!beliefs.contains_key("gene_drive"));
+        assert!(beliefs.contains_key("solar"));
+
+        // 100 * 0.9 * 2 = 180 kWh/day is over budget.
+        let adapter = NeuromorphicPolicyAdapter::new(
+            spec(1.0),
+            NeuromorphicMappingWeights {
+                energy_kwh_per_exposed_agent: 2.0,
+                ..NeuromorphicMappingWeights::default()
+            },
+            &CountingVerifier::default(),
+        );
+        let mut world = world_with(&[("solar", 0.9)]);
+        step_world(&mut world, &adapter, 1.0);
+        assert!(world.agents[0].beliefs.is_empty());
+    }
+
+    #[test]
+    fn failed_verification_forbids_every_transition() {
+        struct Rejecting;
+
+        impl DidLedgerVerifier for Rejecting {
+            fn verify_consent_envelope(&self, _env: &SignedConsentEnvelope) -> anyhow::Result<()> {
+                anyhow::bail!("unsigned")
+            }
+
+            fn verify_safety_certificate(&self, _cert: &SafetyCertificate) -> anyhow::Result<()> {
+                Ok(())
+            }
+        }
+
+        let adapter = NeuromorphicPolicyAdapter::new(
+            spec(1.0),
+            NeuromorphicMappingWeights::default(),
+            &Rejecting,
+        );
+        let mut world = world_with(&[("calm", 0.1)]);
+        step_world(&mut world, &adapter, 1.0);
+        assert!(world.agents[0].beliefs.is_empty());
+    }
+}
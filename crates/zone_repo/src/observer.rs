@@ -0,0 +1,164 @@
+use crate::{AgentId, BeliefStrength, FearIndex, PolicyContext};
+
+/// Receives the outcome of every transition an agent considers during a tick.
+///
+/// For applied transitions `ctx.current_belief` is the old belief and
+/// `ctx.proposed_strength` the new one.
+pub trait TransitionObserver {
+    fn on_transition_applied(&mut self, _ctx: &PolicyContext, _fear: &FearIndex) {}
+
+    /// `reason` is the engine's `forbid_reason`, when it provides one.
+    fn on_transition_forbidden(&mut self, _ctx: &PolicyContext, _reason: Option<&str>) {}
+
+    /// Called for an allowed transition before it is applied, with its
+    /// `PolicyEngine::transition_cost`. Returning false defers it: it is
+    /// skipped this tick and reported through `on_transition_deferred`.
+    fn admit_cost(&mut self, _ctx: &PolicyContext, _cost: f64) -> bool {
+        true
+    }
+
+    /// An allowed transition skipped because it did not fit the budget.
+    fn on_transition_deferred(&mut self, _ctx: &PolicyContext) {}
+}
+
+/// Observer that ignores everything; used by plain `step_world`.
+impl TransitionObserver for () {}
+
+#[derive(Clone, Debug)]
+pub enum TransitionEvent {
+    Applied {
+        time: f64,
+        agent_id: AgentId,
+        region_id: String,
+        concept_key: String,
+        old: Option<BeliefStrength>,
+        new: BeliefStrength,
+        fear: FearIndex,
+    },
+    Forbidden {
+        time: f64,
+        agent_id: AgentId,
+        region_id: String,
+        concept_key: String,
+        proposed: BeliefStrength,
+        reason: Option<String>,
+    },
+    /// Allowed by policy but skipped for budget ("budget-deferred").
+    Deferred {
+        time: f64,
+        agent_id: AgentId,
+        region_id: String,
+        concept_key: String,
+        proposed: BeliefStrength,
+    },
+}
+
+/// Collects every event in order of occurrence.
+#[derive(Clone, Debug, Default)]
+pub struct VecObserver {
+    pub events: Vec<TransitionEvent>,
+}
+
+impl TransitionObserver for VecObserver {
+    fn on_transition_applied(&mut self, ctx: &PolicyContext, fear: &FearIndex) {
+        self.events.push(TransitionEvent::Applied {
+            time: ctx.env_time,
+            agent_id: ctx.agent_id.clone(),
+            region_id: ctx.region_id.to_string(),
+            concept_key: ctx.concept_key.to_string(),
+            old: ctx.current_belief.map(|b| b.strength.clone()),
+            new: ctx.proposed_strength.clone(),
+            fear: fear.clone(),
+        });
+    }
+
+    fn on_transition_forbidden(&mut self, ctx: &PolicyContext, reason: Option<&str>) {
+        self.events.push(TransitionEvent::Forbidden {
+            time: ctx.env_time,
+            agent_id: ctx.agent_id.clone(),
+            region_id: ctx.region_id.to_string(),
+            concept_key: ctx.concept_key.to_string(),
+            proposed: ctx.proposed_strength.clone(),
+            reason: reason.map(str::to_string),
+        });
+    }
+
+    fn on_transition_deferred(&mut self, ctx: &PolicyContext) {
+        self.events.push(TransitionEvent::Deferred {
+            time: ctx.env_time,
+            agent_id: ctx.agent_id.clone(),
+            region_id: ctx.region_id.to_string(),
+            concept_key: ctx.concept_key.to_string(),
+            proposed: ctx.proposed_strength.clone(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{step_world_observed, HumanAgent, Location, World, ZoneRepoPolicyEngine};
+
+    #[test]
+    fn vec_observer_collects_applied_and_forbidden_events() {
+        let mut world = World::default();
+        world.region_populations.insert("calm".to_string(), 0);
+        world
+            .region_populations
+            .insert("crowded".to_string(), 20_000);
+        for (id, region_id) in [(1, "calm"), (2, "crowded")] {
+            world.agents.push(HumanAgent::new(
+                AgentId(id),
+                Location::new(0.0, 0.0, region_id),
+            ));
+        }
+        for region_id in ["calm", "crowded"] {
+            world
+                .concept_fields
+                .insert(("alpha".to_string(), region_id.to_string()), 0.8);
+        }
+
+        let mut observer = VecObserver::default();
+        step_world_observed(
+            &mut world,
+            &ZoneRepoPolicyEngine::new(1.0),
+            1.0,
+            &mut observer,
+        );
+
+        assert_eq!(observer.events.len(), 2);
+        match &observer.events[0] {
+            TransitionEvent::Applied {
+                time,
+                agent_id,
+                concept_key,
+                old,
+                new,
+                fear,
+                ..
+            } => {
+                assert_eq!(*time, 1.0);
+                assert_eq!(*agent_id, AgentId(1));
+                assert_eq!(concept_key, "alpha");
+                assert!(old.is_none());
+                assert_eq!(new.to_scalar(), 0.8);
+                assert!(fear.systemic_harm > 0.0);
+            }
+            other => panic!("expected an applied event, got {other:?}"),
+        }
+        match &observer.events[1] {
+            TransitionEvent::Forbidden {
+                agent_id,
+                region_id,
+                reason,
+                ..
+            } => {
+                assert_eq!(*agent_id, AgentId(2));
+                assert_eq!(region_id, "crowded");
+                assert!(reason.as_deref().unwrap().contains("ethical ceiling"));
+            }
+            other => panic!("expected a forbidden event, got {other:?}"),
+        }
+        assert!(world.agents[1].beliefs.is_empty());
+    }
+}
@@ -0,0 +1,318 @@
+//! Agents entering and leaving the world mid-run.
+//!
+//! `spawn_agent` / `remove_agent` act immediately and are meant to be called
+//! between ticks. Changes requested while a tick may be in progress go
+//! through `queue_spawn` / `queue_removal` and are applied at the start of
+//! the next tick, as is the `PopulationSchedule`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AgentId, HumanAgent, PopulationMode, World};
+
+/// Spawn `count` clones of `template` into `region_id`, spread evenly over
+/// `[start_time, start_time + duration)`. Clones get fresh ids and the
+/// window's region; coordinates and beliefs come from the template.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PopulationWindow {
+    pub region_id: String,
+    pub start_time: f64,
+    pub duration: f64,
+    pub count: usize,
+    pub template: HumanAgent,
+    spawned: usize,
+}
+
+impl PopulationWindow {
+    pub fn new(
+        region_id: &str,
+        start_time: f64,
+        duration: f64,
+        count: usize,
+        template: HumanAgent,
+    ) -> Self {
+        Self {
+            region_id: region_id.to_string(),
+            start_time,
+            duration,
+            count,
+            template,
+            spawned: 0,
+        }
+    }
+
+    /// Agents this window should have spawned by `time`.
+    fn due_by(&self, time: f64) -> usize {
+        let elapsed = time - self.start_time;
+        if elapsed < 0.0 {
+            return 0;
+        }
+        if self.duration <= 0.0 || elapsed >= self.duration {
+            return self.count;
+        }
+        ((self.count as f64) * elapsed / self.duration).floor() as usize
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PopulationSchedule {
+    pub windows: Vec<PopulationWindow>,
+}
+
+impl PopulationSchedule {
+    pub fn push(&mut self, window: PopulationWindow) {
+        self.windows.push(window);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+}
+
+/// Spawns and removals waiting for the next tick boundary.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PendingPopulationChanges {
+    pub spawns: Vec<HumanAgent>,
+    pub removals: Vec<AgentId>,
+}
+
+impl World {
+    /// Add an agent now, keeping `agent_index` and (in `AgentDerived` mode)
+    /// `region_populations` in step.
+    pub fn spawn_agent(&mut self, agent: HumanAgent) {
+        if self.population_mode == PopulationMode::AgentDerived {
+            *self
+                .region_populations
+                .entry(agent.location.region_id.clone())
+                .or_insert(0) += 1;
+        }
+        self.next_free_agent_id = self.next_free_agent_id.max(agent.id.0 + 1);
+        self.agent_index.insert(agent.id.clone(), self.agents.len());
+        self.agents.push(agent);
+    }
+
+    /// Remove an agent now, and from its households. The last agent takes
+    /// its place in `agents`.
+    pub fn remove_agent(&mut self, id: &AgentId) -> Option<HumanAgent> {
+        let i = match self.index_of(id) {
+            Some(i) => i,
+            None => {
+                self.rebuild_agent_index();
+                self.index_of(id)?
+            }
+        };
+        let agent = self.agents.swap_remove(i);
+        self.agent_index.remove(id);
+        if let Some(moved) = self.agents.get(i) {
+            self.agent_index.insert(moved.id.clone(), i);
+        }
+        for household in &mut self.households {
+            household.member_ids.retain(|member| member != id);
+        }
+        if self.population_mode == PopulationMode::AgentDerived {
+            if let Some(count) = self.region_populations.get_mut(&agent.location.region_id) {
+                *count = count.saturating_sub(1);
+            }
+        }
+        Some(agent)
+    }
+
+    pub fn queue_spawn(&mut self, agent: HumanAgent) {
+        self.pending_population.spawns.push(agent);
+    }
+
+    pub fn queue_removal(&mut self, id: AgentId) {
+        self.pending_population.removals.push(id);
+    }
+
+    /// An id above every agent's, current or removed. Scans `agents` only
+    /// when some were pushed directly rather than through `spawn_agent`.
+    pub fn next_agent_id(&self) -> AgentId {
+        if self.agent_index.len() == self.agents.len() {
+            return AgentId(self.next_free_agent_id);
+        }
+        let max = self.agents.iter().map(|a| a.id.0 + 1).max().unwrap_or(0);
+        AgentId(max.max(self.next_free_agent_id))
+    }
+
+    /// Apply queued changes, then spawn whatever the schedule has due by
+    /// the current time. Called at the start of every tick.
+    pub fn apply_population_changes(&mut self) {
+        let pending = std::mem::take(&mut self.pending_population);
+        for id in &pending.removals {
+            self.remove_agent(id);
+        }
+        for agent in pending.spawns {
+            self.spawn_agent(agent);
+        }
+
+        if self.population_schedule.is_empty() {
+            return;
+        }
+        let mut schedule = std::mem::take(&mut self.population_schedule);
+        for window in &mut schedule.windows {
+            let due = window.due_by(self.time);
+            while window.spawned < due {
+                let mut agent = window.template.clone();
+                agent.id = self.next_agent_id();
+                agent.location.region_id = window.region_id.clone();
+                self.spawn_agent(agent);
+                window.spawned += 1;
+            }
+        }
+        self.population_schedule = schedule;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feedback::FeedbackConfig;
+    use crate::monotone::MonotonePolicyWrapper;
+    use crate::{step_world, Belief, BeliefStrength, Environment, Location, ZoneRepoPolicyEngine};
+
+    fn agent(id: u64, region_id: &str) -> HumanAgent {
+        HumanAgent::new(AgentId(id), Location::new(0.0, 0.0, region_id))
+    }
+
+    fn derived_world() -> World {
+        let mut world = World {
+            population_mode: PopulationMode::AgentDerived,
+            ..World::default()
+        };
+        world.region_populations.insert("r1".to_string(), 0);
+        world.region_populations.insert("r2".to_string(), 0);
+        world
+    }
+
+    fn ids(world: &World) -> Vec<u64> {
+        let mut ids: Vec<u64> = world.agents.iter().map(|a| a.id.0).collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn spawns_and_removals_keep_populations_in_step() {
+        let mut world = derived_world();
+        for id in 0..3 {
+            world.spawn_agent(agent(id, "r1"));
+        }
+        world.spawn_agent(agent(3, "r2"));
+        assert_eq!(world.region_populations["r1"], 3);
+        assert_eq!(world.region_populations["r2"], 1);
+
+        assert_eq!(
+            world.remove_agent(&AgentId(1)).map(|a| a.id),
+            Some(AgentId(1))
+        );
+        assert!(world.remove_agent(&AgentId(1)).is_none());
+        assert_eq!(world.region_populations["r1"], 2);
+
+        world.queue_removal(AgentId(3));
+        world.queue_spawn(agent(9, "r2"));
+        world.queue_spawn(agent(10, "r2"));
+        // Queued changes wait for the next tick.
+        assert_eq!(world.agents.len(), 3);
+        step_world(&mut world, &ZoneRepoPolicyEngine::new(1.0), 1.0);
+        assert_eq!(ids(&world), vec![0, 2, 9, 10]);
+        assert_eq!(world.region_populations["r1"], 2);
+        assert_eq!(world.region_populations["r2"], 2);
+    }
+
+    #[test]
+    fn schedule_spreads_spawns_over_its_window() {
+        let mut world = derived_world();
+        world.spawn_agent(agent(0, "r1"));
+        world
+            .population_schedule
+            .push(PopulationWindow::new("r2", 1.0, 4.0, 8, agent(0, "r1")));
+        let policy = ZoneRepoPolicyEngine::new(1.0);
+        let mut counts = Vec::new();
+        for _ in 0..7 {
+            step_world(&mut world, &policy, 1.0);
+            counts.push(world.region_populations["r2"]);
+        }
+        // Each tick applies what was due at its start time: 0, 1, 2, ...
+        assert_eq!(counts, vec![0, 0, 2, 4, 6, 8, 8]);
+        assert_eq!(world.region_populations["r1"], 1);
+        assert_eq!(ids(&world), (0..9).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn removal_keeps_the_index_consistent_and_ids_unused() {
+        let mut world = derived_world();
+        for id in 0..5 {
+            world.spawn_agent(agent(id, "r1"));
+        }
+        world.remove_agent(&AgentId(1));
+        world.remove_agent(&AgentId(4));
+        assert_eq!(world.agent_index.len(), world.agents.len());
+        for (i, a) in world.agents.iter().enumerate() {
+            assert_eq!(world.index_of(&a.id), Some(i));
+        }
+        // Ids of removed agents are not handed out again.
+        assert_eq!(world.next_agent_id(), AgentId(5));
+
+        // Agents pushed directly are still accounted for.
+        world.agents.push(agent(12, "r1"));
+        assert_eq!(world.next_agent_id(), AgentId(13));
+    }
+
+    #[test]
+    fn removed_believer_stops_feeding_the_field() {
+        let mut world = derived_world();
+        world.feedback = Some(FeedbackConfig::default());
+        world
+            .concept_fields
+            .insert(("alpha".to_string(), "r1".to_string()), 0.5);
+        let mut believer = agent(0, "r1");
+        believer.beliefs.insert(
+            "alpha".to_string(),
+            Belief {
+                key: "alpha".to_string(),
+                strength: BeliefStrength::Strong,
+            },
+        );
+        world.spawn_agent(believer);
+        world.spawn_agent(agent(1, "r2"));
+        let policy = MonotonePolicyWrapper::new(ZoneRepoPolicyEngine::new(1.0));
+
+        step_world(&mut world, &policy, 1.0);
+        let held = world.get_concept_intensity("alpha", "r1");
+        world.queue_removal(AgentId(0));
+        let mut previous = held;
+        for _ in 0..5 {
+            step_world(&mut world, &policy, 1.0);
+            let intensity = world.get_concept_intensity("alpha", "r1");
+            assert!(intensity < previous, "{intensity} after {previous}");
+            previous = intensity;
+        }
+        assert!(world.agents.iter().all(|a| a.beliefs.is_empty()));
+    }
+
+    #[test]
+    fn snapshot_resumes_a_window_part_way_through() {
+        let mut world = derived_world();
+        world
+            .population_schedule
+            .push(PopulationWindow::new("r2", 0.0, 4.0, 4, agent(0, "r1")));
+        let policy = ZoneRepoPolicyEngine::new(1.0);
+        for _ in 0..2 {
+            step_world(&mut world, &policy, 1.0);
+        }
+        world.queue_spawn(agent(50, "r1"));
+        let snapshot = world.snapshot();
+        for _ in 0..4 {
+            step_world(&mut world, &policy, 1.0);
+        }
+        let expected = ids(&world);
+        assert_eq!(expected, vec![0, 50, 51, 52, 53]);
+
+        let mut resumed = derived_world();
+        resumed.restore(&snapshot);
+        for _ in 0..4 {
+            step_world(&mut resumed, &policy, 1.0);
+        }
+        assert_eq!(ids(&resumed), expected);
+        assert_eq!(resumed.region_populations, world.region_populations);
+    }
+}
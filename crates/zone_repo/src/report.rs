@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+
+use crate::observer::TransitionObserver;
+use crate::{FearIndex, PolicyContext};
+
+/// Component-wise sum and max of the fear indices seen in a tick.
+#[derive(Clone, Debug, Default)]
+pub struct FearAggregate {
+    pub sum: FearIndex,
+    pub max: FearIndex,
+}
+
+impl FearAggregate {
+    pub fn record(&mut self, fear: &FearIndex) {
+        self.sum = self.sum.add(fear);
+        self.max.systemic_harm = self.max.systemic_harm.max(fear.systemic_harm);
+        self.max.regret = self.max.regret.max(fear.regret);
+        self.max.ecological_damage = self.max.ecological_damage.max(fear.ecological_damage);
+    }
+}
+
+/// Summary of one `step_world` tick.
+#[derive(Clone, Debug, Default)]
+pub struct TickReport {
+    pub time: f64,
+    pub total: FearAggregate,
+    pub by_region: BTreeMap<String, FearAggregate>,
+    pub applied: usize,
+    pub forbidden: usize,
+    /// Allowed transitions skipped for budget.
+    pub deferred: usize,
+    /// Resource cost of applied transitions.
+    pub cost: f64,
+    pub cost_by_region: BTreeMap<String, f64>,
+}
+
+impl TransitionObserver for TickReport {
+    fn on_transition_applied(&mut self, ctx: &PolicyContext, fear: &FearIndex) {
+        self.applied += 1;
+        self.total.record(fear);
+        self.by_region
+            .entry(ctx.region_id.to_string())
+            .or_default()
+            .record(fear);
+    }
+
+    fn on_transition_forbidden(&mut self, _ctx: &PolicyContext, _reason: Option<&str>) {
+        self.forbidden += 1;
+    }
+
+    fn admit_cost(&mut self, ctx: &PolicyContext, cost: f64) -> bool {
+        self.cost += cost;
+        *self
+            .cost_by_region
+            .entry(ctx.region_id.to_string())
+            .or_insert(0.0) += cost;
+        true
+    }
+
+    fn on_transition_deferred(&mut self, _ctx: &PolicyContext) {
+        self.deferred += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        step_world_with_report, AgentId, HumanAgent, Location, World, ZoneRepoPolicyEngine,
+    };
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    #[test]
+    fn report_matches_hand_computed_fears() {
+        let mut world = World::default();
+        for (region_id, population) in [("a", 0), ("b", 0), ("crowded", 20_000)] {
+            world
+                .region_populations
+                .insert(region_id.to_string(), population);
+        }
+        for (id, region_id, intensity) in [(1, "a", 0.5), (2, "b", 1.0), (3, "crowded", 0.8)] {
+            world.agents.push(HumanAgent::new(
+                AgentId(id),
+                Location::new(0.0, 0.0, region_id),
+            ));
+            world
+                .concept_fields
+                .insert(("alpha".to_string(), region_id.to_string()), intensity);
+        }
+
+        let report = step_world_with_report(&mut world, &ZoneRepoPolicyEngine::new(1.0), 2.0);
+
+        // a: harm 0.5 * 0.5, regret interpolated between weak and moderate
+        // (0.1 + 0.1 * 0.3 / 0.4); b: harm 0.5, regret strong. Empty regions
+        // carry no ecological damage; the crowded one is over its ceiling.
+        assert_eq!(report.time, 2.0);
+        assert_eq!(report.applied, 2);
+        assert_eq!(report.forbidden, 1);
+        assert!(close(report.total.sum.systemic_harm, 0.75));
+        assert!(close(report.total.sum.regret, 0.175 + 0.4));
+        assert!(close(report.total.sum.ecological_damage, 0.0));
+        assert!(close(report.total.max.systemic_harm, 0.5));
+        assert!(close(report.total.max.regret, 0.4));
+        assert_eq!(report.by_region.keys().collect::<Vec<_>>(), vec!["a", "b"]);
+        assert!(close(report.by_region["a"].sum.systemic_harm, 0.25));
+        assert!(close(report.by_region["a"].sum.regret, 0.175));
+    }
+}
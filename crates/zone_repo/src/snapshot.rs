@@ -0,0 +1,223 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::injection::InjectionSchedule;
+use crate::population::{PendingPopulationChanges, PopulationSchedule};
+use crate::{step_world, AgentId, HumanAgent, PolicyEngine, World};
+
+/// Mutable state of a `World` at one instant. Static configuration
+/// (neighbors, diffusion, point sources) is not captured; the injection and
+/// population schedules are, since they track what they have applied.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    pub time: f64,
+    pub agents: Vec<HumanAgent>,
+    pub region_populations: BTreeMap<String, usize>,
+    #[serde(with = "crate::checkpoint::pair_keyed")]
+    pub concept_fields: BTreeMap<(String, String), f64>,
+    pub rng: ChaCha12Rng,
+    pub injections: InjectionSchedule,
+    #[serde(default)]
+    pub resource_spent: f64,
+    #[serde(default)]
+    pub population_schedule: PopulationSchedule,
+    #[serde(default)]
+    pub pending_population: PendingPopulationChanges,
+    /// `World::agent_index` by raw id, ordered so output is reproducible.
+    #[serde(default)]
+    pub agent_index: BTreeMap<u64, usize>,
+    #[serde(default)]
+    pub next_free_agent_id: u64,
+}
+
+impl World {
+    pub fn snapshot(&self) -> WorldSnapshot {
+        WorldSnapshot {
+            time: self.time,
+            agents: self.agents.clone(),
+            region_populations: self.region_populations.clone(),
+            concept_fields: self.concept_fields.clone(),
+            rng: self.rng.clone(),
+            injections: self.injections.clone(),
+            resource_spent: self.resource_spent,
+            population_schedule: self.population_schedule.clone(),
+            pending_population: self.pending_population.clone(),
+            agent_index: self.agent_index.iter().map(|(id, &i)| (id.0, i)).collect(),
+            next_free_agent_id: self.next_free_agent_id,
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: &WorldSnapshot) {
+        self.time = snapshot.time;
+        self.agents = snapshot.agents.clone();
+        self.region_populations = snapshot.region_populations.clone();
+        self.concept_fields = snapshot.concept_fields.clone();
+        self.rng = snapshot.rng.clone();
+        self.injections = snapshot.injections.clone();
+        self.resource_spent = snapshot.resource_spent;
+        self.population_schedule = snapshot.population_schedule.clone();
+        self.pending_population = snapshot.pending_population.clone();
+        self.agent_index = snapshot
+            .agent_index
+            .iter()
+            .map(|(&id, &i)| (AgentId(id), i))
+            .collect();
+        self.next_free_agent_id = snapshot.next_free_agent_id;
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SnapshotPolicy {
+    pub every_n_ticks: u64,
+    pub keep_last: usize,
+}
+
+/// Bounded history of snapshots taken according to a `SnapshotPolicy`.
+#[derive(Clone, Debug)]
+pub struct SnapshotRing {
+    pub policy: SnapshotPolicy,
+    ticks: u64,
+    snapshots: VecDeque<WorldSnapshot>,
+}
+
+impl SnapshotRing {
+    pub fn new(policy: SnapshotPolicy) -> Self {
+        Self {
+            policy,
+            ticks: 0,
+            snapshots: VecDeque::new(),
+        }
+    }
+
+    /// Store a snapshot, evicting the oldest once `keep_last` is exceeded.
+    pub fn push(&mut self, snapshot: WorldSnapshot) {
+        if self.policy.keep_last == 0 {
+            return;
+        }
+        self.snapshots.push_back(snapshot);
+        while self.snapshots.len() > self.policy.keep_last {
+            self.snapshots.pop_front();
+        }
+    }
+
+    /// Count a finished tick and snapshot `world` if the policy says so.
+    pub fn record(&mut self, world: &World) {
+        self.ticks += 1;
+        if self.policy.every_n_ticks > 0 && self.ticks.is_multiple_of(self.policy.every_n_ticks) {
+            self.push(world.snapshot());
+        }
+    }
+
+    /// Drop the `n` most recent snapshots and return the newest remaining
+    /// one, i.e. the state `n` snapshots ago.
+    pub fn rewind(&mut self, n: usize) -> Option<WorldSnapshot> {
+        if n >= self.snapshots.len() {
+            return None;
+        }
+        self.snapshots.truncate(self.snapshots.len() - n);
+        self.snapshots.back().cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+}
+
+/// `step_world`, then record a snapshot into `ring` per its policy.
+pub fn step_world_with_snapshots<P: PolicyEngine>(
+    world: &mut World,
+    policies: &P,
+    dt: f64,
+    ring: &mut SnapshotRing,
+) {
+    step_world(world, policies, dt);
+    ring.record(world);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diffusion::DiffusionConfig;
+    use crate::injection::{Injection, InjectionShape};
+    use crate::{AdoptionNoise, AgentId, Location, ZoneRepoPolicyEngine};
+
+    fn scenario() -> World {
+        let mut world = World::seeded(11);
+        for (region_id, neighbor) in [("a", "b"), ("b", "a")] {
+            world.region_populations.insert(region_id.to_string(), 0);
+            world
+                .region_neighbors
+                .insert(region_id.to_string(), vec![neighbor.to_string()]);
+        }
+        world.diffusion = Some(DiffusionConfig::default());
+        world.injections.push(Injection {
+            concept_key: "alpha".to_string(),
+            region_id: "a".to_string(),
+            start_time: 2.0,
+            duration: 6.0,
+            peak_intensity: 0.8,
+            shape: InjectionShape::Pulse,
+        });
+        for id in 0..6 {
+            let region_id = if id % 2 == 0 { "a" } else { "b" };
+            let mut agent = HumanAgent::new(AgentId(id), Location::new(0.0, 0.0, region_id));
+            agent.adoption_noise = Some(AdoptionNoise::default());
+            world.agents.push(agent);
+        }
+        world
+    }
+
+    fn state(world: &World) -> String {
+        serde_json::to_string(&world.snapshot()).unwrap()
+    }
+
+    #[test]
+    fn rollback_and_rerun_matches_a_fresh_run() {
+        let policy = ZoneRepoPolicyEngine::new(1.0);
+        let mut ring = SnapshotRing::new(SnapshotPolicy {
+            every_n_ticks: 1,
+            keep_last: 10,
+        });
+        let mut world = scenario();
+        for _ in 0..10 {
+            step_world_with_snapshots(&mut world, &policy, 1.0, &mut ring);
+        }
+        assert_eq!(ring.len(), 10);
+
+        let seven = ring.rewind(3).unwrap();
+        assert_eq!(seven.time, 7.0);
+        world.restore(&seven);
+        for _ in 0..3 {
+            step_world(&mut world, &policy, 1.0);
+        }
+
+        let mut fresh = scenario();
+        for _ in 0..10 {
+            step_world(&mut fresh, &policy, 1.0);
+        }
+        assert_eq!(state(&world), state(&fresh));
+    }
+
+    #[test]
+    fn ring_keeps_only_the_last_snapshots() {
+        let policy = ZoneRepoPolicyEngine::new(1.0);
+        let mut ring = SnapshotRing::new(SnapshotPolicy {
+            every_n_ticks: 2,
+            keep_last: 2,
+        });
+        let mut world = scenario();
+        for _ in 0..8 {
+            step_world_with_snapshots(&mut world, &policy, 1.0, &mut ring);
+        }
+        assert_eq!(ring.len(), 2);
+        assert_eq!(ring.rewind(0).unwrap().time, 8.0);
+        assert_eq!(ring.rewind(1).unwrap().time, 6.0);
+        assert!(ring.rewind(1).is_none());
+    }
+}
@@ -0,0 +1,195 @@
+//! Conversions between this crate's `FearIndex` and `sovereign_neuro`'s
+//! map-based one. Enabled with the `sovereign-neuro` feature.
+//!
+//! Components travel under their field names. `value` is derived from them,
+//! so the way back accepts only maps whose `value` agrees with their
+//! components; anything it accepts converts back to an identical index.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{FearIndex, FearWeights};
+
+pub const SYSTEMIC_HARM: &str = "systemic_harm";
+pub const REGRET: &str = "regret";
+pub const ECOLOGICAL_DAMAGE: &str = "ecological_damage";
+
+/// Tolerance when checking `value` against the components.
+const VALUE_EPSILON: f64 = 1e-9;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FearConversionError {
+    /// A component required by `zone_repo::FearIndex` is absent from the map.
+    MissingComponent(&'static str),
+    /// A required component is NaN or infinite.
+    NonFiniteComponent { key: &'static str, value: f64 },
+    /// `value` is not the composite of the components, so it would be lost.
+    ValueMismatch { value: f64, expected: f64 },
+}
+
+impl fmt::Display for FearConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FearConversionError::MissingComponent(key) => {
+                write!(f, "fear index has no '{key}' component")
+            }
+            FearConversionError::NonFiniteComponent { key, value } => {
+                write!(f, "fear index component '{key}' is {value}")
+            }
+            FearConversionError::ValueMismatch { value, expected } => write!(
+                f,
+                "fear index value {value} does not match its components ({expected})"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FearConversionError {}
+
+/// Weighted mean of the components under default weights, clamped to 0..1.
+fn composite_value(fear: &FearIndex) -> f64 {
+    let weights = FearWeights::default();
+    let weight_sum = weights.systemic_harm + weights.regret + weights.ecological_damage;
+    (fear.weighted_total(&weights) / weight_sum).clamp(0.0, 1.0)
+}
+
+impl From<FearIndex> for sovereign_neuro::FearIndex {
+    fn from(fear: FearIndex) -> Self {
+        let value = composite_value(&fear);
+        let components = HashMap::from([
+            (SYSTEMIC_HARM.to_string(), fear.systemic_harm),
+            (REGRET.to_string(), fear.regret),
+            (ECOLOGICAL_DAMAGE.to_string(), fear.ecological_damage),
+        ]);
+        Self { value, components }
+    }
+}
+
+impl TryFrom<sovereign_neuro::FearIndex> for FearIndex {
+    type Error = FearConversionError;
+
+    /// Extra components are ignored; `value` must match the required ones.
+    fn try_from(fear: sovereign_neuro::FearIndex) -> Result<Self, Self::Error> {
+        let component = |key: &'static str| {
+            let value = fear
+                .components
+                .get(key)
+                .copied()
+                .ok_or(FearConversionError::MissingComponent(key))?;
+            if !value.is_finite() {
+                return Err(FearConversionError::NonFiniteComponent { key, value });
+            }
+            Ok(value)
+        };
+        let index = FearIndex {
+            systemic_harm: component(SYSTEMIC_HARM)?,
+            regret: component(REGRET)?,
+            ecological_damage: component(ECOLOGICAL_DAMAGE)?,
+        };
+        let expected = composite_value(&index);
+        if fear.value.is_nan() || (fear.value - expected).abs() > VALUE_EPSILON {
+            return Err(FearConversionError::ValueMismatch {
+                value: fear.value,
+                expected,
+            });
+        }
+        Ok(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn components(fear: &FearIndex) -> [f64; 3] {
+        [fear.systemic_harm, fear.regret, fear.ecological_damage]
+    }
+
+    #[test]
+    fn zone_index_roundtrips_exactly() {
+        for fear in [
+            FearIndex {
+                systemic_harm: 0.1,
+                regret: 0.25,
+                ecological_damage: 0.7,
+            },
+            FearIndex {
+                systemic_harm: 0.0,
+                regret: 0.0,
+                ecological_damage: 0.0,
+            },
+            // Out of range components survive; only the derived value is clamped.
+            FearIndex {
+                systemic_harm: 3.5,
+                regret: -0.2,
+                ecological_damage: 1.0,
+            },
+        ] {
+            let sovereign = sovereign_neuro::FearIndex::from(fear.clone());
+            assert!((0.0..=1.0).contains(&sovereign.value));
+            assert_eq!(sovereign.components[SYSTEMIC_HARM], fear.systemic_harm);
+            let back = FearIndex::try_from(sovereign).unwrap();
+            assert_eq!(components(&back), components(&fear));
+        }
+    }
+
+    #[test]
+    fn consistent_sovereign_index_roundtrips_exactly() {
+        let fear = FearIndex {
+            systemic_harm: 0.4,
+            regret: 0.9,
+            ecological_damage: 0.05,
+        };
+        let mut sovereign = sovereign_neuro::FearIndex::from(fear);
+        sovereign.components.insert("other".to_string(), 0.3);
+        let back =
+            sovereign_neuro::FearIndex::from(FearIndex::try_from(sovereign.clone()).unwrap());
+        assert_eq!(back.value, sovereign.value);
+        for key in [SYSTEMIC_HARM, REGRET, ECOLOGICAL_DAMAGE] {
+            assert_eq!(back.components[key], sovereign.components[key]);
+        }
+    }
+
+    #[test]
+    fn missing_component_is_an_error() {
+        let fear = FearIndex {
+            systemic_harm: 0.4,
+            regret: 0.9,
+            ecological_damage: 0.05,
+        };
+        let mut sovereign = sovereign_neuro::FearIndex::from(fear);
+        sovereign.components.remove(REGRET);
+        assert_eq!(
+            FearIndex::try_from(sovereign).unwrap_err(),
+            FearConversionError::MissingComponent(REGRET)
+        );
+    }
+
+    #[test]
+    fn value_that_disagrees_with_components_is_rejected() {
+        let fear = FearIndex {
+            systemic_harm: 0.4,
+            regret: 0.9,
+            ecological_damage: 0.05,
+        };
+        let mut sovereign = sovereign_neuro::FearIndex::from(fear);
+        for value in [sovereign.value + 0.1, 7.0, f64::NAN] {
+            sovereign.value = value;
+            assert!(matches!(
+                FearIndex::try_from(sovereign.clone()),
+                Err(FearConversionError::ValueMismatch { .. })
+            ));
+        }
+
+        sovereign
+            .components
+            .insert(ECOLOGICAL_DAMAGE.to_string(), f64::INFINITY);
+        assert!(matches!(
+            FearIndex::try_from(sovereign),
+            Err(FearConversionError::NonFiniteComponent {
+                key: ECOLOGICAL_DAMAGE,
+                ..
+            })
+        ));
+    }
+}
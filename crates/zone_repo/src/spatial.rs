@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+use std::fmt;
+
+/// Sources spanning more grid cells than this per axis are kept off the
+/// grid and checked on every lookup instead.
+const MAX_CELLS_PER_AXIS: f64 = 16.0;
+
+/// A point emitter of concept intensity; influence falls off linearly to
+/// zero at `radius`.
+#[derive(Clone, Debug)]
+pub struct ConceptSource {
+    pub concept_key: String,
+    pub x: f64,
+    pub y: f64,
+    pub strength: f64,
+    pub radius: f64,
+}
+
+impl ConceptSource {
+    pub fn intensity_at(&self, x: f64, y: f64) -> f64 {
+        if self.radius <= 0.0 {
+            return 0.0;
+        }
+        let d = ((x - self.x).powi(2) + (y - self.y).powi(2)).sqrt();
+        if d >= self.radius {
+            0.0
+        } else {
+            self.strength * (1.0 - d / self.radius)
+        }
+    }
+}
+
+/// Reasons `ConceptSources::push` refuses a source.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConceptSourceError {
+    /// NaN or infinite position, strength or radius.
+    NonFinite {
+        concept_key: String,
+        field: &'static str,
+        value: f64,
+    },
+    NonPositiveRadius { concept_key: String, radius: f64 },
+}
+
+impl fmt::Display for ConceptSourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConceptSourceError::NonFinite {
+                concept_key,
+                field,
+                value,
+            } => write!(f, "source of '{concept_key}' has {field} {value}"),
+            ConceptSourceError::NonPositiveRadius {
+                concept_key,
+                radius,
+            } => write!(f, "source of '{concept_key}' has non-positive radius {radius}"),
+        }
+    }
+}
+
+impl std::error::Error for ConceptSourceError {}
+
+/// Point sources bucketed into a uniform grid, so a lookup only visits
+/// sources whose radius overlaps the queried cell, plus the few sources too
+/// wide to bucket.
+#[derive(Clone, Debug)]
+pub struct ConceptSources {
+    cell_size: f64,
+    sources: Vec<ConceptSource>,
+    grid: HashMap<(i64, i64), Vec<usize>>,
+    wide: Vec<usize>, // sources spanning more than `MAX_CELLS_PER_AXIS` cells
+}
+
+impl Default for ConceptSources {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl ConceptSources {
+    /// `cell_size` should be on the order of a typical source radius.
+    pub fn new(cell_size: f64) -> Self {
+        Self {
+            cell_size: if cell_size > 0.0 && cell_size.is_finite() {
+                cell_size
+            } else {
+                1.0
+            },
+            sources: Vec::new(),
+            grid: HashMap::new(),
+            wide: Vec::new(),
+        }
+    }
+
+    fn cell(&self, x: f64, y: f64) -> (i64, i64) {
+        (
+            (x / self.cell_size).floor() as i64,
+            (y / self.cell_size).floor() as i64,
+        )
+    }
+
+    pub fn push(&mut self, source: ConceptSource) -> Result<(), ConceptSourceError> {
+        let fields = [
+            ("x", source.x),
+            ("y", source.y),
+            ("strength", source.strength),
+            ("radius", source.radius),
+        ];
+        for (field, value) in fields {
+            if !value.is_finite() {
+                return Err(ConceptSourceError::NonFinite {
+                    concept_key: source.concept_key,
+                    field,
+                    value,
+                });
+            }
+        }
+        if source.radius <= 0.0 {
+            return Err(ConceptSourceError::NonPositiveRadius {
+                concept_key: source.concept_key,
+                radius: source.radius,
+            });
+        }
+
+        let idx = self.sources.len();
+        let r = source.radius;
+        if 2.0 * r / self.cell_size > MAX_CELLS_PER_AXIS {
+            self.wide.push(idx);
+        } else {
+            let (x0, y0) = self.cell(source.x - r, source.y - r);
+            let (x1, y1) = self.cell(source.x + r, source.y + r);
+            for cx in x0..=x1 {
+                for cy in y0..=y1 {
+                    self.grid.entry((cx, cy)).or_default().push(idx);
+                }
+            }
+        }
+        self.sources.push(source);
+        Ok(())
+    }
+
+    pub fn sources(&self) -> &[ConceptSource] {
+        &self.sources
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Sources that may reach (x, y).
+    fn candidates(&self, x: f64, y: f64) -> impl Iterator<Item = &ConceptSource> {
+        let bucketed = self.grid.get(&self.cell(x, y)).into_iter().flatten();
+        bucketed.chain(&self.wide).map(|&i| &self.sources[i])
+    }
+
+    /// Summed influence of every source of `concept_key` at (x, y).
+    pub fn intensity_at(&self, concept_key: &str, x: f64, y: f64) -> f64 {
+        self.candidates(x, y)
+            .filter(|s| s.concept_key == concept_key)
+            .map(|s| s.intensity_at(x, y))
+            .sum()
+    }
+
+    /// Concept keys of the sources with influence at (x, y), sorted.
+    pub fn concepts_near(&self, x: f64, y: f64) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .candidates(x, y)
+            .filter(|s| s.intensity_at(x, y) != 0.0)
+            .map(|s| s.concept_key.clone())
+            .collect();
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AgentId, Environment, HumanAgent, Location, World};
+
+    fn source(concept_key: &str, x: f64, y: f64, strength: f64, radius: f64) -> ConceptSource {
+        ConceptSource {
+            concept_key: concept_key.to_string(),
+            x,
+            y,
+            strength,
+            radius,
+        }
+    }
+
+    #[test]
+    fn influence_falls_off_linearly_to_the_radius() {
+        let s = source("alpha", 0.0, 0.0, 0.8, 2.0);
+        assert_eq!(s.intensity_at(0.0, 0.0), 0.8);
+        assert!((s.intensity_at(1.0, 0.0) - 0.4).abs() < 1e-12);
+        assert!((s.intensity_at(0.0, -1.5) - 0.2).abs() < 1e-12);
+        assert_eq!(s.intensity_at(2.0, 0.0), 0.0);
+        assert_eq!(s.intensity_at(3.0, 3.0), 0.0);
+    }
+
+    #[test]
+    fn sources_are_found_across_cell_boundaries() {
+        let mut sources = ConceptSources::new(1.0);
+        sources.push(source("alpha", 0.9, 0.5, 1.0, 0.5)).unwrap();
+        // The source sits in cell (0, 0) and reaches into cell (1, 0).
+        assert!((sources.intensity_at("alpha", 1.2, 0.5) - 0.4).abs() < 1e-12);
+        assert_eq!(sources.concepts_near(1.2, 0.5), ["alpha"]);
+        assert_eq!(sources.intensity_at("alpha", 1.6, 0.5), 0.0);
+        assert!(sources.concepts_near(1.6, 0.5).is_empty());
+        assert_eq!(sources.intensity_at("beta", 1.2, 0.5), 0.0);
+    }
+
+    #[test]
+    fn negative_coordinates_map_to_their_own_cells() {
+        let mut sources = ConceptSources::new(1.0);
+        sources.push(source("alpha", -0.5, -0.5, 1.0, 0.4)).unwrap();
+        // Truncating instead of flooring would put the source in cell (0, 0).
+        assert_eq!(sources.grid.keys().collect::<Vec<_>>(), [&(-1, -1)]);
+        assert!((sources.intensity_at("alpha", -0.5, -0.3) - 0.5).abs() < 1e-12);
+        assert_eq!(sources.intensity_at("alpha", 0.1, 0.1), 0.0);
+    }
+
+    #[test]
+    fn overlapping_sources_sum_and_are_listed_once() {
+        let mut sources = ConceptSources::default();
+        sources.push(source("beta", 0.0, 0.0, 0.3, 1.0)).unwrap();
+        sources.push(source("alpha", 0.0, 0.0, 0.2, 1.0)).unwrap();
+        sources.push(source("alpha", 0.5, 0.0, 0.2, 1.0)).unwrap();
+        assert!((sources.intensity_at("alpha", 0.0, 0.0) - 0.3).abs() < 1e-12);
+        assert_eq!(sources.concepts_near(0.0, 0.0), ["alpha", "beta"]);
+    }
+
+    #[test]
+    fn invalid_sources_are_rejected() {
+        let mut sources = ConceptSources::default();
+        for radius in [0.0, -1.0] {
+            assert_eq!(
+                sources.push(source("alpha", 0.0, 0.0, 1.0, radius)),
+                Err(ConceptSourceError::NonPositiveRadius {
+                    concept_key: "alpha".into(),
+                    radius,
+                })
+            );
+        }
+        let err = sources
+            .push(source("alpha", 0.0, 0.0, 1.0, f64::INFINITY))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ConceptSourceError::NonFinite {
+                field: "radius",
+                ..
+            }
+        ));
+        let err = sources
+            .push(source("alpha", f64::NAN, 0.0, 1.0, 1.0))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ConceptSourceError::NonFinite { field: "x", .. }
+        ));
+        assert!(sources.is_empty());
+    }
+
+    #[test]
+    fn wide_sources_stay_off_the_grid() {
+        let mut sources = ConceptSources::new(1.0);
+        sources.push(source("alpha", 0.0, 0.0, 1.0, 1e4)).unwrap();
+        assert!(sources.grid.is_empty());
+        assert_eq!(sources.wide, [0]);
+        assert!((sources.intensity_at("alpha", 5e3, 0.0) - 0.5).abs() < 1e-12);
+        assert_eq!(sources.concepts_near(-5e3, 0.0), ["alpha"]);
+    }
+
+    #[test]
+    fn exposure_blends_fields_and_sources() {
+        let mut world = World::default();
+        world.region_populations.insert("r1".to_string(), 1);
+        world
+            .concept_fields
+            .insert(("alpha".to_string(), "r1".to_string()), 0.5);
+        world
+            .concept_sources
+            .push(source("alpha", 0.0, 0.0, 0.5, 1.0))
+            .unwrap();
+        world
+            .concept_sources
+            .push(source("beta", 0.0, 0.0, 0.4, 1.0))
+            .unwrap();
+        let agent = HumanAgent::new(AgentId(1), Location::new(0.0, 0.0, "r1"));
+
+        // 1 - (1 - 0.5) * (1 - 0.5)
+        assert!((agent.exposure(&world, "alpha") - 0.75).abs() < 1e-12);
+        assert!((agent.exposure(&world, "beta") - 0.4).abs() < 1e-12);
+        let far = HumanAgent::new(AgentId(2), Location::new(5.0, 5.0, "r1"));
+        assert_eq!(far.exposure(&world, "alpha"), 0.5);
+        assert_eq!(far.exposure(&world, "beta"), 0.0);
+
+        // A concept with only a source is still proposed.
+        let mut rng = rand::rngs::mock::StepRng::new(0, 0);
+        let proposed: Vec<String> = agent
+            .propose_transitions(&world, &mut rng)
+            .into_iter()
+            .map(|p| p.concept_key)
+            .collect();
+        assert_eq!(proposed, ["alpha", "beta"]);
+        assert_eq!(far.propose_transitions(&world, &mut rng).len(), 1);
+        assert_eq!(world.concepts_near(5.0, 5.0), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,269 @@
+//! `tracing` telemetry for `step_world` and its substepped and batched
+//! variants. Enabled with the `trace` feature.
+//!
+//! Each tick runs inside a `tick` span (`time`, `agents`). Forbidden
+//! transitions are reported as `debug` events for a sample of agents, and a
+//! single `info` summary event closes the tick with aggregate fear.
+
+use crate::observer::TransitionObserver;
+use crate::report::TickReport;
+use crate::{FearIndex, PolicyContext};
+
+#[derive(Clone, Copy, Debug)]
+pub struct TraceConfig {
+    /// Emit per-transition events only for agents whose id is a multiple of
+    /// this; 1 logs every agent, 0 disables per-transition events.
+    pub log_every_n_agents: u64,
+}
+
+impl Default for TraceConfig {
+    fn default() -> Self {
+        Self {
+            log_every_n_agents: 1,
+        }
+    }
+}
+
+/// Observer behind `step_world`, `step_world_substeps` and
+/// `step_world_batched` when tracing is enabled.
+pub struct TracingObserver {
+    config: TraceConfig,
+    report: TickReport,
+}
+
+impl TracingObserver {
+    pub fn new(config: TraceConfig) -> Self {
+        Self {
+            config,
+            report: TickReport::default(),
+        }
+    }
+
+    fn sampled(&self, ctx: &PolicyContext) -> bool {
+        self.config.log_every_n_agents > 0
+            && ctx.agent_id.0.is_multiple_of(self.config.log_every_n_agents)
+    }
+
+    /// Emit the tick summary and return the totals it reported.
+    pub fn finish(mut self, time: f64) -> TickReport {
+        self.report.time = time;
+        let total = &self.report.total.sum;
+        tracing::info!(
+            time,
+            applied = self.report.applied,
+            forbidden = self.report.forbidden,
+            deferred = self.report.deferred,
+            cost = self.report.cost,
+            systemic_harm = total.systemic_harm,
+            regret = total.regret,
+            ecological_damage = total.ecological_damage,
+            "tick summary"
+        );
+        self.report
+    }
+}
+
+impl TransitionObserver for TracingObserver {
+    fn on_transition_applied(&mut self, ctx: &PolicyContext, fear: &FearIndex) {
+        self.report.on_transition_applied(ctx, fear);
+    }
+
+    fn on_transition_forbidden(&mut self, ctx: &PolicyContext, reason: Option<&str>) {
+        self.report.on_transition_forbidden(ctx, reason);
+        if self.sampled(ctx) {
+            tracing::debug!(
+                agent_id = ctx.agent_id.0,
+                concept_key = ctx.concept_key,
+                reason,
+                "transition forbidden"
+            );
+        }
+    }
+
+    fn admit_cost(&mut self, ctx: &PolicyContext, cost: f64) -> bool {
+        self.report.admit_cost(ctx, cost)
+    }
+
+    fn on_transition_deferred(&mut self, ctx: &PolicyContext) {
+        self.report.on_transition_deferred(ctx);
+        if self.sampled(ctx) {
+            tracing::debug!(
+                agent_id = ctx.agent_id.0,
+                concept_key = ctx.concept_key,
+                "transition budget-deferred"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::fmt;
+    use std::sync::{Arc, Mutex};
+
+    use tracing::field::{Field, Visit};
+    use tracing::{Event, Subscriber};
+    use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+    use tracing_subscriber::registry::LookupSpan;
+
+    use super::*;
+    use crate::{
+        step_world, step_world_batched, step_world_substeps, step_world_with_report, AgentId,
+        HumanAgent, Location, World, ZoneRepoPolicyEngine,
+    };
+
+    /// An event's fields as text, and the span it was emitted in.
+    #[derive(Debug)]
+    struct Captured {
+        span: Option<String>,
+        fields: BTreeMap<String, String>,
+    }
+
+    impl Captured {
+        fn message(&self) -> &str {
+            &self.fields["message"]
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct CaptureLayer(Arc<Mutex<Vec<Captured>>>);
+
+    struct FieldText<'a>(&'a mut BTreeMap<String, String>);
+
+    impl Visit for FieldText<'_> {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            self.0
+                .insert(field.name().to_string(), format!("{value:?}"));
+        }
+    }
+
+    impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for CaptureLayer {
+        fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+            let mut fields = BTreeMap::new();
+            event.record(&mut FieldText(&mut fields));
+            let span = ctx.event_span(event).map(|span| span.name().to_string());
+            self.0.lock().unwrap().push(Captured { span, fields });
+        }
+    }
+
+    /// Agents 0..4 in an overloaded region, where every adoption is
+    /// forbidden, and 4..6 in a calm one.
+    fn world(log_every_n_agents: u64) -> World {
+        let mut world = World::default();
+        world
+            .region_populations
+            .insert("crowded".to_string(), 20_000);
+        world.region_populations.insert("calm".to_string(), 0);
+        for region_id in ["crowded", "calm"] {
+            world
+                .concept_fields
+                .insert(("alpha".to_string(), region_id.to_string()), 0.8);
+        }
+        for id in 0..6 {
+            let region_id = if id < 4 { "crowded" } else { "calm" };
+            world.agents.push(HumanAgent::new(
+                AgentId(id),
+                Location::new(0.0, 0.0, region_id),
+            ));
+        }
+        world.trace = TraceConfig { log_every_n_agents };
+        world
+    }
+
+    fn traced_tick(world: World) -> Vec<Captured> {
+        traced(world, |world| {
+            step_world(world, &ZoneRepoPolicyEngine::new(1.0), 1.0)
+        })
+    }
+
+    /// Events emitted while `step` advances `world`.
+    fn traced(mut world: World, step: impl FnOnce(&mut World)) -> Vec<Captured> {
+        let layer = CaptureLayer::default();
+        let subscriber = tracing_subscriber::registry().with(layer.clone());
+        tracing::subscriber::with_default(subscriber, || step(&mut world));
+        let events = std::mem::take(&mut *layer.0.lock().unwrap());
+        events
+    }
+
+    fn summaries(events: &[Captured]) -> Vec<&Captured> {
+        events
+            .iter()
+            .filter(|e| e.message() == "tick summary")
+            .collect()
+    }
+
+    #[test]
+    fn summary_event_reports_the_tick_totals() {
+        let expected = step_world_with_report(&mut world(1), &ZoneRepoPolicyEngine::new(1.0), 1.0);
+        assert_eq!((expected.applied, expected.forbidden), (2, 4));
+
+        let events = traced_tick(world(1));
+        let summaries = summaries(&events);
+        assert_eq!(summaries.len(), 1);
+        let summary = summaries[0];
+        assert_eq!(summary.span.as_deref(), Some("tick"));
+        let field = |name: &str| summary.fields[name].as_str();
+        assert_eq!(field("time"), "1.0");
+        assert_eq!(field("applied"), "2");
+        assert_eq!(field("forbidden"), "4");
+        assert_eq!(field("deferred"), "0");
+        let total = &expected.total.sum;
+        assert_eq!(field("systemic_harm"), format!("{:?}", total.systemic_harm));
+        assert_eq!(field("regret"), format!("{:?}", total.regret));
+        assert_eq!(
+            field("ecological_damage"),
+            format!("{:?}", total.ecological_damage)
+        );
+    }
+
+    #[test]
+    fn forbidden_events_follow_the_agent_sampling() {
+        let forbidden_agents = |log_every_n_agents| -> Vec<String> {
+            traced_tick(world(log_every_n_agents))
+                .into_iter()
+                .filter(|e| e.message() == "transition forbidden")
+                .map(|e| e.fields["agent_id"].clone())
+                .collect()
+        };
+        assert_eq!(forbidden_agents(1), vec!["0", "1", "2", "3"]);
+        assert_eq!(forbidden_agents(2), vec!["0", "2"]);
+        assert!(forbidden_agents(0).is_empty());
+    }
+
+    #[test]
+    fn substepped_ticks_are_traced() {
+        let events = traced(world(1), |world| {
+            step_world_substeps(world, &ZoneRepoPolicyEngine::new(1.0), 1.0, 4)
+        });
+        let summaries = summaries(&events);
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].span.as_deref(), Some("tick"));
+        assert_eq!(summaries[0].fields["forbidden"], "4");
+        let forbidden = events
+            .iter()
+            .filter(|e| e.message() == "transition forbidden")
+            .count();
+        assert_eq!(forbidden, 4);
+    }
+
+    #[test]
+    fn batched_ticks_are_traced() {
+        let events = traced(world(2), |world| {
+            step_world_batched(world, &ZoneRepoPolicyEngine::new(1.0), 1.0)
+        });
+        let summaries = summaries(&events);
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].fields["applied"], "2");
+        let forbidden: Vec<&str> = events
+            .iter()
+            .filter(|e| e.message() == "transition forbidden")
+            .map(|e| e.fields["agent_id"].as_str())
+            .collect();
+        assert_eq!(forbidden, ["0", "2"]);
+    }
+}
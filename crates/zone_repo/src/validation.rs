@@ -0,0 +1,368 @@
+//! Range checks on `World::concept_fields` and consistency checks on the
+//! region tables.
+
+use std::fmt;
+
+use crate::{AgentId, World};
+
+/// Clamp warnings kept in `World::intensity_warnings` between drains; later
+/// clamps are only counted in `World::intensity_warnings_dropped`.
+pub const MAX_INTENSITY_WARNINGS: usize = 1024;
+
+/// Range intensities are clamped to on write. Build with `new` to have the
+/// range checked; `World::validate` reports bounds set any other way.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IntensityBounds {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl IntensityBounds {
+    pub fn new(min: f64, max: f64) -> Result<Self, ValidationIssue> {
+        let bounds = Self { min, max };
+        if bounds.is_valid() {
+            Ok(bounds)
+        } else {
+            Err(ValidationIssue::InvalidIntensityBounds { min, max })
+        }
+    }
+
+    /// Finite, with `min <= max`.
+    pub fn is_valid(&self) -> bool {
+        self.min.is_finite() && self.max.is_finite() && self.min <= self.max
+    }
+}
+
+impl Default for IntensityBounds {
+    fn default() -> Self {
+        Self { min: 0.0, max: 1.0 }
+    }
+}
+
+/// An intensity that had to be clamped into `World::intensity_bounds`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IntensityClamp {
+    pub time: f64,
+    pub concept_key: String,
+    pub region_id: String,
+    pub requested: f64,
+    pub stored: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidationIssue {
+    /// `intensity_bounds` is not a finite range with `min <= max`.
+    InvalidIntensityBounds { min: f64, max: f64 },
+    /// NaN or infinite intensity.
+    NonFiniteIntensity {
+        concept_key: String,
+        region_id: String,
+        value: f64,
+    },
+    IntensityOutOfBounds {
+        concept_key: String,
+        region_id: String,
+        value: f64,
+    },
+    /// A field is keyed by a region missing from `region_populations`.
+    UnknownFieldRegion {
+        concept_key: String,
+        region_id: String,
+    },
+    /// An agent lives or commutes in a region missing from `region_populations`.
+    UnknownAgentRegion { agent_id: AgentId, region_id: String },
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationIssue::InvalidIntensityBounds { min, max } => {
+                write!(f, "intensity bounds [{min}, {max}] are not a finite range")
+            }
+            ValidationIssue::NonFiniteIntensity {
+                concept_key,
+                region_id,
+                value,
+            } => write!(f, "intensity of '{concept_key}' in '{region_id}' is {value}"),
+            ValidationIssue::IntensityOutOfBounds {
+                concept_key,
+                region_id,
+                value,
+            } => write!(
+                f,
+                "intensity of '{concept_key}' in '{region_id}' is out of bounds: {value}"
+            ),
+            ValidationIssue::UnknownFieldRegion {
+                concept_key,
+                region_id,
+            } => write!(f, "field '{concept_key}' refers to unknown region '{region_id}'"),
+            ValidationIssue::UnknownAgentRegion {
+                agent_id,
+                region_id,
+            } => write!(f, "agent {} refers to unknown region '{region_id}'", agent_id.0),
+        }
+    }
+}
+
+impl std::error::Error for ValidationIssue {}
+
+impl World {
+    /// Store an intensity, clamped to `intensity_bounds`. A clamp is recorded
+    /// in `intensity_warnings`; NaN and infinities, or invalid bounds, are
+    /// rejected and leave the field as it was.
+    pub fn set_intensity(
+        &mut self,
+        concept_key: &str,
+        region_id: &str,
+        value: f64,
+    ) -> Result<f64, ValidationIssue> {
+        let bounds = self.intensity_bounds;
+        if !bounds.is_valid() {
+            return Err(ValidationIssue::InvalidIntensityBounds {
+                min: bounds.min,
+                max: bounds.max,
+            });
+        }
+        if !value.is_finite() {
+            return Err(ValidationIssue::NonFiniteIntensity {
+                concept_key: concept_key.to_string(),
+                region_id: region_id.to_string(),
+                value,
+            });
+        }
+        let stored = self.clamp_intensity(concept_key, region_id, value);
+        self.concept_fields
+            .insert((concept_key.to_string(), region_id.to_string()), stored);
+        Ok(stored)
+    }
+
+    fn clamp_intensity(&mut self, concept_key: &str, region_id: &str, value: f64) -> f64 {
+        let bounds = self.intensity_bounds;
+        let stored = value.clamp(bounds.min, bounds.max);
+        if stored != value {
+            if self.intensity_warnings.len() < MAX_INTENSITY_WARNINGS {
+                self.intensity_warnings.push(IntensityClamp {
+                    time: self.time,
+                    concept_key: concept_key.to_string(),
+                    region_id: region_id.to_string(),
+                    requested: value,
+                    stored,
+                });
+            } else {
+                self.intensity_warnings_dropped += 1;
+            }
+        }
+        stored
+    }
+
+    /// Clamp every field into `intensity_bounds`, recording each clamp.
+    /// NaN fields, and every field under invalid bounds, are left for
+    /// `validate` to report.
+    pub fn clamp_intensities(&mut self) {
+        let bounds = self.intensity_bounds;
+        if !bounds.is_valid() {
+            return;
+        }
+        let out_of_bounds: Vec<(String, String, f64)> = self
+            .concept_fields
+            .iter()
+            .filter(|(_, v)| **v < bounds.min || **v > bounds.max)
+            .map(|((c, r), v)| (c.clone(), r.clone(), *v))
+            .collect();
+        for (concept_key, region_id, value) in out_of_bounds {
+            let stored = self.clamp_intensity(&concept_key, &region_id, value);
+            self.concept_fields.insert((concept_key, region_id), stored);
+        }
+    }
+
+    /// Drain the clamp warnings collected so far, making room for more.
+    pub fn take_intensity_warnings(&mut self) -> Vec<IntensityClamp> {
+        std::mem::take(&mut self.intensity_warnings)
+    }
+
+    /// Every problem found in the fields and region references, in a stable
+    /// order. Empty means the world is consistent.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let bounds = self.intensity_bounds;
+        let mut issues = Vec::new();
+        if !bounds.is_valid() {
+            issues.push(ValidationIssue::InvalidIntensityBounds {
+                min: bounds.min,
+                max: bounds.max,
+            });
+        }
+
+        for ((concept_key, region_id), &value) in &self.concept_fields {
+            if !value.is_finite() {
+                issues.push(ValidationIssue::NonFiniteIntensity {
+                    concept_key: concept_key.clone(),
+                    region_id: region_id.clone(),
+                    value,
+                });
+            } else if bounds.is_valid() && (value < bounds.min || value > bounds.max) {
+                issues.push(ValidationIssue::IntensityOutOfBounds {
+                    concept_key: concept_key.clone(),
+                    region_id: region_id.clone(),
+                    value,
+                });
+            }
+            if !self.region_populations.contains_key(region_id) {
+                issues.push(ValidationIssue::UnknownFieldRegion {
+                    concept_key: concept_key.clone(),
+                    region_id: region_id.clone(),
+                });
+            }
+        }
+
+        for agent in &self.agents {
+            let regions = std::iter::once(&agent.location.region_id)
+                .chain(agent.location.secondary_region.as_ref());
+            for region_id in regions {
+                if !self.region_populations.contains_key(region_id) {
+                    issues.push(ValidationIssue::UnknownAgentRegion {
+                        agent_id: agent.id.clone(),
+                        region_id: region_id.clone(),
+                    });
+                }
+            }
+        }
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{step_world, Environment, HumanAgent, Location, ZoneRepoPolicyEngine};
+
+    fn world() -> World {
+        let mut world = World::default();
+        world.region_populations.insert("r1".to_string(), 0);
+        world
+    }
+
+    #[test]
+    fn non_finite_intensities_are_rejected() {
+        let mut world = world();
+        world.set_intensity("alpha", "r1", 0.4).unwrap();
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let issue = world.set_intensity("alpha", "r1", value).unwrap_err();
+            assert!(matches!(issue, ValidationIssue::NonFiniteIntensity { .. }));
+        }
+        assert_eq!(world.get_concept_intensity("alpha", "r1"), 0.4);
+        assert!(world.take_intensity_warnings().is_empty());
+    }
+
+    #[test]
+    fn clamps_are_recorded_as_warnings() {
+        let mut world = world();
+        world.intensity_bounds = IntensityBounds { min: 0.1, max: 0.9 };
+        assert_eq!(world.set_intensity("alpha", "r1", 1.7), Ok(0.9));
+        assert_eq!(world.set_intensity("beta", "r1", -0.5), Ok(0.1));
+        assert_eq!(world.set_intensity("gamma", "r1", 0.5), Ok(0.5));
+
+        let warnings = world.take_intensity_warnings();
+        let clamped: Vec<(&str, f64, f64)> = warnings
+            .iter()
+            .map(|w| (w.concept_key.as_str(), w.requested, w.stored))
+            .collect();
+        assert_eq!(clamped, vec![("alpha", 1.7, 0.9), ("beta", -0.5, 0.1)]);
+        assert!(world.take_intensity_warnings().is_empty());
+    }
+
+    #[test]
+    fn validate_reports_bad_values_and_unknown_regions() {
+        let mut world = world();
+        world
+            .concept_fields
+            .insert(("alpha".to_string(), "r1".to_string()), f64::NAN);
+        world
+            .concept_fields
+            .insert(("beta".to_string(), "r1".to_string()), 1.5);
+        world
+            .concept_fields
+            .insert(("gamma".to_string(), "nowhere".to_string()), 0.5);
+        world.agents.push(HumanAgent::new(
+            AgentId(7),
+            Location::new(0.0, 0.0, "r1").with_secondary("elsewhere"),
+        ));
+
+        let issues = world.validate();
+        assert_eq!(issues.len(), 4, "{issues:?}");
+        assert!(
+            matches!(&issues[0], ValidationIssue::NonFiniteIntensity { concept_key, .. } if concept_key == "alpha")
+        );
+        assert!(
+            matches!(&issues[1], ValidationIssue::IntensityOutOfBounds { value, .. } if *value == 1.5)
+        );
+        assert!(
+            matches!(&issues[2], ValidationIssue::UnknownFieldRegion { region_id, .. } if region_id == "nowhere")
+        );
+        assert!(
+            matches!(&issues[3], ValidationIssue::UnknownAgentRegion { agent_id, region_id } if agent_id.0 == 7 && region_id == "elsewhere")
+        );
+    }
+
+    #[test]
+    fn fields_written_directly_are_clamped_at_tick_start() {
+        let mut world = world();
+        world
+            .agents
+            .push(HumanAgent::new(AgentId(1), Location::new(0.0, 0.0, "r1")));
+        world
+            .concept_fields
+            .insert(("alpha".to_string(), "r1".to_string()), 1.7);
+        step_world(&mut world, &ZoneRepoPolicyEngine::new(1.0), 1.0);
+
+        assert_eq!(world.get_concept_intensity("alpha", "r1"), 1.0);
+        let warnings = world.take_intensity_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].requested, 1.7);
+        assert!(world.validate().is_empty());
+    }
+
+    #[test]
+    fn invalid_bounds_are_reported_not_applied() {
+        assert_eq!(
+            IntensityBounds::new(0.9, 0.1),
+            Err(ValidationIssue::InvalidIntensityBounds { min: 0.9, max: 0.1 })
+        );
+        assert!(IntensityBounds::new(f64::NAN, 1.0).is_err());
+        assert_eq!(
+            IntensityBounds::new(0.1, 0.9),
+            Ok(IntensityBounds { min: 0.1, max: 0.9 })
+        );
+
+        let mut world = world();
+        world
+            .concept_fields
+            .insert(("alpha".to_string(), "r1".to_string()), 1.7);
+        world.intensity_bounds = IntensityBounds { min: 0.9, max: 0.1 };
+        assert!(matches!(
+            world.set_intensity("beta", "r1", 0.5),
+            Err(ValidationIssue::InvalidIntensityBounds { .. })
+        ));
+        world.clamp_intensities();
+        assert_eq!(world.get_concept_intensity("alpha", "r1"), 1.7);
+        assert_eq!(
+            world.validate(),
+            [ValidationIssue::InvalidIntensityBounds { min: 0.9, max: 0.1 }]
+        );
+    }
+
+    #[test]
+    fn warnings_past_the_cap_are_counted() {
+        let mut world = world();
+        for _ in 0..MAX_INTENSITY_WARNINGS + 5 {
+            world.set_intensity("alpha", "r1", 2.0).unwrap();
+        }
+        assert_eq!(world.intensity_warnings_dropped, 5);
+        assert_eq!(
+            world.take_intensity_warnings().len(),
+            MAX_INTENSITY_WARNINGS
+        );
+        world.set_intensity("alpha", "r1", 2.0).unwrap();
+        assert_eq!(world.take_intensity_warnings().len(), 1);
+        assert_eq!(world.intensity_warnings_dropped, 5);
+    }
+}
@@ -0,0 +1,362 @@
+//! How likely an agent is to adopt a concept it sees: the `AdoptionModel`
+//! consulted by `Agent::step`, the default scoring rule with tunable
+//! coefficients, and a logistic regression for weights fitted to data.
+
+use crate::concept::Concept;
+use crate::core::agent::{Agent, BehaviorParams};
+use crate::policy::PolicyContext;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Income floor used in `resource_cost / income_level`.
+const INCOME_EPSILON: f32 = 1e-3;
+
+pub trait AdoptionModel: fmt::Debug + Send + Sync {
+    /// Chance (0..1) that `agent` adopts `concept` this tick, given its
+    /// `exposure` (regional plus social). Besides the agent, concept,
+    /// exposure and policy, models get the run's `behavior`: affordability,
+    /// prerequisites, regret and trust all weigh on adoption.
+    fn probability(
+        &self,
+        agent: &Agent,
+        concept: &Concept,
+        exposure: f32,
+        policy: &PolicyContext,
+        behavior: &BehaviorParams,
+    ) -> f32;
+
+    fn clone_box(&self) -> Box<dyn AdoptionModel>;
+
+    /// The spec that builds this model, so checkpoints can keep it; `None`
+    /// for models a scenario cannot name.
+    fn spec(&self) -> Option<AdoptionModelSpec> {
+        None
+    }
+}
+
+impl Clone for Box<dyn AdoptionModel> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+impl Default for Box<dyn AdoptionModel> {
+    fn default() -> Self {
+        Box::new(DefaultAdoptionModel::default())
+    }
+}
+
+/// Weights of the terms of `DefaultAdoptionModel`'s score.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdoptionCoefficients {
+    pub attractiveness: f32,
+    pub controversy: f32,
+    pub openness: f32,
+    pub risk_tolerance: f32,
+    pub expected_fear: f32,
+    pub eco_harm: f32,
+    pub exposure: f32,
+}
+
+impl Default for AdoptionCoefficients {
+    fn default() -> Self {
+        Self {
+            attractiveness: 1.0,
+            controversy: 1.0,
+            openness: 0.5,
+            risk_tolerance: 0.3,
+            expected_fear: 1.2,
+            eco_harm: 0.8,
+            exposure: 1.0,
+        }
+    }
+}
+
+/// Fear-before-benefit scoring: appeal, openness and exposure against
+/// expected fear, eco harm, policy, cost, missing prerequisites and regret,
+/// squashed by a sigmoid and damped by fatigue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct DefaultAdoptionModel {
+    pub coefficients: AdoptionCoefficients,
+}
+
+impl AdoptionModel for DefaultAdoptionModel {
+    fn probability(
+        &self,
+        agent: &Agent,
+        concept: &Concept,
+        exposure: f32,
+        policy: &PolicyContext,
+        behavior: &BehaviorParams,
+    ) -> f32 {
+        let c = &self.coefficients;
+        let Some((missing, cost_ratio)) = reachable(agent, concept, behavior) else {
+            return 0.0;
+        };
+        let prereq_penalty = match behavior.soft_prereq_penalty {
+            Some(per_missing) if missing > 0 => per_missing * missing as f32,
+            _ => 0.0,
+        };
+
+        // Base attractiveness vs controversy
+        let attrs = &concept.attrs;
+        let mut score = c.attractiveness * attrs.attractiveness - c.controversy * attrs.controversy;
+
+        // Agent openness and risk tolerance
+        score += c.openness * agent.beliefs.openness_to_change;
+        score += c.risk_tolerance * agent.attrs.risk_tolerance;
+
+        // Fear-first: discount by expected fear and ecological harm
+        let fear_penalty = concept.risk_profile.expected_fear;
+        let eco_penalty = concept.risk_profile.eco_harm_score;
+
+        // Policy can add further penalty if near ethical ceiling
+        let policy_penalty = policy.policy_penalty_for(concept);
+
+        score -= c.expected_fear * fear_penalty;
+        score -= c.eco_harm * eco_penalty;
+        score -= policy_penalty;
+        score -= behavior.affordability.cost_weight * cost_ratio;
+        score -= prereq_penalty;
+
+        // Past bad experiences make fear-inducing concepts harder to take up
+        score -= behavior.regret.weight * agent.state.regret * fear_penalty;
+
+        // Trusting agents favour low data-abuse concepts; distrustful ones are
+        // put off high data-abuse concepts the more they hear of them, fully
+        // (multiplier -1) at zero trust
+        let trust = &behavior.trust;
+        let trust_level = agent.beliefs.trust_in_institutions;
+        let data_abuse_risk = concept.risk_profile.data_abuse_risk;
+        if data_abuse_risk <= trust.low_risk_max {
+            score += trust.trust_weight * trust_level;
+        }
+        let exposure = if data_abuse_risk >= trust.high_risk_min
+            && trust_level < trust.distrust_threshold
+        {
+            -exposure * (1.0 - trust_level / trust.distrust_threshold)
+        } else {
+            exposure
+        };
+
+        // Exposure intensity and local norms
+        score += c.exposure * exposure;
+
+        damped(agent, score)
+    }
+
+    fn clone_box(&self) -> Box<dyn AdoptionModel> {
+        Box::new(*self)
+    }
+
+    fn spec(&self) -> Option<AdoptionModelSpec> {
+        Some(AdoptionModelSpec::Default(self.coefficients))
+    }
+}
+
+/// Names of `LogisticRegressionModel`'s features, in weight order.
+pub const LOGISTIC_FEATURES: [&str; 14] = [
+    "bias",
+    "attractiveness",
+    "controversy",
+    "openness_to_change",
+    "risk_tolerance",
+    "expected_fear",
+    "eco_harm_score",
+    "data_abuse_risk",
+    "trust_in_institutions",
+    "cost_ratio",
+    "missing_prerequisites",
+    "regret",
+    "exposure",
+    "policy_penalty",
+];
+
+/// `sigmoid(weights · features)`, damped by fatigue, with the features of
+/// `LOGISTIC_FEATURES` (`bias` is always 1). Missing weights count as 0 and
+/// extra ones are ignored. Missing hard prerequisites and unaffordable
+/// concepts still give 0, as under `DefaultAdoptionModel`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LogisticRegressionModel {
+    pub weights: Vec<f32>,
+}
+
+impl LogisticRegressionModel {
+    pub fn features(
+        agent: &Agent,
+        concept: &Concept,
+        exposure: f32,
+        policy: &PolicyContext,
+        missing: usize,
+        cost_ratio: f32,
+    ) -> [f32; LOGISTIC_FEATURES.len()] {
+        [
+            1.0,
+            concept.attrs.attractiveness,
+            concept.attrs.controversy,
+            agent.beliefs.openness_to_change,
+            agent.attrs.risk_tolerance,
+            concept.risk_profile.expected_fear,
+            concept.risk_profile.eco_harm_score,
+            concept.risk_profile.data_abuse_risk,
+            agent.beliefs.trust_in_institutions,
+            cost_ratio,
+            missing as f32,
+            agent.state.regret,
+            exposure,
+            policy.policy_penalty_for(concept),
+        ]
+    }
+}
+
+impl AdoptionModel for LogisticRegressionModel {
+    fn probability(
+        &self,
+        agent: &Agent,
+        concept: &Concept,
+        exposure: f32,
+        policy: &PolicyContext,
+        behavior: &BehaviorParams,
+    ) -> f32 {
+        let Some((missing, cost_ratio)) = reachable(agent, concept, behavior) else {
+            return 0.0;
+        };
+        let features = Self::features(agent, concept, exposure, policy, missing, cost_ratio);
+        let score: f32 = self.weights.iter().zip(features).map(|(w, x)| w * x).sum();
+        damped(agent, score)
+    }
+
+    fn clone_box(&self) -> Box<dyn AdoptionModel> {
+        Box::new(self.clone())
+    }
+
+    fn spec(&self) -> Option<AdoptionModelSpec> {
+        Some(AdoptionModelSpec::Logistic(self.clone()))
+    }
+}
+
+/// An adoption model as named in a scenario: `type = "default"` with the
+/// `AdoptionCoefficients` fields, or `type = "logistic"` with `weights`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AdoptionModelSpec {
+    Default(AdoptionCoefficients),
+    Logistic(LogisticRegressionModel),
+}
+
+impl Default for AdoptionModelSpec {
+    fn default() -> Self {
+        AdoptionModelSpec::Default(AdoptionCoefficients::default())
+    }
+}
+
+impl AdoptionModelSpec {
+    pub fn build(&self) -> Box<dyn AdoptionModel> {
+        match self {
+            AdoptionModelSpec::Default(coefficients) => Box::new(DefaultAdoptionModel {
+                coefficients: *coefficients,
+            }),
+            AdoptionModelSpec::Logistic(model) => Box::new(model.clone()),
+        }
+    }
+}
+
+/// Serde of `Simulation::adoption_model` as its `AdoptionModel::spec`,
+/// rebuilt when read back; models without a spec are written as `null` and
+/// read back as the default model.
+pub(crate) mod checkpointed {
+    use super::{AdoptionModel, AdoptionModelSpec};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    // `serde(with)` passes the field as it is declared
+    #[allow(clippy::borrowed_box)]
+    pub fn serialize<S: Serializer>(
+        model: &Box<dyn AdoptionModel>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        model.spec().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Box<dyn AdoptionModel>, D::Error> {
+        Ok(Option::<AdoptionModelSpec>::deserialize(deserializer)?
+            .map_or_else(Default::default, |spec| spec.build()))
+    }
+}
+
+/// Missing prerequisites and cost ratio of `concept` for `agent`; `None`
+/// when it is out of reach: a prerequisite is missing and they are hard, or
+/// it costs more than `max_cost_ratio`.
+fn reachable(agent: &Agent, concept: &Concept, behavior: &BehaviorParams) -> Option<(usize, f32)> {
+    let missing = concept
+        .prerequisites
+        .iter()
+        .filter(|id| !agent.state.adopted_concepts.contains(id))
+        .count();
+    if missing > 0 && behavior.soft_prereq_penalty.is_none() {
+        return None;
+    }
+    let cost_ratio = concept.attrs.resource_cost / agent.attrs.income_level.max(INCOME_EPSILON);
+    if cost_ratio > behavior.affordability.max_cost_ratio {
+        return None;
+    }
+    Some((missing, cost_ratio))
+}
+
+/// `score` squashed to 0..1, then damped by the agent's fatigue.
+fn damped(agent: &Agent, score: f32) -> f32 {
+    let p = (1.0 / (1.0 + (-score).exp())).clamp(0.0, 1.0);
+    p * (1.0 - agent.state.fatigue.clamp(0.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::tests::sim;
+
+    fn adopters_under(model: LogisticRegressionModel) -> u32 {
+        let mut sim = sim(200, 3);
+        sim.adoption_model = Box::new(model);
+        let result = sim.run();
+        result
+            .adoption_curves
+            .values()
+            .flatten()
+            .copied()
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn the_logistic_bias_sets_the_adoption_level() {
+        assert_eq!(
+            adopters_under(LogisticRegressionModel {
+                weights: vec![-50.0]
+            }),
+            0
+        );
+        assert!(adopters_under(LogisticRegressionModel { weights: vec![5.0] }) > 50);
+    }
+
+    #[test]
+    fn specs_build_the_model_they_came_from() {
+        let logistic = LogisticRegressionModel {
+            weights: vec![1.0, 2.0],
+        };
+        assert_eq!(
+            logistic.spec().unwrap().build().spec(),
+            Some(AdoptionModelSpec::Logistic(logistic))
+        );
+        let coefficients = AdoptionCoefficients {
+            openness: 0.7,
+            ..Default::default()
+        };
+        assert_eq!(
+            DefaultAdoptionModel { coefficients }.spec(),
+            Some(AdoptionModelSpec::Default(coefficients))
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! After-the-fact reading of a run's series: when a concept tipped (its
+//! growth peaked) and when it settled.
+
+use crate::core::id::Tick;
+use serde::{Deserialize, Serialize};
+
+/// Parameters of `tipping_point` and `steady_state`, applied to every
+/// adoption curve of a run.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnalysisConfig {
+    /// Width of the centered moving average over first differences; 1
+    /// leaves them as they are.
+    pub smoothing_window: usize,
+    /// How far (0..1) the peak of smoothed growth must rise above its
+    /// lowest point, relative to the peak, to count as a tipping point.
+    /// Keeps steady or linear growth from reporting one.
+    pub min_prominence: f32,
+    /// Points a series must stay within `steady_epsilon` of each other, up
+    /// to its end, to be steady.
+    pub steady_window: usize,
+    pub steady_epsilon: f32,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        Self {
+            smoothing_window: 3,
+            min_prominence: 0.5,
+            steady_window: 10,
+            steady_epsilon: 1.0,
+        }
+    }
+}
+
+impl AnalysisConfig {
+    /// Tick at which the smoothed growth of `series` (ordered by tick)
+    /// peaks, the first on ties. Growth between two points counts at the
+    /// later one. `None` for fewer than three points, no growth, or a peak
+    /// below `min_prominence`.
+    pub fn tipping_point(&self, series: &[(Tick, f32)]) -> Option<Tick> {
+        if series.len() < 3 {
+            return None;
+        }
+        let diffs: Vec<f32> = series.windows(2).map(|w| w[1].1 - w[0].1).collect();
+        let half = self.smoothing_window.max(1) / 2;
+        let smoothed: Vec<f32> = (0..diffs.len())
+            .map(|i| {
+                let around = &diffs[i.saturating_sub(half)..(i + half + 1).min(diffs.len())];
+                around.iter().sum::<f32>() / around.len() as f32
+            })
+            .collect();
+
+        let (peak_index, peak) = smoothed
+            .iter()
+            .enumerate()
+            .fold((0, f32::NEG_INFINITY), |best, (i, &growth)| {
+                if growth > best.1 {
+                    (i, growth)
+                } else {
+                    best
+                }
+            });
+        let lowest = smoothed.iter().copied().fold(f32::INFINITY, f32::min);
+        if peak <= 0.0 || (peak - lowest) / peak < self.min_prominence {
+            return None;
+        }
+        Some(series[peak_index + 1].0)
+    }
+
+    /// `detect_steady_state` with `steady_window` and `steady_epsilon`.
+    pub fn steady_state(&self, series: &[(Tick, f32)]) -> Option<Tick> {
+        detect_steady_state(series, self.steady_window, self.steady_epsilon)
+    }
+}
+
+/// `AnalysisConfig::tipping_point` under the default config.
+pub fn detect_tipping_point(series: &[(Tick, f32)]) -> Option<Tick> {
+    AnalysisConfig::default().tipping_point(series)
+}
+
+/// First tick of the longest tail of `series` (ordered by tick) whose values
+/// all lie within less than `epsilon` of each other, if that tail holds at
+/// least `window` points; i.e. when the series settled for good.
+pub fn detect_steady_state(series: &[(Tick, f32)], window: usize, epsilon: f32) -> Option<Tick> {
+    if window == 0 {
+        return None;
+    }
+    let (mut lo, mut hi) = (f32::INFINITY, f32::NEG_INFINITY);
+    let mut start = series.len();
+    for (i, &(_, value)) in series.iter().enumerate().rev() {
+        let (next_lo, next_hi) = (lo.min(value), hi.max(value));
+        if next_hi - next_lo >= epsilon {
+            break;
+        }
+        (lo, hi, start) = (next_lo, next_hi, i);
+    }
+    (series.len() - start >= window).then(|| series[start].0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logistic(midpoint: f32, rate: f32, ticks: Tick) -> Vec<(Tick, f32)> {
+        (0..ticks)
+            .map(|tick| {
+                (
+                    tick,
+                    1000.0 / (1.0 + (-rate * (tick as f32 - midpoint)).exp()),
+                )
+            })
+            .collect()
+    }
+
+    fn flat(ticks: Tick) -> Vec<(Tick, f32)> {
+        (0..ticks).map(|tick| (tick, 5.0)).collect()
+    }
+
+    #[test]
+    fn a_logistic_curve_tips_at_its_midpoint() {
+        for (midpoint, rate) in [(20.0, 0.4), (35.0, 0.2), (12.0, 1.0)] {
+            let tipping = detect_tipping_point(&logistic(midpoint, rate, 60)).unwrap();
+            assert!(
+                (tipping as f32 - midpoint).abs() <= 1.0,
+                "{tipping} vs {midpoint}"
+            );
+        }
+        let unsmoothed = AnalysisConfig {
+            smoothing_window: 1,
+            ..Default::default()
+        };
+        assert_eq!(unsmoothed.tipping_point(&logistic(25.5, 0.5, 60)), Some(26));
+    }
+
+    #[test]
+    fn flat_and_linear_curves_do_not_tip() {
+        assert_eq!(detect_tipping_point(&flat(30)), None);
+        assert_eq!(detect_tipping_point(&flat(2)), None);
+        let linear: Vec<(Tick, f32)> = (0..30).map(|tick| (tick, 2.0 * tick as f32)).collect();
+        assert_eq!(detect_tipping_point(&linear), None);
+    }
+
+    #[test]
+    fn steady_state_starts_once_the_curve_settles() {
+        let curve = logistic(20.0, 0.5, 80);
+        let steady = detect_steady_state(&curve, 10, 1.0).unwrap();
+        assert!(steady > 20 && steady < 50, "{steady}");
+        assert_eq!(detect_steady_state(&curve[..30], 10, 1.0), None);
+        assert_eq!(detect_steady_state(&flat(30), 10, 0.5), Some(0));
+        assert_eq!(detect_steady_state(&flat(30), 0, 0.5), None);
+    }
+}
@@ -0,0 +1,178 @@
+//! Exposure injected from outside the population, e.g. a media campaign.
+
+use crate::core::id::{ConceptId, RegionId, Tick};
+use crate::policy::{PolicyContext, PolicyDenial};
+use crate::world::World;
+use serde::{Deserialize, Serialize};
+
+/// One campaign: `intensity_per_tick` of exposure to `concept_id` added to
+/// each target region every tick in `start_tick..end_tick` that the concept
+/// is available, until `budget` (summed over regions and ticks) is spent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Broadcast {
+    pub concept_id: ConceptId,
+    /// `None` broadcasts to every region.
+    pub regions: Option<Vec<RegionId>>,
+    pub start_tick: Tick,
+    /// Exclusive.
+    pub end_tick: Tick,
+    pub intensity_per_tick: f32,
+    pub budget: f32,
+}
+
+impl Broadcast {
+    pub fn is_active(&self, tick: Tick) -> bool {
+        (self.start_tick..self.end_tick).contains(&tick)
+    }
+}
+
+/// Broadcasts applied by `Simulation::run` at the start of every tick,
+/// before agents step.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BroadcastSchedule {
+    pub entries: Vec<Broadcast>,
+}
+
+impl BroadcastSchedule {
+    /// Add this tick's broadcasts to `world.exposure_field`. `spent[i]` is
+    /// the budget entry `i` has used so far and is updated. Regions where
+    /// `policy` does not allow exposing the concept are skipped, each with a
+    /// denial in the returned list. Intensities are scaled by
+    /// `policy.exposure_multiplier()`.
+    pub fn apply(
+        &self,
+        tick: Tick,
+        world: &mut World,
+        policy: &PolicyContext,
+        spent: &mut Vec<f32>,
+    ) -> Vec<PolicyDenial> {
+        spent.resize(self.entries.len(), 0.0);
+        let mut denials = Vec::new();
+        let multiplier = policy.exposure_multiplier();
+        for (i, (entry, spent)) in self.entries.iter().zip(spent.iter_mut()).enumerate() {
+            if !entry.is_active(tick) {
+                continue;
+            }
+            let Some(concept) = world.concepts.get(&entry.concept_id) else {
+                continue;
+            };
+            if !concept.is_available(tick) {
+                continue;
+            }
+            // Region order decides who gets the last of the budget.
+            let mut regions = match &entry.regions {
+                Some(regions) => regions.clone(),
+                None => world.regions.keys().copied().collect(),
+            };
+            regions.sort_unstable();
+            regions.dedup();
+
+            for region in regions {
+                if let Some(rule) = policy.exposure_denial(concept, region, tick) {
+                    denials.push(PolicyDenial {
+                        tick,
+                        agent_id: None,
+                        concept_id: Some(entry.concept_id),
+                        region: Some(region),
+                        rule,
+                        details: format!("broadcast {i} of {} skipped", concept.attrs.name),
+                    });
+                    continue;
+                }
+                let amount = (entry.intensity_per_tick * multiplier).min(entry.budget - *spent);
+                if amount <= 0.0 {
+                    break;
+                }
+                *spent += amount;
+                world.exposure_field.add(region, entry.concept_id, amount);
+            }
+        }
+        denials
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concept::ConceptLegalStatus;
+    use crate::sim::tests::sim;
+    use crate::sim::{SimEvent, Simulation};
+
+    /// Concept 1 is only broadcast, into region 0 for ten ticks; nobody moves.
+    fn scenario() -> Simulation {
+        let mut sim = sim(300, 5);
+        sim.config.max_ticks = 20;
+        sim.config.stop_conditions = Vec::new();
+        for agent in &mut sim.agents {
+            agent.attrs.mobility_score = 0.0;
+        }
+        sim.world
+            .exposure_field
+            .retain(|_, concept, _| concept != 1);
+        sim.broadcasts.entries.push(Broadcast {
+            concept_id: 1,
+            regions: Some(vec![0]),
+            start_tick: 0,
+            end_tick: 10,
+            intensity_per_tick: 0.5,
+            budget: 2.0,
+        });
+        sim
+    }
+
+    /// Tick of the first adoption of concept 1 in `region`.
+    fn first_adoption(sim: &Simulation, region: RegionId) -> Option<Tick> {
+        sim.log.events.iter().find_map(|(tick, event)| match event {
+            SimEvent::Adopted { agent, concept: 1 }
+                if sim
+                    .agents
+                    .iter()
+                    .any(|a| a.id == *agent && a.state.region == region) =>
+            {
+                Some(*tick)
+            }
+            _ => None,
+        })
+    }
+
+    fn broadcast_exposure(world: &World) -> f32 {
+        world
+            .exposure_field
+            .entries()
+            .iter()
+            .filter(|(_, concept, _)| *concept == 1)
+            .map(|(_, _, intensity)| intensity)
+            .sum()
+    }
+
+    #[test]
+    fn the_broadcast_region_adopts_first() {
+        let mut sim = scenario();
+        sim.run();
+        let broadcast = first_adoption(&sim, 0).expect("adopted in region 0");
+        assert!(first_adoption(&sim, 1).is_none_or(|elsewhere| broadcast < elsewhere));
+        assert_eq!(
+            Simulation::replay(&scenario(), &sim.log).log.events,
+            sim.log.events
+        );
+    }
+
+    #[test]
+    fn spending_stops_at_the_budget() {
+        let mut sim = scenario();
+        sim.agents.clear();
+        sim.broadcasts.entries[0].regions = None;
+        let result = sim.run();
+        assert!((broadcast_exposure(&sim.world) - 2.0).abs() < 1e-5);
+        assert_eq!(result.blocked_broadcasts, 0);
+    }
+
+    #[test]
+    fn policy_blocks_broadcasts() {
+        let mut sim = scenario();
+        sim.world.concepts.get_mut(&1).unwrap().legal_status = ConceptLegalStatus::Prohibited;
+        let result = sim.run();
+        assert_eq!(result.blocked_broadcasts, 10);
+        assert_eq!(broadcast_exposure(&sim.world), 0.0);
+    }
+}
@@ -0,0 +1,377 @@
+//! Fitting `DefaultAdoptionModel` coefficients to an observed adoption
+//! curve.
+//!
+//! `fit` searches a box of coefficient values by random sampling with a
+//! shrinking box: each round samples a batch of candidates around the best
+//! point so far, runs the scenario once per seed for each of them and keeps
+//! the one whose adoption share curve is closest (RMSE) to the observed one.
+//! Candidates of a round are independent and can run on the rayon pool.
+
+use crate::adoption::{AdoptionCoefficients, DefaultAdoptionModel};
+use crate::core::id::{ConceptId, Tick};
+use crate::sim::Simulation;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A coefficient of `AdoptionCoefficients` that `fit` may change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CalibrationParam {
+    Attractiveness,
+    Controversy,
+    Openness,
+    RiskTolerance,
+    ExpectedFear,
+    EcoHarm,
+    Exposure,
+}
+
+impl CalibrationParam {
+    pub fn get(self, coefficients: &AdoptionCoefficients) -> f32 {
+        match self {
+            CalibrationParam::Attractiveness => coefficients.attractiveness,
+            CalibrationParam::Controversy => coefficients.controversy,
+            CalibrationParam::Openness => coefficients.openness,
+            CalibrationParam::RiskTolerance => coefficients.risk_tolerance,
+            CalibrationParam::ExpectedFear => coefficients.expected_fear,
+            CalibrationParam::EcoHarm => coefficients.eco_harm,
+            CalibrationParam::Exposure => coefficients.exposure,
+        }
+    }
+
+    pub fn set(self, coefficients: &mut AdoptionCoefficients, value: f32) {
+        let field = match self {
+            CalibrationParam::Attractiveness => &mut coefficients.attractiveness,
+            CalibrationParam::Controversy => &mut coefficients.controversy,
+            CalibrationParam::Openness => &mut coefficients.openness,
+            CalibrationParam::RiskTolerance => &mut coefficients.risk_tolerance,
+            CalibrationParam::ExpectedFear => &mut coefficients.expected_fear,
+            CalibrationParam::EcoHarm => &mut coefficients.eco_harm,
+            CalibrationParam::Exposure => &mut coefficients.exposure,
+        };
+        *field = value;
+    }
+}
+
+/// Which concept the observed curve is for and how `fit` runs candidates.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CalibrationConfig {
+    pub concept_id: ConceptId,
+    /// Coefficients every candidate starts from; only the fitted ones vary.
+    pub base: AdoptionCoefficients,
+    /// Each candidate is scored on the mean share curve of one run per
+    /// seed; the scenario's own `random_seed` when empty.
+    pub seeds: Vec<u64>,
+    /// Candidates sampled per round, besides the best point so far.
+    pub batch_size: usize,
+    /// Side of the search box, relative to the previous round, after each
+    /// round (0..1).
+    pub shrink: f32,
+    /// Seed of the sampler, so a fit can be repeated.
+    pub search_seed: u64,
+    /// Evaluate a round's candidates on the rayon pool.
+    pub parallel: bool,
+}
+
+impl Default for CalibrationConfig {
+    fn default() -> Self {
+        Self {
+            concept_id: 0,
+            base: AdoptionCoefficients::default(),
+            seeds: Vec::new(),
+            batch_size: 16,
+            shrink: 0.6,
+            search_seed: 0,
+            parallel: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalibrationError {
+    /// `params` and `bounds` have different lengths.
+    BoundsMismatch {
+        params: usize,
+        bounds: usize,
+    },
+    /// A bound is not finite or its low end is above its high end.
+    InvalidBounds {
+        param: CalibrationParam,
+        low: f32,
+        high: f32,
+    },
+    NoObservations,
+    UnknownConcept(ConceptId),
+}
+
+impl fmt::Display for CalibrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalibrationError::BoundsMismatch { params, bounds } => {
+                write!(f, "{params} parameters but {bounds} bounds")
+            }
+            CalibrationError::InvalidBounds { param, low, high } => {
+                write!(f, "invalid bounds {low}..{high} for {param:?}")
+            }
+            CalibrationError::NoObservations => write!(f, "no observed points to fit"),
+            CalibrationError::UnknownConcept(id) => write!(f, "unknown concept {id}"),
+        }
+    }
+}
+
+impl std::error::Error for CalibrationError {}
+
+/// Best candidate found by `fit`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationResult {
+    /// Fitted value of each parameter, in the order they were given.
+    pub params: Vec<(CalibrationParam, f32)>,
+    /// `config.base` with the fitted values applied.
+    pub coefficients: AdoptionCoefficients,
+    /// RMSE between the observed and simulated share curves.
+    pub score: f32,
+    /// Candidates evaluated, the starting point included.
+    pub evaluations: usize,
+}
+
+/// Fit `params` of the scenario's coefficients, each within its `bounds`
+/// entry, to `observed` (tick, share of all agents that adopted the
+/// concept), evaluating at most `budget` candidates (at least one: the
+/// middle of the box).
+///
+/// `scenario` is a simulation that has not been run; its adoption model is
+/// replaced by a `DefaultAdoptionModel` for every candidate, starting from
+/// `config.base`. Ticks after a run has stopped take its final share.
+pub fn fit(
+    scenario: &Simulation,
+    config: &CalibrationConfig,
+    observed: &[(Tick, f32)],
+    params: &[CalibrationParam],
+    bounds: &[(f32, f32)],
+    budget: usize,
+) -> Result<CalibrationResult, CalibrationError> {
+    if params.len() != bounds.len() {
+        return Err(CalibrationError::BoundsMismatch {
+            params: params.len(),
+            bounds: bounds.len(),
+        });
+    }
+    for (&param, &(low, high)) in params.iter().zip(bounds) {
+        if !low.is_finite() || !high.is_finite() || low > high {
+            return Err(CalibrationError::InvalidBounds { param, low, high });
+        }
+    }
+    if observed.is_empty() {
+        return Err(CalibrationError::NoObservations);
+    }
+    if !scenario.world.concepts.contains_key(&config.concept_id) {
+        return Err(CalibrationError::UnknownConcept(config.concept_id));
+    }
+
+    let base = config.base;
+    let score = |point: &Vec<f32>| {
+        let mut coefficients = base;
+        for (param, value) in params.iter().zip(point) {
+            param.set(&mut coefficients, *value);
+        }
+        rmse(scenario, config, observed, coefficients)
+    };
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(config.search_seed);
+    let mut best: Vec<f32> = bounds
+        .iter()
+        .map(|(low, high)| (low + high) / 2.0)
+        .collect();
+    let mut best_score = score(&best);
+    let mut evaluations = 1;
+    let mut half_widths: Vec<f32> = bounds
+        .iter()
+        .map(|(low, high)| (high - low) / 2.0)
+        .collect();
+    while evaluations < budget {
+        let n = config.batch_size.max(1).min(budget - evaluations);
+        let candidates: Vec<Vec<f32>> = (0..n)
+            .map(|_| {
+                best.iter()
+                    .zip(&half_widths)
+                    .zip(bounds)
+                    .map(|((center, half), (low, high))| {
+                        let from = (center - half).max(*low);
+                        let to = (center + half).min(*high);
+                        if from < to {
+                            rng.gen_range(from..=to)
+                        } else {
+                            from
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        let scores: Vec<f32> = if config.parallel {
+            candidates.par_iter().map(score).collect()
+        } else {
+            candidates.iter().map(score).collect()
+        };
+        evaluations += n;
+        // Ties keep the earlier candidate, so the result does not depend on
+        // the order the pool finished them in.
+        for (candidate, candidate_score) in candidates.into_iter().zip(scores) {
+            if candidate_score < best_score {
+                best = candidate;
+                best_score = candidate_score;
+            }
+        }
+        for half in &mut half_widths {
+            *half *= config.shrink.clamp(0.0, 1.0);
+        }
+    }
+
+    let mut coefficients = base;
+    for (param, value) in params.iter().zip(&best) {
+        param.set(&mut coefficients, *value);
+    }
+    Ok(CalibrationResult {
+        params: params.iter().copied().zip(best).collect(),
+        coefficients,
+        score: best_score,
+        evaluations,
+    })
+}
+
+/// RMSE between `observed` and the mean share curve of `config.concept_id`
+/// over the runs of `scenario` with `coefficients`.
+fn rmse(
+    scenario: &Simulation,
+    config: &CalibrationConfig,
+    observed: &[(Tick, f32)],
+    coefficients: AdoptionCoefficients,
+) -> f32 {
+    let seeds = if config.seeds.is_empty() {
+        vec![scenario.config.random_seed]
+    } else {
+        config.seeds.clone()
+    };
+    let mut simulated = vec![0.0f32; observed.len()];
+    for &seed in &seeds {
+        let mut sim = scenario.clone();
+        sim.config.random_seed = seed;
+        sim.adoption_model = Box::new(DefaultAdoptionModel { coefficients });
+        let agents = sim.agents.len().max(1) as f32;
+        let result = sim.run();
+        let curve = result.adoption_curves.get(&config.concept_id);
+        for (share, &(tick, _)) in simulated.iter_mut().zip(observed) {
+            let adopters = curve
+                .and_then(|curve| curve.get(tick as usize).or(curve.last()))
+                .copied()
+                .unwrap_or(0);
+            *share += adopters as f32 / agents;
+        }
+    }
+    let runs = seeds.len() as f32;
+    let squared: f32 = simulated
+        .iter()
+        .zip(observed)
+        .map(|(share, (_, target))| (share / runs - target).powi(2))
+        .sum();
+    (squared / observed.len() as f32).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::tests::sim;
+
+    const PARAMS: [CalibrationParam; 2] =
+        [CalibrationParam::Attractiveness, CalibrationParam::Exposure];
+    const BOUNDS: [(f32, f32); 2] = [(0.0, 3.0), (0.0, 2.0)];
+
+    /// The scenario to fit and the share curve of concept 0 it gives with
+    /// attractiveness 1.8 and exposure 0.4.
+    fn observed_run() -> (Simulation, Vec<(Tick, f32)>) {
+        let mut scenario = sim(200, 5);
+        scenario.config.max_ticks = 15;
+        let truth = AdoptionCoefficients {
+            attractiveness: 1.8,
+            exposure: 0.4,
+            ..Default::default()
+        };
+        let mut observed = scenario.clone();
+        observed.adoption_model = Box::new(DefaultAdoptionModel {
+            coefficients: truth,
+        });
+        let agents = observed.agents.len() as f32;
+        let curve = observed
+            .run()
+            .adoption_curves
+            .remove(&0)
+            .unwrap()
+            .into_iter()
+            .enumerate()
+            .map(|(tick, adopters)| (tick as Tick, adopters as f32 / agents))
+            .collect();
+        (scenario, curve)
+    }
+
+    #[test]
+    fn a_fit_recovers_the_coefficients_behind_a_curve() {
+        let (scenario, observed) = observed_run();
+        let config = CalibrationConfig::default();
+        let result = fit(&scenario, &config, &observed, &PARAMS, &BOUNDS, 100).unwrap();
+        assert_eq!(result.evaluations, 100);
+        assert!((result.params[0].1 - 1.8).abs() < 0.3, "{result:?}");
+        assert!((result.params[1].1 - 0.4).abs() < 0.3, "{result:?}");
+        let json = serde_json::to_string(&result).unwrap();
+        assert_eq!(
+            serde_json::from_str::<CalibrationResult>(&json).unwrap(),
+            result
+        );
+
+        let sequential = CalibrationConfig {
+            parallel: false,
+            ..config
+        };
+        assert_eq!(
+            fit(&scenario, &sequential, &observed, &PARAMS, &BOUNDS, 100).unwrap(),
+            result
+        );
+    }
+
+    #[test]
+    fn bad_inputs_are_rejected() {
+        let (scenario, observed) = observed_run();
+        let config = CalibrationConfig::default();
+        assert_eq!(
+            fit(&scenario, &config, &observed, &PARAMS, &BOUNDS[..1], 5),
+            Err(CalibrationError::BoundsMismatch {
+                params: 2,
+                bounds: 1
+            })
+        );
+        assert!(matches!(
+            fit(
+                &scenario,
+                &config,
+                &observed,
+                &PARAMS[..1],
+                &[(1.0, 0.0)],
+                5
+            ),
+            Err(CalibrationError::InvalidBounds { .. })
+        ));
+        assert_eq!(
+            fit(&scenario, &config, &[], &PARAMS, &BOUNDS, 5),
+            Err(CalibrationError::NoObservations)
+        );
+        let unknown = CalibrationConfig {
+            concept_id: 9,
+            ..Default::default()
+        };
+        assert_eq!(
+            fit(&scenario, &unknown, &observed, &PARAMS, &BOUNDS, 5),
+            Err(CalibrationError::UnknownConcept(9))
+        );
+    }
+}
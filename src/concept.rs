@@ -1,14 +1,16 @@
-use crate::core::id::ConceptId;
+use crate::core::id::{ConceptId, Tick};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConceptAttributes {
     pub name: String,
     pub attractiveness: f32, // perceived benefit
     pub controversy: f32,    // perceived social risk
     pub resource_cost: f32,  // money/time/energy per use
+    pub virality: f32,       // 0..1 share of a Share's exposure spilling into neighbors
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConceptRiskProfile {
     pub expected_fear: f32,      // 0..1 (panic, regret, social harm)
     pub eco_harm_score: f32,     // 0..1 (ecological damage)
@@ -16,15 +18,41 @@ pub struct ConceptRiskProfile {
     pub irreversible_bio_risk: f32, // 0..1 (hard ethical stop)
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Concept {
     pub id: ConceptId,
     pub attrs: ConceptAttributes,
     pub risk_profile: ConceptRiskProfile,
     pub legal_status: ConceptLegalStatus,
+    /// Concepts an agent must have adopted before adopting this one.
+    pub prerequisites: Vec<ConceptId>,
+    /// First tick the concept can be seen, broadcast, shared or adopted.
+    #[serde(default)]
+    pub available_from: Tick,
+    /// Tick it is withdrawn; unavailable from then on.
+    #[serde(default)]
+    pub sunset_at: Option<Tick>,
+    /// From `sunset_at`, the per-tick probability that each adopter
+    /// abandons it; 0 lets adopters keep it.
+    #[serde(default)]
+    pub sunset_abandon_rate: f32,
+    /// Concept this one mutated from while spreading (see
+    /// `MutationDynamics`); `None` for concepts of the scenario.
+    #[serde(default)]
+    pub parent: Option<ConceptId>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl Concept {
+    pub fn is_available(&self, tick: Tick) -> bool {
+        tick >= self.available_from && !self.is_sunset(tick)
+    }
+
+    pub fn is_sunset(&self, tick: Tick) -> bool {
+        self.sunset_at.is_some_and(|sunset| tick >= sunset)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ConceptLegalStatus {
     Allowed,
     Restricted,
@@ -1,32 +1,207 @@
-use crate::core::id::{AgentId, RegionId, ConceptId};
+use crate::adoption::AdoptionModel;
+use crate::core::id::{AgentId, RegionId, ConceptId, Tick};
+use crate::concept::Concept;
+use crate::metrics::FunnelCounter;
 use crate::policy::PolicyContext;
-use crate::world::WorldView;
+use crate::social::SocialView;
+use crate::world::{MovementModel, WorldView};
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+/// Random stream owned by a single agent, so agents can be stepped in
+/// parallel without sharing an RNG. ChaCha12 (the algorithm behind `StdRng`)
+/// from `rand_chacha`, whose `serde1` feature lets checkpoints store it.
+pub type AgentRng = rand_chacha::ChaCha12Rng;
+
+/// Stream for `agent_id` in a run seeded with `seed`: SplitMix64 of
+/// `seed ^ agent_id`, so each agent's draws do not depend on the others.
+pub fn agent_rng(seed: u64, agent_id: AgentId) -> AgentRng {
+    let mut z = (seed ^ agent_id).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    AgentRng::seed_from_u64(z ^ (z >> 31))
+}
+
+/// How `AgentState::fatigue` builds up with actions and wears off.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FatigueDynamics {
+    /// Fatigue added by each Adopt or Share.
+    pub per_action: f32,
+    /// Fatigue removed at the start of each tick.
+    pub recovery_per_tick: f32,
+    /// Above this, the agent does not consider moving.
+    pub movement_threshold: f32,
+}
+
+impl Default for FatigueDynamics {
+    fn default() -> Self {
+        Self {
+            per_action: 0.1,
+            recovery_per_tick: 0.05,
+            movement_threshold: 0.8,
+        }
+    }
+}
+
+/// Scale of the per-tick chance to abandon an adopted concept.
+const ABANDON_RATE: f32 = 0.5;
+/// Openness floor used when dividing by `openness_to_change`.
+const OPENNESS_EPSILON: f32 = 0.1;
+
+/// How a concept's `resource_cost` weighs against an agent's `income_level`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Affordability {
+    /// Score penalty per unit of cost-to-income ratio.
+    pub cost_weight: f32,
+    /// Above this ratio the concept is never adopted.
+    pub max_cost_ratio: f32,
+}
+
+impl Default for Affordability {
+    fn default() -> Self {
+        Self {
+            cost_weight: 0.5,
+            max_cost_ratio: 4.0,
+        }
+    }
+}
+
+/// How `data_abuse_risk` erodes `trust_in_institutions`, and how trust in
+/// turn weighs on adoption.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrustDynamics {
+    /// Trust lost each tick per unit of summed `data_abuse_risk` of the
+    /// agent's adopted concepts.
+    pub erosion_rate: f32,
+    /// Concepts at or below this `data_abuse_risk` gain
+    /// `trust_weight * trust` in their adoption score.
+    pub low_risk_max: f32,
+    pub trust_weight: f32,
+    /// Concepts at or above this `data_abuse_risk` see exposure turned
+    /// against them once trust falls below `distrust_threshold`.
+    pub high_risk_min: f32,
+    pub distrust_threshold: f32,
+}
+
+impl Default for TrustDynamics {
+    fn default() -> Self {
+        Self {
+            erosion_rate: 0.01,
+            low_risk_max: 0.3,
+            trust_weight: 0.3,
+            high_risk_min: 0.7,
+            distrust_threshold: 0.3,
+        }
+    }
+}
+
+impl TrustDynamics {
+    /// Trust after one tick of holding `adopted`; never below 0.
+    pub fn eroded(&self, trust: f32, adopted: &[&Concept]) -> f32 {
+        let risk: f32 = adopted
+            .iter()
+            .map(|concept| concept.risk_profile.data_abuse_risk)
+            .sum();
+        (trust - self.erosion_rate * risk).max(0.0)
+    }
+}
+
+/// How bad experiences build up `AgentState::regret`, how it fades, and
+/// how it holds back adopting fear-inducing concepts.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RegretDynamics {
+    /// Regret added by each abandonment.
+    pub per_abandonment: f32,
+    /// Regret added when fear ends a tick at least `spike_threshold` above
+    /// where it stood just before an adoption less than
+    /// `attribution_window` ticks earlier. Counted once per adoption.
+    pub per_spike: f32,
+    pub spike_threshold: f32,
+    pub attribution_window: Tick,
+    /// Regret removed at the end of each tick.
+    pub decay_per_tick: f32,
+    /// Score penalty per unit of regret times the candidate concept's
+    /// `expected_fear`.
+    pub weight: f32,
+}
+
+impl Default for RegretDynamics {
+    fn default() -> Self {
+        Self {
+            per_abandonment: 0.2,
+            per_spike: 0.1,
+            spike_threshold: 0.2,
+            attribution_window: 5,
+            decay_per_tick: 0.005,
+            weight: 1.0,
+        }
+    }
+}
+
+impl RegretDynamics {
+    /// Regret after `added`, kept within 0..1.
+    pub fn raised(&self, regret: f32, added: f32) -> f32 {
+        (regret + added).clamp(0.0, 1.0)
+    }
+}
+
+/// Behaviour parameters shared by every agent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BehaviorParams {
+    pub fatigue: FatigueDynamics,
+    pub affordability: Affordability,
+    pub movement: MovementModel,
+    /// Score penalty per missing prerequisite. `None` blocks adoption until
+    /// every prerequisite is adopted.
+    pub soft_prereq_penalty: Option<f32>,
+    #[serde(default)]
+    pub trust: TrustDynamics,
+    #[serde(default)]
+    pub regret: RegretDynamics,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentAttributes {
     pub age: u8,
     pub income_level: f32,
     pub risk_tolerance: f32, // 0..1
     pub mobility_score: f32, // 0..1
     pub eco_values: f32,     // 0..1 (nature-first concern)
+    /// How much others heed this agent's shares, 0..1.
+    pub influence: f32,
+    /// Labels matched by `AttributeRule::predicate_tag`, e.g. "professional".
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentBeliefs {
     pub openness_to_change: f32, // 0..1
     pub trust_in_institutions: f32,
     pub tech_skepticism: f32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentState {
     pub region: RegionId,
     pub adopted_concepts: Vec<ConceptId>,
     pub fatigue: f32,  // 0..1
     pub fear_level: f32, // 0..1 (per-agent fear)
+    /// 0..1, from abandonments and fear spikes after adopting; see
+    /// `RegretDynamics`.
+    #[serde(default)]
+    pub regret: f32,
+    /// Tick of the latest adoption not yet blamed for a fear spike, and
+    /// the fear just before it.
+    #[serde(default)]
+    pub last_adoption: Option<(Tick, f32)>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Agent {
     pub id: AgentId,
     pub attrs: AgentAttributes,
@@ -35,19 +210,38 @@ pub struct Agent {
 }
 
 impl Agent {
-    /// Decide actions for this tick: move, adopt, share, etc.
+    /// Decide actions for `world`'s tick: move, adopt, share, etc., appended
+    /// to `out.actions` in the order taken. `rng` is this agent's own stream
+    /// (see `agent_rng`); the concepts the agent saw, could adopt and chose
+    /// to adopt are counted in `out.funnel`.
     pub fn step(
         &mut self,
-        tick: Tick,
         world: &WorldView,
-        policy: &PolicyContext,
-        rng: &mut impl rand::Rng,
-    ) -> Vec<AgentAction> {
-        let mut actions = Vec::new();
-
-        // 1. Movement decision (simplified)
-        if rng.gen::<f32>() < self.attrs.mobility_score {
-            if let Some(new_region) = world.sample_neighbor_region(self.state.region, rng) {
+        social: &SocialView,
+        rules: &StepRules,
+        rng: &mut AgentRng,
+        out: &mut StepOutput,
+    ) {
+        let StepRules {
+            policy,
+            behavior,
+            adoption,
+        } = *rules;
+        let tick = world.tick();
+        let fatigue = &behavior.fatigue;
+        let StepOutput { actions, funnel } = out;
+        self.recover_fatigue(fatigue);
+
+        // 1. Movement decision (simplified); exhausted agents stay put
+        if self.state.fatigue <= fatigue.movement_threshold
+            && rng.gen::<f32>() < self.attrs.mobility_score
+        {
+            if let Some(new_region) = world.sample_neighbor_region(
+                self.state.region,
+                behavior.movement,
+                &policy.closed_regions,
+                rng,
+            ) {
                 actions.push(AgentAction::Move {
                     agent_id: self.id,
                     from: self.state.region,
@@ -57,75 +251,148 @@ impl Agent {
         }
 
         // 2. Concept adoption/share decisions
-        for concept in world.visible_concepts(self.state.region) {
-            // Check policy: is exposure/adoption allowed here?
-            if !policy.is_exposure_allowed(concept.id, self.state.region, tick) {
+        for concept in self.candidate_concepts(world, social) {
+            let exposure_intensity = world.local_exposure_intensity(concept.id, self.state.region)
+                + social.influence(self.id, concept.id);
+            let exposed = funnel.expose(concept.id, exposure_intensity);
+
+            // Check policy: is exposure/adoption allowed here, for us?
+            if !policy.is_adoption_allowed(self, concept, self.state.region, tick) {
                 continue;
             }
+            if exposed {
+                funnel.consider(concept.id);
+            }
 
-            let exposure_intensity =
-                world.local_exposure_intensity(concept.id, self.state.region);
-
-            let p_adopt = adoption_probability(self, concept, exposure_intensity, policy);
-            if rng.gen::<f32>() < p_adopt {
-                if !self.state.adopted_concepts.contains(&concept.id) {
-                    actions.push(AgentAction::Adopt {
-                        agent_id: self.id,
-                        concept_id: concept.id,
-                    });
+            let p_adopt =
+                adoption.probability(self, concept, exposure_intensity, policy, behavior);
+            if rng.gen::<f32>() < p_adopt && !self.state.adopted_concepts.contains(&concept.id) {
+                actions.push(AgentAction::Adopt {
+                    agent_id: self.id,
+                    concept_id: concept.id,
+                });
+                self.add_fatigue(fatigue);
+                if exposed {
+                    funnel.adopt(concept.id);
                 }
             }
 
-            // Optionally share concept (word-of-mouth)
-            let p_share = p_adopt * 0.5;
+            // Optionally share concept (word-of-mouth); p_adopt already
+            // carries the fatigue damping. Influential agents share a bit
+            // more readily (x0.75 to x1.25).
+            let p_share = p_adopt * 0.5 * (0.75 + 0.5 * self.attrs.influence.clamp(0.0, 1.0));
             if rng.gen::<f32>() < p_share {
                 actions.push(AgentAction::Share {
                     agent_id: self.id,
                     concept_id: concept.id,
                     region: self.state.region,
                 });
+                self.add_fatigue(fatigue);
+            }
+        }
+
+        // 3. Regret: fearful, closed-minded agents drop fear-inducing concepts,
+        // and withdrawn concepts may be dropped regardless
+        for concept_id in &self.state.adopted_concepts {
+            let Some(concept) = world.concept(*concept_id) else {
+                continue;
+            };
+            let mut p_abandon = (ABANDON_RATE
+                * concept.risk_profile.expected_fear
+                * self.state.fear_level
+                / self.beliefs.openness_to_change.max(OPENNESS_EPSILON))
+            .clamp(0.0, 1.0);
+            if concept.is_sunset(tick) {
+                p_abandon = p_abandon.max(concept.sunset_abandon_rate);
+            }
+            if rng.gen::<f32>() < p_abandon {
+                actions.push(AgentAction::Abandon {
+                    agent_id: self.id,
+                    concept_id: *concept_id,
+                });
+            }
+        }
+    }
+
+    /// Concepts `step` considers, in id order: those visible in the agent's
+    /// region or heard about from social neighbors, if available. Policy is
+    /// not applied.
+    pub fn candidate_concepts<'v>(
+        &self,
+        world: &'v WorldView,
+        social: &SocialView,
+    ) -> Vec<&'v Concept> {
+        let mut candidates: Vec<&Concept> = world.visible_concepts(self.state.region).to_vec();
+        for concept_id in social.concepts(self.id) {
+            if let Some(concept) = world.concept(concept_id) {
+                if concept.is_available(world.tick())
+                    && !candidates.iter().any(|c| c.id == concept_id)
+                {
+                    candidates.push(concept);
+                }
             }
         }
+        candidates.sort_by_key(|concept| concept.id);
+        candidates
+    }
 
-        actions
+    /// Per-tick recovery, applied at the start of `step`.
+    pub fn recover_fatigue(&mut self, fatigue: &FatigueDynamics) {
+        self.state.fatigue = (self.state.fatigue - fatigue.recovery_per_tick).max(0.0);
+    }
+
+    /// Fatigue from one Adopt or Share.
+    pub fn add_fatigue(&mut self, fatigue: &FatigueDynamics) {
+        self.state.fatigue = (self.state.fatigue + fatigue.per_action).min(1.0);
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum AgentAction {
     Move { agent_id: AgentId, from: RegionId, to: RegionId },
     Adopt { agent_id: AgentId, concept_id: ConceptId },
     Share { agent_id: AgentId, concept_id: ConceptId, region: RegionId },
+    Abandon { agent_id: AgentId, concept_id: ConceptId },
 }
 
-// Simple adoption probability including fear-before-benefit
-fn adoption_probability(
-    agent: &Agent,
-    concept: &crate::concept::Concept,
-    exposure: f32,
-    policy: &PolicyContext,
-) -> f32 {
-    // Base attractiveness vs controversy
-    let mut score = concept.attrs.attractiveness - concept.attrs.controversy;
-
-    // Agent openness and risk tolerance
-    score += 0.5 * agent.beliefs.openness_to_change;
-    score += 0.3 * agent.attrs.risk_tolerance;
-
-    // Fear-first: discount by expected fear and ecological harm
-    let fear_penalty = concept.risk_profile.expected_fear;
-    let eco_penalty = concept.risk_profile.eco_harm_score;
+impl AgentAction {
+    pub fn agent_id(&self) -> AgentId {
+        match self {
+            AgentAction::Move { agent_id, .. }
+            | AgentAction::Adopt { agent_id, .. }
+            | AgentAction::Share { agent_id, .. }
+            | AgentAction::Abandon { agent_id, .. } => *agent_id,
+        }
+    }
+}
 
-    // Policy can add further penalty if near ethical ceiling
-    let policy_penalty = policy.policy_penalty_for(concept.id);
+/// What `Agent::step` decides by, shared by every agent.
+#[derive(Clone, Copy)]
+pub struct StepRules<'a> {
+    pub policy: &'a PolicyContext,
+    pub behavior: &'a BehaviorParams,
+    pub adoption: &'a dyn AdoptionModel,
+}
 
-    score -= 1.2 * fear_penalty;
-    score -= 0.8 * eco_penalty;
-    score -= policy_penalty;
+/// Buffer `Agent::step` appends to, so one can be reused across agents and
+/// ticks instead of allocating per step; clear it between uses.
+#[derive(Debug, Clone, Default)]
+pub struct StepOutput {
+    pub actions: Vec<AgentAction>,
+    pub funnel: FunnelCounter,
+}
 
-    // Exposure intensity and local norms
-    score += exposure;
+impl StepOutput {
+    pub fn new(funnel_threshold: f32) -> Self {
+        Self {
+            actions: Vec::new(),
+            funnel: FunnelCounter::new(funnel_threshold),
+        }
+    }
 
-    // Squash to [0,1]
-    (1.0 / (1.0 + (-score).exp())).clamp(0.0, 1.0)
+    /// Drop the actions and counts, keeping the allocated capacity.
+    pub fn clear(&mut self) {
+        self.actions.clear();
+        self.funnel.clear();
+    }
 }
@@ -0,0 +1,931 @@
+//! Batch runs of one scenario: policy comparisons, Monte Carlo sweeps and
+//! single-intervention counterfactuals.
+//!
+//! Every run starts from a clone of the same initial simulation with
+//! `random_seed` set to the run's seed. In a comparison, runs that share a
+//! seed differ only in their policy and can be compared pairwise.
+
+use crate::core::id::{ConceptId, RegionId, Tick};
+use crate::observer::SimObserver;
+use crate::policy::{DenialRule, PolicyContext};
+use crate::sim::{SimEvent, Simulation};
+use crate::snapshot::RegionSnapshot;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::ops::ControlFlow;
+
+pub use crate::intervention::Intervention;
+
+/// What one run of a comparison produced.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunOutcome {
+    pub seed: u64,
+    /// Highest global fear index over the run.
+    pub peak_fear: f32,
+    pub eco_damage_score: f32,
+    /// concept -> adopters at the end of the run
+    pub final_adopters: BTreeMap<ConceptId, u32>,
+    /// Ticks executed before the ethical ceiling stopped the run, if it did.
+    pub ticks_to_ceiling: Option<Tick>,
+}
+
+/// One policy's runs and their means across seeds.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PolicySummary {
+    pub name: String,
+    /// One per seed, in the order the seeds were given.
+    pub runs: Vec<RunOutcome>,
+    pub mean_peak_fear: f32,
+    pub mean_eco_damage_score: f32,
+    pub mean_final_adopters: BTreeMap<ConceptId, f32>,
+    /// Runs stopped by the ethical ceiling.
+    pub ceiling_hits: usize,
+    /// Mean over the runs in `ceiling_hits`; `None` when there are none.
+    pub mean_ticks_to_ceiling: Option<f32>,
+}
+
+/// A policy's outcome minus the baseline's (the first policy) for one seed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PairedDifference {
+    pub policy: String,
+    pub seed: u64,
+    pub peak_fear: f32,
+    pub eco_damage_score: f32,
+    pub final_adopters: BTreeMap<ConceptId, i64>,
+    /// Only when both runs hit the ceiling.
+    pub ticks_to_ceiling: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ComparisonReport {
+    pub seeds: Vec<u64>,
+    /// In the order the policies were given; the first is the baseline.
+    pub policies: Vec<PolicySummary>,
+    /// For every policy after the baseline, one entry per seed.
+    pub differences: Vec<PairedDifference>,
+}
+
+/// Run `scenario` (a simulation that has not been run) once per policy and
+/// seed.
+pub fn run_comparison(
+    scenario: &Simulation,
+    policies: Vec<(String, PolicyContext)>,
+    seeds: &[u64],
+) -> ComparisonReport {
+    let summaries: Vec<PolicySummary> = policies
+        .into_iter()
+        .map(|(name, policy)| {
+            let runs = seeds
+                .iter()
+                .map(|&seed| run_once(scenario, &policy, seed))
+                .collect();
+            summarize(name, runs)
+        })
+        .collect();
+
+    let mut differences = Vec::new();
+    if let Some((baseline, others)) = summaries.split_first() {
+        for summary in others {
+            for (run, base) in summary.runs.iter().zip(&baseline.runs) {
+                differences.push(PairedDifference {
+                    policy: summary.name.clone(),
+                    seed: run.seed,
+                    peak_fear: run.peak_fear - base.peak_fear,
+                    eco_damage_score: run.eco_damage_score - base.eco_damage_score,
+                    final_adopters: concept_ids(&[run, base])
+                        .map(|id| (id, adopters(run, id) as i64 - adopters(base, id) as i64))
+                        .collect(),
+                    ticks_to_ceiling: run
+                        .ticks_to_ceiling
+                        .zip(base.ticks_to_ceiling)
+                        .map(|(a, b)| a as i64 - b as i64),
+                });
+            }
+        }
+    }
+
+    ComparisonReport {
+        seeds: seeds.to_vec(),
+        policies: summaries,
+        differences,
+    }
+}
+
+fn run_once(scenario: &Simulation, policy: &PolicyContext, seed: u64) -> RunOutcome {
+    let mut sim = scenario.clone();
+    sim.policy = policy.clone();
+    sim.config.random_seed = seed;
+    let result = sim.run();
+    RunOutcome {
+        seed,
+        peak_fear: sim
+            .fear_metrics
+            .time_series
+            .iter()
+            .map(|(_, f)| *f)
+            .fold(0.0_f32, f32::max),
+        eco_damage_score: sim.fear_metrics.peak_eco_damage(),
+        final_adopters: result
+            .adoption_curves
+            .iter()
+            .map(|(id, curve)| (*id, curve.last().copied().unwrap_or(0)))
+            .collect(),
+        ticks_to_ceiling: result
+            .stopped_by_ethical_ceiling
+            .then_some(result.ticks_executed),
+    }
+}
+
+fn adopters(run: &RunOutcome, concept_id: ConceptId) -> u32 {
+    run.final_adopters.get(&concept_id).copied().unwrap_or(0)
+}
+
+/// Concepts adopted in any of `runs`, in id order.
+fn concept_ids<'a>(runs: &[&'a RunOutcome]) -> impl Iterator<Item = ConceptId> + 'a {
+    let mut ids: Vec<ConceptId> = runs
+        .iter()
+        .flat_map(|run| run.final_adopters.keys().copied())
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+    ids.into_iter()
+}
+
+/// Mean of `values`, 0 when empty.
+fn mean(values: impl Iterator<Item = f32>) -> f32 {
+    let (sum, n) = values.fold((0.0, 0), |(sum, n), v| (sum + v, n + 1));
+    if n == 0 {
+        0.0
+    } else {
+        sum / n as f32
+    }
+}
+
+fn summarize(name: String, runs: Vec<RunOutcome>) -> PolicySummary {
+    let all: Vec<&RunOutcome> = runs.iter().collect();
+    let ceiling_ticks: Vec<f32> = runs
+        .iter()
+        .filter_map(|run| run.ticks_to_ceiling.map(|t| t as f32))
+        .collect();
+    PolicySummary {
+        mean_peak_fear: mean(runs.iter().map(|run| run.peak_fear)),
+        mean_eco_damage_score: mean(runs.iter().map(|run| run.eco_damage_score)),
+        mean_final_adopters: concept_ids(&all)
+            .map(|id| (id, mean(runs.iter().map(|run| adopters(run, id) as f32))))
+            .collect(),
+        ceiling_hits: ceiling_ticks.len(),
+        mean_ticks_to_ceiling: (!ceiling_ticks.is_empty())
+            .then(|| mean(ceiling_ticks.iter().copied())),
+        name,
+        runs,
+    }
+}
+
+/// Plain-text tables: per-policy means, then paired differences against the
+/// baseline.
+impl fmt::Display for ComparisonReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let concepts: Vec<ConceptId> = {
+            let runs: Vec<&RunOutcome> = self.policies.iter().flat_map(|p| &p.runs).collect();
+            concept_ids(&runs).collect()
+        };
+        let width = self
+            .policies
+            .iter()
+            .map(|p| p.name.len())
+            .chain(["policy".len()])
+            .max()
+            .unwrap_or(0);
+        let optional = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+
+        write!(
+            f,
+            "{:<width$}  {:>9}  {:>10}  {:>12}  {:>16}",
+            "policy", "peak_fear", "eco_damage", "ceiling_hits", "ticks_to_ceiling"
+        )?;
+        for id in &concepts {
+            write!(f, "  {:>12}", format!("adopters[{id}]"))?;
+        }
+        writeln!(f)?;
+        for policy in &self.policies {
+            write!(
+                f,
+                "{:<width$}  {:>9.3}  {:>10.3}  {:>12}  {:>16}",
+                policy.name,
+                policy.mean_peak_fear,
+                policy.mean_eco_damage_score,
+                format!("{}/{}", policy.ceiling_hits, policy.runs.len()),
+                optional(policy.mean_ticks_to_ceiling.map(|t| format!("{t:.1}"))),
+            )?;
+            for id in &concepts {
+                let n = policy.mean_final_adopters.get(id).copied().unwrap_or(0.0);
+                write!(f, "  {n:>12.1}")?;
+            }
+            writeln!(f)?;
+        }
+
+        let Some(baseline) = self.policies.first() else {
+            return Ok(());
+        };
+        if self.differences.is_empty() {
+            return Ok(());
+        }
+        writeln!(f)?;
+        writeln!(f, "paired differences vs {}", baseline.name)?;
+        write!(
+            f,
+            "{:<width$}  {:>20}  {:>9}  {:>10}  {:>16}",
+            "policy", "seed", "peak_fear", "eco_damage", "ticks_to_ceiling"
+        )?;
+        for id in &concepts {
+            write!(f, "  {:>12}", format!("adopters[{id}]"))?;
+        }
+        writeln!(f)?;
+        for diff in &self.differences {
+            write!(
+                f,
+                "{:<width$}  {:>20}  {:>+9.3}  {:>+10.3}  {:>16}",
+                diff.policy,
+                diff.seed,
+                diff.peak_fear,
+                diff.eco_damage_score,
+                optional(diff.ticks_to_ceiling.map(|t| format!("{t:+}"))),
+            )?;
+            for id in &concepts {
+                let n = diff.final_adopters.get(id).copied().unwrap_or(0);
+                write!(f, "  {n:>+12}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// How `run_monte_carlo` seeds and schedules its runs.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MonteCarloConfig {
+    /// Run `i` uses seed `base_seed + i`.
+    pub base_seed: u64,
+    /// Run simulations on the rayon pool, one `Simulation` per task.
+    pub parallel: bool,
+}
+
+impl Default for MonteCarloConfig {
+    fn default() -> Self {
+        Self {
+            base_seed: 0,
+            parallel: true,
+        }
+    }
+}
+
+/// Distribution of one quantity at one tick across the runs that reached it.
+/// `p5` and `p95` are streaming estimates, exact for fewer than five runs.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Band {
+    pub tick: Tick,
+    pub runs: usize,
+    pub mean: f32,
+    pub stddev: f32,
+    pub p5: f32,
+    pub p95: f32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MonteCarloSummary {
+    pub n_runs: usize,
+    /// Fraction of runs stopped by the ethical ceiling.
+    pub ceiling_hit_fraction: f32,
+    /// Global fear index, one band per tick.
+    pub global_fear: Vec<Band>,
+    /// concept -> adopter count, one band per tick
+    pub adoption: BTreeMap<ConceptId, Vec<Band>>,
+    /// concept -> adoption inequality across regions, one band per tick;
+    /// empty unless the scenario sets `track_inequality`
+    #[serde(default)]
+    pub inequality: BTreeMap<ConceptId, Vec<Band>>,
+}
+
+impl MonteCarloSummary {
+    /// Long-format CSV, one row per band:
+    /// `series,concept,tick,runs,mean,stddev,p5,p95`, with series
+    /// `global_fear`, `adoption` and `inequality`. `concept` is empty for
+    /// `global_fear`.
+    pub fn write_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "series,concept,tick,runs,mean,stddev,p5,p95")?;
+        let rows = self
+            .global_fear
+            .iter()
+            .map(|band| ("global_fear", None, band))
+            .chain(self.adoption.iter().flat_map(|(concept_id, bands)| {
+                bands.iter().map(move |band| ("adoption", Some(concept_id), band))
+            }))
+            .chain(self.inequality.iter().flat_map(|(concept_id, bands)| {
+                bands.iter().map(move |band| ("inequality", Some(concept_id), band))
+            }));
+        for (series, concept_id, band) in rows {
+            let concept = concept_id.map(|id| id.to_string()).unwrap_or_default();
+            writeln!(
+                writer,
+                "{series},{concept},{},{},{},{},{},{}",
+                band.tick, band.runs, band.mean, band.stddev, band.p5, band.p95
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Streaming estimate of one quantile (the P² algorithm of Jain and
+/// Chlamtac): five markers whose heights track the minimum, the quantile,
+/// the maximum and the points halfway to them, adjusted by piecewise
+/// parabolic interpolation as values arrive. Memory is constant; until five
+/// values have arrived they are kept and the quantile is exact.
+#[derive(Debug, Clone, Copy)]
+struct StreamingQuantile {
+    p: f64,
+    count: usize,
+    heights: [f64; 5],
+    /// Actual marker positions, 1-based
+    positions: [f64; 5],
+    desired: [f64; 5],
+}
+
+impl StreamingQuantile {
+    fn new(p: f64) -> Self {
+        Self {
+            p,
+            count: 0,
+            heights: [0.0; 5],
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [1.0, 1.0 + 2.0 * p, 1.0 + 4.0 * p, 3.0 + 2.0 * p, 5.0],
+        }
+    }
+
+    fn push(&mut self, value: f64) {
+        if self.count < 5 {
+            self.heights[self.count] = value;
+            self.count += 1;
+            if self.count == 5 {
+                self.heights.sort_by(f64::total_cmp);
+            }
+            return;
+        }
+        self.count += 1;
+
+        let q = &mut self.heights;
+        let cell = if value < q[0] {
+            q[0] = value;
+            0
+        } else if value >= q[4] {
+            q[4] = value;
+            3
+        } else {
+            (0..4).rfind(|&i| q[i] <= value).unwrap_or(0)
+        };
+        for position in &mut self.positions[cell + 1..] {
+            *position += 1.0;
+        }
+        let p = self.p;
+        let steps = [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0];
+        for (desired, step) in self.desired.iter_mut().zip(steps) {
+            *desired += step;
+        }
+
+        let n = &mut self.positions;
+        for i in 1..4 {
+            let offset = self.desired[i] - n[i];
+            if (offset >= 1.0 && n[i + 1] - n[i] > 1.0)
+                || (offset <= -1.0 && n[i - 1] - n[i] < -1.0)
+            {
+                let d = offset.signum();
+                let parabolic = q[i]
+                    + d / (n[i + 1] - n[i - 1])
+                        * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                            + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]));
+                q[i] = if q[i - 1] < parabolic && parabolic < q[i + 1] {
+                    parabolic
+                } else {
+                    let j = if d > 0.0 { i + 1 } else { i - 1 };
+                    q[i] + d * (q[j] - q[i]) / (n[j] - n[i])
+                };
+                n[i] += d;
+            }
+        }
+    }
+
+    /// The estimate; 0 before the first value.
+    fn estimate(&self) -> f64 {
+        match self.count {
+            0 => 0.0,
+            1..=4 => {
+                let mut values = self.heights[..self.count].to_vec();
+                values.sort_by(f64::total_cmp);
+                // Linear interpolation between the nearest ranks
+                let rank = self.p * (values.len() - 1) as f64;
+                let (lo, hi) = (rank.floor() as usize, rank.ceil() as usize);
+                values[lo] + (values[hi] - values[lo]) * (rank - lo as f64)
+            }
+            _ => self.heights[2],
+        }
+    }
+}
+
+/// Running statistics of one quantity at one tick: mean and variance by
+/// Welford's method, p5 and p95 by `StreamingQuantile`.
+#[derive(Debug, Clone, Copy)]
+struct Accumulator {
+    count: usize,
+    mean: f64,
+    /// Sum of squared differences from the mean
+    m2: f64,
+    p5: StreamingQuantile,
+    p95: StreamingQuantile,
+}
+
+impl Default for Accumulator {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            p5: StreamingQuantile::new(0.05),
+            p95: StreamingQuantile::new(0.95),
+        }
+    }
+}
+
+impl Accumulator {
+    fn push(&mut self, value: f32) {
+        let value = f64::from(value);
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+        self.p5.push(value);
+        self.p95.push(value);
+    }
+
+    fn band(&self, tick: usize) -> Band {
+        Band {
+            tick: tick as Tick,
+            runs: self.count,
+            mean: self.mean as f32,
+            stddev: (self.m2 / self.count as f64).sqrt() as f32,
+            p5: self.p5.estimate() as f32,
+            p95: self.p95.estimate() as f32,
+        }
+    }
+}
+
+/// One accumulator per tick of a series.
+type Series = Vec<Accumulator>;
+
+fn push_at(series: &mut Series, tick: usize, value: f32) {
+    if series.len() <= tick {
+        series.resize_with(tick + 1, Accumulator::default);
+    }
+    series[tick].push(value);
+}
+
+/// Bands for ticks at least one run reached.
+fn bands(series: &Series) -> Vec<Band> {
+    series
+        .iter()
+        .enumerate()
+        .filter(|(_, accumulator)| accumulator.count > 0)
+        .map(|(tick, accumulator)| accumulator.band(tick))
+        .collect()
+}
+
+/// What `Samples` keeps of one finished run: one value per tick and
+/// series. The run's log and result are dropped once it has been sampled.
+struct RunSeries {
+    ceiling_hit: bool,
+    global_fear: Vec<(Tick, f32)>,
+    adoption: BTreeMap<ConceptId, Vec<u32>>,
+    inequality: BTreeMap<ConceptId, Vec<(Tick, f32)>>,
+}
+
+impl RunSeries {
+    fn run(scenario: &Simulation, seed: u64) -> RunSeries {
+        let mut sim = scenario.clone();
+        sim.config.random_seed = seed;
+        let result = sim.run();
+        RunSeries {
+            ceiling_hit: result.stopped_by_ethical_ceiling,
+            global_fear: std::mem::take(&mut sim.fear_metrics.time_series),
+            adoption: result.adoption_curves,
+            inequality: result.inequality_series,
+        }
+    }
+}
+
+/// Running per-tick statistics over the runs added so far, in memory that
+/// grows with the ticks and concepts but not with the number of runs.
+#[derive(Default)]
+struct Samples {
+    runs: usize,
+    ceiling_hits: usize,
+    global_fear: Series,
+    adoption: BTreeMap<ConceptId, Series>,
+    inequality: BTreeMap<ConceptId, Series>,
+}
+
+impl Samples {
+    fn add(&mut self, run: RunSeries) {
+        self.runs += 1;
+        self.ceiling_hits += usize::from(run.ceiling_hit);
+        for (tick, fear) in run.global_fear {
+            push_at(&mut self.global_fear, tick as usize, fear);
+        }
+        for (concept_id, curve) in run.adoption {
+            let series = self.adoption.entry(concept_id).or_default();
+            for (tick, count) in curve.into_iter().enumerate() {
+                push_at(series, tick, count as f32);
+            }
+        }
+        for (concept_id, points) in run.inequality {
+            let series = self.inequality.entry(concept_id).or_default();
+            for (tick, gini) in points {
+                push_at(series, tick as usize, gini);
+            }
+        }
+    }
+}
+
+/// Run `scenario` (a simulation that has not been run) `n_runs` times with
+/// consecutive seeds and summarize global fear, adoption and (when tracked)
+/// adoption inequality per tick.
+///
+/// In parallel, runs go to the rayon pool one batch (a run per thread) at a
+/// time; their series are added in seed order either way, so the summary
+/// does not depend on `parallel` or the number of threads.
+pub fn run_monte_carlo(
+    scenario: &Simulation,
+    config: &MonteCarloConfig,
+    n_runs: usize,
+) -> MonteCarloSummary {
+    let seed = |i: usize| config.base_seed.wrapping_add(i as u64);
+    let mut samples = Samples::default();
+    if config.parallel {
+        let batch = rayon::current_num_threads().max(1);
+        for start in (0..n_runs).step_by(batch) {
+            let runs: Vec<RunSeries> = (start..n_runs.min(start + batch))
+                .into_par_iter()
+                .map(|i| RunSeries::run(scenario, seed(i)))
+                .collect();
+            runs.into_iter().for_each(|run| samples.add(run));
+        }
+    } else {
+        for i in 0..n_runs {
+            samples.add(RunSeries::run(scenario, seed(i)));
+        }
+    }
+
+    MonteCarloSummary {
+        n_runs: samples.runs,
+        ceiling_hit_fraction: if samples.runs == 0 {
+            0.0
+        } else {
+            samples.ceiling_hits as f32 / samples.runs as f32
+        },
+        global_fear: bands(&samples.global_fear),
+        adoption: samples
+            .adoption
+            .iter()
+            .map(|(concept_id, series)| (*concept_id, bands(series)))
+            .collect(),
+        inequality: samples
+            .inequality
+            .iter()
+            .map(|(concept_id, series)| (*concept_id, bands(series)))
+            .collect(),
+    }
+}
+
+/// Both runs of a counterfactual at one tick. A run's fear is `None` once
+/// it has stopped; the deltas (counterfactual minus baseline) only list
+/// non-zero entries.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CounterfactualTick {
+    pub tick: Tick,
+    pub baseline_fear: Option<f32>,
+    pub counterfactual_fear: Option<f32>,
+    /// region -> concept -> change in adopters living there; empty unless
+    /// both runs executed the tick
+    pub adopters_delta: BTreeMap<RegionId, BTreeMap<ConceptId, i64>>,
+    pub denials_delta: BTreeMap<DenialRule, i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CounterfactualReport {
+    pub seed: u64,
+    pub intervention: Intervention,
+    pub baseline_ticks: Tick,
+    pub counterfactual_ticks: Tick,
+    /// One entry per tick either run executed.
+    pub ticks: Vec<CounterfactualTick>,
+    /// First tick whose logged events, fear, adopters or denials differ;
+    /// `None` when the runs are identical.
+    pub first_divergence: Option<Tick>,
+}
+
+/// Per-tick adopters by region, from the run's own counters.
+#[derive(Default)]
+struct AdopterRecorder {
+    by_tick: Vec<BTreeMap<RegionId, BTreeMap<ConceptId, u32>>>,
+}
+
+impl SimObserver for AdopterRecorder {
+    fn wants_region_snapshots(&self, _tick: Tick) -> bool {
+        true
+    }
+
+    fn on_region_snapshots(
+        &mut self,
+        _tick: Tick,
+        snapshots: &[RegionSnapshot],
+    ) -> ControlFlow<()> {
+        self.by_tick.push(
+            snapshots
+                .iter()
+                .map(|s| (s.region_id, s.adopters_by_concept.clone()))
+                .collect(),
+        );
+        ControlFlow::Continue(())
+    }
+}
+
+/// What a run of `run_counterfactual` keeps for the diff.
+struct Trajectory {
+    ticks: Tick,
+    fear: BTreeMap<Tick, f32>,
+    adopters: Vec<BTreeMap<RegionId, BTreeMap<ConceptId, u32>>>,
+    denials: BTreeMap<Tick, BTreeMap<DenialRule, i64>>,
+    events: BTreeMap<Tick, Vec<SimEvent>>,
+}
+
+impl Trajectory {
+    fn run(mut sim: Simulation, seed: u64) -> Trajectory {
+        sim.config.random_seed = seed;
+        let mut recorder = AdopterRecorder::default();
+        let result = sim.run_with_observer(&mut recorder);
+        let mut denials: BTreeMap<Tick, BTreeMap<DenialRule, i64>> = BTreeMap::new();
+        for denial in &sim.log.denials {
+            *denials
+                .entry(denial.tick)
+                .or_default()
+                .entry(denial.rule)
+                .or_insert(0) += 1;
+        }
+        let mut events: BTreeMap<Tick, Vec<SimEvent>> = BTreeMap::new();
+        for (tick, event) in sim.log.events {
+            events.entry(tick).or_default().push(event);
+        }
+        Trajectory {
+            ticks: result.ticks_executed,
+            fear: sim.fear_metrics.time_series.into_iter().collect(),
+            adopters: recorder.by_tick,
+            denials,
+            events,
+        }
+    }
+}
+
+/// Run `scenario` (a simulation that has not been run) as is and with
+/// `intervention` applied, both with `seed`. Every agent draws from its own
+/// RNG stream (see `agent_rng`), so until the intervention changes what an
+/// agent sees, both runs make the same draws and the divergence is the
+/// intervention's doing.
+pub fn run_counterfactual(
+    scenario: &Simulation,
+    seed: u64,
+    intervention: Intervention,
+) -> CounterfactualReport {
+    let mut modified = scenario.clone();
+    intervention.apply(&mut modified);
+    let (baseline, counterfactual) = rayon::join(
+        || Trajectory::run(scenario.clone(), seed),
+        || Trajectory::run(modified, seed),
+    );
+
+    let mut first_divergence = None;
+    let ticks = (0..baseline.ticks.max(counterfactual.ticks))
+        .map(|tick| {
+            let mut adopters_delta: BTreeMap<RegionId, BTreeMap<ConceptId, i64>> = BTreeMap::new();
+            let both = baseline
+                .adopters
+                .get(tick as usize)
+                .zip(counterfactual.adopters.get(tick as usize));
+            if let Some((base, cf)) = both {
+                for region in base.keys().chain(cf.keys()) {
+                    let (base, cf) = (base.get(region), cf.get(region));
+                    for concept_id in base.into_iter().chain(cf).flat_map(|m| m.keys()) {
+                        let count = |m: Option<&BTreeMap<ConceptId, u32>>| {
+                            m.and_then(|m| m.get(concept_id)).copied().unwrap_or(0) as i64
+                        };
+                        let delta = count(cf) - count(base);
+                        if delta != 0 {
+                            adopters_delta
+                                .entry(*region)
+                                .or_default()
+                                .insert(*concept_id, delta);
+                        }
+                    }
+                }
+            }
+            let no_denials = BTreeMap::new();
+            let base_denials = baseline.denials.get(&tick).unwrap_or(&no_denials);
+            let cf_denials = counterfactual.denials.get(&tick).unwrap_or(&no_denials);
+            let denials_delta: BTreeMap<DenialRule, i64> = base_denials
+                .keys()
+                .chain(cf_denials.keys())
+                .filter_map(|rule| {
+                    let count = |m: &BTreeMap<DenialRule, i64>| m.get(rule).copied().unwrap_or(0);
+                    let delta = count(cf_denials) - count(base_denials);
+                    (delta != 0).then_some((*rule, delta))
+                })
+                .collect();
+
+            let entry = CounterfactualTick {
+                tick,
+                baseline_fear: baseline.fear.get(&tick).copied(),
+                counterfactual_fear: counterfactual.fear.get(&tick).copied(),
+                adopters_delta,
+                denials_delta,
+            };
+            let diverged = entry.baseline_fear != entry.counterfactual_fear
+                || !entry.adopters_delta.is_empty()
+                || !entry.denials_delta.is_empty()
+                || baseline.events.get(&tick) != counterfactual.events.get(&tick);
+            if diverged && first_divergence.is_none() {
+                first_divergence = Some(tick);
+            }
+            entry
+        })
+        .collect();
+
+    CounterfactualReport {
+        seed,
+        intervention,
+        baseline_ticks: baseline.ticks,
+        counterfactual_ticks: counterfactual.ticks,
+        ticks,
+        first_divergence,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::ExposureWindowRule;
+    use crate::sim::tests::sim;
+
+    #[test]
+    fn a_stricter_policy_has_fewer_adoptions_for_every_seed() {
+        let mut scenario = sim(200, 1);
+        scenario.config.max_ticks = 20;
+        let base = scenario.policy.clone();
+        let mut strict = base.clone();
+        // Concept 1 is never exposable
+        strict.exposure_windows.push(ExposureWindowRule {
+            concept_id: Some(1),
+            region: None,
+            allowed_ticks: 0..0,
+        });
+        let seeds = [1, 2, 3, 4];
+
+        let report = run_comparison(
+            &scenario,
+            vec![("base".into(), base), ("strict".into(), strict)],
+            &seeds,
+        );
+        assert_eq!(report.policies.len(), 2);
+        assert_eq!(report.differences.len(), seeds.len());
+        for (difference, seed) in report.differences.iter().zip(seeds) {
+            assert_eq!(difference.policy, "strict");
+            assert_eq!(difference.seed, seed);
+            assert!(difference.final_adopters[&1] < 0, "{difference:?}");
+            assert!(
+                difference.final_adopters.values().sum::<i64>() < 0,
+                "{difference:?}"
+            );
+        }
+        assert!(report.policies[1]
+            .runs
+            .iter()
+            .all(|run| adopters(run, 1) == 0));
+
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["policies"][1]["name"], "strict");
+        let table = report.to_string();
+        assert!(
+            table.contains("base") && table.contains("strict"),
+            "{table}"
+        );
+    }
+
+    #[test]
+    fn monte_carlo_bands_are_ordered() {
+        let mut scenario = sim(60, 1);
+        scenario.config.max_ticks = 15;
+        let config = MonteCarloConfig::default();
+        let summary = run_monte_carlo(&scenario, &config, 20);
+        assert_eq!(summary.n_runs, 20);
+        assert_eq!(summary.global_fear.len(), 15);
+        for band in summary
+            .global_fear
+            .iter()
+            .chain(summary.adoption.values().flatten())
+        {
+            assert!(
+                band.p5 <= band.mean + 1e-5 && band.mean <= band.p95 + 1e-5,
+                "{band:?}"
+            );
+            assert!(band.stddev >= 0.0);
+        }
+
+        let sequential = run_monte_carlo(
+            &scenario,
+            &MonteCarloConfig {
+                parallel: false,
+                ..config
+            },
+            20,
+        );
+        assert_eq!(summary, sequential);
+
+        let mut csv = Vec::new();
+        summary.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(
+            csv.lines().next(),
+            Some("series,concept,tick,runs,mean,stddev,p5,p95")
+        );
+        assert_eq!(csv.lines().count(), 1 + 15 * 3);
+    }
+
+    #[test]
+    fn streaming_statistics_track_exact_ones() {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let mut values: Vec<f32> = (0..10_000).map(|_| rng.gen_range(0.0..100.0)).collect();
+        let mut accumulator = Accumulator::default();
+        for value in &values {
+            accumulator.push(*value);
+        }
+        let band = accumulator.band(0);
+        values.sort_by(f32::total_cmp);
+        let mean = values.iter().map(|v| f64::from(*v)).sum::<f64>() / values.len() as f64;
+        assert!((f64::from(band.mean) - mean).abs() < 1e-3);
+        // Uniform on 0..100: stddev 100 / sqrt(12), p5 5, p95 95
+        assert!((band.stddev - 28.87).abs() < 0.5, "{band:?}");
+        assert!((band.p5 - values[500]).abs() < 1.0, "{band:?}");
+        assert!((band.p95 - values[9500]).abs() < 1.0, "{band:?}");
+    }
+
+    #[test]
+    fn few_values_give_exact_quantiles() {
+        let mut accumulator = Accumulator::default();
+        for value in [4.0, 1.0, 3.0] {
+            accumulator.push(value);
+        }
+        let band = accumulator.band(0);
+        assert_eq!((band.runs, band.mean), (3, 8.0 / 3.0));
+        assert!(
+            (band.p5 - 1.2).abs() < 1e-6 && (band.p95 - 3.9).abs() < 1e-6,
+            "{band:?}"
+        );
+    }
+
+    #[test]
+    fn a_ban_diverges_from_the_baseline_at_once() {
+        let mut scenario = sim(120, 5);
+        scenario.config.max_ticks = 30;
+        scenario.config.stop_conditions = vec![];
+        scenario
+            .world
+            .exposure_field
+            .retain(|_, concept, _| concept != 1);
+
+        let report = run_counterfactual(&scenario, 11, Intervention::BanConcept { concept_id: 0 });
+        assert_eq!(report.ticks.len(), 30);
+        assert_eq!(report.first_divergence, Some(0));
+        let last = report.ticks.last().unwrap();
+        let adopters: i64 = last
+            .adopters_delta
+            .values()
+            .filter_map(|by_concept| by_concept.get(&0))
+            .sum();
+        assert!(adopters < 0, "{last:?}");
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.contains(r#""type":"ban_concept""#), "{json}");
+
+        let report = run_counterfactual(&scenario, 11, Intervention::RaiseCeiling { delta: 0.0 });
+        assert_eq!(report.first_divergence, None);
+    }
+}
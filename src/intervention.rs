@@ -0,0 +1,144 @@
+//! Changes to a simulation made from outside its rules: scheduled for a
+//! tick with `Simulation::schedule_intervention`, pushed by an observer, or
+//! applied up front as in `experiments::run_counterfactual`.
+
+use crate::concept::ConceptLegalStatus;
+use crate::core::id::{ConceptId, RegionId};
+use crate::policy::{AttributeRule, ExposureWindowRule};
+use crate::sim::Simulation;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Intervention {
+    /// Make the concept `Prohibited`.
+    BanConcept { concept_id: ConceptId },
+    /// Make the concept `Allowed` again.
+    UnbanConcept { concept_id: ConceptId },
+    SetLegalStatus {
+        concept_id: ConceptId,
+        status: ConceptLegalStatus,
+    },
+    /// Put the region in `closed_regions`.
+    CloseRegion { region: RegionId },
+    /// Take the region out of `closed_regions`.
+    OpenRegion { region: RegionId },
+    /// Raise the global ceiling's and every regional ceiling's
+    /// `max_fear_index` and `max_eco_damage` by `delta` (negative lowers
+    /// them).
+    RaiseCeiling { delta: f32 },
+    /// Add `amount` to the concept's exposure in the region, regardless of
+    /// exposure policy.
+    InjectExposure {
+        concept_id: ConceptId,
+        region: RegionId,
+        amount: f32,
+    },
+    AddExposureWindow { rule: ExposureWindowRule },
+    /// Drop the exposure windows whose `concept_id` and `region` are exactly
+    /// these.
+    RemoveExposureWindow {
+        concept_id: Option<ConceptId>,
+        region: Option<RegionId>,
+    },
+    AddAttributeRule { rule: AttributeRule },
+}
+
+impl Intervention {
+    /// Change `sim` right away, without logging; concepts and regions not
+    /// in the world are ignored.
+    pub fn apply(&self, sim: &mut Simulation) {
+        let policy = &mut sim.policy;
+        match self {
+            Intervention::BanConcept { concept_id } => {
+                set_legal_status(sim, *concept_id, ConceptLegalStatus::Prohibited);
+            }
+            Intervention::UnbanConcept { concept_id } => {
+                set_legal_status(sim, *concept_id, ConceptLegalStatus::Allowed);
+            }
+            Intervention::SetLegalStatus { concept_id, status } => {
+                set_legal_status(sim, *concept_id, *status);
+            }
+            Intervention::CloseRegion { region } => {
+                if sim.world.regions.contains_key(region) {
+                    policy.closed_regions.insert(*region);
+                }
+            }
+            Intervention::OpenRegion { region } => {
+                policy.closed_regions.remove(region);
+            }
+            Intervention::RaiseCeiling { delta } => {
+                for ceiling in std::iter::once(&mut policy.ethical_ceiling)
+                    .chain(policy.region_ceilings.values_mut())
+                {
+                    ceiling.max_fear_index += delta;
+                    ceiling.max_eco_damage += delta;
+                }
+            }
+            Intervention::InjectExposure {
+                concept_id,
+                region,
+                amount,
+            } => {
+                if sim.world.regions.contains_key(region)
+                    && sim.world.concepts.contains_key(concept_id)
+                {
+                    sim.world.exposure_field.add(*region, *concept_id, *amount);
+                }
+            }
+            Intervention::AddExposureWindow { rule } => {
+                policy.exposure_windows.push(rule.clone());
+            }
+            Intervention::RemoveExposureWindow { concept_id, region } => {
+                policy
+                    .exposure_windows
+                    .retain(|rule| rule.concept_id != *concept_id || rule.region != *region);
+            }
+            Intervention::AddAttributeRule { rule } => {
+                policy.attribute_rules.push(rule.clone());
+            }
+        }
+    }
+}
+
+fn set_legal_status(sim: &mut Simulation, concept_id: ConceptId, status: ConceptLegalStatus) {
+    if let Some(concept) = sim.world.concepts.get_mut(&concept_id) {
+        concept.legal_status = status;
+    }
+}
+
+impl fmt::Display for Intervention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Intervention::BanConcept { concept_id } => write!(f, "ban concept {concept_id}"),
+            Intervention::UnbanConcept { concept_id } => write!(f, "unban concept {concept_id}"),
+            Intervention::SetLegalStatus { concept_id, status } => {
+                write!(f, "set concept {concept_id} to {status:?}")
+            }
+            Intervention::CloseRegion { region } => write!(f, "close region {region}"),
+            Intervention::OpenRegion { region } => write!(f, "open region {region}"),
+            Intervention::RaiseCeiling { delta } => write!(f, "raise ceilings by {delta}"),
+            Intervention::InjectExposure {
+                concept_id,
+                region,
+                amount,
+            } => write!(
+                f,
+                "inject exposure {amount} of concept {concept_id} in region {region}"
+            ),
+            Intervention::AddExposureWindow { rule } => {
+                write!(f, "add exposure window {:?}", rule.allowed_ticks)
+            }
+            Intervention::RemoveExposureWindow { concept_id, region } => {
+                write!(
+                    f,
+                    "remove exposure windows for concept {concept_id:?} in region {region:?}"
+                )
+            }
+            Intervention::AddAttributeRule { rule } => {
+                write!(f, "add attribute rule for concept {}", rule.concept_id)
+            }
+        }
+    }
+}
@@ -0,0 +1,24 @@
+//! Fear-first simulation of concepts spreading through a population of
+//! agents across regions, under policy ceilings that can stop a run.
+
+pub mod core {
+    pub mod agent;
+    pub mod id;
+}
+
+pub mod adoption;
+pub mod analysis;
+pub mod broadcast;
+pub mod calibration;
+pub mod concept;
+pub mod experiments;
+pub mod intervention;
+pub mod metrics;
+pub mod observer;
+pub mod policy;
+pub mod population;
+pub mod scenario;
+pub mod sim;
+pub mod snapshot;
+pub mod social;
+pub mod world;
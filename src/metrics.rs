@@ -1,12 +1,342 @@
-use crate::core::id::{RegionId, Tick};
+use crate::core::id::{ConceptId, RegionId, Tick};
+use crate::policy::EthicalCeiling;
 use crate::world::World;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::{self, Write};
 
-#[derive(Debug, Default)]
+/// Quantity compared against an ethical ceiling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CeilingMetric {
+    /// Population-weighted global fear index.
+    GlobalFear,
+    EcoDamage,
+    /// A region's `by_region` fear, against its own (or the global) ceiling.
+    RegionFear(RegionId),
+}
+
+/// How many fear values reduce to one: agents' fear to a region's, and a
+/// region's fear over ticks to its `FearIndexMetrics::by_region` entry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregationMode {
+    /// The highest value, so one panicked agent sets the region's fear.
+    #[default]
+    Max,
+    Mean,
+    /// Nearest-rank percentile, 0..=100.
+    Percentile(f32),
+}
+
+impl AggregationMode {
+    /// Aggregate of `values`, reordering them in place; 0 when empty.
+    pub fn aggregate(self, values: &mut [f32]) -> f32 {
+        if values.is_empty() {
+            return 0.0;
+        }
+        match self {
+            AggregationMode::Max => values.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+            AggregationMode::Mean => values.iter().sum::<f32>() / values.len() as f32,
+            AggregationMode::Percentile(p) => {
+                let n = values.len();
+                let rank = ((p.clamp(0.0, 100.0) / 100.0) * n as f32).ceil() as usize;
+                let index = rank.clamp(1, n) - 1;
+                *values
+                    .select_nth_unstable_by(index, |a, b| a.total_cmp(b))
+                    .1
+            }
+        }
+    }
+}
+
+/// Which ceiling a run breached, and where to look for the cause, as
+/// reported in the early-stop log entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CeilingBreach {
+    pub metric: CeilingMetric,
+    /// Peak value of `metric`.
+    pub value: f32,
+    pub ceiling: f32,
+    /// Tick the peak was reached.
+    pub tick: Option<Tick>,
+    /// Region with the highest fear (the highest eco damage share for
+    /// `EcoDamage`); the breaching one for `RegionFear`.
+    pub worst_region: Option<RegionId>,
+    /// Concept whose adopters carry the most expected fear in
+    /// `worst_region`. Set by `Simulation`, which knows the adoptions.
+    pub top_concept: Option<ConceptId>,
+    /// The largest sources of fear added over the ticks checked, largest
+    /// first; at most `BREACH_FEAR_DRIVERS`.
+    #[serde(default)]
+    pub fear_drivers: Vec<FearDriver>,
+}
+
+/// How far one tick's metrics stayed below their ceilings; negative where
+/// a metric was above its ceiling.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CeilingMargin {
+    pub tick: Tick,
+    /// `max_fear_index` minus the global fear index.
+    pub fear: f32,
+    /// `max_eco_damage` minus the eco damage proxy.
+    pub eco_damage: f32,
+    /// Region whose fear came closest to (or furthest past) its ceiling,
+    /// and its margin; `None` with no region recorded for the tick.
+    pub region_fear: Option<(RegionId, f32)>,
+}
+
+impl CeilingMargin {
+    pub fn is_breach(&self) -> bool {
+        self.fear < 0.0
+            || self.eco_damage < 0.0
+            || self.region_fear.is_some_and(|(_, margin)| margin < 0.0)
+    }
+}
+
+/// How many `FearDriver`s a `CeilingBreach` lists.
+pub const BREACH_FEAR_DRIVERS: usize = 3;
+
+/// Where an increase in an agent's `fear_level` came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FearSource {
+    /// Adopting the concept (`FearDynamics::adoption_gain`).
+    Concept(ConceptId),
+    /// Other agents' fear, in the region or spilled over from neighbors.
+    Contagion,
+}
+
+impl fmt::Display for FearSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FearSource::Concept(concept_id) => write!(f, "concept {concept_id}"),
+            FearSource::Contagion => f.write_str("contagion"),
+        }
+    }
+}
+
+/// Fear added by one source, summed over agents and ticks.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FearDriver {
+    pub source: FearSource,
+    pub fear: f32,
+}
+
+impl fmt::Display for CeilingBreach {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (value, ceiling) = (self.value, self.ceiling);
+        match self.metric {
+            CeilingMetric::GlobalFear => write!(f, "global fear index {value:.3} > {ceiling:.3}")?,
+            CeilingMetric::EcoDamage => write!(f, "eco damage score {value:.3} > {ceiling:.3}")?,
+            CeilingMetric::RegionFear(region) => {
+                write!(f, "fear index {value:.3} > {ceiling:.3} in region {region}")?
+            }
+        }
+        if let Some(tick) = self.tick {
+            write!(f, ", peak at tick {tick}")?;
+        }
+        match self.metric {
+            CeilingMetric::RegionFear(_) => {}
+            CeilingMetric::GlobalFear | CeilingMetric::EcoDamage => {
+                if let Some(region) = self.worst_region {
+                    write!(f, ", worst region {region}")?;
+                }
+            }
+        }
+        if let Some(concept) = self.top_concept {
+            write!(f, ", mostly concept {concept}")?;
+        }
+        Ok(())
+    }
+}
+
+/// First tick holding the highest value of `series`.
+pub fn peak_tick(series: &[(Tick, f32)]) -> Option<Tick> {
+    series
+        .iter()
+        .fold(None, |peak: Option<(Tick, f32)>, &(tick, value)| match peak {
+            Some((_, best)) if best >= value => peak,
+            _ => Some((tick, value)),
+        })
+        .map(|(tick, _)| tick)
+}
+
+/// Region with the highest value; the lowest id on ties.
+fn highest(by_region: &BTreeMap<RegionId, f32>) -> Option<RegionId> {
+    by_region
+        .iter()
+        .fold(None, |worst: Option<(RegionId, f32)>, (region, value)| match worst {
+            Some((_, highest)) if highest >= *value => worst,
+            _ => Some((*region, *value)),
+        })
+        .map(|(region, _)| region)
+}
+
+/// The entries of `series` (ordered by tick) from `since` on.
+fn from_tick(series: &[(Tick, f32)], since: Tick) -> &[(Tick, f32)] {
+    &series[series.partition_point(|(tick, _)| *tick < since)..]
+}
+
+/// One row of the long-format export of `FearIndexMetrics`. The columns
+/// are stable, in this order: `tick` (u64; empty for whole-run
+/// aggregates), `metric`, `region_or_global` (a region id, or `global`) and
+/// `value` (f32).
+///
+/// Metrics: `global_fear` and `eco_damage` (global, per tick),
+/// `region_fear`, `regional_fear`, `trust` and `regret` (per region and
+/// tick), `region_fear_aggregate` and `eco_damage_share` (per region, no
+/// tick: the `by_region` and `eco_by_region` values) and
+/// `inequality:<concept id>`, `fear_contribution:<concept id>`,
+/// `fear_contribution:contagion` and `funnel_<stage>:<concept id>` for the
+/// `FunnelPoint` stages (global, per tick). Rows come in that order, then
+/// by region and tick.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricRow {
+    pub tick: Option<Tick>,
+    pub metric: String,
+    pub region_or_global: String,
+    pub value: f32,
+}
+
+/// One concept's conversion funnel over one tick, counted over agents:
+/// `exposed` agents had it in view with intensity at or above the
+/// threshold, `considered` of them were allowed by policy to adopt it, and
+/// `adopted` of those chose to. So `exposed >= considered >= adopted`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunnelPoint {
+    pub exposed: u32,
+    pub considered: u32,
+    pub adopted: u32,
+}
+
+impl FunnelPoint {
+    /// Each stage's name and count, widest first.
+    pub fn stages(&self) -> [(&'static str, u32); 3] {
+        [
+            ("exposed", self.exposed),
+            ("considered", self.considered),
+            ("adopted", self.adopted),
+        ]
+    }
+}
+
+/// Funnel counts gathered while agents step, e.g. one per agent, then
+/// merged into `FunnelMetrics`.
+#[derive(Debug, Clone, Default)]
+pub struct FunnelCounter {
+    /// Exposure intensity at which a concept counts as seen.
+    pub threshold: f32,
+    counts: BTreeMap<ConceptId, FunnelPoint>,
+}
+
+impl FunnelCounter {
+    pub fn new(threshold: f32) -> Self {
+        Self {
+            threshold,
+            counts: BTreeMap::new(),
+        }
+    }
+
+    /// Count `concept_id` as exposed if `intensity` reaches the threshold;
+    /// returns whether it did.
+    pub fn expose(&mut self, concept_id: ConceptId, intensity: f32) -> bool {
+        let exposed = intensity >= self.threshold;
+        if exposed {
+            self.counts.entry(concept_id).or_default().exposed += 1;
+        }
+        exposed
+    }
+
+    pub fn consider(&mut self, concept_id: ConceptId) {
+        self.counts.entry(concept_id).or_default().considered += 1;
+    }
+
+    pub fn adopt(&mut self, concept_id: ConceptId) {
+        self.counts.entry(concept_id).or_default().adopted += 1;
+    }
+
+    /// Reset the counts, keeping the threshold.
+    pub fn clear(&mut self) {
+        self.counts.clear();
+    }
+
+    pub fn merge(&mut self, other: &FunnelCounter) {
+        for (&concept_id, point) in &other.counts {
+            let total = self.counts.entry(concept_id).or_default();
+            total.exposed += point.exposed;
+            total.considered += point.considered;
+            total.adopted += point.adopted;
+        }
+    }
+}
+
+/// Exposure-to-adoption funnel of every concept, per tick. Filled from
+/// `Agent::step`, so `Simulation::replay` leaves it empty.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FunnelMetrics {
+    /// concept -> funnel of each tick in which any agent was exposed to it
+    pub by_concept: BTreeMap<ConceptId, Vec<(Tick, FunnelPoint)>>,
+}
+
+impl FunnelMetrics {
+    pub fn record(&mut self, tick: Tick, counter: FunnelCounter) {
+        for (concept_id, point) in counter.counts {
+            self.by_concept
+                .entry(concept_id)
+                .or_default()
+                .push((tick, point));
+        }
+    }
+}
+
+/// `region_or_global` of global rows.
+pub const GLOBAL: &str = "global";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FearIndexMetrics {
     pub time_series: Vec<(Tick, f32)>, // global fear index over time
-    pub by_region: HashMap<RegionId, f32>, // cumulative / peak fear by region
-    pub eco_damage_score: f32,
+    /// Each region's fear over ticks, reduced by the run's `AggregationMode`
+    /// (the peak by default).
+    pub by_region: BTreeMap<RegionId, f32>,
+    /// Eco damage proxy of each tick; see `peak_eco_damage`.
+    #[serde(default)]
+    pub eco_damage_series: Vec<(Tick, f32)>,
+    /// Each region's highest share of the eco damage proxy: its
+    /// vulnerability-weighted fear over the total vulnerability, so the
+    /// shares of one tick add up to that tick's proxy.
+    #[serde(default)]
+    pub eco_by_region: BTreeMap<RegionId, f32>,
+    /// Each region's aggregated fear over time.
+    #[serde(default)]
+    pub region_series: BTreeMap<RegionId, Vec<(Tick, f32)>>,
+    /// `World::regional_fear` over time: residents' fear plus what spilled
+    /// over from neighbors, kept apart from `region_series`.
+    #[serde(default)]
+    pub regional_fear: BTreeMap<RegionId, Vec<(Tick, f32)>>,
+    /// Mean `trust_in_institutions` of each region's residents over time.
+    #[serde(default)]
+    pub trust_by_region: BTreeMap<RegionId, Vec<(Tick, f32)>>,
+    /// Mean `AgentState::regret` of each region's residents over time.
+    #[serde(default)]
+    pub regret_by_region: BTreeMap<RegionId, Vec<(Tick, f32)>>,
+    /// Each concept's `adoption_inequality` over time, when
+    /// `SimulationConfig::track_inequality` is set.
+    #[serde(default)]
+    pub inequality_series: BTreeMap<ConceptId, Vec<(Tick, f32)>>,
+    /// Fear added to agents by adopting each concept, summed per tick;
+    /// ticks adding none are left out.
+    #[serde(default)]
+    pub fear_contribution: BTreeMap<ConceptId, Vec<(Tick, f32)>>,
+    /// Fear added by contagion, summed per tick; ticks adding none are left
+    /// out.
+    #[serde(default)]
+    pub contagion_fear: Vec<(Tick, f32)>,
+    #[serde(default)]
+    pub funnel: FunnelMetrics,
+    /// variant -> concept it mutated from, for every variant spawned
+    #[serde(default)]
+    pub lineage: BTreeMap<ConceptId, ConceptId>,
 }
 
 impl FearIndexMetrics {
@@ -15,17 +345,39 @@ impl FearIndexMetrics {
         tick: Tick,
         world: &World,
         agent_fear_by_region: &HashMap<RegionId, f32>, // fraction 0..1
+        mode: AggregationMode,
     ) {
+        // Sum in region id order so the series is bit-for-bit reproducible.
+        let mut regions: Vec<(&RegionId, &f32)> = agent_fear_by_region.iter().collect();
+        regions.sort_by_key(|(region_id, _)| **region_id);
+
         // population-weighted mean fear
         let mut total_pop = 0.0;
         let mut weighted_fear = 0.0;
-        for (region_id, fear) in agent_fear_by_region {
+        for &(region_id, fear) in &regions {
             if let Some(region) = world.regions.get(region_id) {
                 let pop = region.population as f32;
                 total_pop += pop;
                 weighted_fear += pop * fear;
-                let entry = self.by_region.entry(*region_id).or_insert(0.0);
-                *entry = (*entry).max(*fear);
+                let series = self.region_series.entry(*region_id).or_default();
+                series.push((tick, *fear));
+                let aggregate = match mode {
+                    // Running forms of the same aggregates, avoiding a pass
+                    // over the whole series
+                    AggregationMode::Max => self
+                        .by_region
+                        .get(region_id)
+                        .map_or(*fear, |peak| peak.max(*fear)),
+                    AggregationMode::Mean => {
+                        let mean = self.by_region.get(region_id).copied().unwrap_or(0.0);
+                        mean + (fear - mean) / series.len() as f32
+                    }
+                    AggregationMode::Percentile(_) => {
+                        let mut values: Vec<f32> = series.iter().map(|(_, f)| *f).collect();
+                        mode.aggregate(&mut values)
+                    }
+                };
+                self.by_region.insert(*region_id, aggregate);
             }
         }
         if total_pop > 0.0 {
@@ -36,7 +388,7 @@ impl FearIndexMetrics {
         // ecological damage proxy: fear × eco_vulnerability
         let mut eco_weighted = 0.0;
         let mut eco_total = 0.0;
-        for (region_id, fear) in agent_fear_by_region {
+        for &(region_id, fear) in &regions {
             if let Some(region) = world.regions.get(region_id) {
                 let w = region.eco_vulnerability.max(0.0);
                 eco_weighted += w * *fear;
@@ -44,17 +396,566 @@ impl FearIndexMetrics {
             }
         }
         if eco_total > 0.0 {
-            self.eco_damage_score = (eco_weighted / eco_total).max(self.eco_damage_score);
+            self.eco_damage_series.push((tick, eco_weighted / eco_total));
+            for &(region_id, fear) in &regions {
+                if let Some(region) = world.regions.get(region_id) {
+                    let share = region.eco_vulnerability.max(0.0) * *fear / eco_total;
+                    let peak = self.eco_by_region.entry(*region_id).or_insert(0.0);
+                    *peak = peak.max(share);
+                }
+            }
         }
     }
 
-    pub fn is_above_ethical_ceiling(&self, ceiling: &crate::policy::EthicalCeiling) -> bool {
-        let peak_fear = self
-            .time_series
+    /// Highest eco damage proxy so far, or 0 before the first update.
+    pub fn peak_eco_damage(&self) -> f32 {
+        self.eco_damage_series
             .iter()
-            .map(|(_, f)| *f)
-            .fold(0.0_f32, f32::max);
+            .map(|(_, d)| *d)
+            .fold(0.0_f32, f32::max)
+    }
+
+    /// Region with the highest `eco_by_region` share; the lowest id on ties.
+    pub fn worst_eco_region(&self) -> Option<RegionId> {
+        highest(&self.eco_by_region)
+    }
+
+    /// Append this tick's mean trust for each region in `mean_trust_by_region`.
+    pub fn record_trust(&mut self, tick: Tick, mean_trust_by_region: &HashMap<RegionId, f32>) {
+        for (region_id, trust) in mean_trust_by_region {
+            self.trust_by_region
+                .entry(*region_id)
+                .or_default()
+                .push((tick, *trust));
+        }
+    }
+
+    /// Append this tick's mean regret for each region in `mean_regret_by_region`.
+    pub fn record_regret(&mut self, tick: Tick, mean_regret_by_region: &HashMap<RegionId, f32>) {
+        for (region_id, regret) in mean_regret_by_region {
+            self.regret_by_region
+                .entry(*region_id)
+                .or_default()
+                .push((tick, *regret));
+        }
+    }
 
-        peak_fear > ceiling.max_fear_index || self.eco_damage_score > ceiling.max_eco_damage
+    /// Gini coefficient (0 even .. 1 concentrated) of `concept`'s adoption
+    /// rate across regions, each region weighted by its population; regions
+    /// with no population are left out. 0 with fewer than two populated
+    /// regions, no adopters, or a concept not in `world`.
+    pub fn adoption_inequality(
+        &self,
+        world: &World,
+        adopters_by_region: &HashMap<RegionId, u32>,
+        concept: ConceptId,
+    ) -> f32 {
+        if !world.concepts.contains_key(&concept) {
+            return 0.0;
+        }
+        // (population, adopters per head), in region id order
+        let rates: Vec<(f64, f64)> = world
+            .regions
+            .values()
+            .filter(|region| region.population > 0)
+            .map(|region| {
+                let population = region.population as f64;
+                let adopters = adopters_by_region.get(&region.id).copied().unwrap_or(0);
+                (population, adopters as f64 / population)
+            })
+            .collect();
+        if rates.len() < 2 {
+            return 0.0;
+        }
+        let total: f64 = rates.iter().map(|(population, _)| population).sum();
+        let mean = rates.iter().map(|(population, rate)| population * rate).sum::<f64>() / total;
+        if mean <= 0.0 {
+            return 0.0;
+        }
+        let mut spread = 0.0;
+        for (pop_a, rate_a) in &rates {
+            for (pop_b, rate_b) in &rates {
+                spread += pop_a * pop_b * (rate_a - rate_b).abs();
+            }
+        }
+        (spread / (2.0 * total * total * mean)) as f32
+    }
+
+    /// Append this tick's `adoption_inequality` of `concept`.
+    pub fn record_inequality(
+        &mut self,
+        tick: Tick,
+        world: &World,
+        adopters_by_region: &HashMap<RegionId, u32>,
+        concept: ConceptId,
+    ) {
+        let gini = self.adoption_inequality(world, adopters_by_region, concept);
+        self.inequality_series
+            .entry(concept)
+            .or_default()
+            .push((tick, gini));
+    }
+
+    /// Append this tick's fear added per concept and by contagion.
+    pub fn record_fear_contribution(
+        &mut self,
+        tick: Tick,
+        by_concept: &BTreeMap<ConceptId, f32>,
+        contagion: f32,
+    ) {
+        for (concept_id, fear) in by_concept {
+            if *fear > 0.0 {
+                self.fear_contribution
+                    .entry(*concept_id)
+                    .or_default()
+                    .push((tick, *fear));
+            }
+        }
+        if contagion > 0.0 {
+            self.contagion_fear.push((tick, contagion));
+        }
+    }
+
+    /// Every source of fear added from `since` on, largest first (ties in
+    /// `FearSource` order); sources that added none are left out.
+    pub fn top_fear_drivers(&self, since: Tick) -> Vec<FearDriver> {
+        let total = |series: &[(Tick, f32)]| -> f32 {
+            from_tick(series, since).iter().map(|(_, f)| f).sum()
+        };
+        let mut drivers: Vec<FearDriver> = self
+            .fear_contribution
+            .iter()
+            .map(|(concept_id, series)| FearDriver {
+                source: FearSource::Concept(*concept_id),
+                fear: total(series),
+            })
+            .chain(std::iter::once(FearDriver {
+                source: FearSource::Contagion,
+                fear: total(&self.contagion_fear),
+            }))
+            .filter(|driver| driver.fear > 0.0)
+            .collect();
+        drivers.sort_by(|a, b| b.fear.total_cmp(&a.fear).then(a.source.cmp(&b.source)));
+        drivers
+    }
+
+    pub fn record_regional_fear(&mut self, tick: Tick, regional_fear: &BTreeMap<RegionId, f32>) {
+        for (region_id, fear) in regional_fear {
+            self.regional_fear
+                .entry(*region_id)
+                .or_default()
+                .push((tick, *fear));
+        }
+    }
+
+    /// Most recent global fear index, or 0 before the first update.
+    pub fn current_global_fear(&self) -> f32 {
+        self.time_series.last().map_or(0.0, |(_, f)| *f)
+    }
+
+    pub fn is_above_ethical_ceiling(
+        &self,
+        ceiling: &EthicalCeiling,
+        region_ceilings: &BTreeMap<RegionId, EthicalCeiling>,
+    ) -> bool {
+        self.check_ethical_ceiling(ceiling, region_ceilings).is_some()
+    }
+
+    /// First breached ceiling: global fear, eco damage, then each region's
+    /// `by_region` fear against its entry in `region_ceilings` (falling back
+    /// to `ceiling`), in region id order. `top_concept` is left unset.
+    pub fn check_ethical_ceiling(
+        &self,
+        ceiling: &EthicalCeiling,
+        region_ceilings: &BTreeMap<RegionId, EthicalCeiling>,
+    ) -> Option<CeilingBreach> {
+        self.check_ethical_ceiling_since(ceiling, region_ceilings, 0, AggregationMode::default())
+    }
+
+    /// `check_ethical_ceiling` counting only ticks from `since` on, e.g. to
+    /// ignore a warm-up. For `since` > 0 each region's fear is re-aggregated
+    /// from `region_series` under `mode`, which should be the run's.
+    pub fn check_ethical_ceiling_since(
+        &self,
+        ceiling: &EthicalCeiling,
+        region_ceilings: &BTreeMap<RegionId, EthicalCeiling>,
+        since: Tick,
+        mode: AggregationMode,
+    ) -> Option<CeilingBreach> {
+        let fear_series = from_tick(&self.time_series, since);
+        let peak_fear = fear_series.iter().map(|(_, f)| *f).fold(0.0_f32, f32::max);
+
+        if peak_fear > ceiling.max_fear_index {
+            return Some(CeilingBreach {
+                metric: CeilingMetric::GlobalFear,
+                value: peak_fear,
+                ceiling: ceiling.max_fear_index,
+                tick: peak_tick(fear_series),
+                worst_region: self.worst_region(),
+                top_concept: None,
+                fear_drivers: self.breach_fear_drivers(since),
+            });
+        }
+        let eco_series = from_tick(&self.eco_damage_series, since);
+        let eco_damage = eco_series.iter().map(|(_, d)| *d).fold(0.0_f32, f32::max);
+        if eco_damage > ceiling.max_eco_damage {
+            return Some(CeilingBreach {
+                metric: CeilingMetric::EcoDamage,
+                value: eco_damage,
+                ceiling: ceiling.max_eco_damage,
+                tick: peak_tick(eco_series),
+                worst_region: self.worst_eco_region(),
+                top_concept: None,
+                fear_drivers: self.breach_fear_drivers(since),
+            });
+        }
+
+        self.by_region.iter().find_map(|(region, fear)| {
+            let series = self
+                .region_series
+                .get(region)
+                .map_or(&[][..], |series| from_tick(series, since));
+            let fear = if since == 0 {
+                *fear
+            } else {
+                let mut values: Vec<f32> = series.iter().map(|(_, f)| *f).collect();
+                if values.is_empty() {
+                    return None;
+                }
+                mode.aggregate(&mut values)
+            };
+            let max = region_ceilings
+                .get(region)
+                .unwrap_or(ceiling)
+                .max_fear_index;
+            (fear > max).then(|| CeilingBreach {
+                metric: CeilingMetric::RegionFear(*region),
+                value: fear,
+                ceiling: max,
+                tick: peak_tick(series),
+                worst_region: Some(*region),
+                top_concept: None,
+                fear_drivers: self.breach_fear_drivers(since),
+            })
+        })
+    }
+
+    /// Margins of the values recorded for `tick` to `ceiling`, or to the
+    /// region's own ceiling in `region_ceilings`; a metric not recorded for
+    /// `tick` counts as 0.
+    pub fn ceiling_margin(
+        &self,
+        tick: Tick,
+        ceiling: &EthicalCeiling,
+        region_ceilings: &BTreeMap<RegionId, EthicalCeiling>,
+    ) -> CeilingMargin {
+        let at_tick = |series: &[(Tick, f32)]| match series.last() {
+            Some(&(at, value)) if at == tick => value,
+            _ => 0.0,
+        };
+        let region_fear = self
+            .region_series
+            .iter()
+            .filter(|(_, series)| series.last().is_some_and(|(at, _)| *at == tick))
+            .map(|(region, series)| {
+                let max = region_ceilings.get(region).unwrap_or(ceiling).max_fear_index;
+                (*region, max - at_tick(series))
+            })
+            .fold(None, |closest: Option<(RegionId, f32)>, (region, margin)| {
+                match closest {
+                    Some((_, least)) if least <= margin => closest,
+                    _ => Some((region, margin)),
+                }
+            });
+        CeilingMargin {
+            tick,
+            fear: ceiling.max_fear_index - at_tick(&self.time_series),
+            eco_damage: ceiling.max_eco_damage - at_tick(&self.eco_damage_series),
+            region_fear,
+        }
+    }
+
+    /// Concept `concept_id` descends from through `lineage`; itself when it
+    /// is not a variant.
+    pub fn root_concept_of(&self, concept_id: ConceptId) -> ConceptId {
+        let mut root = concept_id;
+        while let Some(parent) = self.lineage.get(&root) {
+            root = *parent;
+        }
+        root
+    }
+
+    /// `curves` (concept -> value per tick) summed tick by tick over each
+    /// root concept's family: the root and all its variants.
+    pub fn family_curves(
+        &self,
+        curves: &BTreeMap<ConceptId, Vec<u32>>,
+    ) -> BTreeMap<ConceptId, Vec<u32>> {
+        let mut families: BTreeMap<ConceptId, Vec<u32>> = BTreeMap::new();
+        for (concept_id, curve) in curves {
+            let family = families.entry(self.root_concept_of(*concept_id)).or_default();
+            if family.len() < curve.len() {
+                family.resize(curve.len(), 0);
+            }
+            for (total, value) in family.iter_mut().zip(curve) {
+                *total += value;
+            }
+        }
+        families
+    }
+
+    fn breach_fear_drivers(&self, since: Tick) -> Vec<FearDriver> {
+        let mut drivers = self.top_fear_drivers(since);
+        drivers.truncate(BREACH_FEAR_DRIVERS);
+        drivers
+    }
+
+    /// Region with the highest `by_region` fear; the lowest id on ties.
+    pub fn worst_region(&self) -> Option<RegionId> {
+        highest(&self.by_region)
+    }
+
+    /// Every series as `MetricRow`s, in the documented order.
+    pub fn rows(&self) -> Vec<MetricRow> {
+        fn series<'a>(
+            rows: &mut Vec<MetricRow>,
+            metric: &str,
+            region_or_global: &str,
+            points: impl IntoIterator<Item = &'a (Tick, f32)>,
+        ) {
+            rows.extend(points.into_iter().map(|&(tick, value)| MetricRow {
+                tick: Some(tick),
+                metric: metric.to_string(),
+                region_or_global: region_or_global.to_string(),
+                value,
+            }));
+        }
+
+        let mut rows = Vec::new();
+        series(&mut rows, "global_fear", GLOBAL, &self.time_series);
+        series(&mut rows, "eco_damage", GLOBAL, &self.eco_damage_series);
+        let by_region = [
+            ("region_fear", &self.region_series),
+            ("regional_fear", &self.regional_fear),
+            ("trust", &self.trust_by_region),
+            ("regret", &self.regret_by_region),
+        ];
+        for (metric, map) in by_region {
+            for (region_id, points) in map {
+                series(&mut rows, metric, &region_id.to_string(), points);
+            }
+        }
+        let aggregates = [
+            ("region_fear_aggregate", &self.by_region),
+            ("eco_damage_share", &self.eco_by_region),
+        ];
+        for (metric, map) in aggregates {
+            rows.extend(map.iter().map(|(region_id, value)| MetricRow {
+                tick: None,
+                metric: metric.to_string(),
+                region_or_global: region_id.to_string(),
+                value: *value,
+            }));
+        }
+        for (concept_id, points) in &self.inequality_series {
+            series(&mut rows, &format!("inequality:{concept_id}"), GLOBAL, points);
+        }
+        for (concept_id, points) in &self.fear_contribution {
+            series(&mut rows, &format!("fear_contribution:{concept_id}"), GLOBAL, points);
+        }
+        series(&mut rows, "fear_contribution:contagion", GLOBAL, &self.contagion_fear);
+        for (concept_id, points) in &self.funnel.by_concept {
+            for stage in 0..FunnelPoint::default().stages().len() {
+                rows.extend(points.iter().map(|(tick, point)| {
+                    let (name, count) = point.stages()[stage];
+                    MetricRow {
+                        tick: Some(*tick),
+                        metric: format!("funnel_{name}:{concept_id}"),
+                        region_or_global: GLOBAL.to_string(),
+                        value: count as f32,
+                    }
+                }));
+            }
+        }
+        rows
+    }
+
+    /// Tidy CSV of `rows`, with a header: `tick,metric,region_or_global,value`.
+    pub fn export_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "tick,metric,region_or_global,value")?;
+        for row in self.rows() {
+            let tick = row.tick.map(|tick| tick.to_string()).unwrap_or_default();
+            writeln!(
+                writer,
+                "{tick},{},{},{}",
+                row.metric, row.region_or_global, row.value
+            )?;
+        }
+        Ok(())
+    }
+
+    /// `rows` as a single Parquet row group, columns as in `MetricRow`.
+    #[cfg(feature = "parquet")]
+    pub fn export_parquet<W: Write + Send>(&self, writer: W) -> parquet::errors::Result<()> {
+        use arrow::array::{ArrayRef, Float32Array, StringArray, UInt64Array};
+        use arrow::datatypes::{DataType, Field, Schema};
+        use arrow::record_batch::RecordBatch;
+        use parquet::arrow::ArrowWriter;
+        use std::sync::Arc;
+
+        let rows = self.rows();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("tick", DataType::UInt64, true),
+            Field::new("metric", DataType::Utf8, false),
+            Field::new("region_or_global", DataType::Utf8, false),
+            Field::new("value", DataType::Float32, false),
+        ]));
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(rows.iter().map(|row| row.tick).collect::<UInt64Array>()),
+            Arc::new(rows.iter().map(|row| Some(row.metric.as_str())).collect::<StringArray>()),
+            Arc::new(
+                rows.iter()
+                    .map(|row| Some(row.region_or_global.as_str()))
+                    .collect::<StringArray>(),
+            ),
+            Arc::new(rows.iter().map(|row| row.value).collect::<Float32Array>()),
+        ];
+        let batch = RecordBatch::try_new(schema.clone(), columns)?;
+        let mut writer = ArrowWriter::try_new(writer, schema, None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_pick_the_nearest_rank() {
+        let mut values = vec![3.0, 1.0, 2.0, 5.0, 4.0];
+        assert_eq!(AggregationMode::Max.aggregate(&mut values), 5.0);
+        assert_eq!(AggregationMode::Mean.aggregate(&mut values), 3.0);
+        assert_eq!(
+            AggregationMode::Percentile(90.0).aggregate(&mut values),
+            5.0
+        );
+        assert_eq!(
+            AggregationMode::Percentile(40.0).aggregate(&mut values),
+            2.0
+        );
+        assert_eq!(AggregationMode::Percentile(0.0).aggregate(&mut values), 1.0);
+
+        let mode: AggregationMode = serde_json::from_str(r#"{"percentile":90.0}"#).unwrap();
+        assert_eq!(mode, AggregationMode::Percentile(90.0));
+    }
+
+    /// Gini of concept 0 over regions of `populations` with `adopters`.
+    fn gini(populations: &[u32], adopters: &[u32]) -> f32 {
+        let mut world = crate::sim::tests::sim(0, 1).world;
+        let template = world.regions[&0].clone();
+        world.regions.clear();
+        for (id, &population) in (0..).zip(populations) {
+            let mut region = template.clone();
+            region.id = id;
+            region.population = population;
+            world.regions.insert(id, region);
+        }
+        let adopters: HashMap<RegionId, u32> = (0..).zip(adopters.iter().copied()).collect();
+        FearIndexMetrics::default().adoption_inequality(&world, &adopters, 0)
+    }
+
+    #[test]
+    fn gini_matches_hand_computed_values() {
+        assert!((gini(&[100, 100], &[10, 0]) - 0.5).abs() < 1e-6);
+        assert!(gini(&[100, 100], &[10, 10]).abs() < 1e-6);
+        // Rates 0.4 and 0, weighted 1:3.
+        assert!((gini(&[100, 300], &[40, 0]) - 0.75).abs() < 1e-6);
+        assert!((gini(&[10, 10, 10, 10], &[0, 0, 0, 10]) - 0.75).abs() < 1e-6);
+        assert!((gini(&[10, 10, 10, 10], &[10, 20, 30, 40]) - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn gini_is_zero_without_a_comparison() {
+        assert!((gini(&[100, 0, 100], &[10, 5, 0]) - 0.5).abs() < 1e-6);
+        assert_eq!(gini(&[100], &[10]), 0.0);
+        assert_eq!(gini(&[100, 0], &[10, 3]), 0.0);
+        assert_eq!(gini(&[100, 100], &[0, 0]), 0.0);
+    }
+
+    fn tracked_run() -> FearIndexMetrics {
+        let mut sim = crate::sim::tests::sim(30, 2);
+        sim.config.track_inequality = true;
+        sim.config.stop_conditions = vec![];
+        sim.run();
+        sim.fear_metrics
+    }
+
+    #[test]
+    fn csv_export_has_one_row_per_metric_value() {
+        let metrics = tracked_run();
+        let mut out = Vec::new();
+        metrics.export_csv(&mut out).unwrap();
+        let mut reader = csv::Reader::from_reader(out.as_slice());
+        assert_eq!(
+            reader.headers().unwrap(),
+            vec!["tick", "metric", "region_or_global", "value"]
+        );
+        let records: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+        assert_eq!(records.len(), metrics.rows().len());
+
+        let global = records
+            .iter()
+            .find(|record| &record[0] == "5" && &record[1] == "global_fear")
+            .unwrap();
+        assert_eq!(&global[2], "global");
+        assert_eq!(global[3].parse::<f32>().unwrap(), metrics.time_series[5].1);
+        let aggregate = records
+            .iter()
+            .find(|record| &record[1] == "region_fear_aggregate" && &record[2] == "1")
+            .unwrap();
+        assert_eq!(&aggregate[0], "");
+        assert_eq!(aggregate[3].parse::<f32>().unwrap(), metrics.by_region[&1]);
+        assert_eq!(
+            records
+                .iter()
+                .filter(|record| &record[1] == "inequality:1")
+                .count(),
+            10
+        );
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn parquet_export_has_the_same_rows() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let metrics = tracked_run();
+        let path =
+            std::env::temp_dir().join(format!("zone-sim-metrics-{}.parquet", std::process::id()));
+        metrics
+            .export_parquet(std::fs::File::create(&path).unwrap())
+            .unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let rows: usize = reader.map(|batch| batch.unwrap().num_rows()).sum();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(rows, metrics.rows().len());
+    }
+
+    #[test]
+    fn funnel_stages_are_exported() {
+        let mut out = Vec::new();
+        tracked_run().export_csv(&mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        for stage in ["exposed", "considered", "adopted"] {
+            assert!(
+                csv.contains(&format!(",funnel_{stage}:1,global,")),
+                "{stage}"
+            );
+        }
     }
 }
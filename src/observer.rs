@@ -0,0 +1,259 @@
+//! Per-tick hooks into `Simulation::run_with_observer`, e.g. for a GUI.
+
+use crate::core::agent::AgentAction;
+use crate::core::id::{RegionId, Tick};
+use crate::intervention::Intervention;
+use crate::metrics::FearIndexMetrics;
+use crate::sim::StopCondition;
+use crate::snapshot::RegionSnapshot;
+use crate::world::World;
+use std::collections::BTreeMap;
+use std::ops::ControlFlow;
+
+/// Called at fixed points of every tick. Returning `ControlFlow::Break`
+/// from any hook makes the current tick the last one: it still completes,
+/// then the run stops with `StopCondition::Observer`.
+pub trait SimObserver {
+    /// Before agents decide, with the world they will see.
+    fn on_tick_start(&mut self, _tick: Tick, _world: &World) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// The tick's actions in agent-id order, before they are applied.
+    fn on_actions(&mut self, _tick: Tick, _actions: &[AgentAction]) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// After the tick's fear metrics update.
+    fn on_tick_end(&mut self, _tick: Tick, _metrics: &FearIndexMetrics) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Whether `on_region_snapshots` should be called for `tick`; the
+    /// snapshots are only built when it returns true.
+    fn wants_region_snapshots(&self, _tick: Tick) -> bool {
+        false
+    }
+
+    /// After `on_tick_end`, one snapshot per region in region id order.
+    fn on_region_snapshots(
+        &mut self,
+        _tick: Tick,
+        _snapshots: &[RegionSnapshot],
+    ) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Last in the tick: interventions to apply at the start of the next
+    /// one, as if passed to `Simulation::schedule_intervention`.
+    fn interventions(&mut self, _tick: Tick) -> Vec<Intervention> {
+        Vec::new()
+    }
+
+    /// Once, when the run ends, with the condition that ended it.
+    fn on_stop(&mut self, _reason: StopCondition) {}
+}
+
+/// Observer that ignores everything; used by plain `Simulation::run`.
+impl SimObserver for () {}
+
+/// Forwards only ticks that are a multiple of `every_n_ticks` (0 forwards
+/// none); `on_stop` is always forwarded.
+pub struct ThrottledObserver<O> {
+    pub every_n_ticks: Tick,
+    pub inner: O,
+}
+
+impl<O: SimObserver> ThrottledObserver<O> {
+    pub fn new(every_n_ticks: Tick, inner: O) -> Self {
+        Self {
+            every_n_ticks,
+            inner,
+        }
+    }
+
+    fn forwards(&self, tick: Tick) -> bool {
+        self.every_n_ticks > 0 && tick.is_multiple_of(self.every_n_ticks)
+    }
+}
+
+impl<O: SimObserver> SimObserver for ThrottledObserver<O> {
+    fn on_tick_start(&mut self, tick: Tick, world: &World) -> ControlFlow<()> {
+        if !self.forwards(tick) {
+            return ControlFlow::Continue(());
+        }
+        self.inner.on_tick_start(tick, world)
+    }
+
+    fn on_actions(&mut self, tick: Tick, actions: &[AgentAction]) -> ControlFlow<()> {
+        if !self.forwards(tick) {
+            return ControlFlow::Continue(());
+        }
+        self.inner.on_actions(tick, actions)
+    }
+
+    fn on_tick_end(&mut self, tick: Tick, metrics: &FearIndexMetrics) -> ControlFlow<()> {
+        if !self.forwards(tick) {
+            return ControlFlow::Continue(());
+        }
+        self.inner.on_tick_end(tick, metrics)
+    }
+
+    fn wants_region_snapshots(&self, tick: Tick) -> bool {
+        self.forwards(tick) && self.inner.wants_region_snapshots(tick)
+    }
+
+    fn on_region_snapshots(&mut self, tick: Tick, snapshots: &[RegionSnapshot]) -> ControlFlow<()> {
+        if !self.forwards(tick) {
+            return ControlFlow::Continue(());
+        }
+        self.inner.on_region_snapshots(tick, snapshots)
+    }
+
+    fn interventions(&mut self, tick: Tick) -> Vec<Intervention> {
+        if !self.forwards(tick) {
+            return Vec::new();
+        }
+        self.inner.interventions(tick)
+    }
+
+    fn on_stop(&mut self, reason: StopCondition) {
+        self.inner.on_stop(reason);
+    }
+}
+
+/// Counts the Move actions of a run per `(from, to)` pair, for
+/// `World::flows_to_dot`. Moves refused by closure or capacity are counted
+/// too, since `on_actions` sees actions before they are applied.
+#[derive(Debug, Clone, Default)]
+pub struct MigrationFlowRecorder {
+    pub flows: BTreeMap<(RegionId, RegionId), u32>,
+}
+
+impl SimObserver for MigrationFlowRecorder {
+    fn on_actions(&mut self, _tick: Tick, actions: &[AgentAction]) -> ControlFlow<()> {
+        for action in actions {
+            if let AgentAction::Move { from, to, .. } = action {
+                *self.flows.entry((*from, *to)).or_default() += 1;
+            }
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::tests::sim;
+    use crate::sim::{SimEvent, Simulation};
+
+    /// Counts hook calls and breaks at the end of `stop_at`.
+    #[derive(Default)]
+    struct Counter {
+        starts: u64,
+        actions: u64,
+        ends: u64,
+        stop: Option<StopCondition>,
+        stop_at: Option<Tick>,
+    }
+
+    impl SimObserver for Counter {
+        fn on_tick_start(&mut self, _tick: Tick, _world: &World) -> ControlFlow<()> {
+            self.starts += 1;
+            ControlFlow::Continue(())
+        }
+
+        fn on_actions(&mut self, _tick: Tick, _actions: &[AgentAction]) -> ControlFlow<()> {
+            self.actions += 1;
+            ControlFlow::Continue(())
+        }
+
+        fn on_tick_end(&mut self, tick: Tick, _metrics: &FearIndexMetrics) -> ControlFlow<()> {
+            self.ends += 1;
+            if Some(tick) == self.stop_at {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        }
+
+        fn on_stop(&mut self, reason: StopCondition) {
+            self.stop = Some(reason);
+        }
+    }
+
+    fn scenario() -> Simulation {
+        let mut sim = sim(50, 1);
+        sim.config.max_ticks = 20;
+        sim.config.stop_conditions = Vec::new();
+        sim
+    }
+
+    #[test]
+    fn every_hook_is_called_once_per_tick() {
+        let mut counter = Counter::default();
+        let result = scenario().run_with_observer(&mut counter);
+        assert_eq!(
+            (counter.starts, counter.actions, counter.ends),
+            (20, 20, 20)
+        );
+        assert_eq!(counter.stop, Some(StopCondition::MaxTicks));
+        assert_eq!(result.ticks_executed, 20);
+    }
+
+    #[test]
+    fn breaking_stops_after_the_tick() {
+        let initial = scenario();
+        let mut sim = initial.clone();
+        let mut counter = Counter {
+            stop_at: Some(7),
+            ..Counter::default()
+        };
+        let result = sim.run_with_observer(&mut counter);
+        assert_eq!(result.ticks_executed, 8);
+        assert_eq!(result.stop_condition, Some(StopCondition::Observer));
+        assert_eq!(counter.stop, Some(StopCondition::Observer));
+        assert_eq!(
+            sim.log.events.last(),
+            Some(&(
+                7,
+                SimEvent::Stopped {
+                    condition: StopCondition::Observer
+                }
+            ))
+        );
+        // The stop is in the log, so a replay ends at the same tick
+        assert_eq!(
+            Simulation::replay(&initial, &sim.log).log.events,
+            sim.log.events
+        );
+    }
+
+    #[test]
+    fn throttling_forwards_every_nth_tick_and_the_stop() {
+        let mut throttled = ThrottledObserver::new(5, Counter::default());
+        scenario().run_with_observer(&mut throttled);
+        assert_eq!((throttled.inner.starts, throttled.inner.ends), (4, 4));
+        assert_eq!(throttled.inner.stop, Some(StopCondition::MaxTicks));
+    }
+
+    #[test]
+    fn migration_flows_count_every_move() {
+        let mut sim = scenario();
+        for agent in &mut sim.agents {
+            agent.attrs.mobility_score = 1.0;
+        }
+        let mut recorder = MigrationFlowRecorder::default();
+        sim.run_with_observer(&mut recorder);
+
+        let moves = sim
+            .log
+            .events
+            .iter()
+            .filter(|(_, event)| matches!(event, SimEvent::Moved { .. }))
+            .count();
+        assert!(moves > 0);
+        assert_eq!(recorder.flows.values().sum::<u32>() as usize, moves);
+        assert!(recorder.flows.keys().all(|(from, to)| from != to));
+    }
+}
@@ -1,28 +1,455 @@
-use crate::core::id::{ConceptId, RegionId, Tick};
+use crate::concept::{Concept, ConceptLegalStatus};
+use crate::core::agent::Agent;
+use crate::core::id::{AgentId, ConceptId, RegionId, Tick};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::ops::Range;
 
-#[derive(Debug, Clone)]
+/// Fraction of `max_fear_index` below the ceiling at which adoption is blocked.
+pub const CEILING_MARGIN: f32 = 0.05;
+/// Penalty that makes adoption effectively impossible (sigmoid(-20) ~ 2e-9).
+pub const BLOCKING_PENALTY: f32 = 20.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EthicalCeiling {
     pub max_fear_index: f32,          // global 0..1
     pub max_eco_damage: f32,          // global 0..1
     pub forbid_irreversible_bio: bool,
+    /// With `forbid_irreversible_bio`, concepts whose `irreversible_bio_risk`
+    /// exceeds this are never exposable.
+    pub max_irreversible_bio_risk: f32,
+}
+
+/// How policy responds as metrics near an ethical ceiling. Under either,
+/// `StopCondition::EthicalCeiling` still stops the run on a breach, unless
+/// `EnforcementMode::Advisory`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum CeilingResponse {
+    /// Nothing short of the stop.
+    #[default]
+    HardStop,
+    /// While any metric is within `recovery_margin` (a fraction of its
+    /// ceiling) of a ceiling, exposure gains are multiplied by
+    /// `exposure_multiplier` and `policy_penalty_for` is raised; normal
+    /// once every metric is back below the margin.
+    Throttle {
+        exposure_multiplier: f32,
+        recovery_margin: f32,
+    },
+}
+
+/// Whether a breached ethical ceiling stops the run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnforcementMode {
+    /// `StopCondition::EthicalCeiling` stops the run on a breach.
+    #[default]
+    Enforce,
+    /// Dry run: a breach never stops the run. `SimulationResult::advisory`
+    /// reports each tick's margin to the ceilings and the breach, and tick,
+    /// `Enforce` would have stopped on.
+    Advisory,
+}
+
+/// Curfew / launch window: matching concept/region combinations are only
+/// exposable during `allowed_ticks`. `None` matches any concept or region.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExposureWindowRule {
+    pub concept_id: Option<ConceptId>,
+    pub region: Option<RegionId>,
+    pub allowed_ticks: Range<Tick>,
+}
+
+impl ExposureWindowRule {
+    pub fn applies_to(&self, concept_id: ConceptId, region: RegionId) -> bool {
+        self.concept_id.is_none_or(|id| id == concept_id)
+            && self.region.is_none_or(|r| r == region)
+    }
+}
+
+/// Who may adopt or share `concept_id`: agents meeting every bound that is
+/// set. Several rules for one concept must all be met.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttributeRule {
+    pub concept_id: ConceptId,
+    pub min_age: Option<u8>,
+    pub max_age: Option<u8>,
+    pub min_income: Option<f32>,
+    /// The agent must carry this tag (see `AgentAttributes::tags`).
+    pub predicate_tag: Option<String>,
+}
+
+impl AttributeRule {
+    pub fn admits(&self, agent: &Agent) -> bool {
+        let attrs = &agent.attrs;
+        self.min_age.is_none_or(|min| attrs.age >= min)
+            && self.max_age.is_none_or(|max| attrs.age <= max)
+            && self.min_income.is_none_or(|min| attrs.income_level >= min)
+            && self
+                .predicate_tag
+                .as_ref()
+                .is_none_or(|tag| attrs.tags.contains(tag))
+    }
 }
 
-#[derive(Debug, Clone)]
+/// The policy rule behind a `PolicyDenial`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum DenialRule {
+    /// The region's ceiling forbids the concept's irreversible bio-risk.
+    IrreversibleBioRisk,
+    /// Outside every exposure window matching the concept and region.
+    ExposureWindow,
+    /// The concept is `Prohibited`.
+    LegalStatus,
+    /// The concept is `Restricted` and the region is not allowed it.
+    RestrictedRegion,
+    /// The region is at `adoption_caps` for the concept.
+    AdoptionCap,
+    /// The destination is in `closed_regions`.
+    ClosedRegion,
+    /// The run was stopped for breaching an ethical ceiling.
+    EthicalCeiling,
+    /// An applied action failed a policy check its proposer skipped; the
+    /// details name the check.
+    PostHocValidation,
+    /// The agent does not meet an `AttributeRule` for the concept.
+    AgentAttributes,
+}
+
+impl fmt::Display for DenialRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DenialRule::IrreversibleBioRisk => "irreversible bio-risk",
+            DenialRule::ExposureWindow => "exposure window",
+            DenialRule::LegalStatus => "legal status",
+            DenialRule::RestrictedRegion => "restricted region",
+            DenialRule::AdoptionCap => "adoption cap",
+            DenialRule::ClosedRegion => "closed region",
+            DenialRule::EthicalCeiling => "ethical ceiling",
+            DenialRule::PostHocValidation => "post-hoc validation",
+            DenialRule::AgentAttributes => "agent attributes",
+        })
+    }
+}
+
+/// Something a policy rule prevented, for governance review.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PolicyDenial {
+    pub tick: Tick,
+    /// `None` when no single agent was involved, e.g. a broadcast, a stop
+    /// or concepts withheld from a whole region's agents.
+    pub agent_id: Option<AgentId>,
+    pub concept_id: Option<ConceptId>,
+    pub region: Option<RegionId>,
+    pub rule: DenialRule,
+    pub details: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PolicyContext {
     pub ethical_ceiling: EthicalCeiling,
+    /// Stricter ceilings for individual regions; others use `ethical_ceiling`.
+    pub region_ceilings: BTreeMap<RegionId, EthicalCeiling>,
+    /// Regions where each `Restricted` concept may still be exposed.
+    pub restricted_allow_regions: BTreeMap<ConceptId, Vec<RegionId>>,
+    /// A combination matched by any rule is exposable only inside at least
+    /// one matching window; unmatched combinations are always exposable.
+    pub exposure_windows: Vec<ExposureWindowRule>,
+    /// Regions under lockdown: no agent may move into them.
+    pub closed_regions: BTreeSet<RegionId>,
+    /// Most a concept's adopters may make up of a region's residents
+    /// (0..1). Adoptions past the cap are rejected; agents who already
+    /// adopted may still move in.
+    #[serde(default, with = "cap_entries")]
+    pub adoption_caps: HashMap<(ConceptId, RegionId), f32>,
+    /// Age, income or tag conditions on adopting and sharing concepts.
+    #[serde(default)]
+    pub attribute_rules: Vec<AttributeRule>,
+    #[serde(default)]
+    pub ceiling_response: CeilingResponse,
+    #[serde(default)]
+    pub enforcement: EnforcementMode,
+    /// Latest global fear index, refreshed by `Simulation::run` every tick.
+    pub global_fear: f32,
+    /// `ceiling_response` is throttling, refreshed with `global_fear`.
+    #[serde(default)]
+    pub throttled: bool,
     // future: per-region rules, time windows, logging policies
 }
 
+/// `adoption_caps` as a list of `[[concept, region], cap]`, since JSON map
+/// keys must be strings.
+mod cap_entries {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        caps: &HashMap<(ConceptId, RegionId), f32>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut entries: Vec<_> = caps.iter().collect();
+        entries.sort_by_key(|(key, _)| **key);
+        serializer.collect_seq(entries)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<(ConceptId, RegionId), f32>, D::Error> {
+        let entries = Vec::<((ConceptId, RegionId), f32)>::deserialize(deserializer)?;
+        Ok(entries.into_iter().collect())
+    }
+}
+
 impl PolicyContext {
-    pub fn is_exposure_allowed(&self, concept_id: ConceptId, region: RegionId, _tick: Tick) -> bool {
-        // Here you can implement fine-grained rules (curfews, bans, etc.)
-        let _ = (concept_id, region);
-        true
+    /// Ceiling that applies in `region`.
+    pub fn ceiling_for(&self, region: RegionId) -> &EthicalCeiling {
+        self.region_ceilings
+            .get(&region)
+            .unwrap_or(&self.ethical_ceiling)
+    }
+
+    /// Hard stop: the region's ceiling forbids irreversible bio-risk and the
+    /// concept's risk is over its threshold.
+    pub fn is_bio_risk_blocked(&self, concept: &Concept, region: RegionId) -> bool {
+        let ceiling = self.ceiling_for(region);
+        ceiling.forbid_irreversible_bio
+            && concept.risk_profile.irreversible_bio_risk > ceiling.max_irreversible_bio_risk
+    }
+
+    /// Most agents among `residents` of `region` that may hold `concept_id`,
+    /// or `None` if uncapped.
+    pub fn adoption_cap(
+        &self,
+        concept_id: ConceptId,
+        region: RegionId,
+        residents: u32,
+    ) -> Option<u32> {
+        self.adoption_caps
+            .get(&(concept_id, region))
+            // Tolerance so that e.g. 0.29 of 100 allows 29 despite f32 rounding
+            .map(|fraction| (fraction.clamp(0.0, 1.0) * residents as f32 + 1e-3).floor() as u32)
+    }
+
+    pub fn is_exposure_allowed(&self, concept: &Concept, region: RegionId, tick: Tick) -> bool {
+        self.exposure_denial(concept, region, tick).is_none()
+    }
+
+    /// The first rule that forbids exposing `concept` in `region` at `tick`,
+    /// if any.
+    pub fn exposure_denial(
+        &self,
+        concept: &Concept,
+        region: RegionId,
+        tick: Tick,
+    ) -> Option<DenialRule> {
+        if self.is_bio_risk_blocked(concept, region) {
+            return Some(DenialRule::IrreversibleBioRisk);
+        }
+
+        let mut windows = self
+            .exposure_windows
+            .iter()
+            .filter(|rule| rule.applies_to(concept.id, region))
+            .peekable();
+        if windows.peek().is_some() && !windows.any(|rule| rule.allowed_ticks.contains(&tick)) {
+            return Some(DenialRule::ExposureWindow);
+        }
+
+        match concept.legal_status {
+            ConceptLegalStatus::Allowed => None,
+            ConceptLegalStatus::Restricted => {
+                let allowed = self
+                    .restricted_allow_regions
+                    .get(&concept.id)
+                    .is_some_and(|regions| regions.contains(&region));
+                (!allowed).then_some(DenialRule::RestrictedRegion)
+            }
+            ConceptLegalStatus::Prohibited => Some(DenialRule::LegalStatus),
+        }
+    }
+
+    /// Whether `agent` may adopt or share `concept` in `region` at `tick`:
+    /// exposure is allowed there and the agent meets `attribute_rules`.
+    pub fn is_adoption_allowed(
+        &self,
+        agent: &Agent,
+        concept: &Concept,
+        region: RegionId,
+        tick: Tick,
+    ) -> bool {
+        self.adoption_denial(agent, concept, region, tick).is_none()
+    }
+
+    /// `exposure_denial`, then the agent's attributes.
+    pub fn adoption_denial(
+        &self,
+        agent: &Agent,
+        concept: &Concept,
+        region: RegionId,
+        tick: Tick,
+    ) -> Option<DenialRule> {
+        self.exposure_denial(concept, region, tick).or_else(|| {
+            let denied = self
+                .attribute_rules
+                .iter()
+                .any(|rule| rule.concept_id == concept.id && !rule.admits(agent));
+            denied.then_some(DenialRule::AgentAttributes)
+        })
     }
 
     /// Extra penalty when concept risk profile is near/over ceilings.
-    pub fn policy_penalty_for(&self, _concept_id: ConceptId) -> f32 {
-        // In full implementation, look up concept, compare with ceilings
-        0.0
+    ///
+    /// Grows smoothly with how close `global_fear` is to `max_fear_index`,
+    /// scaled by the concept's expected fear and eco harm, and jumps to
+    /// `BLOCKING_PENALTY` within `CEILING_MARGIN` of the ceiling. While
+    /// throttled, the concept's risk is added and the sum divided by the
+    /// exposure multiplier.
+    pub fn policy_penalty_for(&self, concept: &Concept) -> f32 {
+        let max_fear = self.ethical_ceiling.max_fear_index;
+        if max_fear <= 0.0 {
+            return BLOCKING_PENALTY;
+        }
+        // 0 with no fear, 1 at the blocking threshold
+        let proximity = (self.global_fear / (max_fear * (1.0 - CEILING_MARGIN))).max(0.0);
+        if proximity >= 1.0 {
+            return BLOCKING_PENALTY;
+        }
+        let risk = concept.risk_profile.expected_fear + concept.risk_profile.eco_harm_score;
+        let mut penalty = risk * proximity * proximity / (1.0 - proximity);
+        if self.throttled {
+            penalty = (penalty + risk) / self.exposure_multiplier().max(1e-3);
+        }
+        penalty.min(BLOCKING_PENALTY)
+    }
+
+    /// Factor applied to exposure added by shares and broadcasts: the
+    /// throttle's multiplier while throttled, otherwise 1.
+    pub fn exposure_multiplier(&self) -> f32 {
+        match self.ceiling_response {
+            CeilingResponse::Throttle {
+                exposure_multiplier,
+                ..
+            } if self.throttled => exposure_multiplier,
+            _ => 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::tests::sim;
+
+    #[test]
+    fn legal_status_gates_exposure() {
+        let mut policy = sim(0, 1).policy;
+        let mut concept = sim(0, 1).world.concepts[&0].clone();
+        assert_eq!(policy.exposure_denial(&concept, 0, 0), None);
+
+        concept.legal_status = ConceptLegalStatus::Prohibited;
+        assert_eq!(
+            policy.exposure_denial(&concept, 0, 0),
+            Some(DenialRule::LegalStatus)
+        );
+
+        concept.legal_status = ConceptLegalStatus::Restricted;
+        assert_eq!(
+            policy.exposure_denial(&concept, 1, 0),
+            Some(DenialRule::RestrictedRegion)
+        );
+        policy.restricted_allow_regions.insert(concept.id, vec![1]);
+        assert!(policy.is_exposure_allowed(&concept, 1, 0));
+        assert_eq!(
+            policy.exposure_denial(&concept, 0, 0),
+            Some(DenialRule::RestrictedRegion)
+        );
+    }
+
+    #[test]
+    fn penalty_grows_smoothly_then_blocks_near_the_ceiling() {
+        let mut policy = sim(0, 1).policy;
+        let concept = sim(0, 1).world.concepts[&0].clone();
+        let max = policy.ethical_ceiling.max_fear_index;
+        let blocking_from = max * (1.0 - CEILING_MARGIN);
+
+        policy.global_fear = 0.0;
+        assert_eq!(policy.policy_penalty_for(&concept), 0.0);
+        let mut previous = 0.0;
+        for step in 1..=90 {
+            policy.global_fear = blocking_from * step as f32 / 100.0;
+            let penalty = policy.policy_penalty_for(&concept);
+            assert!(
+                penalty > previous && penalty < BLOCKING_PENALTY,
+                "{penalty} at step {step}"
+            );
+            previous = penalty;
+        }
+        policy.global_fear = blocking_from;
+        assert_eq!(policy.policy_penalty_for(&concept), BLOCKING_PENALTY);
+        policy.global_fear = max;
+        assert_eq!(policy.policy_penalty_for(&concept), BLOCKING_PENALTY);
+    }
+
+    #[test]
+    fn penalty_scales_with_concept_risk() {
+        let mut policy = sim(0, 1).policy;
+        policy.global_fear = 0.5;
+        let mild = sim(0, 1).world.concepts[&0].clone();
+        let mut risky = mild.clone();
+        risky.risk_profile.expected_fear *= 2.0;
+        risky.risk_profile.eco_harm_score *= 2.0;
+        assert!(
+            (policy.policy_penalty_for(&risky) - 2.0 * policy.policy_penalty_for(&mild)).abs()
+                < 1e-5
+        );
+    }
+
+    #[test]
+    fn exposure_windows_gate_matching_combinations_only() {
+        let mut policy = sim(0, 1).policy;
+        let concepts = sim(0, 1).world.concepts;
+        policy.exposure_windows.push(ExposureWindowRule {
+            concept_id: Some(1),
+            region: None,
+            allowed_ticks: 50..60,
+        });
+        policy.exposure_windows.push(ExposureWindowRule {
+            concept_id: Some(1),
+            region: Some(2),
+            allowed_ticks: 10..20,
+        });
+
+        assert_eq!(
+            policy.exposure_denial(&concepts[&1], 0, 49),
+            Some(DenialRule::ExposureWindow)
+        );
+        assert!(policy.is_exposure_allowed(&concepts[&1], 0, 50));
+        assert_eq!(
+            policy.exposure_denial(&concepts[&1], 0, 60),
+            Some(DenialRule::ExposureWindow)
+        );
+        // Either matching window opens region 2
+        assert!(policy.is_exposure_allowed(&concepts[&1], 2, 15));
+        assert!(policy.is_exposure_allowed(&concepts[&1], 2, 55));
+        assert!(!policy.is_exposure_allowed(&concepts[&1], 2, 30));
+        // Unmatched concepts are always exposable
+        assert!(policy.is_exposure_allowed(&concepts[&0], 0, 0));
+    }
+
+    #[test]
+    fn bio_risk_blocks_only_past_the_threshold() {
+        let mut policy = sim(0, 1).policy;
+        let mut concept = sim(0, 1).world.concepts[&1].clone();
+        concept.risk_profile.irreversible_bio_risk = 0.5;
+        policy.ethical_ceiling.max_irreversible_bio_risk = 0.5;
+        assert!(policy.is_exposure_allowed(&concept, 0, 0));
+        policy.ethical_ceiling.max_irreversible_bio_risk = 0.49;
+        assert_eq!(
+            policy.exposure_denial(&concept, 0, 0),
+            Some(DenialRule::IrreversibleBioRisk)
+        );
+        policy.ethical_ceiling.forbid_irreversible_bio = false;
+        assert!(policy.is_exposure_allowed(&concept, 0, 0));
     }
 }
@@ -0,0 +1,432 @@
+//! Generate agents from per-region attribute distributions.
+//!
+//! `income_level` and `risk_tolerance` can be correlated through a Gaussian
+//! copula: both are drawn from correlated standard normals mapped through
+//! their distribution's quantile function, which keeps each marginal as
+//! specified.
+
+use crate::core::agent::{Agent, AgentAttributes, AgentBeliefs, AgentState};
+use crate::core::id::{AgentId, RegionId};
+use rand::Rng;
+use serde::Deserialize;
+use std::f64::consts::{FRAC_1_SQRT_2, TAU};
+use std::fmt;
+
+/// A value to draw: fixed, normal, uniform or bucketed. In scenario files a
+/// plain number is `Fixed`, `{ mean, std_dev }` (optionally with `min` and
+/// `max` to clamp to) is `Normal`, `{ min, max }` is `Uniform` and a list of
+/// `{ min, max, weight }` is `Buckets`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum Distribution {
+    Fixed(f32),
+    /// Normal, clamped to `[min, max]`.
+    Normal {
+        mean: f32,
+        std_dev: f32,
+        #[serde(default = "unbounded_min")]
+        min: f32,
+        #[serde(default = "unbounded_max")]
+        max: f32,
+    },
+    Uniform {
+        min: f32,
+        max: f32,
+    },
+    /// A bucket chosen with probability proportional to its weight, then a
+    /// uniform value inside it; e.g. age brackets.
+    Buckets(Vec<Bucket>),
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Bucket {
+    pub min: f32,
+    pub max: f32,
+    pub weight: f32,
+}
+
+fn unbounded_min() -> f32 {
+    f32::NEG_INFINITY
+}
+
+fn unbounded_max() -> f32 {
+    f32::INFINITY
+}
+
+/// Standard normal draw (Box-Muller).
+fn standard_normal(rng: &mut impl Rng) -> f64 {
+    let u1 = 1.0 - rng.gen::<f64>(); // (0, 1], so ln(u1) is finite
+    let u2 = rng.gen::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (TAU * u2).cos()
+}
+
+/// Standard normal CDF, via the Abramowitz-Stegun 7.1.26 erf approximation
+/// (absolute error below 1.5e-7).
+fn standard_normal_cdf(z: f64) -> f64 {
+    let x = z.abs() * FRAC_1_SQRT_2;
+    let t = 1.0 / (1.0 + 0.327_591_1 * x);
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+    if z >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+impl Distribution {
+    /// Why the distribution cannot be sampled, if it cannot.
+    pub fn check(&self) -> Result<(), String> {
+        match self {
+            Distribution::Fixed(_) => Ok(()),
+            Distribution::Normal { std_dev, .. } if *std_dev < 0.0 => {
+                Err(format!("std_dev {std_dev} is negative"))
+            }
+            Distribution::Normal { min, max, .. } | Distribution::Uniform { min, max }
+                if min > max =>
+            {
+                Err(format!("min {min} > max {max}"))
+            }
+            Distribution::Normal { .. } | Distribution::Uniform { .. } => Ok(()),
+            Distribution::Buckets(buckets) => {
+                for (i, bucket) in buckets.iter().enumerate() {
+                    if bucket.min > bucket.max {
+                        return Err(format!(
+                            "bucket {i}: min {} > max {}",
+                            bucket.min, bucket.max
+                        ));
+                    }
+                    if bucket.weight < 0.0 {
+                        return Err(format!("bucket {i}: weight {} is negative", bucket.weight));
+                    }
+                }
+                if buckets.iter().map(|b| b.weight).sum::<f32>() <= 0.0 {
+                    return Err("buckets need a positive total weight".to_string());
+                }
+                Ok(())
+            }
+        }
+    }
+
+    pub fn sample(&self, rng: &mut impl Rng) -> f32 {
+        match self {
+            Distribution::Fixed(value) => *value,
+            Distribution::Normal { .. } => self.at_standard_normal(standard_normal(rng)),
+            Distribution::Uniform { min, max } if min == max => *min,
+            Distribution::Uniform { min, max } => rng.gen_range(*min..=*max),
+            Distribution::Buckets(buckets) => bucket_quantile(buckets, rng.gen::<f64>()),
+        }
+    }
+
+    /// The value at standard normal `z`, keeping this distribution as the
+    /// marginal (the copula step).
+    fn at_standard_normal(&self, z: f64) -> f32 {
+        match self {
+            Distribution::Fixed(value) => *value,
+            Distribution::Normal {
+                mean,
+                std_dev,
+                min,
+                max,
+            } => (*mean + *std_dev * z as f32).clamp(*min, *max),
+            Distribution::Uniform { min, max } => min + (max - min) * standard_normal_cdf(z) as f32,
+            Distribution::Buckets(buckets) => bucket_quantile(buckets, standard_normal_cdf(z)),
+        }
+    }
+}
+
+/// Inverse CDF of `Distribution::Buckets` at `u` in [0, 1].
+fn bucket_quantile(buckets: &[Bucket], u: f64) -> f32 {
+    let total: f64 = buckets.iter().map(|b| b.weight as f64).sum();
+    let mut target = u * total;
+    for bucket in buckets {
+        let weight = bucket.weight as f64;
+        if weight > 0.0 && target <= weight {
+            let t = (target / weight) as f32;
+            return bucket.min + (bucket.max - bucket.min) * t;
+        }
+        target -= weight;
+    }
+    // Rounding left `target` just past the last bucket.
+    buckets
+        .iter()
+        .rev()
+        .find(|b| b.weight > 0.0)
+        .map_or(0.0, |b| b.max)
+}
+
+/// A field of `AgentPopulationSpec` that `check` rejected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PopulationSpecError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for PopulationSpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl std::error::Error for PopulationSpecError {}
+
+/// Distributions for the attributes and beliefs of a region's agents.
+/// Every field defaults to a fixed mid value (age 35).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AgentPopulationSpec {
+    pub age: Distribution,
+    pub income_level: Distribution,
+    pub risk_tolerance: Distribution,
+    pub mobility_score: Distribution,
+    pub eco_values: Distribution,
+    pub influence: Distribution,
+    pub openness_to_change: Distribution,
+    pub trust_in_institutions: Distribution,
+    pub tech_skepticism: Distribution,
+    /// Given to every agent, for `AttributeRule::predicate_tag`.
+    pub tags: Vec<String>,
+    /// Gaussian-copula correlation (-1..1) between `income_level` and
+    /// `risk_tolerance`; 0 draws them independently.
+    pub income_risk_correlation: f32,
+}
+
+impl Default for AgentPopulationSpec {
+    fn default() -> Self {
+        let mid = Distribution::Fixed(0.5);
+        Self {
+            age: Distribution::Fixed(35.0),
+            income_level: mid.clone(),
+            risk_tolerance: mid.clone(),
+            mobility_score: mid.clone(),
+            eco_values: mid.clone(),
+            influence: mid.clone(),
+            openness_to_change: mid.clone(),
+            trust_in_institutions: mid.clone(),
+            tech_skepticism: mid,
+            tags: Vec::new(),
+            income_risk_correlation: 0.0,
+        }
+    }
+}
+
+impl AgentPopulationSpec {
+    pub fn check(&self) -> Result<(), PopulationSpecError> {
+        let fields = [
+            ("age", &self.age),
+            ("income_level", &self.income_level),
+            ("risk_tolerance", &self.risk_tolerance),
+            ("mobility_score", &self.mobility_score),
+            ("eco_values", &self.eco_values),
+            ("influence", &self.influence),
+            ("openness_to_change", &self.openness_to_change),
+            ("trust_in_institutions", &self.trust_in_institutions),
+            ("tech_skepticism", &self.tech_skepticism),
+        ];
+        for (field, dist) in fields {
+            dist.check()
+                .map_err(|message| PopulationSpecError { field, message })?;
+        }
+        if !(-1.0..=1.0).contains(&self.income_risk_correlation) {
+            return Err(PopulationSpecError {
+                field: "income_risk_correlation",
+                message: format!("{} is outside [-1, 1]", self.income_risk_correlation),
+            });
+        }
+        Ok(())
+    }
+
+    /// One agent in `region` with zero fatigue, fear and regret and no adoptions.
+    pub fn sample_agent(&self, id: AgentId, region: RegionId, rng: &mut impl Rng) -> Agent {
+        let age = self.age.sample(rng).round().clamp(0.0, u8::MAX as f32) as u8;
+        let (income_level, risk_tolerance) = if self.income_risk_correlation == 0.0 {
+            (
+                self.income_level.sample(rng),
+                self.risk_tolerance.sample(rng),
+            )
+        } else {
+            let rho = self.income_risk_correlation as f64;
+            let z_income = standard_normal(rng);
+            let z_risk = rho * z_income + (1.0 - rho * rho).sqrt() * standard_normal(rng);
+            (
+                self.income_level.at_standard_normal(z_income),
+                self.risk_tolerance.at_standard_normal(z_risk),
+            )
+        };
+        Agent {
+            id,
+            attrs: AgentAttributes {
+                age,
+                income_level,
+                risk_tolerance,
+                mobility_score: self.mobility_score.sample(rng),
+                eco_values: self.eco_values.sample(rng),
+                influence: self.influence.sample(rng),
+                tags: self.tags.clone(),
+            },
+            beliefs: AgentBeliefs {
+                openness_to_change: self.openness_to_change.sample(rng),
+                trust_in_institutions: self.trust_in_institutions.sample(rng),
+                tech_skepticism: self.tech_skepticism.sample(rng),
+            },
+            state: AgentState {
+                region,
+                adopted_concepts: Vec::new(),
+                fatigue: 0.0,
+                fear_level: 0.0,
+                regret: 0.0,
+                last_adoption: None,
+            },
+        }
+    }
+}
+
+/// `count` agents in `region` with ids `first_id, first_id + 1, ...`.
+/// `spec` must pass `check`.
+pub fn synthesize_agents(
+    spec: &AgentPopulationSpec,
+    region: RegionId,
+    first_id: AgentId,
+    count: u32,
+    rng: &mut impl Rng,
+) -> Vec<Agent> {
+    (0..count as AgentId)
+        .map(|i| spec.sample_agent(first_id + i, region, rng))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    const N: usize = 20_000;
+
+    /// Mean and standard deviation.
+    fn stats(values: &[f32]) -> (f32, f32) {
+        let n = values.len() as f32;
+        let mean = values.iter().sum::<f32>() / n;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / n;
+        (mean, variance.sqrt())
+    }
+
+    fn spec() -> AgentPopulationSpec {
+        AgentPopulationSpec {
+            age: Distribution::Buckets(vec![
+                Bucket {
+                    min: 18.0,
+                    max: 30.0,
+                    weight: 1.0,
+                },
+                Bucket {
+                    min: 60.0,
+                    max: 80.0,
+                    weight: 3.0,
+                },
+            ]),
+            income_level: Distribution::Normal {
+                mean: 0.5,
+                std_dev: 0.1,
+                min: 0.0,
+                max: 1.0,
+            },
+            risk_tolerance: Distribution::Uniform { min: 0.2, max: 0.6 },
+            mobility_score: Distribution::Normal {
+                mean: 0.3,
+                std_dev: 0.05,
+                min: f32::NEG_INFINITY,
+                max: f32::INFINITY,
+            },
+            income_risk_correlation: 0.8,
+            ..AgentPopulationSpec::default()
+        }
+    }
+
+    fn synthesize() -> Vec<Agent> {
+        let spec = spec();
+        spec.check().unwrap();
+        synthesize_agents(
+            &spec,
+            2,
+            100,
+            N as u32,
+            &mut rand::rngs::StdRng::seed_from_u64(3),
+        )
+    }
+
+    #[test]
+    fn agents_get_consecutive_ids_and_a_fresh_state() {
+        let agents = synthesize();
+        assert_eq!(agents.len(), N);
+        assert_eq!(agents[0].id, 100);
+        assert_eq!(agents[N - 1].id, 100 + N as AgentId - 1);
+        assert!(agents.iter().all(|a| a.state.region == 2
+            && a.state.fatigue == 0.0
+            && a.state.fear_level == 0.0
+            && a.state.adopted_concepts.is_empty()));
+    }
+
+    #[test]
+    fn marginals_match_their_distributions() {
+        let agents = synthesize();
+        let income: Vec<f32> = agents.iter().map(|a| a.attrs.income_level).collect();
+        let risk: Vec<f32> = agents.iter().map(|a| a.attrs.risk_tolerance).collect();
+        let mobility: Vec<f32> = agents.iter().map(|a| a.attrs.mobility_score).collect();
+
+        let (mean, sd) = stats(&income);
+        assert!(
+            (mean - 0.5).abs() < 0.01 && (sd - 0.1).abs() < 0.01,
+            "{mean} {sd}"
+        );
+        let (mean, sd) = stats(&mobility);
+        assert!(
+            (mean - 0.3).abs() < 0.005 && (sd - 0.05).abs() < 0.005,
+            "{mean} {sd}"
+        );
+        let (mean, sd) = stats(&risk);
+        assert!(
+            (mean - 0.4).abs() < 0.01 && (sd - 0.4 / 12f32.sqrt()).abs() < 0.01,
+            "{mean} {sd}"
+        );
+        assert!(risk.iter().all(|r| (0.2..=0.6).contains(r)));
+
+        let old = agents.iter().filter(|a| a.attrs.age >= 60).count() as f32 / N as f32;
+        assert!((old - 0.75).abs() < 0.02, "{old}");
+        assert!(agents
+            .iter()
+            .all(|a| (18..=30).contains(&a.attrs.age) || (60..=80).contains(&a.attrs.age)));
+    }
+
+    #[test]
+    fn income_and_risk_are_correlated() {
+        let agents = synthesize();
+        let income: Vec<f32> = agents.iter().map(|a| a.attrs.income_level).collect();
+        let risk: Vec<f32> = agents.iter().map(|a| a.attrs.risk_tolerance).collect();
+        let ((mean_income, sd_income), (mean_risk, sd_risk)) = (stats(&income), stats(&risk));
+        let covariance = income
+            .iter()
+            .zip(&risk)
+            .map(|(i, r)| (i - mean_income) * (r - mean_risk))
+            .sum::<f32>()
+            / N as f32;
+        let correlation = covariance / (sd_income * sd_risk);
+        assert!(correlation > 0.7 && correlation < 0.85, "{correlation}");
+    }
+
+    #[test]
+    fn specs_are_checked_and_distributions_parse_untagged() {
+        let bad = AgentPopulationSpec {
+            income_risk_correlation: 2.0,
+            ..AgentPopulationSpec::default()
+        };
+        assert_eq!(bad.check().unwrap_err().field, "income_risk_correlation");
+        let normal: Distribution = serde_json::from_str(r#"{"mean":1,"std_dev":2}"#).unwrap();
+        assert!(matches!(normal, Distribution::Normal { .. }));
+        let uniform: Distribution = serde_json::from_str(r#"{"min":1,"max":2}"#).unwrap();
+        assert!(matches!(uniform, Distribution::Uniform { .. }));
+    }
+}
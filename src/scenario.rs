@@ -0,0 +1,1026 @@
+//! Build a `Simulation` from a scenario document (TOML or JSON).
+//!
+//! Sections: `config` (max_ticks, seed, stop conditions, social graph model,
+//! soft prerequisite penalty, adoption model, fear aggregation and fear,
+//! share, mutation, fatigue, affordability, movement, trust and regret
+//! parameters), `policy`
+//! (ethical ceiling and rules), `regions`, `concepts` (with acyclic
+//! prerequisites), `exposures` (initial exposure of
+//! a concept in a region; concepts are only visible where exposed),
+//! `broadcasts` (budgeted per-tick exposure campaigns), `agents` (explicit
+//! agents) and `populations` (generated agents per region). Agent
+//! `attributes` and `fear_level` are `population::Distribution`s, drawn
+//! using the scenario seed.
+
+use crate::adoption::AdoptionModelSpec;
+use crate::analysis::AnalysisConfig;
+use crate::broadcast::{Broadcast, BroadcastSchedule};
+use crate::concept::{Concept, ConceptAttributes, ConceptLegalStatus, ConceptRiskProfile};
+use crate::core::agent::{
+    Affordability, BehaviorParams, FatigueDynamics, RegretDynamics, TrustDynamics,
+};
+use crate::core::id::{AgentId, ConceptId, RegionId, Tick};
+use crate::metrics::{AggregationMode, FearIndexMetrics};
+use crate::policy::{
+    AttributeRule, CeilingResponse, EnforcementMode, EthicalCeiling, ExposureWindowRule,
+    PolicyContext,
+};
+use crate::population::{AgentPopulationSpec, Distribution};
+use crate::sim::{
+    FearDynamics, MutationDynamics, ShareDynamics, Simulation, SimulationConfig, SimulationLog,
+    StopCondition,
+};
+use crate::social::{generate_social_graph, SocialGraphModel};
+use crate::world::{ExposureField, MovementModel, Region, World, WorldValidationError};
+use rand::SeedableRng;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScenarioError {
+    Io {
+        path: String,
+        message: String,
+    },
+    /// The document is not valid TOML/JSON or does not match the schema.
+    Parse {
+        format: &'static str,
+        message: String,
+    },
+    /// A value is well-formed but inconsistent, e.g. an unknown region id.
+    Invalid {
+        section: String,
+        key: &'static str,
+        message: String,
+    },
+    /// The assembled simulation fails `Simulation::validate`.
+    World(Vec<WorldValidationError>),
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Io { path, message } => write!(f, "{path}: {message}"),
+            ScenarioError::Parse { format, message } => write!(f, "scenario {format}: {message}"),
+            ScenarioError::Invalid {
+                section,
+                key,
+                message,
+            } => write!(f, "{section}.{key}: {message}"),
+            ScenarioError::World(errors) => {
+                let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+                write!(f, "invalid world: {}", messages.join("; "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+fn invalid(
+    section: impl Into<String>,
+    key: &'static str,
+    message: impl Into<String>,
+) -> ScenarioError {
+    ScenarioError::Invalid {
+        section: section.into(),
+        key,
+        message: message.into(),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigSection {
+    max_ticks: Tick,
+    #[serde(default)]
+    seed: u64,
+    #[serde(default)]
+    fear: FearDynamics,
+    #[serde(default)]
+    fear_aggregation: AggregationMode,
+    #[serde(default)]
+    share: ShareDynamics,
+    #[serde(default)]
+    mutation: MutationDynamics,
+    #[serde(default)]
+    adoption_model: AdoptionModelSpec,
+    #[serde(default)]
+    fatigue: FatigueDynamics,
+    #[serde(default)]
+    affordability: Affordability,
+    #[serde(default)]
+    movement: MovementModel,
+    #[serde(default)]
+    trust: TrustDynamics,
+    #[serde(default)]
+    regret: RegretDynamics,
+    #[serde(default = "StopCondition::defaults")]
+    stop_conditions: Vec<StopCondition>,
+    #[serde(default = "default_true")]
+    enforce_at_apply: bool,
+    #[serde(default)]
+    ceiling_warmup_ticks: Tick,
+    #[serde(default)]
+    track_inequality: bool,
+    #[serde(default)]
+    analysis: AnalysisConfig,
+    #[serde(default)]
+    funnel_exposure_threshold: f32,
+    /// Generate a social graph over all agents, seeded with `seed`.
+    social_graph: Option<SocialGraphModel>,
+    soft_prereq_penalty: Option<f32>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CeilingSpec {
+    max_fear_index: f32,
+    max_eco_damage: f32,
+    #[serde(default = "default_true")]
+    forbid_irreversible_bio: bool,
+    #[serde(default)]
+    max_irreversible_bio_risk: f32,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl From<&CeilingSpec> for EthicalCeiling {
+    fn from(spec: &CeilingSpec) -> Self {
+        EthicalCeiling {
+            max_fear_index: spec.max_fear_index,
+            max_eco_damage: spec.max_eco_damage,
+            forbid_irreversible_bio: spec.forbid_irreversible_bio,
+            max_irreversible_bio_risk: spec.max_irreversible_bio_risk,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RegionCeilingSpec {
+    region: RegionId,
+    ceiling: CeilingSpec,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RestrictedSpec {
+    concept: ConceptId,
+    regions: Vec<RegionId>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AttributeRuleSpec {
+    concept: ConceptId,
+    min_age: Option<u8>,
+    max_age: Option<u8>,
+    min_income: Option<f32>,
+    predicate_tag: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WindowSpec {
+    concept: Option<ConceptId>,
+    region: Option<RegionId>,
+    start: Tick,
+    end: Tick,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CapSpec {
+    concept: ConceptId,
+    region: RegionId,
+    /// Fraction of the region's residents.
+    cap: f32,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicySection {
+    ethical_ceiling: CeilingSpec,
+    #[serde(default)]
+    region_ceilings: Vec<RegionCeilingSpec>,
+    #[serde(default)]
+    restricted: Vec<RestrictedSpec>,
+    #[serde(default)]
+    exposure_windows: Vec<WindowSpec>,
+    #[serde(default)]
+    closed_regions: Vec<RegionId>,
+    #[serde(default)]
+    adoption_caps: Vec<CapSpec>,
+    #[serde(default)]
+    attribute_rules: Vec<AttributeRuleSpec>,
+    #[serde(default)]
+    ceiling_response: CeilingResponse,
+    #[serde(default)]
+    enforcement: EnforcementMode,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RegionSpec {
+    id: RegionId,
+    name: String,
+    population: u32,
+    #[serde(default)]
+    area_km2: f32,
+    #[serde(default)]
+    neighbors: Vec<RegionId>,
+    #[serde(default)]
+    neighbor_weights: Vec<f32>,
+    #[serde(default)]
+    eco_vulnerability: f32,
+    capacity: Option<u32>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum LegalStatusSpec {
+    Allowed,
+    Restricted,
+    Prohibited,
+}
+
+impl From<LegalStatusSpec> for ConceptLegalStatus {
+    fn from(spec: LegalStatusSpec) -> Self {
+        match spec {
+            LegalStatusSpec::Allowed => ConceptLegalStatus::Allowed,
+            LegalStatusSpec::Restricted => ConceptLegalStatus::Restricted,
+            LegalStatusSpec::Prohibited => ConceptLegalStatus::Prohibited,
+        }
+    }
+}
+
+fn default_legal_status() -> LegalStatusSpec {
+    LegalStatusSpec::Allowed
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConceptSpec {
+    id: ConceptId,
+    name: String,
+    attractiveness: f32,
+    #[serde(default)]
+    controversy: f32,
+    #[serde(default)]
+    resource_cost: f32,
+    #[serde(default)]
+    virality: f32,
+    #[serde(default)]
+    expected_fear: f32,
+    #[serde(default)]
+    eco_harm_score: f32,
+    #[serde(default)]
+    data_abuse_risk: f32,
+    #[serde(default)]
+    irreversible_bio_risk: f32,
+    #[serde(default = "default_legal_status")]
+    legal_status: LegalStatusSpec,
+    #[serde(default)]
+    prerequisites: Vec<ConceptId>,
+    #[serde(default)]
+    available_from: Tick,
+    sunset_at: Option<Tick>,
+    #[serde(default)]
+    sunset_abandon_rate: f32,
+}
+
+fn default_fear_level() -> Distribution {
+    Distribution::Fixed(0.0)
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AgentSpec {
+    id: AgentId,
+    region: RegionId,
+    #[serde(default)]
+    adopted: Vec<ConceptId>,
+    #[serde(default)]
+    attributes: AgentPopulationSpec,
+    #[serde(default = "default_fear_level")]
+    fear_level: Distribution,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PopulationSpec {
+    region: RegionId,
+    count: u32,
+    #[serde(default)]
+    attributes: AgentPopulationSpec,
+    #[serde(default = "default_fear_level")]
+    fear_level: Distribution,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExposureSpec {
+    region: RegionId,
+    concept: ConceptId,
+    intensity: f32,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BroadcastSpec {
+    concept: ConceptId,
+    /// Omitted broadcasts to every region.
+    regions: Option<Vec<RegionId>>,
+    start: Tick,
+    end: Tick,
+    intensity_per_tick: f32,
+    budget: f32,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScenarioDocument {
+    config: ConfigSection,
+    policy: PolicySection,
+    regions: Vec<RegionSpec>,
+    #[serde(default)]
+    concepts: Vec<ConceptSpec>,
+    #[serde(default)]
+    exposures: Vec<ExposureSpec>,
+    #[serde(default)]
+    broadcasts: Vec<BroadcastSpec>,
+    #[serde(default)]
+    agents: Vec<AgentSpec>,
+    #[serde(default)]
+    populations: Vec<PopulationSpec>,
+}
+
+fn check_unit(section: &str, key: &'static str, value: f32) -> Result<(), ScenarioError> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(())
+    } else {
+        Err(invalid(section, key, format!("{value} is outside 0..1")))
+    }
+}
+
+fn check_region(
+    regions: &HashSet<RegionId>,
+    section: &str,
+    key: &'static str,
+    region: RegionId,
+) -> Result<(), ScenarioError> {
+    if regions.contains(&region) {
+        Ok(())
+    } else {
+        Err(invalid(section, key, format!("unknown region {region}")))
+    }
+}
+
+fn check_attributes(
+    section: &str,
+    attributes: &AgentPopulationSpec,
+    fear_level: &Distribution,
+) -> Result<(), ScenarioError> {
+    attributes
+        .check()
+        .map_err(|e| invalid(format!("{section}.attributes"), e.field, e.message))?;
+    fear_level
+        .check()
+        .map_err(|message| invalid(section, "fear_level", message))
+}
+
+/// A prerequisite cycle, as the concepts along it with the first repeated
+/// at the end; searched in concept id order.
+fn prerequisite_cycle(concepts: &BTreeMap<ConceptId, Concept>) -> Option<Vec<ConceptId>> {
+    fn visit(
+        id: ConceptId,
+        concepts: &BTreeMap<ConceptId, Concept>,
+        done: &mut HashSet<ConceptId>,
+        path: &mut Vec<ConceptId>,
+    ) -> Option<Vec<ConceptId>> {
+        if let Some(start) = path.iter().position(|p| *p == id) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(id);
+            return Some(cycle);
+        }
+        if done.contains(&id) {
+            return None;
+        }
+        path.push(id);
+        for prerequisite in concepts.get(&id).map_or(&[][..], |c| &c.prerequisites) {
+            if let Some(cycle) = visit(*prerequisite, concepts, done, path) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        done.insert(id);
+        None
+    }
+
+    let mut done = HashSet::new();
+    concepts
+        .keys()
+        .find_map(|id| visit(*id, concepts, &mut done, &mut Vec::new()))
+}
+
+fn check_concept(
+    concepts: &BTreeMap<ConceptId, Concept>,
+    section: &str,
+    key: &'static str,
+    concept: ConceptId,
+) -> Result<(), ScenarioError> {
+    if concepts.contains_key(&concept) {
+        Ok(())
+    } else {
+        Err(invalid(section, key, format!("unknown concept {concept}")))
+    }
+}
+
+impl Simulation {
+    /// Load a scenario file; `.json` files are parsed as JSON, anything else
+    /// as TOML.
+    pub fn from_scenario_file(path: impl AsRef<Path>) -> Result<Simulation, ScenarioError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|e| ScenarioError::Io {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        if path.extension().is_some_and(|ext| ext == "json") {
+            Self::from_scenario_json(&text)
+        } else {
+            Self::from_scenario_toml(&text)
+        }
+    }
+
+    /// Parse a scenario document; one starting with `{` is read as JSON,
+    /// anything else as TOML.
+    pub fn from_scenario_str(text: &str) -> Result<Simulation, ScenarioError> {
+        if text.trim_start().starts_with('{') {
+            Self::from_scenario_json(text)
+        } else {
+            Self::from_scenario_toml(text)
+        }
+    }
+
+    fn from_scenario_json(text: &str) -> Result<Simulation, ScenarioError> {
+        let doc = serde_json::from_str(text).map_err(|e| ScenarioError::Parse {
+            format: "JSON",
+            message: e.to_string(),
+        })?;
+        Self::from_scenario(doc)
+    }
+
+    fn from_scenario_toml(text: &str) -> Result<Simulation, ScenarioError> {
+        let doc = toml::from_str(text).map_err(|e| ScenarioError::Parse {
+            format: "TOML",
+            message: e.to_string(),
+        })?;
+        Self::from_scenario(doc)
+    }
+
+    fn from_scenario(doc: ScenarioDocument) -> Result<Simulation, ScenarioError> {
+        if let AggregationMode::Percentile(p) = doc.config.fear_aggregation {
+            if !(0.0..=100.0).contains(&p) {
+                return Err(invalid(
+                    "config",
+                    "fear_aggregation",
+                    format!("percentile {p} is outside 0..100"),
+                ));
+            }
+        }
+
+        check_unit("config.mutation", "rate", doc.config.mutation.rate)?;
+
+        // Regions
+        let mut regions = BTreeMap::new();
+        for (i, spec) in doc.regions.iter().enumerate() {
+            let section = format!("regions[{i}]");
+            check_unit(&section, "eco_vulnerability", spec.eco_vulnerability)?;
+            if !spec.neighbor_weights.is_empty()
+                && spec.neighbor_weights.len() != spec.neighbors.len()
+            {
+                return Err(invalid(
+                    section,
+                    "neighbor_weights",
+                    format!(
+                        "{} weights for {} neighbors",
+                        spec.neighbor_weights.len(),
+                        spec.neighbors.len()
+                    ),
+                ));
+            }
+            if let Some(w) = spec.neighbor_weights.iter().find(|w| **w < 0.0) {
+                return Err(invalid(
+                    section,
+                    "neighbor_weights",
+                    format!("{w} is negative"),
+                ));
+            }
+            let region = Region {
+                id: spec.id,
+                name: spec.name.clone(),
+                population: spec.population,
+                area_km2: spec.area_km2,
+                neighbors: spec.neighbors.clone(),
+                neighbor_weights: spec.neighbor_weights.clone(),
+                capacity: spec.capacity,
+                eco_vulnerability: spec.eco_vulnerability,
+            };
+            if regions.insert(spec.id, region).is_some() {
+                return Err(invalid(
+                    section,
+                    "id",
+                    format!("duplicate region {}", spec.id),
+                ));
+            }
+        }
+        let region_ids: HashSet<RegionId> = regions.keys().copied().collect();
+        for (i, spec) in doc.regions.iter().enumerate() {
+            for neighbor in &spec.neighbors {
+                check_region(
+                    &region_ids,
+                    &format!("regions[{i}]"),
+                    "neighbors",
+                    *neighbor,
+                )?;
+            }
+        }
+
+        // Concepts
+        let mut concepts = BTreeMap::new();
+        for (i, spec) in doc.concepts.iter().enumerate() {
+            let section = format!("concepts[{i}]");
+            check_unit(&section, "virality", spec.virality)?;
+            check_unit(&section, "expected_fear", spec.expected_fear)?;
+            check_unit(&section, "eco_harm_score", spec.eco_harm_score)?;
+            check_unit(&section, "data_abuse_risk", spec.data_abuse_risk)?;
+            check_unit(
+                &section,
+                "irreversible_bio_risk",
+                spec.irreversible_bio_risk,
+            )?;
+            check_unit(&section, "sunset_abandon_rate", spec.sunset_abandon_rate)?;
+            if let Some(sunset_at) = spec.sunset_at {
+                if sunset_at < spec.available_from {
+                    return Err(invalid(
+                        section,
+                        "sunset_at",
+                        format!(
+                            "sunset_at {sunset_at} is before available_from {}",
+                            spec.available_from
+                        ),
+                    ));
+                }
+            }
+            let concept = Concept {
+                id: spec.id,
+                attrs: ConceptAttributes {
+                    name: spec.name.clone(),
+                    attractiveness: spec.attractiveness,
+                    controversy: spec.controversy,
+                    resource_cost: spec.resource_cost,
+                    virality: spec.virality,
+                },
+                risk_profile: ConceptRiskProfile {
+                    expected_fear: spec.expected_fear,
+                    eco_harm_score: spec.eco_harm_score,
+                    data_abuse_risk: spec.data_abuse_risk,
+                    irreversible_bio_risk: spec.irreversible_bio_risk,
+                },
+                legal_status: spec.legal_status.into(),
+                prerequisites: spec.prerequisites.clone(),
+                available_from: spec.available_from,
+                sunset_at: spec.sunset_at,
+                sunset_abandon_rate: spec.sunset_abandon_rate,
+                parent: None,
+            };
+            if concepts.insert(spec.id, concept).is_some() {
+                return Err(invalid(
+                    section,
+                    "id",
+                    format!("duplicate concept {}", spec.id),
+                ));
+            }
+        }
+
+        for (i, spec) in doc.concepts.iter().enumerate() {
+            for prerequisite in &spec.prerequisites {
+                check_concept(
+                    &concepts,
+                    &format!("concepts[{i}]"),
+                    "prerequisites",
+                    *prerequisite,
+                )?;
+            }
+        }
+        if let Some(cycle) = prerequisite_cycle(&concepts) {
+            let names: Vec<&str> = cycle
+                .iter()
+                .map(|id| concepts[id].attrs.name.as_str())
+                .collect();
+            return Err(invalid(
+                "concepts",
+                "prerequisites",
+                format!("dependency cycle {}", names.join(" -> ")),
+            ));
+        }
+
+        // Exposures
+        let mut exposure_field = ExposureField::default();
+        for (i, spec) in doc.exposures.iter().enumerate() {
+            let section = format!("exposures[{i}]");
+            check_region(&region_ids, &section, "region", spec.region)?;
+            check_concept(&concepts, &section, "concept", spec.concept)?;
+            if spec.intensity < 0.0 {
+                return Err(invalid(
+                    section,
+                    "intensity",
+                    format!("{} is negative", spec.intensity),
+                ));
+            }
+            exposure_field.set(spec.region, spec.concept, spec.intensity);
+        }
+
+        // Broadcasts
+        let mut broadcasts = BroadcastSchedule::default();
+        for (i, spec) in doc.broadcasts.iter().enumerate() {
+            let section = format!("broadcasts[{i}]");
+            check_concept(&concepts, &section, "concept", spec.concept)?;
+            for region in spec.regions.iter().flatten() {
+                check_region(&region_ids, &section, "regions", *region)?;
+            }
+            if spec.start > spec.end {
+                return Err(invalid(
+                    section,
+                    "start",
+                    format!("start {} is after end {}", spec.start, spec.end),
+                ));
+            }
+            if spec.intensity_per_tick < 0.0 {
+                return Err(invalid(
+                    section,
+                    "intensity_per_tick",
+                    format!("{} is negative", spec.intensity_per_tick),
+                ));
+            }
+            if spec.budget < 0.0 {
+                return Err(invalid(
+                    section,
+                    "budget",
+                    format!("{} is negative", spec.budget),
+                ));
+            }
+            broadcasts.entries.push(Broadcast {
+                concept_id: spec.concept,
+                regions: spec.regions.clone(),
+                start_tick: spec.start,
+                end_tick: spec.end,
+                intensity_per_tick: spec.intensity_per_tick,
+                budget: spec.budget,
+            });
+        }
+
+        // Policy
+        let policy_spec = &doc.policy;
+        let mut region_ceilings = BTreeMap::new();
+        for (i, spec) in policy_spec.region_ceilings.iter().enumerate() {
+            let section = format!("policy.region_ceilings[{i}]");
+            check_region(&region_ids, &section, "region", spec.region)?;
+            region_ceilings.insert(spec.region, EthicalCeiling::from(&spec.ceiling));
+        }
+        let mut restricted_allow_regions = BTreeMap::new();
+        for (i, spec) in policy_spec.restricted.iter().enumerate() {
+            let section = format!("policy.restricted[{i}]");
+            check_concept(&concepts, &section, "concept", spec.concept)?;
+            for region in &spec.regions {
+                check_region(&region_ids, &section, "regions", *region)?;
+            }
+            restricted_allow_regions.insert(spec.concept, spec.regions.clone());
+        }
+        let mut exposure_windows = Vec::new();
+        for (i, spec) in policy_spec.exposure_windows.iter().enumerate() {
+            let section = format!("policy.exposure_windows[{i}]");
+            if let Some(concept) = spec.concept {
+                check_concept(&concepts, &section, "concept", concept)?;
+            }
+            if let Some(region) = spec.region {
+                check_region(&region_ids, &section, "region", region)?;
+            }
+            if spec.start > spec.end {
+                return Err(invalid(
+                    section,
+                    "start",
+                    format!("start {} is after end {}", spec.start, spec.end),
+                ));
+            }
+            exposure_windows.push(ExposureWindowRule {
+                concept_id: spec.concept,
+                region: spec.region,
+                allowed_ticks: spec.start..spec.end,
+            });
+        }
+        for region in &policy_spec.closed_regions {
+            check_region(&region_ids, "policy", "closed_regions", *region)?;
+        }
+        let mut adoption_caps = HashMap::new();
+        for (i, spec) in policy_spec.adoption_caps.iter().enumerate() {
+            let section = format!("policy.adoption_caps[{i}]");
+            check_concept(&concepts, &section, "concept", spec.concept)?;
+            check_region(&region_ids, &section, "region", spec.region)?;
+            check_unit(&section, "cap", spec.cap)?;
+            adoption_caps.insert((spec.concept, spec.region), spec.cap);
+        }
+        let mut attribute_rules = Vec::new();
+        for (i, spec) in policy_spec.attribute_rules.iter().enumerate() {
+            let section = format!("policy.attribute_rules[{i}]");
+            check_concept(&concepts, &section, "concept", spec.concept)?;
+            if let (Some(min), Some(max)) = (spec.min_age, spec.max_age) {
+                if min > max {
+                    return Err(invalid(
+                        section,
+                        "min_age",
+                        format!("min_age {min} is above max_age {max}"),
+                    ));
+                }
+            }
+            attribute_rules.push(AttributeRule {
+                concept_id: spec.concept,
+                min_age: spec.min_age,
+                max_age: spec.max_age,
+                min_income: spec.min_income,
+                predicate_tag: spec.predicate_tag.clone(),
+            });
+        }
+        if let CeilingResponse::Throttle {
+            exposure_multiplier,
+            recovery_margin,
+        } = policy_spec.ceiling_response
+        {
+            let section = "policy.ceiling_response";
+            check_unit(section, "exposure_multiplier", exposure_multiplier)?;
+            check_unit(section, "recovery_margin", recovery_margin)?;
+        }
+        let policy = PolicyContext {
+            ethical_ceiling: EthicalCeiling::from(&policy_spec.ethical_ceiling),
+            region_ceilings,
+            restricted_allow_regions,
+            exposure_windows,
+            closed_regions: policy_spec.closed_regions.iter().copied().collect(),
+            adoption_caps,
+            attribute_rules,
+            ceiling_response: policy_spec.ceiling_response,
+            enforcement: policy_spec.enforcement,
+            global_fear: 0.0,
+            throttled: false,
+        };
+
+        // Agents: explicit ones first, then populations with fresh ids.
+        let mut rng = rand::rngs::StdRng::seed_from_u64(doc.config.seed);
+        let mut agents = Vec::new();
+        let mut agent_ids = HashSet::new();
+        for (i, spec) in doc.agents.iter().enumerate() {
+            let section = format!("agents[{i}]");
+            check_region(&region_ids, &section, "region", spec.region)?;
+            for concept in &spec.adopted {
+                check_concept(&concepts, &section, "adopted", *concept)?;
+            }
+            check_attributes(&section, &spec.attributes, &spec.fear_level)?;
+            if !agent_ids.insert(spec.id) {
+                return Err(invalid(
+                    section,
+                    "id",
+                    format!("duplicate agent {}", spec.id),
+                ));
+            }
+            let mut agent = spec.attributes.sample_agent(spec.id, spec.region, &mut rng);
+            agent.state.adopted_concepts = spec.adopted.clone();
+            agent.state.fear_level = spec.fear_level.sample(&mut rng);
+            agents.push(agent);
+        }
+        let mut next_id = agent_ids.iter().max().map_or(0, |id| id + 1);
+        for (i, spec) in doc.populations.iter().enumerate() {
+            let section = format!("populations[{i}]");
+            check_region(&region_ids, &section, "region", spec.region)?;
+            check_attributes(&section, &spec.attributes, &spec.fear_level)?;
+            for _ in 0..spec.count {
+                let mut agent = spec.attributes.sample_agent(next_id, spec.region, &mut rng);
+                agent.state.fear_level = spec.fear_level.sample(&mut rng);
+                agents.push(agent);
+                next_id += 1;
+            }
+        }
+        let social_graph = doc.config.social_graph.map(|model| {
+            let ids: Vec<AgentId> = agents.iter().map(|agent| agent.id).collect();
+            generate_social_graph(&ids, model, doc.config.seed)
+        });
+
+        let sim = Simulation {
+            world: World {
+                regions,
+                concepts,
+                exposure_field,
+                regional_fear: BTreeMap::new(),
+            },
+            agents,
+            policy,
+            config: SimulationConfig {
+                max_ticks: doc.config.max_ticks,
+                random_seed: doc.config.seed,
+                fear: doc.config.fear,
+                fear_aggregation: doc.config.fear_aggregation,
+                share: doc.config.share,
+                mutation: doc.config.mutation,
+                behavior: BehaviorParams {
+                    fatigue: doc.config.fatigue,
+                    affordability: doc.config.affordability,
+                    movement: doc.config.movement,
+                    soft_prereq_penalty: doc.config.soft_prereq_penalty,
+                    trust: doc.config.trust,
+                    regret: doc.config.regret,
+                },
+                stop_conditions: doc.config.stop_conditions,
+                enforce_at_apply: doc.config.enforce_at_apply,
+                ceiling_warmup_ticks: doc.config.ceiling_warmup_ticks,
+                track_inequality: doc.config.track_inequality,
+                analysis: doc.config.analysis,
+                funnel_exposure_threshold: doc.config.funnel_exposure_threshold,
+            },
+            log: SimulationLog::default(),
+            fear_metrics: FearIndexMetrics::default(),
+            social_graph,
+            social_exposure: BTreeMap::new(),
+            broadcasts,
+            interventions: BTreeMap::new(),
+            adoption_model: doc.config.adoption_model.build(),
+            region_index: HashMap::new(),
+            progress: None,
+        };
+        sim.validate().map_err(ScenarioError::World)?;
+        Ok(sim)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adoption::LogisticRegressionModel;
+
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/scenario.toml");
+
+    #[test]
+    fn fixture_runs_to_golden_adoption_counts() {
+        let mut sim = Simulation::from_scenario_file(FIXTURE).unwrap();
+        assert_eq!(sim.agents.len(), 41);
+        let result = sim.run();
+        assert_eq!(result.ticks_executed, 10);
+        let finals: BTreeMap<ConceptId, u32> = result
+            .adoption_curves
+            .iter()
+            .map(|(id, curve)| (*id, *curve.last().unwrap()))
+            .collect();
+        assert_eq!(finals, BTreeMap::from([(1, 40), (2, 21)]));
+    }
+
+    #[test]
+    fn errors_name_the_section_and_key() {
+        let fixture = std::fs::read_to_string(FIXTURE).unwrap();
+        let unknown_neighbor = fixture.replace("neighbors = [0]", "neighbors = [7]");
+        let err = Simulation::from_scenario_str(&unknown_neighbor)
+            .err()
+            .unwrap();
+        assert_eq!(err.to_string(), "regions[1].neighbors: unknown region 7");
+
+        let misspelled = fixture.replace("population = 500", "populaton = 500");
+        let err = Simulation::from_scenario_str(&misspelled).err().unwrap();
+        assert!(
+            matches!(&err, ScenarioError::Parse { format: "TOML", .. }),
+            "{err}"
+        );
+        assert!(err.to_string().contains("populaton"), "{err}");
+    }
+
+    const PREREQUISITE_CYCLE: &str = r#"
+[config]
+max_ticks = 5
+
+[policy]
+ethical_ceiling = { max_fear_index = 0.9, max_eco_damage = 0.9 }
+
+[[regions]]
+id = 0
+name = "a"
+population = 10
+
+[[concepts]]
+id = 1
+name = "rooftop-solar"
+attractiveness = 0.5
+prerequisites = [2]
+
+[[concepts]]
+id = 2
+name = "rooftop-solar-sharing"
+attractiveness = 0.5
+prerequisites = [3]
+
+[[concepts]]
+id = 3
+name = "grid"
+attractiveness = 0.5
+prerequisites = [1]
+"#;
+
+    #[test]
+    fn prerequisite_cycles_are_named() {
+        let err = Simulation::from_scenario_str(PREREQUISITE_CYCLE).unwrap_err();
+        assert_eq!(err.to_string(), "concepts.prerequisites: dependency cycle rooftop-solar -> rooftop-solar-sharing -> grid -> rooftop-solar");
+
+        let unknown = PREREQUISITE_CYCLE.replace("prerequisites = [1]", "prerequisites = [9]");
+        let err = Simulation::from_scenario_str(&unknown).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ScenarioError::Invalid {
+                    key: "prerequisites",
+                    ..
+                }
+            ),
+            "{err}"
+        );
+
+        let acyclic = PREREQUISITE_CYCLE.replace("prerequisites = [1]", "");
+        assert!(Simulation::from_scenario_str(&acyclic).is_ok());
+    }
+
+    #[test]
+    fn invalid_worlds_are_rejected_as_a_whole() {
+        let doc = r#"
+[config]
+max_ticks = 5
+
+[policy]
+ethical_ceiling = { max_fear_index = 0.9, max_eco_damage = 0.9 }
+
+[[regions]]
+id = 0
+name = "a"
+population = 0
+
+[[populations]]
+region = 0
+count = 2
+"#;
+        let err = Simulation::from_scenario_str(doc).unwrap_err();
+        assert!(
+            matches!(err, ScenarioError::World(ref errors) if errors.len() == 1),
+            "{err}"
+        );
+        assert!(
+            Simulation::from_scenario_str(&doc.replace("population = 0", "population = 5")).is_ok()
+        );
+    }
+
+    #[test]
+    fn adoption_models_are_named_in_the_config() {
+        let doc = r#"
+[config]
+max_ticks = 5
+adoption_model = { type = "logistic", weights = [1.0, 2.0] }
+
+[policy]
+ethical_ceiling = { max_fear_index = 0.9, max_eco_damage = 0.9 }
+
+[[regions]]
+id = 0
+name = "a"
+population = 10
+"#;
+        let logistic = Simulation::from_scenario_str(doc).unwrap();
+        assert_eq!(
+            logistic.adoption_model.spec(),
+            Some(AdoptionModelSpec::Logistic(LogisticRegressionModel {
+                weights: vec![1.0, 2.0]
+            }))
+        );
+        let model = r#"{ type = "logistic", weights = [1.0, 2.0] }"#;
+        let default = Simulation::from_scenario_str(
+            &doc.replace(model, r#"{ type = "default", openness = 0.7 }"#),
+        )
+        .unwrap();
+        let Some(AdoptionModelSpec::Default(coefficients)) = default.adoption_model.spec() else {
+            panic!("{:?}", default.adoption_model);
+        };
+        assert_eq!(coefficients.openness, 0.7);
+
+        assert!(Simulation::from_scenario_str(&doc.replace("weights", "wieghts")).is_err());
+        assert!(Simulation::from_scenario_str(
+            &doc.replace(model, r#"{ type = "default", opennes = 0.7 }"#)
+        )
+        .is_err());
+    }
+}
@@ -1,27 +1,639 @@
-use crate::core::agent::{Agent, AgentAction};
-use crate::core::id::Tick;
-use crate::metrics::FearIndexMetrics;
-use crate::policy::PolicyContext;
-use crate::world::World;
-use rand::SeedableRng;
-
-#[derive(Debug)]
+use crate::analysis::AnalysisConfig;
+use crate::broadcast::BroadcastSchedule;
+use crate::adoption::AdoptionModel;
+use crate::core::agent::{
+    agent_rng, Agent, AgentAction, AgentRng, BehaviorParams, StepOutput, StepRules,
+};
+use crate::core::id::{AgentId, ConceptId, RegionId, Tick};
+use crate::intervention::Intervention;
+use crate::metrics::{
+    peak_tick, AggregationMode, CeilingBreach, CeilingMargin, CeilingMetric, FearDriver,
+    FearIndexMetrics, FunnelCounter,
+};
+use crate::observer::SimObserver;
+use crate::policy::{CeilingResponse, DenialRule, EnforcementMode, PolicyContext, PolicyDenial};
+use crate::snapshot::RegionSnapshot;
+use crate::social::{generate_social_graph, SocialGraph, SocialGraphModel, SocialView};
+use crate::world::{World, WorldValidationError, WorldView};
+use rand::Rng;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::{self, Read, Write};
+
+/// Exposure removed from the abandoning agent's region per abandonment.
+const ABANDON_EXPOSURE_DROP: f32 = 0.05;
+
+/// Bumped whenever the checkpoint layout changes; `Simulation::resume`
+/// rejects other versions.
+pub const CHECKPOINT_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationConfig {
     pub max_ticks: Tick,
     pub random_seed: u64,
+    pub fear: FearDynamics,
+    /// How agents' fear reduces to their region's, and a region's fear over
+    /// ticks to the figure checked against ceilings.
+    #[serde(default)]
+    pub fear_aggregation: AggregationMode,
+    #[serde(default)]
+    pub share: ShareDynamics,
+    #[serde(default)]
+    pub mutation: MutationDynamics,
+    pub behavior: BehaviorParams,
+    /// Checked in order after each tick's metrics update; the first that
+    /// holds ends the run. See `StopCondition::defaults`.
+    pub stop_conditions: Vec<StopCondition>,
+    /// Re-check every Adopt and Share against exposure policy when it is
+    /// applied, not only when `Agent::step` proposes it.
+    #[serde(default = "default_true")]
+    pub enforce_at_apply: bool,
+    /// Ticks at the start of a run during which ethical ceilings are checked
+    /// but do not stop it; once over, only later ticks count against them.
+    #[serde(default)]
+    pub ceiling_warmup_ticks: Tick,
+    /// Record each concept's adoption inequality across regions every tick
+    /// (`FearIndexMetrics::inequality_series`).
+    #[serde(default)]
+    pub track_inequality: bool,
+    /// How `SimulationResult::tipping_points` and `steady_state_ticks` are
+    /// found.
+    #[serde(default)]
+    pub analysis: AnalysisConfig,
+    /// Exposure intensity at which a concept in an agent's view counts as
+    /// seen in `FearIndexMetrics::funnel`; 0 counts every concept in view.
+    #[serde(default)]
+    pub funnel_exposure_threshold: f32,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// When `Simulation::run` ends before (or at) `max_ticks`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum StopCondition {
+    /// `max_ticks` reached; always in effect, listed for reporting.
+    MaxTicks,
+    /// A global or regional ethical ceiling was breached.
+    EthicalCeiling,
+    /// At least `share` (0..1) of all agents have adopted `concept`.
+    AdoptionSaturated { concept: ConceptId, share: f32 },
+    /// Over the last `window` ticks, global fear and every concept's adopter
+    /// count varied by less than `epsilon`.
+    SteadyState { window: usize, epsilon: f32 },
+    /// No agent acted for `ticks` consecutive ticks.
+    NoActions { ticks: u64 },
+    /// A `SimObserver` asked to stop; never fires from `stop_conditions`.
+    Observer,
+}
+
+impl StopCondition {
+    /// `MaxTicks` and `EthicalCeiling`: the behaviour before stop conditions
+    /// were configurable.
+    pub fn defaults() -> Vec<StopCondition> {
+        vec![StopCondition::MaxTicks, StopCondition::EthicalCeiling]
+    }
+}
+
+impl fmt::Display for StopCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StopCondition::MaxTicks => f.write_str("max ticks reached"),
+            StopCondition::EthicalCeiling => f.write_str("ethical ceiling violated"),
+            StopCondition::AdoptionSaturated { concept, share } => {
+                write!(f, "concept {concept} reached {:.1}% adoption", share * 100.0)
+            }
+            StopCondition::SteadyState { window, epsilon } => {
+                write!(f, "steady state over {window} ticks (epsilon {epsilon})")
+            }
+            StopCondition::NoActions { ticks } => write!(f, "no actions for {ticks} ticks"),
+            StopCondition::Observer => f.write_str("stopped by observer"),
+        }
+    }
+}
+
+/// Per-tick update of `AgentState::fear_level`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FearDynamics {
+    /// Fear added per unit of `expected_fear` of each concept adopted.
+    pub adoption_gain: f32,
+    /// Level fear relaxes toward.
+    pub baseline: f32,
+    /// Ticks for the distance to `baseline` to halve; 0 resets immediately.
+    pub half_life_ticks: f32,
+    /// Fraction of the gap to the mean fear of the other agents in the same
+    /// region closed each tick.
+    pub contagion: f32,
+    /// Share of neighboring regions' fear (population-weighted mean) added
+    /// to a region's `World::regional_fear`, and fraction of the gap up to
+    /// it closed by each agent below it per tick. 0 keeps fear local.
+    pub regional_contagion: f32,
+}
+
+impl Default for FearDynamics {
+    fn default() -> Self {
+        Self {
+            adoption_gain: 0.5,
+            baseline: 0.0,
+            half_life_ticks: 10.0,
+            contagion: 0.1,
+            regional_contagion: 0.0,
+        }
+    }
+}
+
+/// Exposure added by a Share: `base_gain × influence` of the sharer in its
+/// region, to each social neighbor per unit of edge weight, and times
+/// virality in neighboring regions.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShareDynamics {
+    pub base_gain: f32,
+    /// Most exposure shares may add to one concept in one region per tick.
+    pub max_gain_per_tick: f32,
+}
+
+impl Default for ShareDynamics {
+    fn default() -> Self {
+        Self {
+            base_gain: 0.2,
+            max_gain_per_tick: 1.0,
+        }
+    }
+}
+
+/// Chance that an applied Share spreads a new variant of its concept
+/// instead: a copy with a fresh id, the same legal status (and
+/// `restricted_allow_regions`) and `Concept::parent` set, whose
+/// attractiveness, controversy and risk values are each moved by up to
+/// `spread` either way and clamped to 0..1.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MutationDynamics {
+    /// Probability (0..1) per Share; 0 never mutates.
+    pub rate: f32,
+    pub spread: f32,
+    /// Most variants a run may spawn; later shares spread their concept
+    /// unchanged.
+    pub max_variants: u32,
+}
+
+impl Default for MutationDynamics {
+    fn default() -> Self {
+        Self {
+            rate: 0.0,
+            spread: 0.1,
+            max_variants: 100,
+        }
+    }
+}
+
+/// Stream of `RunTally::mutation_rng`, apart from every agent's.
+const MUTATION_STREAM: AgentId = AgentId::MAX;
+
+/// One logged decision. `Display` gives the human-readable log line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum SimEvent {
+    Moved {
+        agent: AgentId,
+        from: RegionId,
+        to: RegionId,
+    },
+    /// A Move was refused; the agent stays in `from`.
+    MoveRejected {
+        agent: AgentId,
+        from: RegionId,
+        to: RegionId,
+        reason: MoveRejection,
+    },
+    Adopted {
+        agent: AgentId,
+        concept: ConceptId,
+    },
+    Shared {
+        agent: AgentId,
+        concept: ConceptId,
+        region: RegionId,
+    },
+    Abandoned {
+        agent: AgentId,
+        concept: ConceptId,
+    },
+    /// A Share of `parent` spawned `concept`, which was spread instead (see
+    /// `MutationDynamics`); the `Shared` event that follows names `parent`.
+    Mutated {
+        agent: AgentId,
+        parent: ConceptId,
+        concept: ConceptId,
+    },
+    /// An Adopt was refused: `region` already has `cap` adopters of
+    /// `concept` (see `PolicyContext::adoption_caps`).
+    CapRejected {
+        agent: AgentId,
+        concept: ConceptId,
+        region: RegionId,
+        cap: u32,
+    },
+    /// An action was dropped by a policy hard stop.
+    PolicyBlocked {
+        agent: AgentId,
+        concept: ConceptId,
+        region: RegionId,
+        reason: BlockReason,
+    },
+    /// `PolicyContext::ceiling_response` started (`engaged`) or stopped
+    /// throttling exposure.
+    Throttled { engaged: bool },
+    /// An `Intervention` was applied at the start of the tick.
+    Intervened { intervention: Intervention },
+    /// The run ended because `condition` held (ceiling breaches are logged
+    /// as `CeilingViolated` instead).
+    Stopped { condition: StopCondition },
+    /// The run stopped early because `metric` exceeded `ceiling`; the
+    /// fields mirror `CeilingBreach`.
+    CeilingViolated {
+        metric: CeilingMetric,
+        value: f32,
+        ceiling: f32,
+        #[serde(default)]
+        peak_tick: Option<Tick>,
+        #[serde(default)]
+        worst_region: Option<RegionId>,
+        #[serde(default)]
+        top_concept: Option<ConceptId>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MoveRejection {
+    /// Destination is in `PolicyContext::closed_regions`.
+    Closed,
+    /// Destination already holds `Region::capacity` agents.
+    AtCapacity { capacity: u32 },
+}
+
+impl fmt::Display for MoveRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveRejection::Closed => f.write_str("region closed"),
+            MoveRejection::AtCapacity { capacity } => write!(f, "region at capacity {capacity}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlockReason {
+    IrreversibleBioRisk,
+}
+
+impl fmt::Display for BlockReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockReason::IrreversibleBioRisk => f.write_str("irreversible bio-risk"),
+        }
+    }
 }
 
-#[derive(Debug)]
-pub struct DecisionLogEntry {
-    pub tick: Tick,
-    pub description: String,
+impl fmt::Display for SimEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimEvent::Moved { agent, from, to } => write!(f, "Agent {agent} moved {from}->{to}"),
+            SimEvent::MoveRejected {
+                agent,
+                from,
+                to,
+                reason,
+            } => write!(f, "Agent {agent} could not move {from}->{to}: {reason}"),
+            SimEvent::Adopted { agent, concept } => {
+                write!(f, "Agent {agent} adopted concept {concept}")
+            }
+            SimEvent::Shared {
+                agent,
+                concept,
+                region,
+            } => write!(f, "Agent {agent} shared concept {concept} in region {region}"),
+            SimEvent::Abandoned { agent, concept } => {
+                write!(f, "Agent {agent} abandoned concept {concept}")
+            }
+            SimEvent::Mutated {
+                agent,
+                parent,
+                concept,
+            } => write!(f, "Agent {agent} mutated concept {parent} into concept {concept}"),
+            SimEvent::CapRejected {
+                agent,
+                concept,
+                region,
+                cap,
+            } => write!(
+                f,
+                "Agent {agent} could not adopt concept {concept}: region {region} at cap {cap}"
+            ),
+            SimEvent::PolicyBlocked {
+                agent,
+                concept,
+                region,
+                reason,
+            } => write!(
+                f,
+                "Agent {agent} blocked from sharing concept {concept} in region {region}: {reason}"
+            ),
+            SimEvent::Throttled { engaged: true } => {
+                f.write_str("Exposure throttled near the ethical ceiling")
+            }
+            SimEvent::Throttled { engaged: false } => f.write_str("Exposure throttle lifted"),
+            SimEvent::Intervened { intervention } => write!(f, "Intervention: {intervention}"),
+            SimEvent::Stopped { condition } => write!(f, "Simulation stopped: {condition}"),
+            SimEvent::CeilingViolated {
+                metric,
+                value,
+                ceiling,
+                peak_tick,
+                worst_region,
+                top_concept,
+            } => {
+                let breach = CeilingBreach {
+                    metric: *metric,
+                    value: *value,
+                    ceiling: *ceiling,
+                    tick: *peak_tick,
+                    worst_region: *worst_region,
+                    top_concept: *top_concept,
+                    fear_drivers: Vec::new(),
+                };
+                write!(f, "Simulation stopped: ethical ceiling violated ({breach})")
+            }
+        }
+    }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SimulationLog {
-    pub actions: Vec<DecisionLogEntry>,
+    pub events: Vec<(Tick, SimEvent)>,
+    /// Everything policy prevented, in the order it was prevented.
+    #[serde(default)]
+    pub denials: Vec<PolicyDenial>,
+}
+
+impl SimEvent {
+    /// The agent action this event records, for replay. Rejected moves and
+    /// adoptions and blocked shares map back to the action that was
+    /// attempted.
+    pub fn recorded_action(&self) -> Option<AgentAction> {
+        match *self {
+            SimEvent::Moved { agent, from, to }
+            | SimEvent::MoveRejected {
+                agent, from, to, ..
+            } => Some(AgentAction::Move {
+                agent_id: agent,
+                from,
+                to,
+            }),
+            SimEvent::Adopted { agent, concept } | SimEvent::CapRejected { agent, concept, .. } => {
+                Some(AgentAction::Adopt {
+                    agent_id: agent,
+                    concept_id: concept,
+                })
+            }
+            SimEvent::Shared {
+                agent,
+                concept,
+                region,
+            }
+            | SimEvent::PolicyBlocked {
+                agent,
+                concept,
+                region,
+                ..
+            } => Some(AgentAction::Share {
+                agent_id: agent,
+                concept_id: concept,
+                region,
+            }),
+            SimEvent::Abandoned { agent, concept } => Some(AgentAction::Abandon {
+                agent_id: agent,
+                concept_id: concept,
+            }),
+            SimEvent::Mutated { .. }
+            | SimEvent::Throttled { .. }
+            | SimEvent::Intervened { .. }
+            | SimEvent::Stopped { .. }
+            | SimEvent::CeilingViolated { .. } => None,
+        }
+    }
+}
+
+impl SimulationLog {
+    fn push(&mut self, tick: Tick, event: SimEvent) {
+        self.events.push((tick, event));
+    }
+
+    fn deny(
+        &mut self,
+        tick: Tick,
+        agent_id: Option<AgentId>,
+        concept_id: Option<ConceptId>,
+        region: Option<RegionId>,
+        rule: DenialRule,
+        details: String,
+    ) {
+        self.denials.push(PolicyDenial {
+            tick,
+            agent_id,
+            concept_id,
+            region,
+            rule,
+            details,
+        });
+    }
+
+    /// Write `denials` as one JSON object per line.
+    pub fn export_denials_jsonl(&self, mut writer: impl Write) -> io::Result<()> {
+        for denial in &self.denials {
+            serde_json::to_writer(&mut writer, denial)?;
+            writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    /// Write one JSON object per line: `{"tick": .., "type": .., ...}`.
+    pub fn export_jsonl(&self, mut writer: impl Write) -> io::Result<()> {
+        #[derive(Serialize)]
+        struct Line<'a> {
+            tick: Tick,
+            #[serde(flatten)]
+            event: &'a SimEvent,
+        }
+
+        for (tick, event) in &self.events {
+            serde_json::to_writer(&mut writer, &Line { tick: *tick, event })?;
+            writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
+
+/// How close a run under `EnforcementMode::Advisory` came to its ceilings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AdvisoryReport {
+    /// The breach `EnforcementMode::Enforce` would have stopped on, as it
+    /// would have been reported in `SimulationResult::ceiling_breach`.
+    pub breach: Option<CeilingBreach>,
+    /// Tick `Enforce` would have stopped after.
+    pub stop_tick: Option<Tick>,
+    /// Ticks past `ceiling_warmup_ticks` with any metric above its ceiling.
+    pub ticks_in_breach: u32,
+    /// One per executed tick.
+    pub margins: Vec<CeilingMargin>,
+}
+
+/// Adoptions and abandonments applied in one tick.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChurnPoint {
+    pub adoptions: u32,
+    pub abandonments: u32,
+}
+
+/// Summary of a `Simulation::run`, serializable for plotting.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SimulationResult {
+    /// concept -> number of adopters at the end of each executed tick
+    pub adoption_curves: BTreeMap<ConceptId, Vec<u32>>,
+    /// Adoptions vs abandonments, one entry per executed tick
+    pub churn: Vec<ChurnPoint>,
+    /// region -> concept -> fraction (0..1) of the region's agents that adopted it
+    pub final_adoption_share: BTreeMap<RegionId, BTreeMap<ConceptId, f32>>,
+    /// concept -> final adopters in each income quintile (poorest first)
+    pub adoption_by_income_quintile: BTreeMap<ConceptId, [u32; 5]>,
+    /// Tick with the highest global fear index, if any was recorded.
+    pub peak_fear_tick: Option<Tick>,
+    pub stopped_by_ethical_ceiling: bool,
+    /// What tripped the ceiling, when `stopped_by_ethical_ceiling`.
+    pub ceiling_breach: Option<CeilingBreach>,
+    /// First breach during `ceiling_warmup_ticks`, which did not stop the run.
+    #[serde(default)]
+    pub warmup_ceiling_breach: Option<CeilingBreach>,
+    /// Ceiling margins and would-be stop, under `EnforcementMode::Advisory`.
+    #[serde(default)]
+    pub advisory: Option<AdvisoryReport>,
+    /// Condition that ended the run.
+    pub stop_condition: Option<StopCondition>,
+    /// Actions dropped by the irreversible bio-risk hard stop.
+    pub bio_risk_blocked_actions: u64,
+    /// Broadcasts into a region skipped because policy forbids exposing the
+    /// concept there, counted once per entry, region and tick.
+    pub blocked_broadcasts: u64,
+    /// Entries of `SimulationLog::denials` by rule.
+    pub denials_by_rule: BTreeMap<DenialRule, u64>,
+    /// concept -> adoption inequality across regions at each tick; empty
+    /// unless `SimulationConfig::track_inequality`
+    #[serde(default)]
+    pub inequality_series: BTreeMap<ConceptId, Vec<(Tick, f32)>>,
+    /// Every source of fear added over the run, largest first
+    #[serde(default)]
+    pub fear_drivers: Vec<FearDriver>,
+    /// root concept -> adopters of it and its variants at the end of each
+    /// executed tick; empty unless a variant was spawned
+    #[serde(default)]
+    pub family_adoption_curves: BTreeMap<ConceptId, Vec<u32>>,
+    /// concept -> tick its adoption grew fastest, where it tipped
+    #[serde(default)]
+    pub tipping_points: BTreeMap<ConceptId, Tick>,
+    /// concept -> tick its adopter count settled for the rest of the run
+    #[serde(default)]
+    pub steady_state_ticks: BTreeMap<ConceptId, Tick>,
+    pub ticks_executed: Tick,
+}
+
+/// Running counts kept during a run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct RunTally {
+    /// concept -> current number of adopters
+    adopters: BTreeMap<ConceptId, u32>,
+    /// concept -> region -> current number of adopters living there
+    regional_adopters: BTreeMap<ConceptId, BTreeMap<RegionId, u32>>,
+    /// Adoptions and abandonments in the current tick
+    churn: ChurnPoint,
+    bio_risk_blocked: u64,
+    /// Budget used so far by each `BroadcastSchedule` entry
+    broadcast_spent: Vec<f32>,
+    blocked_broadcasts: u64,
+    /// Consecutive ticks without any action
+    idle_ticks: u64,
+    /// One region's fears: buffer for the per-tick aggregation, kept so it
+    /// does not allocate every tick
+    #[serde(skip)]
+    region_fears: Vec<f32>,
+    /// Fear added by adopting each concept in the current tick
+    #[serde(skip)]
+    fear_added: BTreeMap<ConceptId, f32>,
+    /// Draws deciding mutations, in the order shares are applied; created
+    /// by the first draw
+    #[serde(default)]
+    mutation_rng: Option<AgentRng>,
+}
+
+impl RunTally {
+    fn regional_adopters(&self, concept_id: ConceptId, region: RegionId) -> u32 {
+        self.regional_adopters
+            .get(&concept_id)
+            .and_then(|by_region| by_region.get(&region))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Move one agent holding `concepts` into (`delta` 1) or out of
+    /// (`delta` -1) the count for `region`.
+    fn shift_regional(&mut self, concepts: &[ConceptId], region: RegionId, delta: i32) {
+        for concept_id in concepts {
+            let count = self
+                .regional_adopters
+                .entry(*concept_id)
+                .or_default()
+                .entry(region)
+                .or_insert(0);
+            *count = count.saturating_add_signed(delta);
+        }
+    }
+}
+
+/// A run paused between ticks by `Simulation::run_until`: everything the
+/// run loop keeps besides the simulation itself, including the agents' RNG
+/// state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunProgress {
+    next_tick: Tick,
+    rngs: Vec<AgentRng>,
+    tally: RunTally,
+    result: SimulationResult,
+    stopped: bool,
+}
+
+impl RunProgress {
+    /// First tick not yet executed.
+    pub fn next_tick(&self) -> Tick {
+        self.next_tick
+    }
+
+    /// A stop condition ended the run; only finishing it remains.
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    /// Adopters of `concept_id` currently living in `region`, as counted for
+    /// `PolicyContext::adoption_caps`.
+    pub fn regional_adopters(&self, concept_id: ConceptId, region: RegionId) -> u32 {
+        self.tally.regional_adopters(concept_id, region)
+    }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Simulation {
     pub world: World,
     pub agents: Vec<Agent>,
@@ -29,94 +641,3257 @@ pub struct Simulation {
     pub config: SimulationConfig,
     pub log: SimulationLog,
     pub fear_metrics: FearIndexMetrics,
+    /// Word-of-mouth edges between agents; `None` keeps exposure regional.
+    #[serde(default)]
+    pub social_graph: Option<SocialGraph>,
+    /// agent -> concept -> exposure received from neighbors' shares
+    #[serde(default)]
+    pub social_exposure: BTreeMap<AgentId, BTreeMap<ConceptId, f32>>,
+    #[serde(default)]
+    pub broadcasts: BroadcastSchedule,
+    /// Interventions waiting for their tick; see `schedule_intervention`.
+    #[serde(default)]
+    pub interventions: BTreeMap<Tick, Vec<Intervention>>,
+    /// How agents weigh adopting what they see. Checkpointed as its
+    /// `AdoptionModel::spec`; a model without one resumes as
+    /// `DefaultAdoptionModel`.
+    #[serde(default, with = "crate::adoption::checkpointed")]
+    pub adoption_model: Box<dyn AdoptionModel>,
+    /// region -> indices into `agents` of the agents living there. Rebuilt
+    /// when a run starts or resumes and kept current as moves are applied.
+    #[serde(skip)]
+    pub region_index: HashMap<RegionId, Vec<usize>>,
+    /// Set by `run_until` while a run is paused; `None` before a run starts
+    /// and after it finishes.
+    #[serde(default)]
+    pub progress: Option<RunProgress>,
 }
 
 impl Simulation {
-    pub fn run(&mut self) {
-        let mut rng = rand::rngs::StdRng::seed_from_u64(self.config.random_seed);
-
-        for tick in 0..self.config.max_ticks {
-            // 1. Collect actions from all agents
-            let world_view = self.world.view();
-            let mut all_actions = Vec::new();
-            for agent in &mut self.agents {
-                let actions = agent.step(tick, &world_view, &self.policy, &mut rng);
-                all_actions.extend(actions);
-            }
-
-            // 2. Apply actions to world/agents and log them
-            self.apply_actions(tick, &all_actions);
-
-            // 3. Update fear metrics after this tick
-            let mut fear_by_region = std::collections::HashMap::new();
-            for agent in &self.agents {
-                let entry = fear_by_region.entry(agent.state.region).or_insert(0.0);
-                *entry = (*entry).max(agent.state.fear_level);
-            }
-            self.fear_metrics
-                .update_from_snapshot(tick, &self.world, &fear_by_region);
-
-            // 4. Early stop if ethical ceiling is violated
-            if self
-                .fear_metrics
-                .is_above_ethical_ceiling(&self.policy.ethical_ceiling)
-            {
-                self.log.actions.push(DecisionLogEntry {
-                    tick,
-                    description: "Simulation stopped: ethical ceiling violated".into(),
+    /// Run to `max_ticks` or the first stop condition, continuing a run
+    /// paused by `run_until` if there is one.
+    pub fn run(&mut self) -> SimulationResult {
+        self.run_with_observer(&mut ())
+    }
+
+    /// Replace `social_graph` with one generated under `model` over the
+    /// current agents, seeded from `config.random_seed`.
+    pub fn generate_social_graph(&mut self, model: SocialGraphModel) {
+        let ids: Vec<AgentId> = self.agents.iter().map(|agent| agent.id).collect();
+        self.social_graph = Some(generate_social_graph(
+            &ids,
+            model,
+            self.config.random_seed,
+        ));
+    }
+
+    /// `World::validate`, plus agents living in unknown regions or in
+    /// regions of population 0, and NaN agent values. Scenario loading
+    /// refuses simulations that fail it; check ones built by hand before
+    /// running them.
+    pub fn validate(&self) -> Result<(), Vec<WorldValidationError>> {
+        let mut errors = self.world.validate().err().unwrap_or_default();
+        let mut in_empty_regions: BTreeMap<RegionId, Vec<AgentId>> = BTreeMap::new();
+        for agent in &self.agents {
+            let region = agent.state.region;
+            match self.world.regions.get(&region) {
+                None => errors.push(WorldValidationError::UnknownAgentRegion {
+                    agent: agent.id,
+                    region,
+                }),
+                Some(r) if r.population == 0 => {
+                    in_empty_regions.entry(region).or_default().push(agent.id);
+                }
+                Some(_) => {}
+            }
+            let (attrs, beliefs, state) = (&agent.attrs, &agent.beliefs, &agent.state);
+            let values = [
+                ("income_level", attrs.income_level),
+                ("risk_tolerance", attrs.risk_tolerance),
+                ("mobility_score", attrs.mobility_score),
+                ("eco_values", attrs.eco_values),
+                ("influence", attrs.influence),
+                ("openness_to_change", beliefs.openness_to_change),
+                ("trust_in_institutions", beliefs.trust_in_institutions),
+                ("tech_skepticism", beliefs.tech_skepticism),
+                ("fatigue", state.fatigue),
+                ("fear_level", state.fear_level),
+                ("regret", state.regret),
+            ];
+            for (field, value) in values {
+                if value.is_nan() {
+                    errors.push(WorldValidationError::AgentValueNan {
+                        agent: agent.id,
+                        field,
+                    });
+                }
+            }
+        }
+        for (region, agents) in in_empty_regions {
+            errors.push(WorldValidationError::EmptyRegionHasAgents { region, agents });
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// `run`, reporting each tick to `observer`, which may end the run early.
+    pub fn run_with_observer(&mut self, observer: &mut dyn SimObserver) -> SimulationResult {
+        self.run_ticks(self.config.max_ticks, observer);
+        let result = self.finish_run();
+        observer.on_stop(result.stop_condition.unwrap_or(StopCondition::MaxTicks));
+        result
+    }
+
+    /// Apply `intervention` at the start of `tick`, before broadcasts and
+    /// before agents step; one scheduled for a tick already run is applied
+    /// at the start of the next. Interventions for the same tick apply in
+    /// the order they were scheduled, and each is logged as
+    /// `SimEvent::Intervened`.
+    pub fn schedule_intervention(&mut self, tick: Tick, intervention: Intervention) {
+        self.interventions.entry(tick).or_default().push(intervention);
+    }
+
+    /// Execute ticks before `end` (capped at `max_ticks`) and pause, leaving
+    /// the run in `progress` to be checkpointed or continued by `run`.
+    pub fn run_until(&mut self, end: Tick) {
+        self.run_ticks(end, &mut ());
+    }
+
+    fn run_ticks(&mut self, end: Tick, observer: &mut dyn SimObserver) {
+        self.rebuild_region_index();
+        let mut progress = self.progress.take().unwrap_or_else(|| self.begin_run());
+        let end = end.min(self.config.max_ticks);
+        // Agents step in one chunk per worker thread, each into its own
+        // buffer; buffers and `all_actions` are reused from tick to tick.
+        let threshold = self.config.funnel_exposure_threshold;
+        let chunk_len = self.agents.len().div_ceil(rayon::current_num_threads()).max(1);
+        let mut outputs = vec![StepOutput::new(threshold); self.agents.len().div_ceil(chunk_len)];
+        let mut all_actions: Vec<AgentAction> = Vec::new();
+        while !progress.stopped && progress.next_tick < end {
+            let tick = progress.next_tick;
+            let due = self.due_interventions(tick);
+            for intervention in due {
+                self.intervene(tick, intervention);
+            }
+            self.broadcast(tick, &mut progress.tally);
+            let mut flow = observer.on_tick_start(tick, &self.world);
+
+            // 1. Collect actions from all agents in parallel; agents only read
+            // the world, and actions are concatenated in agent-id order.
+            let world_view = self.world.view(tick);
+            let social =
+                SocialView::new(self.social_graph.as_ref(), &self.agents, &self.social_exposure);
+            let denials = self.exposure_denials(tick, &world_view, &social);
+            self.log.denials.extend(denials);
+            let rules = StepRules {
+                policy: &self.policy,
+                behavior: &self.config.behavior,
+                adoption: self.adoption_model.as_ref(),
+            };
+            self.agents
+                .par_chunks_mut(chunk_len)
+                .zip(progress.rngs.par_chunks_mut(chunk_len))
+                .zip(outputs.par_iter_mut())
+                .for_each(|((agents, rngs), out)| {
+                    out.clear();
+                    for (agent, rng) in agents.iter_mut().zip(rngs) {
+                        agent.step(&world_view, &social, &rules, rng, out);
+                    }
                 });
+            let mut funnel = FunnelCounter::new(threshold);
+            all_actions.clear();
+            for out in &outputs {
+                all_actions.extend_from_slice(&out.actions);
+                funnel.merge(&out.funnel);
+            }
+            // Stable, so each agent's actions keep their order; a no-op when
+            // agents are stored in id order.
+            if !all_actions.is_sorted_by_key(AgentAction::agent_id) {
+                all_actions.sort_by_key(AgentAction::agent_id);
+            }
+            self.fear_metrics.funnel.record(tick, funnel);
+            if flow.is_continue() {
+                flow = observer.on_actions(tick, &all_actions);
+            }
+
+            progress.stopped =
+                self.advance(tick, &all_actions, &mut progress.tally, &mut progress.result);
+            progress.next_tick += 1;
+            if flow.is_continue() {
+                flow = observer.on_tick_end(tick, &self.fear_metrics);
+            }
+            if flow.is_continue() && observer.wants_region_snapshots(tick) {
+                let snapshots = self.region_snapshots(tick, &progress.tally);
+                flow = observer.on_region_snapshots(tick, &snapshots);
+            }
+            for intervention in observer.interventions(tick) {
+                self.schedule_intervention(tick + 1, intervention);
+            }
+            if flow.is_break() && !progress.stopped {
+                self.stop_by_observer(tick, &mut progress);
+            }
+        }
+        self.progress = Some(progress);
+    }
+
+    /// Take the interventions scheduled for `tick` or earlier.
+    fn due_interventions(&mut self, tick: Tick) -> Vec<Intervention> {
+        let later = self.interventions.split_off(&(tick + 1));
+        std::mem::replace(&mut self.interventions, later)
+            .into_values()
+            .flatten()
+            .collect()
+    }
+
+    fn intervene(&mut self, tick: Tick, intervention: Intervention) {
+        intervention.apply(self);
+        self.log.push(tick, SimEvent::Intervened { intervention });
+    }
+
+    /// Start of `tick`, before agents step: apply `broadcasts`.
+    fn broadcast(&mut self, tick: Tick, tally: &mut RunTally) {
+        let denials = self.broadcasts.apply(
+            tick,
+            &mut self.world,
+            &self.policy,
+            &mut tally.broadcast_spent,
+        );
+        tally.blocked_broadcasts += denials.len() as u64;
+        self.log.denials.extend(denials);
+    }
+
+    /// Concepts agents would consider this tick but may not be exposed to
+    /// where they are, or may not adopt at all; `Agent::step` skips these.
+    /// One denial per concept, region and rule, naming how many agents it
+    /// turned away, rather than one per agent.
+    fn exposure_denials(
+        &self,
+        tick: Tick,
+        world: &WorldView,
+        social: &SocialView,
+    ) -> Vec<PolicyDenial> {
+        let mut blocked: BTreeMap<(ConceptId, RegionId, DenialRule), (&str, u32)> =
+            BTreeMap::new();
+        for agent in &self.agents {
+            let region = agent.state.region;
+            for concept in agent.candidate_concepts(world, social) {
+                if let Some(rule) = self.policy.adoption_denial(agent, concept, region, tick) {
+                    blocked
+                        .entry((concept.id, region, rule))
+                        .or_insert((&concept.attrs.name, 0))
+                        .1 += 1;
+                }
+            }
+        }
+        blocked
+            .into_iter()
+            .map(|((concept_id, region, rule), (name, agents))| {
+                let details = if rule == DenialRule::AgentAttributes {
+                    format!("{name} not open to {agents} agent(s) in region {region}")
+                } else {
+                    format!("{name} not exposable in region {region} ({agents} agent(s))")
+                };
+                PolicyDenial {
+                    tick,
+                    agent_id: None,
+                    concept_id: Some(concept_id),
+                    region: Some(region),
+                    rule,
+                    details,
+                }
+            })
+            .collect()
+    }
+
+    fn stop_by_observer(&mut self, tick: Tick, progress: &mut RunProgress) {
+        self.log.push(
+            tick,
+            SimEvent::Stopped {
+                condition: StopCondition::Observer,
+            },
+        );
+        progress.result.stop_condition = Some(StopCondition::Observer);
+        progress.stopped = true;
+    }
+
+    /// Write the whole simulation as JSON, including a run paused by
+    /// `run_until`, so `resume` continues it exactly as if uninterrupted.
+    pub fn checkpoint(&self, writer: impl Write) -> io::Result<()> {
+        #[derive(Serialize)]
+        struct Checkpoint<'a> {
+            version: u32,
+            simulation: &'a Simulation,
+        }
+
+        serde_json::to_writer(
+            writer,
+            &Checkpoint {
+                version: CHECKPOINT_FORMAT_VERSION,
+                simulation: self,
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Read a simulation written by `checkpoint`.
+    pub fn resume(reader: impl Read) -> io::Result<Simulation> {
+        #[derive(Deserialize)]
+        struct Checkpoint {
+            version: u32,
+            simulation: Simulation,
+        }
+
+        let checkpoint: Checkpoint = serde_json::from_reader(reader)?;
+        if checkpoint.version != CHECKPOINT_FORMAT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "checkpoint format version {}, expected {CHECKPOINT_FORMAT_VERSION}",
+                    checkpoint.version
+                ),
+            ));
+        }
+        Ok(checkpoint.simulation)
+    }
+
+    /// Re-run `initial` (a simulation that has not been run) from the
+    /// actions recorded in `log`, without calling `Agent::step` or any RNG.
+    /// Fatigue is re-derived from the recorded actions and everything else
+    /// follows from applying them, so the returned simulation ends in the
+    /// same state, with the same log, as the recorded run.
+    pub fn replay(initial: &Simulation, log: &SimulationLog) -> Simulation {
+        let mut sim = initial.clone();
+        sim.log = SimulationLog::default();
+        // Applied as logged, which also covers those pushed by observers
+        sim.interventions.clear();
+        sim.rebuild_region_index();
+        let mut progress = sim.begin_run();
+
+        let last_tick = log.events.last().map_or(0, |(tick, _)| *tick + 1);
+        let mut events = log.events.iter().peekable();
+        for tick in 0..last_tick.min(sim.config.max_ticks) {
+            let mut actions = Vec::new();
+            let mut interventions = Vec::new();
+            let mut observer_stop = false;
+            while let Some((_, event)) = events.next_if(|(t, _)| *t == tick) {
+                actions.extend(event.recorded_action());
+                if let SimEvent::Intervened { intervention } = event {
+                    interventions.push(intervention.clone());
+                }
+                observer_stop |= matches!(
+                    event,
+                    SimEvent::Stopped {
+                        condition: StopCondition::Observer
+                    }
+                );
+            }
+
+            for intervention in interventions {
+                sim.intervene(tick, intervention);
+            }
+            sim.broadcast(tick, &mut progress.tally);
+            let world_view = sim.world.view(tick);
+            let social =
+                SocialView::new(sim.social_graph.as_ref(), &sim.agents, &sim.social_exposure);
+            let denials = sim.exposure_denials(tick, &world_view, &social);
+            sim.log.denials.extend(denials);
+
+            // What `Agent::step` did to fatigue while choosing these actions
+            let fatigue = sim.config.behavior.fatigue;
+            for agent in &mut sim.agents {
+                agent.recover_fatigue(&fatigue);
+            }
+            for action in &actions {
+                if let AgentAction::Adopt { agent_id, .. } | AgentAction::Share { agent_id, .. } =
+                    action
+                {
+                    if let Some(agent) = sim.agents.iter_mut().find(|a| &a.id == agent_id) {
+                        agent.add_fatigue(&fatigue);
+                    }
+                }
+            }
+
+            if sim.advance(tick, &actions, &mut progress.tally, &mut progress.result) {
+                break;
+            }
+            if observer_stop {
+                sim.stop_by_observer(tick, &mut progress);
                 break;
             }
         }
+
+        sim.progress = Some(progress);
+        sim.finish_run();
+        sim
     }
 
-    fn apply_actions(&mut self, tick: Tick, actions: &[AgentAction]) {
-        for action in actions {
-            match action {
-                AgentAction::Move { agent_id, from, to } => {
-                    if let Some(agent) = self.agents.iter_mut().find(|a| &a.id == agent_id) {
-                        if agent.state.region == *from {
-                            agent.state.region = *to;
+    /// Fresh run state. Adopter counts start from the adoptions present
+    /// before the first tick and are kept up to date by `apply_actions`.
+    fn begin_run(&self) -> RunProgress {
+        let mut tally = RunTally {
+            adopters: self.world.concepts.keys().map(|id| (*id, 0)).collect(),
+            ..RunTally::default()
+        };
+        for agent in &self.agents {
+            for concept_id in &agent.state.adopted_concepts {
+                *tally.adopters.entry(*concept_id).or_insert(0) += 1;
+            }
+            tally.shift_regional(&agent.state.adopted_concepts, agent.state.region, 1);
+        }
+        RunProgress {
+            next_tick: 0,
+            // One stream per agent, so stepping order and thread count do
+            // not change any agent's draws.
+            rngs: self
+                .agents
+                .iter()
+                .map(|agent| agent_rng(self.config.random_seed, agent.id))
+                .collect(),
+            tally,
+            result: SimulationResult::default(),
+            stopped: false,
+        }
+    }
+
+    /// Everything in a tick after the agents chose `actions`. Returns true
+    /// when a stop condition ended the run.
+    fn advance(
+        &mut self,
+        tick: Tick,
+        actions: &[AgentAction],
+        tally: &mut RunTally,
+        result: &mut SimulationResult,
+    ) -> bool {
+        // 2. Apply actions to world/agents and log them
+        tally.idle_ticks = if actions.is_empty() { tally.idle_ticks + 1 } else { 0 };
+        self.apply_actions(tick, actions, tally);
+        result.ticks_executed = tick + 1;
+        result.churn.push(std::mem::take(&mut tally.churn));
+        for (concept_id, count) in &tally.adopters {
+            let curve = result.adoption_curves.entry(*concept_id).or_default();
+            // Concepts first adopted this tick get zeros for earlier ticks.
+            curve.resize(tick as usize, 0);
+            curve.push(*count);
+        }
+
+        // 3. Relax fear toward baseline and spread it within regions; erode
+        // trust by the data-abuse risk of what agents hold; blame fear
+        // spikes on recent adoptions
+        let contagion_fear = self.update_fear();
+        self.update_trust();
+        self.update_regret(tick);
+
+        // 4. Update fear metrics after this tick
+        let mode = self.config.fear_aggregation;
+        let values = &mut tally.region_fears;
+        let mut fear_by_region: HashMap<RegionId, f32> = HashMap::new();
+        for (region, residents) in &self.region_index {
+            values.clear();
+            values.extend(residents.iter().map(|&i| self.agents[i].state.fear_level));
+            fear_by_region.insert(*region, mode.aggregate(values));
+        }
+        self.fear_metrics
+            .update_from_snapshot(tick, &self.world, &fear_by_region, mode);
+        self.world
+            .spread_regional_fear(&fear_by_region, self.config.fear.regional_contagion);
+        self.fear_metrics
+            .record_regional_fear(tick, &self.world.regional_fear);
+        self.fear_metrics.record_fear_contribution(
+            tick,
+            &std::mem::take(&mut tally.fear_added),
+            contagion_fear,
+        );
+        let trust_by_region = self.mean_by_region(|agent| agent.beliefs.trust_in_institutions);
+        self.fear_metrics.record_trust(tick, &trust_by_region);
+        let regret_by_region = self.mean_by_region(|agent| agent.state.regret);
+        self.fear_metrics.record_regret(tick, &regret_by_region);
+        if self.config.track_inequality {
+            for concept_id in self.world.concepts.keys() {
+                let adopters: HashMap<RegionId, u32> = tally
+                    .regional_adopters
+                    .get(concept_id)
+                    .map(|by_region| by_region.iter().map(|(r, n)| (*r, *n)).collect())
+                    .unwrap_or_default();
+                self.fear_metrics
+                    .record_inequality(tick, &self.world, &adopters, *concept_id);
+            }
+        }
+        self.policy.global_fear = self.fear_metrics.current_global_fear();
+        if let CeilingResponse::Throttle {
+            recovery_margin, ..
+        } = self.policy.ceiling_response
+        {
+            let throttled = self.near_ceiling(tick, recovery_margin);
+            if throttled != self.policy.throttled {
+                self.log.push(tick, SimEvent::Throttled { engaged: throttled });
+            }
+            self.policy.throttled = throttled;
+        }
+
+        if self.policy.enforcement == EnforcementMode::Advisory {
+            let margin = self.fear_metrics.ceiling_margin(
+                tick,
+                &self.policy.ethical_ceiling,
+                &self.policy.region_ceilings,
+            );
+            let advisory = result.advisory.get_or_insert_with(AdvisoryReport::default);
+            if tick >= self.config.ceiling_warmup_ticks && margin.is_breach() {
+                advisory.ticks_in_breach += 1;
+            }
+            advisory.margins.push(margin);
+        }
+
+        // 5. Early stop on the first configured condition that holds
+        if let Some(condition) = self.check_stop_conditions(tick, result, tally.idle_ticks) {
+            result.stopped_by_ethical_ceiling = condition == StopCondition::EthicalCeiling;
+            result.stop_condition = Some(condition);
+            return true;
+        }
+        false
+    }
+
+    fn finish_run(&mut self) -> SimulationResult {
+        let progress = self.progress.take().unwrap_or_else(|| self.begin_run());
+        let (tally, mut result) = (progress.tally, progress.result);
+        if result.stop_condition.is_none() && result.ticks_executed > 0 {
+            self.log.push(
+                result.ticks_executed - 1,
+                SimEvent::Stopped {
+                    condition: StopCondition::MaxTicks,
+                },
+            );
+            result.stop_condition = Some(StopCondition::MaxTicks);
+        }
+
+        result.peak_fear_tick = peak_tick(&self.fear_metrics.time_series);
+        result.final_adoption_share = self.adoption_share_by_region();
+        result.adoption_by_income_quintile = self.adoption_by_income_quintile();
+        result.inequality_series = self.fear_metrics.inequality_series.clone();
+        result.fear_drivers = self.fear_metrics.top_fear_drivers(0);
+        if !self.fear_metrics.lineage.is_empty() {
+            result.family_adoption_curves =
+                self.fear_metrics.family_curves(&result.adoption_curves);
+        }
+        let analysis = self.config.analysis;
+        for (concept_id, curve) in &result.adoption_curves {
+            let series: Vec<(Tick, f32)> = curve
+                .iter()
+                .enumerate()
+                .map(|(tick, count)| (tick as Tick, *count as f32))
+                .collect();
+            if let Some(tick) = analysis.tipping_point(&series) {
+                result.tipping_points.insert(*concept_id, tick);
+            }
+            if let Some(tick) = analysis.steady_state(&series) {
+                result.steady_state_ticks.insert(*concept_id, tick);
+            }
+        }
+        result.bio_risk_blocked_actions = tally.bio_risk_blocked;
+        result.blocked_broadcasts = tally.blocked_broadcasts;
+        for denial in &self.log.denials {
+            *result.denials_by_rule.entry(denial.rule).or_insert(0) += 1;
+        }
+        result
+    }
+
+    /// First condition in `config.stop_conditions` that holds after `tick`,
+    /// logged. `MaxTicks` is handled by the run loop.
+    fn check_stop_conditions(
+        &mut self,
+        tick: Tick,
+        result: &mut SimulationResult,
+        idle_ticks: u64,
+    ) -> Option<StopCondition> {
+        for condition in self.config.stop_conditions.clone() {
+            let fired = match condition {
+                StopCondition::MaxTicks | StopCondition::Observer => false,
+                StopCondition::EthicalCeiling => {
+                    let warmup = self.config.ceiling_warmup_ticks;
+                    // Past the warm-up its peaks are left out, or they would
+                    // stop the run as soon as it ends.
+                    let since = if tick < warmup { 0 } else { warmup };
+                    let breach = self.fear_metrics.check_ethical_ceiling_since(
+                        &self.policy.ethical_ceiling,
+                        &self.policy.region_ceilings,
+                        since,
+                        self.config.fear_aggregation,
+                    );
+                    if tick < warmup {
+                        if result.warmup_ceiling_breach.is_none() {
+                            result.warmup_ceiling_breach =
+                                breach.map(|breach| self.with_top_concept(breach));
                         }
+                        continue;
                     }
-                    self.log.actions.push(DecisionLogEntry {
-                        tick,
-                        description: format!("Agent {agent_id} moved {from}->{to}"),
-                    });
-                }
-                AgentAction::Adopt { agent_id, concept_id } => {
-                    if let Some(agent) = self.agents.iter_mut().find(|a| &a.id == agent_id) {
-                        if !agent.state.adopted_concepts.contains(concept_id) {
-                            agent.state.adopted_concepts.push(*concept_id);
+                    if self.policy.enforcement == EnforcementMode::Advisory {
+                        let advisory = result.advisory.get_or_insert_with(AdvisoryReport::default);
+                        if advisory.breach.is_none() {
+                            advisory.breach = breach.map(|breach| self.with_top_concept(breach));
+                            advisory.stop_tick = advisory.breach.as_ref().map(|_| tick);
                         }
+                        continue;
                     }
-                    self.log.actions.push(DecisionLogEntry {
-                        tick,
-                        description: format!("Agent {agent_id} adopted concept {concept_id}"),
-                    });
+                    if let Some(breach) = breach {
+                        let breach = self.with_top_concept(breach);
+                        self.log.deny(
+                            tick,
+                            None,
+                            breach.top_concept,
+                            breach.worst_region,
+                            DenialRule::EthicalCeiling,
+                            format!("run stopped: {breach}"),
+                        );
+                        self.log.push(
+                            tick,
+                            SimEvent::CeilingViolated {
+                                metric: breach.metric,
+                                value: breach.value,
+                                ceiling: breach.ceiling,
+                                peak_tick: breach.tick,
+                                worst_region: breach.worst_region,
+                                top_concept: breach.top_concept,
+                            },
+                        );
+                        result.ceiling_breach = Some(breach);
+                        return Some(condition);
+                    }
+                    false
                 }
-                AgentAction::Share {
-                    agent_id,
-                    concept_id,
-                    region,
-                } => {
-                    // simple: bump exposure in region
-                    let region_exposure = self
+                StopCondition::AdoptionSaturated { concept, share } => {
+                    let adopters = result
+                        .adoption_curves
+                        .get(&concept)
+                        .and_then(|curve| curve.last())
+                        .copied()
+                        .unwrap_or(0);
+                    !self.agents.is_empty() && adopters as f32 / self.agents.len() as f32 >= share
+                }
+                StopCondition::SteadyState { window, epsilon } => {
+                    self.is_steady(result, window, epsilon)
+                }
+                StopCondition::NoActions { ticks } => idle_ticks >= ticks,
+            };
+            if fired {
+                self.log.push(tick, SimEvent::Stopped { condition });
+                return Some(condition);
+            }
+        }
+        None
+    }
+
+    /// Every region's aggregates after `tick`. Adopter counts come from
+    /// `tally` rather than a pass over the agents.
+    fn region_snapshots(&self, tick: Tick, tally: &RunTally) -> Vec<RegionSnapshot> {
+        self.world
+            .regions
+            .keys()
+            .map(|&region_id| {
+                let (population, total_fear) = self
+                    .agents_in_region(region_id)
+                    .fold((0, 0.0), |(n, sum), agent| (n + 1, sum + agent.state.fear_level));
+                let exposure = &self.world.exposure_field;
+                RegionSnapshot {
+                    tick,
+                    region_id,
+                    population,
+                    mean_fear: if population > 0 {
+                        total_fear / population as f32
+                    } else {
+                        0.0
+                    },
+                    adopters_by_concept: self
                         .world
-                        .exposure_field
-                        .entry(*region)
-                        .or_default();
-                    let e = region_exposure.entry(*concept_id).or_insert(0.0);
-                    *e += 0.1;
+                        .concepts
+                        .keys()
+                        .map(|&id| (id, tally.regional_adopters(id, region_id)))
+                        .collect(),
+                    exposure_by_concept: self
+                        .world
+                        .concepts
+                        .keys()
+                        .map(|&id| (id, exposure.get(region_id, id)))
+                        .collect(),
+                }
+            })
+            .collect()
+    }
 
-                    self.log.actions.push(DecisionLogEntry {
-                        tick,
-                        description: format!(
-                            "Agent {agent_id} shared concept {concept_id} in region {region}"
-                        ),
-                    });
+    /// Whether `tick`'s global fear, eco damage or any region's fear is
+    /// within `margin` (a fraction of the ceiling) of its ceiling.
+    fn near_ceiling(&self, tick: Tick, margin: f32) -> bool {
+        let ceiling = &self.policy.ethical_ceiling;
+        let near = |value: f32, max: f32| value >= max * (1.0 - margin);
+        let metrics = &self.fear_metrics;
+        let latest = |series: &[(Tick, f32)]| match series.last() {
+            Some(&(at, value)) if at == tick => Some(value),
+            _ => None,
+        };
+        latest(&metrics.time_series).is_some_and(|fear| near(fear, ceiling.max_fear_index))
+            || latest(&metrics.eco_damage_series)
+                .is_some_and(|damage| near(damage, ceiling.max_eco_damage))
+            || metrics.region_series.iter().any(|(region, series)| {
+                let max = self
+                    .policy
+                    .region_ceilings
+                    .get(region)
+                    .unwrap_or(ceiling)
+                    .max_fear_index;
+                latest(series).is_some_and(|fear| near(fear, max))
+            })
+    }
+
+    /// `breach` with its `top_concept` filled in from its `worst_region`.
+    fn with_top_concept(&self, mut breach: CeilingBreach) -> CeilingBreach {
+        breach.top_concept = breach
+            .worst_region
+            .and_then(|region| self.top_fear_concept(region));
+        breach
+    }
+
+    /// Concept whose adopters living in `region` carry the most
+    /// `expected_fear` between them; the lowest id on ties.
+    fn top_fear_concept(&self, region: RegionId) -> Option<ConceptId> {
+        let mut fear_by_concept: BTreeMap<ConceptId, f32> = BTreeMap::new();
+        for agent in self.agents_in_region(region) {
+            for concept_id in &agent.state.adopted_concepts {
+                if let Some(concept) = self.world.concepts.get(concept_id) {
+                    *fear_by_concept.entry(*concept_id).or_insert(0.0) +=
+                        concept.risk_profile.expected_fear;
                 }
             }
         }
+        fear_by_concept
+            .into_iter()
+            .filter(|(_, fear)| *fear > 0.0)
+            .fold(None, |top: Option<(ConceptId, f32)>, (concept_id, fear)| match top {
+                Some((_, highest)) if highest >= fear => top,
+                _ => Some((concept_id, fear)),
+            })
+            .map(|(concept_id, _)| concept_id)
+    }
+
+    /// Global fear and adopter counts varied by less than `epsilon` over the
+    /// last `window` ticks.
+    fn is_steady(&self, result: &SimulationResult, window: usize, epsilon: f32) -> bool {
+        fn spread(values: impl Iterator<Item = f32>) -> f32 {
+            let (lo, hi) = values.fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), v| {
+                (lo.min(v), hi.max(v))
+            });
+            hi - lo
+        }
+
+        let fear = &self.fear_metrics.time_series;
+        if window == 0 || fear.len() < window || (result.ticks_executed as usize) < window {
+            return false;
+        }
+        if spread(fear[fear.len() - window..].iter().map(|(_, f)| *f)) >= epsilon {
+            return false;
+        }
+        result.adoption_curves.values().all(|curve| {
+            spread(curve[curve.len() - window..].iter().map(|n| *n as f32)) < epsilon
+        })
+    }
+
+    /// Returns the fear contagion added, summed over agents.
+    fn update_fear(&mut self) -> f32 {
+        let dynamics = self.config.fear;
+        let decay = if dynamics.half_life_ticks > 0.0 {
+            0.5_f32.powf(1.0 / dynamics.half_life_ticks)
+        } else {
+            0.0
+        };
+
+        // Contagion uses fear as it was before this update.
+        let mut totals: HashMap<RegionId, (f32, u32)> = HashMap::new();
+        for agent in &self.agents {
+            let entry = totals.entry(agent.state.region).or_insert((0.0, 0));
+            entry.0 += agent.state.fear_level;
+            entry.1 += 1;
+        }
+
+        let regional_fear = &self.world.regional_fear;
+        let mut contagion_fear = 0.0;
+        for agent in &mut self.agents {
+            let fear = agent.state.fear_level;
+            let (sum, count) = totals[&agent.state.region];
+            let mut next = dynamics.baseline + (fear - dynamics.baseline) * decay;
+            let mut spread = 0.0;
+            if count > 1 {
+                let others_mean = (sum - fear) / (count - 1) as f32;
+                spread = dynamics.contagion * (others_mean - fear);
+                next += spread;
+            }
+            // Last tick's regional fear, neighbors' panic included, only
+            // raises fear
+            let regional = regional_fear.get(&agent.state.region).copied().unwrap_or(0.0);
+            let spill = dynamics.regional_contagion * (regional - fear).max(0.0);
+            next += spill;
+            spread += spill;
+            agent.state.fear_level = next.clamp(0.0, 1.0);
+            // Contagion is credited with no more than the fear actually added
+            let added = (agent.state.fear_level - fear).max(0.0);
+            contagion_fear += spread.max(0.0).min(added);
+        }
+        contagion_fear
+    }
+
+    fn update_trust(&mut self) {
+        let trust = self.config.behavior.trust;
+        let concepts = &self.world.concepts;
+        for agent in &mut self.agents {
+            let adopted: Vec<_> = agent
+                .state
+                .adopted_concepts
+                .iter()
+                .filter_map(|id| concepts.get(id))
+                .collect();
+            agent.beliefs.trust_in_institutions =
+                trust.eroded(agent.beliefs.trust_in_institutions, &adopted);
+        }
+    }
+
+    fn update_regret(&mut self, tick: Tick) {
+        let regret = self.config.behavior.regret;
+        for agent in &mut self.agents {
+            let state = &mut agent.state;
+            if let Some((adopted_at, fear_before)) = state.last_adoption {
+                if tick - adopted_at >= regret.attribution_window {
+                    state.last_adoption = None;
+                } else if state.fear_level - fear_before >= regret.spike_threshold {
+                    state.regret = regret.raised(state.regret, regret.per_spike);
+                    state.last_adoption = None;
+                }
+            }
+            state.regret = regret.raised(state.regret, -regret.decay_per_tick);
+        }
+    }
+
+    /// Mean of `value` over each region's residents; regions without any
+    /// are left out.
+    fn mean_by_region(&self, value: impl Fn(&Agent) -> f32) -> HashMap<RegionId, f32> {
+        let mut totals: HashMap<RegionId, (f32, u32)> = HashMap::new();
+        for agent in &self.agents {
+            let entry = totals.entry(agent.state.region).or_insert((0.0, 0));
+            entry.0 += value(agent);
+            entry.1 += 1;
+        }
+        totals
+            .into_iter()
+            .map(|(region, (sum, count))| (region, sum / count as f32))
+            .collect()
+    }
+
+    fn adoption_share_by_region(&self) -> BTreeMap<RegionId, BTreeMap<ConceptId, f32>> {
+        let mut residents: BTreeMap<RegionId, u32> = BTreeMap::new();
+        let mut adopters: BTreeMap<RegionId, BTreeMap<ConceptId, u32>> = BTreeMap::new();
+        for agent in &self.agents {
+            *residents.entry(agent.state.region).or_insert(0) += 1;
+            let counts = adopters.entry(agent.state.region).or_default();
+            for concept_id in &agent.state.adopted_concepts {
+                *counts.entry(*concept_id).or_insert(0) += 1;
+            }
+        }
+        adopters
+            .into_iter()
+            .map(|(region, counts)| {
+                let total = residents[&region] as f32;
+                let shares = counts
+                    .into_iter()
+                    .map(|(concept_id, n)| (concept_id, n as f32 / total))
+                    .collect();
+                (region, shares)
+            })
+            .collect()
+    }
+
+    fn adoption_by_income_quintile(&self) -> BTreeMap<ConceptId, [u32; 5]> {
+        let mut by_income: Vec<&Agent> = self.agents.iter().collect();
+        by_income.sort_by(|a, b| {
+            a.attrs
+                .income_level
+                .total_cmp(&b.attrs.income_level)
+                .then(a.id.cmp(&b.id))
+        });
+        let mut counts: BTreeMap<ConceptId, [u32; 5]> = BTreeMap::new();
+        for (rank, agent) in by_income.iter().enumerate() {
+            let quintile = rank * 5 / by_income.len();
+            for concept_id in &agent.state.adopted_concepts {
+                counts.entry(*concept_id).or_default()[quintile] += 1;
+            }
+        }
+        counts
+    }
+
+    /// `Concept::is_available`; Adopts and Shares of unavailable concepts
+    /// are dropped without a trace, since agents never propose them.
+    fn is_available(&self, concept_id: ConceptId, tick: Tick) -> bool {
+        self.world
+            .concepts
+            .get(&concept_id)
+            .is_none_or(|concept| concept.is_available(tick))
+    }
+
+    /// With `config.enforce_at_apply`, drop an Adopt or Share (`verb`) of
+    /// `concept_id` in `region` that policy forbids there or to the agent
+    /// (at `agent` in `agents`, if present), whatever proposed it. Returns
+    /// whether it was dropped.
+    fn reject_at_apply(
+        &mut self,
+        tick: Tick,
+        agent_id: AgentId,
+        agent: Option<usize>,
+        concept_id: ConceptId,
+        region: RegionId,
+        verb: &str,
+    ) -> bool {
+        if !self.config.enforce_at_apply {
+            return false;
+        }
+        let Some(concept) = self.world.concepts.get(&concept_id) else {
+            return false;
+        };
+        let rule = match agent.map(|i| &self.agents[i]) {
+            Some(agent) => self.policy.adoption_denial(agent, concept, region, tick),
+            None => self.policy.exposure_denial(concept, region, tick),
+        };
+        let Some(rule) = rule else {
+            return false;
+        };
+        self.log.deny(
+            tick,
+            Some(agent_id),
+            Some(concept_id),
+            Some(region),
+            DenialRule::PostHocValidation,
+            format!("{verb} in region {region} dropped: {rule}"),
+        );
+        true
+    }
+
+    /// With probability `config.mutation.rate`, while under
+    /// `max_variants`, register a variant of `concept_id` to share instead
+    /// and return its id; otherwise `concept_id`.
+    fn mutate(&mut self, tick: Tick, concept_id: ConceptId, tally: &mut RunTally) -> ConceptId {
+        let mutation = self.config.mutation;
+        if mutation.rate <= 0.0
+            || self.fear_metrics.lineage.len() >= mutation.max_variants as usize
+        {
+            return concept_id;
+        }
+        let Some(mut variant) = self.world.concepts.get(&concept_id).cloned() else {
+            return concept_id;
+        };
+        let seed = self.config.random_seed;
+        let rng = tally
+            .mutation_rng
+            .get_or_insert_with(|| agent_rng(seed, MUTATION_STREAM));
+        if rng.gen::<f32>() >= mutation.rate {
+            return concept_id;
+        }
+
+        let id = self.world.concepts.keys().next_back().map_or(0, |last| last + 1);
+        let mut perturb = |value: &mut f32| {
+            *value = (*value + rng.gen_range(-1.0..=1.0) * mutation.spread).clamp(0.0, 1.0);
+        };
+        perturb(&mut variant.attrs.attractiveness);
+        perturb(&mut variant.attrs.controversy);
+        let risk = &mut variant.risk_profile;
+        for value in [
+            &mut risk.expected_fear,
+            &mut risk.eco_harm_score,
+            &mut risk.data_abuse_risk,
+            &mut risk.irreversible_bio_risk,
+        ] {
+            perturb(value);
+        }
+        variant.attrs.name = format!("{} ({id})", variant.attrs.name);
+        variant.id = id;
+        variant.parent = Some(concept_id);
+        variant.available_from = variant.available_from.min(tick);
+        self.world.concepts.insert(id, variant);
+        if let Some(regions) = self.policy.restricted_allow_regions.get(&concept_id).cloned() {
+            self.policy.restricted_allow_regions.insert(id, regions);
+        }
+        self.fear_metrics.lineage.insert(id, concept_id);
+        id
+    }
+
+    /// Add `amount` of share exposure, up to what is left of
+    /// `config.share.max_gain_per_tick` after `gains` this tick.
+    fn add_share_exposure(
+        &mut self,
+        gains: &mut HashMap<(RegionId, ConceptId), f32>,
+        region: RegionId,
+        concept_id: ConceptId,
+        amount: f32,
+    ) {
+        let gained = gains.entry((region, concept_id)).or_insert(0.0);
+        let amount = amount.min(self.config.share.max_gain_per_tick - *gained);
+        if amount <= 0.0 {
+            return;
+        }
+        *gained += amount;
+        self.world.exposure_field.add(region, concept_id, amount);
+    }
+
+    /// Agents currently living in `region`, in `agents` order.
+    pub fn agents_in_region(&self, region: RegionId) -> impl Iterator<Item = &Agent> + '_ {
+        self.region_index
+            .get(&region)
+            .into_iter()
+            .flatten()
+            .map(|&i| &self.agents[i])
+    }
+
+    fn rebuild_region_index(&mut self) {
+        self.region_index.clear();
+        for (i, agent) in self.agents.iter().enumerate() {
+            self.region_index.entry(agent.state.region).or_default().push(i);
+        }
+    }
+
+    fn residents(&self, region: RegionId) -> u32 {
+        self.region_index.get(&region).map_or(0, Vec::len) as u32
+    }
+
+    fn move_rejection(&self, to: RegionId) -> Option<MoveRejection> {
+        if self.policy.closed_regions.contains(&to) {
+            return Some(MoveRejection::Closed);
+        }
+        let capacity = self.world.regions.get(&to)?.capacity?;
+        (self.residents(to) >= capacity).then_some(MoveRejection::AtCapacity { capacity })
+    }
+
+    fn apply_actions(
+        &mut self,
+        tick: Tick,
+        actions: &[AgentAction],
+        tally: &mut RunTally,
+    ) {
+        // Exposure added by shares this tick, for `max_gain_per_tick`
+        let mut share_gains: HashMap<(RegionId, ConceptId), f32> = HashMap::new();
+        // Agents keep their positions while actions apply
+        let agent_index: HashMap<AgentId, usize> = self
+            .agents
+            .iter()
+            .enumerate()
+            .map(|(i, a)| (a.id, i))
+            .collect();
+
+        for action in actions {
+            match action {
+                AgentAction::Move { agent_id, from, to } => {
+                    if let Some(reason) = self.move_rejection(*to) {
+                        if reason == MoveRejection::Closed {
+                            self.log.deny(
+                                tick,
+                                Some(*agent_id),
+                                None,
+                                Some(*to),
+                                DenialRule::ClosedRegion,
+                                format!("move {from}->{to} into closed region"),
+                            );
+                        }
+                        self.log.push(
+                            tick,
+                            SimEvent::MoveRejected {
+                                agent: *agent_id,
+                                from: *from,
+                                to: *to,
+                                reason,
+                            },
+                        );
+                        continue;
+                    }
+                    if let Some(&i) = agent_index.get(agent_id) {
+                        let agent = &mut self.agents[i];
+                        if agent.state.region == *from {
+                            agent.state.region = *to;
+                            tally.shift_regional(&agent.state.adopted_concepts, *from, -1);
+                            tally.shift_regional(&agent.state.adopted_concepts, *to, 1);
+                            if let Some(residents) = self.region_index.get_mut(from) {
+                                residents.retain(|&j| j != i);
+                                if residents.is_empty() {
+                                    self.region_index.remove(from);
+                                }
+                            }
+                            self.region_index.entry(*to).or_default().push(i);
+                        }
+                    }
+                    self.log.push(
+                        tick,
+                        SimEvent::Moved {
+                            agent: *agent_id,
+                            from: *from,
+                            to: *to,
+                        },
+                    );
+                }
+                AgentAction::Adopt { agent_id, concept_id } => {
+                    if !self.is_available(*concept_id, tick) {
+                        continue;
+                    }
+                    let index = agent_index.get(agent_id).copied();
+                    if let Some(i) = index {
+                        let region = self.agents[i].state.region;
+                        if self.reject_at_apply(tick, *agent_id, index, *concept_id, region, "adopt")
+                        {
+                            continue;
+                        }
+                        let agent = &mut self.agents[i];
+                        if !agent.state.adopted_concepts.contains(concept_id) {
+                            let region = agent.state.region;
+                            let residents =
+                                self.region_index.get(&region).map_or(0, Vec::len) as u32;
+                            if let Some(cap) =
+                                self.policy.adoption_cap(*concept_id, region, residents)
+                            {
+                                if tally.regional_adopters(*concept_id, region) >= cap {
+                                    self.log.deny(
+                                        tick,
+                                        Some(*agent_id),
+                                        Some(*concept_id),
+                                        Some(region),
+                                        DenialRule::AdoptionCap,
+                                        format!("region {region} already has {cap} adopters"),
+                                    );
+                                    self.log.push(
+                                        tick,
+                                        SimEvent::CapRejected {
+                                            agent: *agent_id,
+                                            concept: *concept_id,
+                                            region,
+                                            cap,
+                                        },
+                                    );
+                                    continue;
+                                }
+                            }
+                            agent.state.adopted_concepts.push(*concept_id);
+                            *tally.adopters.entry(*concept_id).or_insert(0) += 1;
+                            tally.shift_regional(&[*concept_id], region, 1);
+                            tally.churn.adoptions += 1;
+                            agent.state.last_adoption = Some((tick, agent.state.fear_level));
+                            if let Some(concept) = self.world.concepts.get(concept_id) {
+                                let rise = self.config.fear.adoption_gain
+                                    * concept.risk_profile.expected_fear;
+                                let before = agent.state.fear_level;
+                                agent.state.fear_level = (before + rise).min(1.0);
+                                let added = (agent.state.fear_level - before).max(0.0);
+                                *tally.fear_added.entry(*concept_id).or_insert(0.0) += added;
+                            }
+                        }
+                    }
+                    self.log.push(
+                        tick,
+                        SimEvent::Adopted {
+                            agent: *agent_id,
+                            concept: *concept_id,
+                        },
+                    );
+                }
+                AgentAction::Abandon {
+                    agent_id,
+                    concept_id,
+                } => {
+                    if let Some(&i) = agent_index.get(agent_id) {
+                        let agent = &mut self.agents[i];
+                        if let Some(pos) = agent
+                            .state
+                            .adopted_concepts
+                            .iter()
+                            .position(|c| c == concept_id)
+                        {
+                            agent.state.adopted_concepts.remove(pos);
+                            if let Some(count) = tally.adopters.get_mut(concept_id) {
+                                *count = count.saturating_sub(1);
+                            }
+                            tally.shift_regional(&[*concept_id], agent.state.region, -1);
+                            tally.churn.abandonments += 1;
+                            let regret = self.config.behavior.regret;
+                            agent.state.regret =
+                                regret.raised(agent.state.regret, regret.per_abandonment);
+
+                            // word-of-mouth turns slightly against the concept
+                            self.world.exposure_field.add(
+                                agent.state.region,
+                                *concept_id,
+                                -ABANDON_EXPOSURE_DROP,
+                            );
+                        }
+                    }
+                    self.log.push(
+                        tick,
+                        SimEvent::Abandoned {
+                            agent: *agent_id,
+                            concept: *concept_id,
+                        },
+                    );
+                }
+                AgentAction::Share {
+                    agent_id,
+                    concept_id,
+                    region,
+                } => {
+                    if !self.is_available(*concept_id, tick) {
+                        continue;
+                    }
+                    let blocked = self
+                        .world
+                        .concepts
+                        .get(concept_id)
+                        .is_some_and(|concept| self.policy.is_bio_risk_blocked(concept, *region));
+                    if blocked {
+                        tally.bio_risk_blocked += 1;
+                        self.log.deny(
+                            tick,
+                            Some(*agent_id),
+                            Some(*concept_id),
+                            Some(*region),
+                            DenialRule::IrreversibleBioRisk,
+                            format!("share in region {region} dropped"),
+                        );
+                        self.log.push(
+                            tick,
+                            SimEvent::PolicyBlocked {
+                                agent: *agent_id,
+                                concept: *concept_id,
+                                region: *region,
+                                reason: BlockReason::IrreversibleBioRisk,
+                            },
+                        );
+                        continue;
+                    }
+                    let index = agent_index.get(agent_id).copied();
+                    if self.reject_at_apply(tick, *agent_id, index, *concept_id, *region, "share") {
+                        continue;
+                    }
+                    let parent = *concept_id;
+                    let concept_id = &self.mutate(tick, parent, tally);
+                    if *concept_id != parent {
+                        self.log.push(
+                            tick,
+                            SimEvent::Mutated {
+                                agent: *agent_id,
+                                parent,
+                                concept: *concept_id,
+                            },
+                        );
+                    }
+
+                    // bump exposure in region by the sharer's influence (less
+                    // while throttled), and spill over into its neighbors by
+                    // the concept's virality
+                    let influence =
+                        index.map_or(0.0, |i| self.agents[i].attrs.influence.clamp(0.0, 1.0));
+                    let gain = self.config.share.base_gain
+                        * influence
+                        * self.policy.exposure_multiplier();
+                    self.add_share_exposure(&mut share_gains, *region, *concept_id, gain);
+                    let virality = self
+                        .world
+                        .concepts
+                        .get(concept_id)
+                        .map_or(0.0, |c| c.attrs.virality);
+                    if virality > 0.0 {
+                        let neighbors = self
+                            .world
+                            .regions
+                            .get(region)
+                            .map(|r| r.neighbors.clone())
+                            .unwrap_or_default();
+                        for neighbor in neighbors {
+                            self.add_share_exposure(
+                                &mut share_gains,
+                                neighbor,
+                                *concept_id,
+                                virality * gain,
+                            );
+                        }
+                    }
+                    // and along the sharer's social edges
+                    let edges = self
+                        .social_graph
+                        .as_ref()
+                        .and_then(|graph| graph.get(agent_id));
+                    for (neighbor, weight) in edges.into_iter().flatten() {
+                        *self
+                            .social_exposure
+                            .entry(*neighbor)
+                            .or_default()
+                            .entry(*concept_id)
+                            .or_insert(0.0) += weight * gain;
+                    }
+
+                    self.log.push(
+                        tick,
+                        SimEvent::Shared {
+                            agent: *agent_id,
+                            concept: parent,
+                            region: *region,
+                        },
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::adoption::{DefaultAdoptionModel, LogisticRegressionModel};
+    use crate::broadcast::{Broadcast, BroadcastSchedule};
+    use crate::concept::{Concept, ConceptAttributes, ConceptLegalStatus, ConceptRiskProfile};
+    use crate::core::agent::{AgentAttributes, AgentBeliefs, AgentState, FatigueDynamics};
+    use crate::metrics::FearSource;
+    use crate::policy::{AttributeRule, EthicalCeiling, ExposureWindowRule};
+    use crate::world::Region;
+
+    /// `n` agents spread over three regions in a ring, each region exposed to
+    /// two allowed concepts; ten ticks under a 0.9 ceiling.
+    pub(crate) fn sim(n: u64, seed: u64) -> Simulation {
+        let regions = (0..3u32)
+            .map(|id| {
+                (
+                    id,
+                    Region {
+                        id,
+                        name: format!("r{id}"),
+                        population: 100,
+                        area_km2: 1.0,
+                        neighbors: vec![(id + 1) % 3],
+                        neighbor_weights: Vec::new(),
+                        capacity: None,
+                        eco_vulnerability: 0.5,
+                    },
+                )
+            })
+            .collect();
+        let concepts = (0..2u32)
+            .map(|id| {
+                (
+                    id,
+                    Concept {
+                        id,
+                        attrs: ConceptAttributes {
+                            name: format!("c{id}"),
+                            attractiveness: 0.5,
+                            controversy: 0.1,
+                            resource_cost: 0.1,
+                            virality: 0.0,
+                        },
+                        risk_profile: ConceptRiskProfile {
+                            expected_fear: 0.2,
+                            eco_harm_score: 0.1,
+                            data_abuse_risk: 0.0,
+                            irreversible_bio_risk: 0.0,
+                        },
+                        legal_status: ConceptLegalStatus::Allowed,
+                        prerequisites: Vec::new(),
+                        available_from: 0,
+                        sunset_at: None,
+                        sunset_abandon_rate: 0.0,
+                        parent: None,
+                    },
+                )
+            })
+            .collect();
+        let agents = (0..n)
+            .map(|id| Agent {
+                id,
+                attrs: AgentAttributes {
+                    age: 30,
+                    income_level: 0.5,
+                    risk_tolerance: 0.5,
+                    mobility_score: 0.3,
+                    eco_values: 0.5,
+                    influence: 0.5,
+                    tags: Vec::new(),
+                },
+                beliefs: AgentBeliefs {
+                    openness_to_change: 0.5,
+                    trust_in_institutions: 0.5,
+                    tech_skepticism: 0.5,
+                },
+                state: AgentState {
+                    region: (id % 3) as RegionId,
+                    adopted_concepts: Vec::new(),
+                    fatigue: 0.0,
+                    fear_level: 0.1 * (id % 5) as f32,
+                    regret: 0.0,
+                    last_adoption: None,
+                },
+            })
+            .collect();
+        Simulation {
+            world: World {
+                regions,
+                concepts,
+                regional_fear: BTreeMap::new(),
+                exposure_field: (0..3u32)
+                    .flat_map(|r| (0..2u32).map(move |c| (r, c, 0.1)))
+                    .collect(),
+            },
+            agents,
+            policy: PolicyContext {
+                ethical_ceiling: EthicalCeiling {
+                    max_fear_index: 0.9,
+                    max_eco_damage: 0.9,
+                    forbid_irreversible_bio: true,
+                    max_irreversible_bio_risk: 0.0,
+                },
+                region_ceilings: Default::default(),
+                restricted_allow_regions: Default::default(),
+                exposure_windows: Vec::new(),
+                closed_regions: Default::default(),
+                adoption_caps: Default::default(),
+                attribute_rules: Vec::new(),
+                ceiling_response: Default::default(),
+                enforcement: Default::default(),
+                global_fear: 0.0,
+                throttled: false,
+            },
+            config: SimulationConfig {
+                max_ticks: 10,
+                random_seed: seed,
+                fear: Default::default(),
+                fear_aggregation: Default::default(),
+                share: Default::default(),
+                mutation: Default::default(),
+                behavior: Default::default(),
+                stop_conditions: StopCondition::defaults(),
+                enforce_at_apply: true,
+                ceiling_warmup_ticks: 0,
+                track_inequality: false,
+                analysis: Default::default(),
+                funnel_exposure_threshold: 0.0,
+            },
+            log: SimulationLog::default(),
+            fear_metrics: FearIndexMetrics::default(),
+            social_graph: None,
+            social_exposure: BTreeMap::new(),
+            broadcasts: BroadcastSchedule::default(),
+            interventions: BTreeMap::new(),
+            adoption_model: Default::default(),
+            region_index: HashMap::new(),
+            progress: None,
+        }
+    }
+
+    fn event_lines(sim: &Simulation) -> Vec<String> {
+        sim.log
+            .events
+            .iter()
+            .map(|(tick, event)| format!("{tick} {event}"))
+            .collect()
+    }
+
+    #[test]
+    fn thread_count_does_not_change_the_log() {
+        let run = |threads| {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            pool.install(|| {
+                let mut sim = sim(500, 7);
+                sim.run();
+                event_lines(&sim)
+            })
+        };
+        let sequential = run(1);
+        assert!(sequential.len() > 100);
+        assert_eq!(sequential, run(8));
+    }
+
+    /// First-tick adoptions, before any agent could abandon and re-adopt,
+    /// with every agent's fear at `fear`.
+    fn adoptions_at_fear(fear: f32) -> u32 {
+        let mut sim = sim(900, 5);
+        sim.config.max_ticks = 1;
+        sim.policy.global_fear = fear;
+        for agent in &mut sim.agents {
+            agent.state.fear_level = fear;
+        }
+        sim.run().churn.iter().map(|point| point.adoptions).sum()
+    }
+
+    #[test]
+    fn adoptions_slow_as_fear_nears_the_ceiling() {
+        let calm = adoptions_at_fear(0.1);
+        let tense = adoptions_at_fear(0.7);
+        assert!(calm > tense && tense > 0, "{calm} calm vs {tense} tense");
+        assert_eq!(adoptions_at_fear(0.87), 0);
+    }
+
+    #[test]
+    fn a_fragile_region_breaches_its_own_ceiling() {
+        let mut sim = sim(30, 1);
+        sim.world.regions.get_mut(&2).unwrap().population = 1;
+        sim.config.fear.adoption_gain = 0.0;
+        for agent in &mut sim.agents {
+            agent.state.fear_level = if agent.state.region == 2 { 0.4 } else { 0.0 };
+            agent.attrs.mobility_score = 0.0;
+        }
+        let strict = EthicalCeiling {
+            max_fear_index: 0.2,
+            ..sim.policy.ethical_ceiling
+        };
+        sim.policy.region_ceilings.insert(2, strict);
+
+        let result = sim.run();
+        assert!(result.stopped_by_ethical_ceiling);
+        let breach = result.ceiling_breach.unwrap();
+        assert_eq!(breach.metric, CeilingMetric::RegionFear(2));
+        assert_eq!(breach.worst_region, Some(2));
+        assert_eq!(breach.ceiling, 0.2);
+        assert!(sim.fear_metrics.current_global_fear() < 0.05);
+        let (_, last) = sim.log.events.last().unwrap();
+        assert!(
+            matches!(
+                last,
+                SimEvent::CeilingViolated {
+                    metric: CeilingMetric::RegionFear(2),
+                    worst_region: Some(2),
+                    ..
+                }
+            ),
+            "{last}"
+        );
+    }
+
+    #[test]
+    fn a_launch_window_holds_back_adoption_and_sharing() {
+        let mut sim = sim(30, 1);
+        sim.config.max_ticks = 60;
+        sim.policy.exposure_windows.push(ExposureWindowRule {
+            concept_id: Some(1),
+            region: None,
+            allowed_ticks: 50..Tick::MAX,
+        });
+
+        let result = sim.run();
+        let curve = &result.adoption_curves[&1];
+        assert!(curve[..50].iter().all(|&adopters| adopters == 0));
+        assert!(curve[59] > 0);
+        let early = sim
+            .log
+            .events
+            .iter()
+            .filter(|(tick, _)| *tick < 50)
+            .filter(|(_, event)| {
+                matches!(
+                    event,
+                    SimEvent::Adopted { concept: 1, .. } | SimEvent::Shared { concept: 1, .. }
+                )
+            });
+        assert_eq!(early.count(), 0);
+    }
+
+    #[test]
+    fn jsonl_export_writes_one_tagged_line_per_event() {
+        let mut sim = sim(20, 3);
+        sim.run();
+        let mut out = Vec::new();
+        sim.log.export_jsonl(&mut out).unwrap();
+        let lines: Vec<serde_json::Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), sim.log.events.len());
+        for (line, (tick, event)) in lines.iter().zip(&sim.log.events) {
+            assert_eq!(line["tick"], *tick);
+            let mut expected = serde_json::to_value(event).unwrap();
+            expected["tick"] = (*tick).into();
+            assert_eq!(*line, expected);
+        }
+    }
+
+    #[test]
+    fn every_applied_action_logs_one_event() {
+        let mut sim = sim(3, 1);
+        sim.rebuild_region_index();
+        let actions = [
+            AgentAction::Adopt {
+                agent_id: 0,
+                concept_id: 0,
+            },
+            AgentAction::Share {
+                agent_id: 0,
+                concept_id: 0,
+                region: 0,
+            },
+            AgentAction::Move {
+                agent_id: 1,
+                from: 1,
+                to: 2,
+            },
+            AgentAction::Abandon {
+                agent_id: 0,
+                concept_id: 0,
+            },
+        ];
+        sim.apply_actions(0, &actions, &mut RunTally::default());
+
+        let events: Vec<&SimEvent> = sim.log.events.iter().map(|(_, event)| event).collect();
+        assert_eq!(
+            events,
+            [
+                &SimEvent::Adopted {
+                    agent: 0,
+                    concept: 0
+                },
+                &SimEvent::Shared {
+                    agent: 0,
+                    concept: 0,
+                    region: 0
+                },
+                &SimEvent::Moved {
+                    agent: 1,
+                    from: 1,
+                    to: 2
+                },
+                &SimEvent::Abandoned {
+                    agent: 0,
+                    concept: 0
+                },
+            ]
+        );
+        assert_eq!(events[2].to_string(), "Agent 1 moved 1->2");
+    }
+
+    #[test]
+    fn adopting_a_fearful_concept_spikes_fear() {
+        let mut sim = sim(3, 3);
+        sim.world
+            .concepts
+            .get_mut(&0)
+            .unwrap()
+            .risk_profile
+            .expected_fear = 0.9;
+        sim.agents[0].state.fear_level = 0.0;
+        sim.rebuild_region_index();
+        let adopt = [AgentAction::Adopt {
+            agent_id: 0,
+            concept_id: 0,
+        }];
+        sim.apply_actions(0, &adopt, &mut RunTally::default());
+        let expected = sim.config.fear.adoption_gain * 0.9;
+        assert!((sim.agents[0].state.fear_level - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn an_isolated_fearful_agent_relaxes_to_baseline() {
+        let mut sim = sim(1, 3);
+        sim.world.concepts.clear();
+        sim.world.exposure_field.clear();
+        sim.agents[0].state.fear_level = 0.8;
+        sim.policy.ethical_ceiling.max_fear_index = 1.0;
+        let half_life = sim.config.fear.half_life_ticks;
+        sim.config.max_ticks = half_life as Tick;
+        sim.run();
+        // One half-life halves the distance to the 0 baseline
+        assert!((sim.agents[0].state.fear_level - 0.4).abs() < 1e-3);
+    }
+
+    /// Adoptions per tick of one agent offered 50 irresistible concepts.
+    fn bombarded_adoptions(fatigue: FatigueDynamics) -> Vec<u32> {
+        let mut sim = sim(1, 3);
+        let template = sim.world.concepts[&0].clone();
+        sim.world.concepts = (0..50)
+            .map(|id| {
+                let mut concept = template.clone();
+                concept.id = id;
+                concept.attrs.attractiveness = 5.0;
+                concept.risk_profile.expected_fear = 0.0;
+                (id, concept)
+            })
+            .collect();
+        for id in 0..50 {
+            sim.world.exposure_field.set(0, id, 0.5);
+        }
+        sim.config.max_ticks = 5;
+        sim.config.behavior.fatigue = fatigue;
+        sim.run()
+            .churn
+            .iter()
+            .map(|point| point.adoptions)
+            .collect()
+    }
+
+    #[test]
+    fn a_bombarded_agent_plateaus() {
+        let rested = FatigueDynamics {
+            per_action: 0.0,
+            ..FatigueDynamics::default()
+        };
+        assert!(bombarded_adoptions(rested)[0] >= 45);
+
+        let tired = bombarded_adoptions(FatigueDynamics::default());
+        assert!(tired[0] < 15, "{tired:?}");
+        assert!(tired[1..].iter().all(|&n| n <= tired[0]), "{tired:?}");
+        assert!(tired.iter().sum::<u32>() < 50, "{tired:?}");
+    }
+
+    /// Adopted concepts after `ticks` held by poor and rich agents, out of
+    /// 300 alternating between `poor` and 0.9 income and otherwise identical.
+    fn adoptions_by_income(poor: f32, ticks: Tick) -> (usize, usize, SimulationResult) {
+        let mut sim = sim(300, 3);
+        sim.config.max_ticks = ticks;
+        for concept in sim.world.concepts.values_mut() {
+            concept.attrs.resource_cost = 0.3;
+        }
+        for agent in &mut sim.agents {
+            agent.attrs.income_level = if agent.id % 2 == 0 { poor } else { 0.9 };
+            agent.attrs.mobility_score = 0.0;
+            agent.state.fear_level = 0.0;
+        }
+        let result = sim.run();
+        let count = |even: bool| {
+            sim.agents
+                .iter()
+                .filter(|a| (a.id % 2 == 0) == even)
+                .map(|a| a.state.adopted_concepts.len())
+                .sum()
+        };
+        (count(true), count(false), result)
+    }
+
+    #[test]
+    fn cost_weighs_more_on_lower_incomes() {
+        let (poor, rich, _) = adoptions_by_income(0.2, 1);
+        assert!(poor > 0 && poor < rich, "{poor} poor vs {rich} rich");
+    }
+
+    #[test]
+    fn unaffordable_concepts_are_never_adopted() {
+        // 0.3 / 0.05 is over the default `max_cost_ratio` of 4
+        let (poor, rich, result) = adoptions_by_income(0.05, 10);
+        assert_eq!(poor, 0);
+        assert!(rich > 0);
+        for quintiles in result.adoption_by_income_quintile.values() {
+            assert_eq!(quintiles[..2], [0, 0]);
+            assert!(quintiles[3] > 0 && quintiles[4] > 0);
+        }
+    }
+
+    #[test]
+    fn bio_risk_shares_are_dropped_and_counted() {
+        let mut sim = sim(3, 3);
+        sim.world
+            .concepts
+            .get_mut(&1)
+            .unwrap()
+            .risk_profile
+            .irreversible_bio_risk = 0.5;
+        sim.rebuild_region_index();
+        let mut tally = RunTally::default();
+        let share = [AgentAction::Share {
+            agent_id: 0,
+            concept_id: 1,
+            region: 0,
+        }];
+        let before = sim.world.exposure_field.get(0, 1);
+        sim.apply_actions(0, &share, &mut tally);
+
+        assert_eq!(tally.bio_risk_blocked, 1);
+        assert_eq!(sim.world.exposure_field.get(0, 1), before);
+        assert_eq!(
+            sim.log.events,
+            [(
+                0,
+                SimEvent::PolicyBlocked {
+                    agent: 0,
+                    concept: 1,
+                    region: 0,
+                    reason: BlockReason::IrreversibleBioRisk
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn bio_risk_does_not_remove_earlier_adoptions() {
+        let mut sim = sim(30, 3);
+        sim.world
+            .concepts
+            .get_mut(&1)
+            .unwrap()
+            .risk_profile
+            .irreversible_bio_risk = 0.5;
+        sim.agents[0].state.adopted_concepts.push(1);
+        sim.agents[0].beliefs.openness_to_change = 1.0;
+        sim.agents[0].state.fear_level = 0.0;
+        sim.config.max_ticks = 3;
+        let result = sim.run();
+        assert!(sim.agents[0].state.adopted_concepts.contains(&1));
+        assert_eq!(result.adoption_curves[&1].last(), Some(&1));
+    }
+
+    #[test]
+    fn a_fearful_concept_declines_once_exposure_stops() {
+        let mut sim = sim(60, 3);
+        sim.world
+            .concepts
+            .get_mut(&1)
+            .unwrap()
+            .risk_profile
+            .expected_fear = 0.9;
+        sim.config.max_ticks = 40;
+        sim.policy.ethical_ceiling.max_fear_index = 1.0;
+        sim.policy.ethical_ceiling.max_eco_damage = 1.0;
+        sim.policy.exposure_windows.push(ExposureWindowRule {
+            concept_id: Some(1),
+            region: None,
+            allowed_ticks: 0..5,
+        });
+
+        let result = sim.run();
+        let curve = &result.adoption_curves[&1];
+        assert!(curve[39] < curve[4], "{curve:?}");
+        assert_eq!(result.churn.len(), 40);
+        assert!(
+            result
+                .churn
+                .iter()
+                .map(|point| point.abandonments)
+                .sum::<u32>()
+                > 0
+        );
+    }
+
+    #[test]
+    fn abandoning_lowers_regional_exposure() {
+        let mut sim = sim(3, 3);
+        sim.agents[0].state.adopted_concepts.push(0);
+        sim.rebuild_region_index();
+        let before = sim.world.exposure_field.get(0, 0);
+        let mut tally = RunTally::default();
+        sim.apply_actions(
+            0,
+            &[AgentAction::Abandon {
+                agent_id: 0,
+                concept_id: 0,
+            }],
+            &mut tally,
+        );
+        assert!(sim.agents[0].state.adopted_concepts.is_empty());
+        assert_eq!(tally.churn.abandonments, 1);
+        assert!(
+            (sim.world.exposure_field.get(0, 0) - (before - ABANDON_EXPOSURE_DROP)).abs() < 1e-6
+        );
+    }
+
+    #[test]
+    fn moves_past_capacity_are_rejected_in_order() {
+        let mut sim = sim(9, 1);
+        // Regions 0 and 2 hold agents 0, 3, 6 and 2, 5, 8; region 1 holds 3
+        sim.world.regions.get_mut(&1).unwrap().capacity = Some(5);
+        sim.rebuild_region_index();
+        let moves: Vec<AgentAction> = [0, 3, 6, 2, 5]
+            .iter()
+            .map(|&agent_id| AgentAction::Move {
+                agent_id,
+                from: (agent_id % 3) as RegionId,
+                to: 1,
+            })
+            .collect();
+        sim.apply_actions(0, &moves, &mut RunTally::default());
+
+        assert_eq!(sim.residents(1), 5);
+        let rejected: Vec<AgentId> = sim
+            .log
+            .events
+            .iter()
+            .filter_map(|(_, event)| match event {
+                SimEvent::MoveRejected {
+                    agent,
+                    reason: MoveRejection::AtCapacity { capacity: 5 },
+                    ..
+                } => Some(*agent),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(rejected, [6, 2, 5]);
+    }
+
+    #[test]
+    fn capacity_and_closures_hold_over_a_run() {
+        let run = || {
+            let mut sim = sim(300, 3);
+            for agent in &mut sim.agents {
+                agent.attrs.mobility_score = 1.0;
+            }
+            sim.world.regions.get_mut(&1).unwrap().capacity = Some(120);
+            sim.policy.closed_regions.insert(2);
+            sim.config.max_ticks = 5;
+            sim.run();
+            (sim.residents(1), sim.residents(2), event_lines(&sim))
+        };
+        let (in_capped, in_closed, log) = run();
+        assert_eq!(in_capped, 120);
+        assert_eq!(in_closed, 0);
+        assert!(log.iter().any(|line| line.contains("capacity")));
+        assert_eq!(log, run().2);
+    }
+
+    /// First tick an agent of region 2 adopts concept 0, seeded only in
+    /// region 0 of the line 0 - 1 - 2. Nobody lives in region 1 to relay
+    /// it and nobody moves, so only spillover from region 0's shares can
+    /// bring it within sight of region 2.
+    fn two_hop_arrival(virality: f32) -> Option<Tick> {
+        let mut sim = sim(90, 3);
+        for agent in &mut sim.agents {
+            agent.attrs.mobility_score = 0.0;
+            if agent.state.region == 1 {
+                agent.state.region = 0;
+            }
+        }
+        sim.world.regions.get_mut(&0).unwrap().neighbors = vec![1];
+        sim.world.regions.get_mut(&1).unwrap().neighbors = vec![0, 2];
+        sim.world.regions.get_mut(&2).unwrap().neighbors = vec![1];
+        sim.world.exposure_field.clear();
+        sim.world.exposure_field.set(0, 0, 0.5);
+        sim.world.concepts.get_mut(&0).unwrap().attrs.virality = virality;
+        sim.config.max_ticks = 30;
+        sim.policy.ethical_ceiling.max_fear_index = 1.0;
+        sim.run();
+        sim.log.events.iter().find(|(_, event)| matches!(event, SimEvent::Adopted { agent, concept: 0 } if agent % 3 == 2)).map(|(tick, _)| *tick)
+    }
+
+    #[test]
+    fn virality_carries_shares_two_hops() {
+        assert!(two_hop_arrival(0.9).is_some_and(|tick| tick < 10));
+        assert_eq!(two_hop_arrival(0.0), None);
+    }
+
+    #[test]
+    fn shares_spill_into_neighbors_by_virality() {
+        let spill = |virality| {
+            let mut sim = sim(3, 1);
+            sim.world.exposure_field.clear();
+            sim.world.concepts.get_mut(&0).unwrap().attrs.virality = virality;
+            sim.rebuild_region_index();
+            let share = [AgentAction::Share {
+                agent_id: 0,
+                concept_id: 0,
+                region: 0,
+            }];
+            sim.apply_actions(0, &share, &mut RunTally::default());
+            (
+                sim.world.exposure_field.get(0, 0),
+                sim.world.exposure_field.get(1, 0),
+            )
+        };
+        let (local, neighbor) = spill(0.0);
+        assert!(local > 0.0);
+        assert_eq!(neighbor, 0.0);
+        let (local, neighbor) = spill(0.5);
+        assert!((neighbor - 0.5 * local).abs() < 1e-6);
+    }
+
+    #[test]
+    fn a_converging_run_stops_at_steady_state() {
+        let mut sim = sim(60, 3);
+        sim.config.max_ticks = 500;
+        for concept in sim.world.concepts.values_mut() {
+            concept.risk_profile.expected_fear = 0.0;
+        }
+        let steady = StopCondition::SteadyState {
+            window: 10,
+            epsilon: 0.01,
+        };
+        sim.config.stop_conditions = vec![StopCondition::MaxTicks, steady];
+
+        let result = sim.run();
+        assert!(result.ticks_executed < 500);
+        assert_eq!(result.stop_condition, Some(steady));
+        let (tick, last) = sim.log.events.last().unwrap();
+        assert_eq!(*tick, result.ticks_executed - 1);
+        assert_eq!(*last, SimEvent::Stopped { condition: steady });
+        let curves = result.adoption_curves.values();
+        assert!(curves
+            .into_iter()
+            .all(|curve| curve[curve.len() - 10..].windows(2).all(|w| w[0] == w[1])));
+    }
+
+    #[test]
+    fn a_run_still_moving_is_not_steady() {
+        let mut sim = sim(60, 3);
+        sim.config.max_ticks = 30;
+        sim.config.stop_conditions = vec![StopCondition::SteadyState {
+            window: 10,
+            epsilon: 0.01,
+        }];
+        // Fear keeps halving toward 0 and stays further apart than epsilon
+        sim.world.concepts.clear();
+        sim.world.exposure_field.clear();
+        for agent in &mut sim.agents {
+            agent.state.fear_level = 1.0;
+        }
+        sim.policy.ethical_ceiling.max_fear_index = 1.0;
+        let result = sim.run();
+        assert_eq!(result.stop_condition, Some(StopCondition::MaxTicks));
+        assert_eq!(result.ticks_executed, 30);
+    }
+
+    #[test]
+    fn verify_replay() {
+        let mut sim = sim(200, 9);
+        sim.config.max_ticks = 40;
+        sim.world
+            .concepts
+            .get_mut(&1)
+            .unwrap()
+            .risk_profile
+            .expected_fear = 0.7;
+        sim.world.regions.get_mut(&1).unwrap().capacity = Some(70);
+        let initial = sim.clone();
+        sim.run();
+
+        let replayed = Simulation::replay(&initial, &sim.log);
+        assert_eq!(replayed.log.events, sim.log.events);
+        assert_eq!(replayed.agents.len(), sim.agents.len());
+        for (original, replayed) in sim.agents.iter().zip(&replayed.agents) {
+            let (a, b) = (&original.state, &replayed.state);
+            assert_eq!(original.id, replayed.id);
+            assert_eq!(a.region, b.region, "agent {}", original.id);
+            assert_eq!(
+                a.adopted_concepts, b.adopted_concepts,
+                "agent {}",
+                original.id
+            );
+            assert_eq!(
+                a.fatigue.to_bits(),
+                b.fatigue.to_bits(),
+                "agent {}",
+                original.id
+            );
+            assert_eq!(
+                a.fear_level.to_bits(),
+                b.fear_level.to_bits(),
+                "agent {}",
+                original.id
+            );
+            assert_eq!(
+                a.regret.to_bits(),
+                b.regret.to_bits(),
+                "agent {}",
+                original.id
+            );
+            assert_eq!(a.last_adoption, b.last_adoption, "agent {}", original.id);
+            assert_eq!(
+                original.beliefs.trust_in_institutions.to_bits(),
+                replayed.beliefs.trust_in_institutions.to_bits()
+            );
+        }
+    }
+
+    #[test]
+    fn a_resumed_run_matches_an_uninterrupted_one() {
+        let mut base = sim(300, 4);
+        base.config.max_ticks = 100;
+        base.config.stop_conditions = Vec::new();
+        base.world.concepts.get_mut(&1).unwrap().attrs.virality = 0.5;
+        let mut reference = base.clone();
+        let expected = reference.run();
+
+        let mut first_half = base.clone();
+        first_half.run_until(50);
+        assert_eq!(first_half.progress.as_ref().unwrap().next_tick(), 50);
+        let mut checkpoint = Vec::new();
+        first_half.checkpoint(&mut checkpoint).unwrap();
+        drop(first_half);
+
+        let mut resumed = Simulation::resume(checkpoint.as_slice()).unwrap();
+        let result = resumed.run();
+        assert_eq!(
+            resumed.fear_metrics.time_series,
+            reference.fear_metrics.time_series
+        );
+        assert_eq!(resumed.log.events, reference.log.events);
+        assert_eq!(result.ticks_executed, expected.ticks_executed);
+        assert_eq!(result.adoption_curves, expected.adoption_curves);
+        assert!(resumed.progress.is_none());
+    }
+
+    #[test]
+    fn checkpoints_of_another_version_are_rejected() {
+        let mut checkpoint = Vec::new();
+        sim(3, 1).checkpoint(&mut checkpoint).unwrap();
+        let text = String::from_utf8(checkpoint).unwrap();
+        let current = format!("\"version\":{CHECKPOINT_FORMAT_VERSION}");
+        assert!(text.contains(&current));
+        let other = text.replacen(&current, "\"version\":999", 1);
+        assert!(Simulation::resume(other.as_bytes()).is_err());
+    }
+
+    fn adoptions_of(sim: &Simulation, concept_id: ConceptId) -> usize {
+        sim.log.events.iter().filter(|(_, event)| matches!(event, SimEvent::Adopted { concept, .. } if *concept == concept_id)).count()
+    }
+
+    #[test]
+    fn prerequisites_gate_adoption() {
+        let mut base = sim(200, 2);
+        base.config.max_ticks = 1;
+        base.world.concepts.get_mut(&1).unwrap().prerequisites = vec![0];
+
+        // Nobody holds concept 0 when the first tick starts
+        let mut blocked = base.clone();
+        blocked.run();
+        assert!(adoptions_of(&blocked, 0) > 0);
+        assert_eq!(adoptions_of(&blocked, 1), 0);
+
+        let mut unblocked = base.clone();
+        for agent in &mut unblocked.agents {
+            agent.state.adopted_concepts.push(0);
+        }
+        unblocked.run();
+        assert!(adoptions_of(&unblocked, 1) > 0);
+
+        let mut soft = base.clone();
+        soft.config.behavior.soft_prereq_penalty = Some(0.5);
+        soft.run();
+        assert!(adoptions_of(&soft, 1) > 0);
+    }
+
+    #[test]
+    fn data_abuse_erodes_trust() {
+        let mut initial = sim(90, 3);
+        initial.config.max_ticks = 30;
+        initial.config.stop_conditions = Vec::new();
+        initial
+            .world
+            .concepts
+            .get_mut(&0)
+            .unwrap()
+            .risk_profile
+            .data_abuse_risk = 0.9;
+        for agent in &mut initial.agents {
+            agent.state.adopted_concepts.push(0);
+        }
+        let mut sim = initial.clone();
+        sim.run();
+
+        let series = &sim.fear_metrics.trust_by_region[&0];
+        assert_eq!(series.len(), 30);
+        assert!(series.last().unwrap().1 < series[0].1, "{series:?}");
+        let replayed = Simulation::replay(&initial, &sim.log);
+        assert_eq!(
+            replayed.fear_metrics.trust_by_region,
+            sim.fear_metrics.trust_by_region
+        );
+    }
+
+    #[test]
+    fn distrust_repels_data_hungry_concepts() {
+        let mut base = sim(300, 4);
+        base.config.max_ticks = 5;
+        base.config.stop_conditions = Vec::new();
+        for concept in base.world.concepts.values_mut() {
+            concept.risk_profile.data_abuse_risk = 0.9;
+        }
+        for (region, concept, _) in base.world.exposure_field.entries() {
+            base.world.exposure_field.set(region, concept, 2.0);
+        }
+        let mut trusting = base.clone();
+        trusting.run();
+        let mut distrustful = base.clone();
+        for agent in &mut distrustful.agents {
+            agent.beliefs.trust_in_institutions = 0.0;
+        }
+        distrustful.config.behavior.trust.erosion_rate = 0.0;
+        distrustful.run();
+
+        let (low, high) = (adoptions_of(&distrustful, 0), adoptions_of(&trusting, 0));
+        assert!(low * 3 < high, "{low} distrustful vs {high} trusting");
+    }
+
+    #[test]
+    fn adoption_caps_hold_across_migrations() {
+        let mut sim = sim(300, 6);
+        sim.config.max_ticks = 40;
+        sim.config.stop_conditions = Vec::new();
+        for agent in &mut sim.agents {
+            agent.attrs.mobility_score = 0.6;
+        }
+        for (region, concept, _) in sim.world.exposure_field.entries() {
+            sim.world.exposure_field.set(region, concept, 2.0);
+        }
+        sim.policy.adoption_caps.insert((0, 1), 0.1);
+        let initial = sim.clone();
+
+        for tick in [5, 13, 27, 40] {
+            sim.run_until(tick);
+            let progress = sim.progress.as_ref().unwrap();
+            for concept in 0..2 {
+                for region in 0..3 {
+                    let scanned = sim
+                        .agents_in_region(region)
+                        .filter(|a| a.state.adopted_concepts.contains(&concept))
+                        .count() as u32;
+                    assert_eq!(
+                        progress.regional_adopters(concept, region),
+                        scanned,
+                        "tick {tick}, concept {concept}, region {region}"
+                    );
+                }
+            }
+        }
+        let mut checkpoint = Vec::new();
+        sim.checkpoint(&mut checkpoint).unwrap();
+        assert_eq!(
+            Simulation::resume(checkpoint.as_slice())
+                .unwrap()
+                .policy
+                .adoption_caps,
+            sim.policy.adoption_caps
+        );
+
+        sim.run();
+        assert!(sim.log.events.iter().any(|(_, event)| matches!(
+            event,
+            SimEvent::CapRejected {
+                concept: 0,
+                region: 1,
+                ..
+            }
+        )));
+        assert_eq!(
+            Simulation::replay(&initial, &sim.log).log.events,
+            sim.log.events
+        );
+    }
+
+    #[test]
+    fn a_zero_cap_blocks_adoption() {
+        let mut sim = sim(90, 7);
+        for agent in &mut sim.agents {
+            agent.attrs.mobility_score = 0.0;
+        }
+        sim.policy.adoption_caps.insert((0, 0), 0.0);
+        sim.run();
+        assert!(sim
+            .agents_in_region(0)
+            .all(|a| !a.state.adopted_concepts.contains(&0)));
+        assert!(sim
+            .agents_in_region(1)
+            .any(|a| a.state.adopted_concepts.contains(&0)));
+    }
+
+    #[test]
+    fn prohibited_concepts_are_denied_once_per_region_and_tick() {
+        let mut sim = sim(90, 8);
+        sim.config.stop_conditions = vec![];
+        sim.world.concepts.get_mut(&1).unwrap().legal_status = ConceptLegalStatus::Prohibited;
+        let initial = sim.clone();
+        let result = sim.run();
+
+        assert_eq!(adoptions_of(&sim, 1), 0);
+        let legal: Vec<_> = sim
+            .log
+            .denials
+            .iter()
+            .filter(|denial| denial.rule == DenialRule::LegalStatus)
+            .collect();
+        assert!(!legal.is_empty());
+        assert!(legal
+            .iter()
+            .all(|denial| denial.concept_id == Some(1) && denial.agent_id.is_none()));
+        let keys: std::collections::BTreeSet<_> = legal
+            .iter()
+            .map(|denial| (denial.tick, denial.region))
+            .collect();
+        assert_eq!(keys.len(), legal.len());
+        assert!(legal.len() <= 3 * 10);
+        assert_eq!(
+            result.denials_by_rule[&DenialRule::LegalStatus],
+            legal.len() as u64
+        );
+        assert_eq!(
+            Simulation::replay(&initial, &sim.log).log.denials,
+            sim.log.denials
+        );
+    }
+
+    fn regional_fear_under(mode: AggregationMode) -> f32 {
+        let mut sim = sim(90, 9);
+        sim.config.max_ticks = 1;
+        sim.config.stop_conditions = vec![];
+        sim.config.fear.contagion = 0.0;
+        sim.config.fear.half_life_ticks = 1e9;
+        sim.config.fear.adoption_gain = 0.0;
+        sim.config.fear_aggregation = mode;
+        for agent in &mut sim.agents {
+            agent.attrs.mobility_score = 0.0;
+            agent.state.fear_level = match agent.id {
+                0 => 1.0,
+                id if id % 3 == 0 && id < 30 => 0.5,
+                _ => 0.1,
+            };
+        }
+        sim.run();
+        sim.fear_metrics.by_region[&0]
+    }
+
+    #[test]
+    fn aggregation_modes_diverge_on_a_skewed_region() {
+        let max = regional_fear_under(AggregationMode::Max);
+        let mean = regional_fear_under(AggregationMode::Mean);
+        let median = regional_fear_under(AggregationMode::Percentile(50.0));
+        assert!((max - 1.0).abs() < 1e-4, "{max}");
+        assert!((median - 0.1).abs() < 1e-4, "{median}");
+        assert!(mean > median + 0.05 && mean < max - 0.5, "{mean}");
+    }
+
+    #[test]
+    fn mean_aggregation_averages_over_ticks() {
+        let mut sim = sim(30, 1);
+        sim.config.max_ticks = 12;
+        sim.config.stop_conditions = vec![];
+        sim.config.fear_aggregation = AggregationMode::Mean;
+        sim.run();
+        let series = &sim.fear_metrics.region_series[&1];
+        let mean = series.iter().map(|(_, fear)| fear).sum::<f32>() / series.len() as f32;
+        assert!((mean - sim.fear_metrics.by_region[&1]).abs() < 1e-5);
+    }
+
+    #[test]
+    fn a_breach_names_the_region_and_concept_behind_it() {
+        let mut sim = sim(90, 10);
+        sim.config.max_ticks = 30;
+        sim.config.fear.contagion = 0.0;
+        for agent in &mut sim.agents {
+            agent.attrs.mobility_score = 0.0;
+            agent.state.fear_level = 0.0;
+        }
+        sim.world
+            .concepts
+            .get_mut(&1)
+            .unwrap()
+            .risk_profile
+            .expected_fear = 0.9;
+        sim.policy.ethical_ceiling.max_fear_index = 0.99;
+        sim.policy.ethical_ceiling.max_eco_damage = 1.0;
+        sim.policy.region_ceilings.insert(
+            2,
+            EthicalCeiling {
+                max_fear_index: 0.3,
+                max_eco_damage: 1.0,
+                forbid_irreversible_bio: true,
+                max_irreversible_bio_risk: 0.0,
+            },
+        );
+        let result = sim.run();
+
+        assert!(result.stopped_by_ethical_ceiling);
+        let breach = result.ceiling_breach.expect("breach");
+        assert_eq!(breach.metric, CeilingMetric::RegionFear(2));
+        assert_eq!(breach.worst_region, Some(2));
+        assert_eq!(breach.top_concept, Some(1));
+        assert!(breach.tick.is_some());
+        let (_, last) = sim.log.events.last().unwrap();
+        assert!(matches!(last, SimEvent::CeilingViolated { .. }));
+        let line = last.to_string();
+        assert!(
+            line.contains("region 2") && line.contains("concept 1"),
+            "{line}"
+        );
+    }
+
+    #[test]
+    fn illegal_adopts_are_dropped_at_apply() {
+        let mut sim = sim(30, 11);
+        sim.world.concepts.get_mut(&1).unwrap().legal_status = ConceptLegalStatus::Prohibited;
+        sim.rebuild_region_index();
+        let adopt = [
+            AgentAction::Adopt {
+                agent_id: 3,
+                concept_id: 1,
+            },
+            AgentAction::Share {
+                agent_id: 3,
+                concept_id: 1,
+                region: 0,
+            },
+        ];
+
+        let mut permissive = sim.clone();
+        sim.apply_actions(0, &adopt, &mut RunTally::default());
+        assert!(!sim.agents[3].state.adopted_concepts.contains(&1));
+        assert_eq!(sim.world.exposure_field.get(0, 1), 0.1);
+        let dropped: Vec<_> = sim
+            .log
+            .denials
+            .iter()
+            .filter(|denial| denial.rule == DenialRule::PostHocValidation)
+            .collect();
+        assert_eq!(dropped.len(), 2);
+        assert!(dropped
+            .iter()
+            .all(|denial| denial.agent_id == Some(3) && denial.concept_id == Some(1)));
+        assert!(
+            dropped[0].details.contains("legal status"),
+            "{}",
+            dropped[0].details
+        );
+
+        permissive.config.enforce_at_apply = false;
+        permissive.apply_actions(0, &adopt, &mut RunTally::default());
+        assert!(permissive.agents[3].state.adopted_concepts.contains(&1));
+        assert!(permissive.log.denials.is_empty());
+    }
+
+    fn exposure_in_region_0(sharer_influence: f32) -> f32 {
+        let mut sim = sim(60, 12);
+        sim.config.max_ticks = 15;
+        sim.config.stop_conditions = vec![];
+        for agent in &mut sim.agents {
+            agent.attrs.mobility_score = 0.0;
+            agent.attrs.influence = 0.0;
+        }
+        for concept in sim.world.concepts.values_mut() {
+            concept.risk_profile.expected_fear = 0.0;
+        }
+        sim.agents[0].attrs.influence = sharer_influence;
+        sim.run();
+        sim.world
+            .exposure_field
+            .entries()
+            .iter()
+            .filter(|(region, _, _)| *region == 0)
+            .map(|(_, _, exposure)| exposure)
+            .sum()
+    }
+
+    #[test]
+    fn influential_sharers_raise_exposure_more() {
+        let isolated = exposure_in_region_0(0.0);
+        let hub = exposure_in_region_0(1.0);
+        assert!(hub > isolated + 0.05, "{hub} vs {isolated}");
+    }
+
+    #[test]
+    fn share_gain_is_capped_per_tick() {
+        let mut sim = sim(300, 13);
+        sim.config.max_ticks = 1;
+        sim.config.stop_conditions = vec![];
+        sim.config.share.max_gain_per_tick = 0.3;
+        for agent in &mut sim.agents {
+            agent.attrs.mobility_score = 0.0;
+            agent.attrs.influence = 1.0;
+        }
+        let before = sim.world.exposure_field.clone();
+        sim.run();
+        for (region, concept, exposure) in sim.world.exposure_field.entries() {
+            assert!(exposure - before.get(region, concept) <= 0.3 + 1e-5);
+        }
+    }
+
+    #[test]
+    fn identical_runs_serialize_identically() {
+        let run = || {
+            let mut sim = sim(60, 7);
+            sim.run();
+            serde_json::to_vec(&sim).unwrap()
+        };
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn region_index_matches_a_recount_after_migration() {
+        let mut sim = sim(300, 3);
+        sim.config.max_ticks = 60;
+        sim.config.stop_conditions = vec![];
+        for agent in &mut sim.agents {
+            agent.attrs.mobility_score = 1.0;
+        }
+        for end in (5..=60).step_by(5) {
+            sim.run_until(end);
+            let mut recount: HashMap<RegionId, Vec<usize>> = HashMap::new();
+            for (i, agent) in sim.agents.iter().enumerate() {
+                recount.entry(agent.state.region).or_default().push(i);
+            }
+            let mut index = sim.region_index.clone();
+            index.retain(|_, residents| !residents.is_empty());
+            for residents in index.values_mut() {
+                residents.sort_unstable();
+            }
+            assert_eq!(index, recount);
+            for region in 0..3 {
+                assert_eq!(
+                    sim.agents_in_region(region).count(),
+                    recount.get(&region).map_or(0, Vec::len)
+                );
+            }
+        }
+        let moves = event_lines(&sim)
+            .iter()
+            .filter(|line| line.contains("moved"))
+            .count();
+        assert!(moves > 200, "{moves}");
+    }
+
+    #[test]
+    fn a_warm_up_breach_is_recorded_but_does_not_stop() {
+        let scenario = |warmup| {
+            let mut sim = sim(60, 1);
+            sim.config.max_ticks = 20;
+            sim.config.stop_conditions = vec![StopCondition::EthicalCeiling];
+            sim.config.fear.half_life_ticks = 2.0;
+            sim.config.fear.adoption_gain = 0.0;
+            sim.config.ceiling_warmup_ticks = warmup;
+            sim.policy.ethical_ceiling.max_fear_index = 0.5;
+            sim.policy.ethical_ceiling.max_eco_damage = 0.5;
+            for agent in &mut sim.agents {
+                agent.state.fear_level = 0.95;
+                agent.attrs.mobility_score = 0.0;
+            }
+            sim
+        };
+
+        let result = scenario(0).run();
+        assert!(result.stopped_by_ethical_ceiling);
+        assert_eq!(result.ticks_executed, 1);
+        assert!(result.warmup_ceiling_breach.is_none());
+
+        let mut sim = scenario(5);
+        let result = sim.run();
+        assert!(
+            !result.stopped_by_ethical_ceiling,
+            "{:?}",
+            result.ceiling_breach
+        );
+        assert_eq!(result.ticks_executed, 20);
+        let breach = result.warmup_ceiling_breach.expect("warm-up breach");
+        assert_eq!(breach.tick, Some(0));
+        assert!(sim.fear_metrics.time_series[0].1 > 0.5);
+    }
+
+    fn fear_driven_by_broadcast(response: CeilingResponse) -> Simulation {
+        let mut sim = sim(300, 4);
+        sim.config.max_ticks = 60;
+        sim.config.stop_conditions = vec![StopCondition::EthicalCeiling];
+        sim.config.fear.adoption_gain = 0.2;
+        sim.policy.ethical_ceiling.max_fear_index = 0.2;
+        sim.policy.ceiling_response = response;
+        sim.world.exposure_field.clear();
+        for agent in &mut sim.agents {
+            agent.state.fear_level = 0.0;
+            agent.beliefs.openness_to_change = 0.1;
+        }
+        for concept in sim.world.concepts.values_mut() {
+            concept.risk_profile.expected_fear = 0.5;
+            concept.attrs.attractiveness = 0.1;
+        }
+        sim.broadcasts = BroadcastSchedule {
+            entries: vec![Broadcast {
+                concept_id: 0,
+                regions: None,
+                start_tick: 0,
+                end_tick: 60,
+                intensity_per_tick: 0.05,
+                budget: 1e9,
+            }],
+        };
+        sim
+    }
+
+    #[test]
+    fn throttling_holds_fear_under_the_ceiling() {
+        let mut hard = fear_driven_by_broadcast(CeilingResponse::HardStop);
+        let result = hard.run();
+        assert!(result.stopped_by_ethical_ceiling);
+        assert!(result.ticks_executed < 60);
+
+        let mut soft = fear_driven_by_broadcast(CeilingResponse::Throttle {
+            exposure_multiplier: 0.0,
+            recovery_margin: 0.5,
+        });
+        let result = soft.run();
+        let peak = soft
+            .fear_metrics
+            .time_series
+            .iter()
+            .map(|(_, fear)| *fear)
+            .fold(0.0, f32::max);
+        assert!(!result.stopped_by_ethical_ceiling, "peak {peak}");
+        assert_eq!(result.ticks_executed, 60);
+        assert!(peak <= 0.2, "{peak}");
+        assert!(soft
+            .log
+            .events
+            .iter()
+            .any(|(_, event)| matches!(event, SimEvent::Throttled { engaged: true })));
+        assert!(!soft
+            .log
+            .events
+            .iter()
+            .any(|(_, event)| matches!(event, SimEvent::CeilingViolated { .. })));
+    }
+
+    #[test]
+    fn under_age_agents_never_adopt_an_adults_only_concept() {
+        let mut sim = sim(120, 9);
+        sim.config.max_ticks = 200;
+        sim.config.stop_conditions = vec![];
+        sim.policy.attribute_rules = vec![AttributeRule {
+            concept_id: 0,
+            min_age: Some(18),
+            max_age: None,
+            min_income: None,
+            predicate_tag: None,
+        }];
+        for agent in &mut sim.agents {
+            if agent.id % 2 == 0 {
+                agent.attrs.age = 15;
+            }
+            agent.beliefs.openness_to_change = 0.9;
+        }
+        let mut forged = sim.clone();
+        let result = sim.run();
+
+        assert_eq!(result.ticks_executed, 200);
+        let mut adult_adoptions = 0;
+        for (_, event) in &sim.log.events {
+            if let SimEvent::Adopted { agent, concept: 0 }
+            | SimEvent::Shared {
+                agent, concept: 0, ..
+            } = event
+            {
+                assert_eq!(agent % 2, 1, "{event}");
+                adult_adoptions += 1;
+            }
+        }
+        assert!(adult_adoptions > 0);
+        assert!(result.denials_by_rule[&DenialRule::AgentAttributes] > 0);
+
+        forged.rebuild_region_index();
+        forged.apply_actions(
+            0,
+            &[AgentAction::Adopt {
+                agent_id: 0,
+                concept_id: 0,
+            }],
+            &mut RunTally::default(),
+        );
+        assert!(!forged.agents[0].state.adopted_concepts.contains(&0));
+        assert_eq!(forged.log.denials[0].rule, DenialRule::PostHocValidation);
+    }
+
+    #[test]
+    fn concepts_launch_and_sunset_on_schedule() {
+        let mut sim = sim(150, 5);
+        sim.config.max_ticks = 60;
+        sim.config.stop_conditions = vec![];
+        for agent in &mut sim.agents {
+            agent.beliefs.openness_to_change = 0.9;
+        }
+        let concept = sim.world.concepts.get_mut(&0).unwrap();
+        concept.available_from = 10;
+        concept.sunset_at = Some(30);
+        concept.sunset_abandon_rate = 0.2;
+        sim.world.concepts.get_mut(&1).unwrap().legal_status = ConceptLegalStatus::Prohibited;
+        let result = sim.run();
+
+        let curve = &result.adoption_curves[&0];
+        assert!(
+            curve[..10].iter().all(|&adopters| adopters == 0),
+            "{curve:?}"
+        );
+        for (tick, event) in &sim.log.events {
+            if matches!(
+                event,
+                SimEvent::Adopted { concept: 0, .. } | SimEvent::Shared { concept: 0, .. }
+            ) {
+                assert!((10..30).contains(tick), "{tick} {event}");
+            }
+        }
+        let peak = curve[29];
+        assert!(peak > 20, "{curve:?}");
+        assert!(curve[31] < peak, "{curve:?}");
+        assert!(curve[59] < peak / 10, "{curve:?}");
+    }
+
+    /// Blended and raw fear of region 2 after one tick next to a fearful
+    /// region 0.
+    fn fear_next_to_a_fearful_region(regional_contagion: f32) -> (f32, f32) {
+        let mut sim = sim(90, 1);
+        sim.config.max_ticks = 2;
+        sim.config.stop_conditions = vec![];
+        sim.config.fear.regional_contagion = regional_contagion;
+        sim.world.exposure_field.clear();
+        for agent in &mut sim.agents {
+            agent.attrs.mobility_score = 0.0;
+            agent.state.fear_level = if agent.state.region == 0 { 0.9 } else { 0.1 };
+        }
+        sim.run();
+        (
+            sim.fear_metrics.regional_fear[&2][1].1,
+            sim.fear_metrics.region_series[&2][1].1,
+        )
+    }
+
+    #[test]
+    fn fear_spills_into_neighboring_regions() {
+        let (isolated, raw) = fear_next_to_a_fearful_region(0.0);
+        assert!((isolated - raw).abs() < 1e-6);
+        let (blended, _) = fear_next_to_a_fearful_region(0.3);
+        assert!(blended > isolated + 0.1, "{blended} vs {isolated}");
+    }
+
+    fn adopters_with_regret(regret: f32, weight: f32) -> u32 {
+        let mut sim = sim(300, 3);
+        sim.config.max_ticks = 3;
+        sim.config.stop_conditions = vec![];
+        sim.config.behavior.regret.weight = weight;
+        for agent in &mut sim.agents {
+            agent.attrs.mobility_score = 0.0;
+            agent.state.regret = regret;
+        }
+        for concept in sim.world.concepts.values_mut() {
+            concept.risk_profile.expected_fear = 0.8;
+        }
+        let result = sim.run();
+        result
+            .adoption_curves
+            .values()
+            .map(|curve| *curve.last().unwrap())
+            .sum()
+    }
+
+    #[test]
+    fn regret_dampens_adoption() {
+        let base = adopters_with_regret(0.0, 1.0);
+        assert!(adopters_with_regret(1.0, 1.0) < base);
+        assert_eq!(adopters_with_regret(1.0, 0.0), base);
+    }
+
+    #[test]
+    fn regret_builds_from_fearful_adoptions() {
+        let mut sim = sim(60, 4);
+        sim.config.stop_conditions = vec![];
+        sim.config.behavior.regret.decay_per_tick = 0.0;
+        for agent in &mut sim.agents {
+            agent.state.fear_level = 0.9;
+            agent.beliefs.openness_to_change = 0.0;
+        }
+        for concept in sim.world.concepts.values_mut() {
+            concept.risk_profile.expected_fear = 1.0;
+        }
+        let result = sim.run();
+
+        assert!(
+            result
+                .churn
+                .iter()
+                .map(|point| point.abandonments)
+                .sum::<u32>()
+                > 0
+        );
+        assert!(sim.agents.iter().any(|agent| agent.state.regret > 0.0));
+        let series = &sim.fear_metrics.regret_by_region[&0];
+        assert_eq!(series.len(), 10);
+        assert!(series.last().unwrap().1 > 0.0);
+    }
+
+    #[test]
+    fn fearless_adoptions_leave_no_regret() {
+        let mut sim = sim(30, 5);
+        sim.config.max_ticks = 1;
+        sim.config.stop_conditions = vec![];
+        sim.config.fear.adoption_gain = 0.0;
+        sim.config.behavior.regret.decay_per_tick = 0.0;
+        for agent in &mut sim.agents {
+            agent.state.fear_level = 0.0;
+        }
+        for concept in sim.world.concepts.values_mut() {
+            concept.risk_profile.expected_fear = 0.0;
+            concept.attrs.attractiveness = 5.0;
+        }
+        sim.run();
+        assert!(sim
+            .agents
+            .iter()
+            .any(|agent| agent.state.last_adoption.is_some()));
+        assert!(sim.agents.iter().all(|agent| agent.state.regret == 0.0));
+    }
+
+    #[test]
+    fn inequality_is_tracked_only_when_asked() {
+        let mut sim = sim(60, 2);
+        sim.config.stop_conditions = vec![];
+        let mut tracked = sim.clone();
+        assert!(sim.run().inequality_series.is_empty());
+
+        tracked.config.track_inequality = true;
+        let result = tracked.run();
+        assert_eq!(result.inequality_series.len(), 2);
+        assert_eq!(result.inequality_series[&0].len(), 10);
+    }
+
+    #[test]
+    fn eco_damage_is_tracked_and_attributed_to_the_worst_region() {
+        let mut sim = sim(90, 2);
+        sim.config.fear.contagion = 0.0;
+        sim.config.fear.regional_contagion = 0.0;
+        sim.policy.ethical_ceiling.max_fear_index = 1.0;
+        sim.policy.ethical_ceiling.max_eco_damage = 0.2;
+        sim.world.exposure_field.clear();
+        sim.world.regions.get_mut(&0).unwrap().eco_vulnerability = 0.2;
+        sim.world.regions.get_mut(&2).unwrap().eco_vulnerability = 0.9;
+        for agent in &mut sim.agents {
+            agent.attrs.mobility_score = 0.0;
+            agent.state.fear_level = if agent.state.region == 2 { 0.8 } else { 0.1 };
+        }
+        let result = sim.run();
+
+        let metrics = &sim.fear_metrics;
+        let peak = metrics
+            .eco_damage_series
+            .iter()
+            .map(|(_, damage)| *damage)
+            .fold(0.0, f32::max);
+        assert_eq!(metrics.peak_eco_damage(), peak);
+        assert_eq!(metrics.worst_eco_region(), Some(2));
+        let breach = result.ceiling_breach.unwrap();
+        assert_eq!(breach.metric, CeilingMetric::EcoDamage);
+        assert_eq!(breach.worst_region, Some(2));
+    }
+
+    #[test]
+    fn tipping_points_are_reported_for_known_concepts() {
+        let mut sim = sim(300, 2);
+        sim.config.max_ticks = 60;
+        sim.config.stop_conditions = vec![];
+        let result = sim.run();
+        assert!(!result.tipping_points.is_empty());
+        assert!(result
+            .tipping_points
+            .keys()
+            .all(|concept| result.adoption_curves.contains_key(concept)));
+    }
+
+    fn without_fear(n: u64) -> Simulation {
+        let mut sim = sim(n, 7);
+        sim.config.max_ticks = 150;
+        sim.config.stop_conditions = vec![];
+        for concept in sim.world.concepts.values_mut() {
+            concept.risk_profile.expected_fear = 0.0;
+        }
+        sim
+    }
+
+    #[test]
+    fn a_scheduled_ban_stops_adoption_from_its_tick() {
+        let mut sim = without_fear(200);
+        sim.schedule_intervention(100, Intervention::BanConcept { concept_id: 1 });
+        let result = sim.run();
+
+        let adoptions = |ticks: std::ops::Range<Tick>| {
+            sim.log
+                .events
+                .iter()
+                .filter(|(tick, event)| {
+                    ticks.contains(tick) && matches!(event, SimEvent::Adopted { concept: 1, .. })
+                })
+                .count()
+        };
+        assert!(adoptions(0..100) > 0);
+        assert_eq!(adoptions(100..150), 0);
+        assert!(result.adoption_curves[&1][149] > 0);
+        let intervened: Vec<Tick> = sim
+            .log
+            .events
+            .iter()
+            .filter(|(_, event)| matches!(event, SimEvent::Intervened { .. }))
+            .map(|(tick, _)| *tick)
+            .collect();
+        assert_eq!(intervened, vec![100]);
+        assert!(sim.interventions.is_empty());
+    }
+
+    /// Closes region 2 the tick after tick 20 ends.
+    struct CloseAfterTick20(bool);
+
+    impl SimObserver for CloseAfterTick20 {
+        fn on_tick_end(
+            &mut self,
+            tick: Tick,
+            _metrics: &FearIndexMetrics,
+        ) -> std::ops::ControlFlow<()> {
+            self.0 |= tick == 20;
+            std::ops::ControlFlow::Continue(())
+        }
+
+        fn interventions(&mut self, _tick: Tick) -> Vec<Intervention> {
+            if std::mem::take(&mut self.0) {
+                vec![Intervention::CloseRegion { region: 2 }]
+            } else {
+                Vec::new()
+            }
+        }
+    }
+
+    #[test]
+    fn observer_interventions_apply_and_replay() {
+        let mut sim = without_fear(200);
+        for agent in &mut sim.agents {
+            agent.attrs.mobility_score = 0.9;
+        }
+        let initial = sim.clone();
+        sim.run_with_observer(&mut CloseAfterTick20(false));
+
+        assert!(sim.policy.closed_regions.contains(&2));
+        assert!(sim
+            .log
+            .events
+            .iter()
+            .any(|(tick, event)| *tick == 21 && matches!(event, SimEvent::Intervened { .. })));
+        assert!(!sim
+            .log
+            .events
+            .iter()
+            .any(|(tick, event)| *tick > 21 && matches!(event, SimEvent::Moved { to: 2, .. })));
+        assert_eq!(
+            Simulation::replay(&initial, &sim.log).log.events,
+            sim.log.events
+        );
+    }
+
+    /// Recorded before the exposure field was flattened; a seeded run must
+    /// not move.
+    #[test]
+    fn a_seeded_run_matches_its_recorded_output() {
+        let mut sim = sim(400, 11);
+        sim.config.max_ticks = 60;
+        sim.config.stop_conditions = vec![];
+        let result = sim.run();
+
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for (_, event) in &sim.log.events {
+            let kind = match event {
+                SimEvent::Shared { .. } => "shared",
+                SimEvent::Moved { .. } => "moved",
+                SimEvent::Adopted { .. } => "adopted",
+                SimEvent::Abandoned { .. } => "abandoned",
+                _ => "other",
+            };
+            *counts.entry(kind).or_default() += 1;
+        }
+        assert_eq!(
+            counts,
+            BTreeMap::from([
+                ("abandoned", 1286),
+                ("adopted", 2069),
+                ("moved", 7186),
+                ("other", 1),
+                ("shared", 12018),
+            ])
+        );
+        assert_eq!(result.adoption_curves[&0][59], 392);
+        assert_eq!(result.adoption_curves[&1][59], 391);
+        assert_eq!(sim.fear_metrics.time_series[59], (59, 0.16278364));
+    }
+
+    #[test]
+    fn fear_is_attributed_to_the_concept_that_raised_it() {
+        let mut sim = sim(150, 3);
+        sim.config.max_ticks = 20;
+        sim.config.stop_conditions = vec![];
+        sim.config.fear.contagion = 0.0;
+        sim.config.fear.regional_contagion = 0.0;
+        sim.config.fear.baseline = 0.0;
+        sim.world
+            .concepts
+            .get_mut(&0)
+            .unwrap()
+            .risk_profile
+            .expected_fear = 0.6;
+        sim.world
+            .concepts
+            .get_mut(&1)
+            .unwrap()
+            .risk_profile
+            .expected_fear = 0.0;
+        for agent in &mut sim.agents {
+            agent.state.fear_level = 0.0;
+            agent.attrs.mobility_score = 0.0;
+        }
+        let result = sim.run();
+
+        assert!(result.adoption_curves[&1][19] > 0);
+        assert_eq!(result.fear_drivers.len(), 1, "{:?}", result.fear_drivers);
+        assert_eq!(result.fear_drivers[0].source, FearSource::Concept(0));
+        assert!(result.fear_drivers[0].fear > 0.0);
+    }
+
+    #[test]
+    fn a_breach_lists_its_largest_fear_drivers() {
+        let mut sim = sim(150, 3);
+        sim.config.max_ticks = 50;
+        sim.config.fear.contagion = 0.3;
+        sim.policy.ethical_ceiling.max_fear_index = 0.3;
+        sim.world
+            .concepts
+            .get_mut(&0)
+            .unwrap()
+            .risk_profile
+            .expected_fear = 0.6;
+        let result = sim.run();
+
+        assert!(result
+            .fear_drivers
+            .iter()
+            .any(|driver| driver.source == FearSource::Contagion));
+        assert!(result
+            .fear_drivers
+            .windows(2)
+            .all(|pair| pair[0].fear >= pair[1].fear));
+        let breach = result.ceiling_breach.expect("breach");
+        assert!((1..=3).contains(&breach.fear_drivers.len()));
+    }
+
+    #[test]
+    fn funnel_stages_only_narrow() {
+        for threshold in [0.0, 0.15] {
+            let mut sim = sim(300, 9);
+            sim.config.max_ticks = 30;
+            sim.config.stop_conditions = vec![];
+            sim.config.funnel_exposure_threshold = threshold;
+            sim.policy.attribute_rules.push(AttributeRule {
+                concept_id: 1,
+                min_age: Some(40),
+                max_age: None,
+                min_income: None,
+                predicate_tag: None,
+            });
+            for agent in sim.agents.iter_mut().step_by(2) {
+                agent.attrs.age = 50;
+            }
+            let result = sim.run();
+
+            let funnel = &sim.fear_metrics.funnel;
+            assert_eq!(funnel.by_concept.len(), 2);
+            let mut adopted = 0;
+            for (_, point) in funnel.by_concept.values().flatten() {
+                assert!(
+                    point.exposed >= point.considered && point.considered >= point.adopted,
+                    "{point:?}"
+                );
+                adopted += point.adopted;
+            }
+            assert!(funnel.by_concept[&1]
+                .iter()
+                .any(|(_, point)| point.considered < point.exposed));
+            if threshold == 0.0 {
+                assert!(adopted >= result.churn.iter().map(|point| point.adoptions).sum());
+            }
+        }
+    }
+
+    /// Actions of every tick, as observers see them.
+    #[derive(Default)]
+    struct ActionRecorder(Vec<Vec<AgentAction>>);
+
+    impl SimObserver for ActionRecorder {
+        fn on_actions(
+            &mut self,
+            _tick: Tick,
+            actions: &[AgentAction],
+        ) -> std::ops::ControlFlow<()> {
+            self.0.push(actions.to_vec());
+            std::ops::ControlFlow::Continue(())
+        }
+    }
+
+    #[test]
+    fn reused_buffers_emit_the_same_actions_as_fresh_ones() {
+        let mut sim = sim(300, 11);
+        sim.config.stop_conditions = vec![];
+        let initial = sim.clone();
+        sim.agents.reverse();
+        let mut recorder = ActionRecorder::default();
+        sim.run_with_observer(&mut recorder);
+
+        // Tick 0 the way `Agent::step` used to run: a fresh buffer per agent,
+        // concatenated in id order.
+        let world = initial.world.view(0);
+        let social = SocialView::new(None, &initial.agents, &initial.social_exposure);
+        let rules = StepRules {
+            policy: &initial.policy,
+            behavior: &initial.config.behavior,
+            adoption: initial.adoption_model.as_ref(),
+        };
+        let mut expected = Vec::new();
+        for mut agent in initial.agents.clone() {
+            let mut rng = agent_rng(initial.config.random_seed, agent.id);
+            let mut out = StepOutput::new(0.0);
+            agent.step(&world, &social, &rules, &mut rng, &mut out);
+            expected.extend(out.actions);
+        }
+
+        assert!(!expected.is_empty());
+        assert_eq!(recorder.0[0], expected);
+        assert_eq!(recorder.0.len(), 10);
+    }
+
+    fn strict_ceiling() -> Simulation {
+        let mut sim = sim(300, 4);
+        sim.config.max_ticks = 40;
+        sim.policy.ethical_ceiling.max_fear_index = 0.25;
+        sim
+    }
+
+    #[test]
+    fn advisory_runs_report_where_enforcement_would_stop() {
+        let enforced = strict_ceiling().run();
+        assert!(enforced.stopped_by_ethical_ceiling);
+        assert!(enforced.advisory.is_none());
+
+        let mut sim = strict_ceiling();
+        sim.policy.enforcement = EnforcementMode::Advisory;
+        let result = sim.run();
+        assert!(!result.stopped_by_ethical_ceiling);
+        assert_eq!(result.ticks_executed, 40);
+        let advisory = result.advisory.unwrap();
+        assert_eq!(advisory.stop_tick, Some(enforced.ticks_executed - 1));
+        assert_eq!(advisory.breach, enforced.ceiling_breach);
+        assert_eq!(advisory.margins.len(), 40);
+        assert!(advisory.ticks_in_breach >= 1);
+        assert_eq!(
+            advisory.ticks_in_breach as usize,
+            advisory
+                .margins
+                .iter()
+                .filter(|margin| margin.is_breach())
+                .count()
+        );
+        let first = &advisory.margins[0];
+        assert!((first.fear - (0.25 - sim.fear_metrics.time_series[0].1)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn advisory_stop_ticks_respect_warm_up_and_clean_runs() {
+        let mut sim = strict_ceiling();
+        sim.config.ceiling_warmup_ticks = 3;
+        let enforced = sim.clone().run();
+        sim.policy.enforcement = EnforcementMode::Advisory;
+        let advisory = sim.run().advisory.unwrap();
+        assert_eq!(advisory.stop_tick, Some(enforced.ticks_executed - 1));
+        assert!(advisory.stop_tick.unwrap() >= 3);
+
+        let mut sim = strict_ceiling();
+        sim.policy.ethical_ceiling.max_fear_index = 0.99;
+        sim.policy.enforcement = EnforcementMode::Advisory;
+        let advisory = sim.run().advisory.unwrap();
+        assert_eq!((advisory.stop_tick, advisory.ticks_in_breach), (None, 0));
+    }
+
+    #[test]
+    fn no_variants_appear_without_mutation() {
+        let mut sim = sim(300, 8);
+        sim.config.max_ticks = 30;
+        sim.config.stop_conditions = vec![];
+        let result = sim.run();
+        assert_eq!(sim.world.concepts.len(), 2);
+        assert!(sim.fear_metrics.lineage.is_empty());
+        assert!(result.family_adoption_curves.is_empty());
+    }
+
+    #[test]
+    fn variants_inherit_status_and_sum_into_their_family() {
+        let mut sim = sim(300, 8);
+        sim.config.max_ticks = 30;
+        sim.config.stop_conditions = vec![];
+        sim.config.mutation.rate = 0.5;
+        sim.config.mutation.max_variants = 40;
+        sim.world.concepts.get_mut(&1).unwrap().legal_status = ConceptLegalStatus::Restricted;
+        sim.policy.restricted_allow_regions.insert(1, vec![0, 1, 2]);
+        let initial = sim.clone();
+        let result = sim.run();
+
+        let variants: Vec<&Concept> = sim
+            .world
+            .concepts
+            .values()
+            .filter(|concept| concept.parent.is_some())
+            .collect();
+        assert_eq!(variants.len(), 40);
+        for variant in &variants {
+            let root = sim.fear_metrics.root_concept_of(variant.id);
+            assert!(root < 2);
+            assert_eq!(variant.legal_status, sim.world.concepts[&root].legal_status);
+            assert!((0.0..=1.0).contains(&variant.attrs.attractiveness));
+        }
+        assert!(variants
+            .iter()
+            .any(|variant| variant.legal_status == ConceptLegalStatus::Restricted));
+        assert!(result.adoption_curves.keys().any(|concept| *concept >= 2));
+        for (root, family) in &result.family_adoption_curves {
+            for (tick, adopters) in family.iter().enumerate() {
+                let members: u32 = result
+                    .adoption_curves
+                    .iter()
+                    .filter(|(concept, _)| sim.fear_metrics.root_concept_of(**concept) == *root)
+                    .map(|(_, curve)| curve[tick])
+                    .sum();
+                assert_eq!(*adopters, members);
+            }
+        }
+
+        let replayed = Simulation::replay(&initial, &sim.log);
+        assert_eq!(replayed.world.concepts.len(), sim.world.concepts.len());
+        assert_eq!(event_lines(&replayed), event_lines(&sim));
+    }
+
+    #[test]
+    fn the_default_model_is_the_default_scoring_rule() {
+        let mut implicit = sim(200, 3);
+        implicit.config.max_ticks = 20;
+        let mut explicit = implicit.clone();
+        explicit.adoption_model = Box::new(DefaultAdoptionModel::default());
+        let (implicit_result, explicit_result) = (implicit.run(), explicit.run());
+        assert_eq!(
+            implicit_result.adoption_curves,
+            explicit_result.adoption_curves
+        );
+        assert_eq!(implicit.log.events, explicit.log.events);
+        let bits = |sim: &Simulation| -> Vec<u32> {
+            sim.fear_metrics
+                .time_series
+                .iter()
+                .map(|(_, fear)| fear.to_bits())
+                .collect()
+        };
+        assert_eq!(bits(&implicit), bits(&explicit));
+    }
+
+    #[test]
+    fn a_resumed_run_keeps_its_adoption_model() {
+        let mut base = sim(200, 3);
+        base.config.max_ticks = 20;
+        base.config.stop_conditions = vec![];
+        base.adoption_model = Box::new(LogisticRegressionModel {
+            weights: vec![-1.0, 2.0, 0.0, 1.0],
+        });
+        let mut reference = base.clone();
+        reference.run();
+
+        base.run_until(10);
+        let mut checkpoint = Vec::new();
+        base.checkpoint(&mut checkpoint).unwrap();
+        let mut resumed = Simulation::resume(checkpoint.as_slice()).unwrap();
+        assert_eq!(
+            resumed.adoption_model.spec(),
+            reference.adoption_model.spec()
+        );
+        resumed.run();
+        assert_eq!(resumed.log.events, reference.log.events);
     }
 }
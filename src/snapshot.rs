@@ -0,0 +1,228 @@
+//! Per-tick, per-region aggregates streamed out of a run, e.g. to animate a
+//! map.
+
+use crate::core::id::{ConceptId, RegionId, Tick};
+use crate::observer::SimObserver;
+use crate::sim::StopCondition;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::ops::ControlFlow;
+
+/// One region after one tick. Every concept in the world is listed, with 0
+/// where it has no adopters or exposure.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegionSnapshot {
+    pub tick: Tick,
+    pub region_id: RegionId,
+    /// Agents living in the region.
+    pub population: u32,
+    /// Mean fear of those agents; 0 when there are none.
+    pub mean_fear: f32,
+    pub adopters_by_concept: BTreeMap<ConceptId, u32>,
+    pub exposure_by_concept: BTreeMap<ConceptId, f32>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotFormat {
+    /// One `RegionSnapshot` JSON object per line.
+    #[default]
+    JsonLines,
+    /// Long format, one row per region and concept:
+    /// `tick,region_id,population,mean_fear,concept,adopters,exposure`.
+    Csv,
+}
+
+enum Sink<W: Write> {
+    Plain(W),
+    Gzip(GzEncoder<W>),
+}
+
+impl<W: Write> Write for Sink<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Sink::Plain(writer) => writer.write(buf),
+            Sink::Gzip(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Sink::Plain(writer) => writer.flush(),
+            Sink::Gzip(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Observer writing the region snapshots of every `every_n_ticks`-th tick
+/// (0 writes none) to a sink, optionally gzipped. The first write error is
+/// kept and returned by `finish`; nothing more is written after it, and
+/// the run is not stopped.
+pub struct SnapshotWriter<W: Write> {
+    pub format: SnapshotFormat,
+    pub every_n_ticks: Tick,
+    sink: Sink<W>,
+    header_written: bool,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> SnapshotWriter<W> {
+    pub fn new(writer: W, format: SnapshotFormat, every_n_ticks: Tick) -> Self {
+        Self {
+            format,
+            every_n_ticks,
+            sink: Sink::Plain(writer),
+            header_written: false,
+            error: None,
+        }
+    }
+
+    /// `new`, gzip-compressing everything written to `writer`.
+    pub fn gzip(writer: W, format: SnapshotFormat, every_n_ticks: Tick) -> Self {
+        Self {
+            format,
+            every_n_ticks,
+            sink: Sink::Gzip(GzEncoder::new(writer, Compression::default())),
+            header_written: false,
+            error: None,
+        }
+    }
+
+    /// Flush (ending the gzip stream, if any) and return the sink, or the
+    /// first error met while writing.
+    pub fn finish(self) -> io::Result<W> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        match self.sink {
+            Sink::Plain(mut writer) => {
+                writer.flush()?;
+                Ok(writer)
+            }
+            Sink::Gzip(encoder) => encoder.finish(),
+        }
+    }
+
+    fn write_snapshots(&mut self, snapshots: &[RegionSnapshot]) -> io::Result<()> {
+        match self.format {
+            SnapshotFormat::JsonLines => {
+                for snapshot in snapshots {
+                    serde_json::to_writer(&mut self.sink, snapshot)?;
+                    self.sink.write_all(b"\n")?;
+                }
+            }
+            SnapshotFormat::Csv => {
+                if !self.header_written {
+                    writeln!(
+                        self.sink,
+                        "tick,region_id,population,mean_fear,concept,adopters,exposure"
+                    )?;
+                    self.header_written = true;
+                }
+                for snapshot in snapshots {
+                    for (concept_id, adopters) in &snapshot.adopters_by_concept {
+                        let exposure = snapshot
+                            .exposure_by_concept
+                            .get(concept_id)
+                            .copied()
+                            .unwrap_or(0.0);
+                        writeln!(
+                            self.sink,
+                            "{},{},{},{},{concept_id},{adopters},{exposure}",
+                            snapshot.tick,
+                            snapshot.region_id,
+                            snapshot.population,
+                            snapshot.mean_fear
+                        )?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> SimObserver for SnapshotWriter<W> {
+    fn wants_region_snapshots(&self, tick: Tick) -> bool {
+        self.error.is_none() && self.every_n_ticks > 0 && tick.is_multiple_of(self.every_n_ticks)
+    }
+
+    fn on_region_snapshots(
+        &mut self,
+        _tick: Tick,
+        snapshots: &[RegionSnapshot],
+    ) -> ControlFlow<()> {
+        if let Err(error) = self.write_snapshots(snapshots) {
+            self.error = Some(error);
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn on_stop(&mut self, _reason: StopCondition) {
+        if let Err(error) = self.sink.flush() {
+            self.error.get_or_insert(error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::tests::sim;
+    use std::io::Read;
+
+    #[test]
+    fn json_lines_snapshots_round_trip() {
+        let mut sim = sim(90, 2);
+        sim.config.stop_conditions = vec![];
+        let mut writer = SnapshotWriter::new(Vec::new(), SnapshotFormat::JsonLines, 2);
+        assert_eq!(sim.run_with_observer(&mut writer).ticks_executed, 10);
+
+        let bytes = writer.finish().unwrap();
+        let rows: Vec<RegionSnapshot> = String::from_utf8(bytes)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(rows.len(), 5 * 3);
+        let row = rows
+            .iter()
+            .find(|row| row.tick == 8 && row.region_id == 1)
+            .unwrap();
+        assert_eq!(row.adopters_by_concept.len(), 2);
+        let population: u32 = rows
+            .iter()
+            .filter(|row| row.tick == 8)
+            .map(|row| row.population)
+            .sum();
+        assert_eq!(population, 90);
+    }
+
+    #[test]
+    fn gzipped_csv_counts_match_the_agents() {
+        let mut sim = sim(90, 2);
+        sim.config.stop_conditions = vec![];
+        let mut writer = SnapshotWriter::gzip(Vec::new(), SnapshotFormat::Csv, 1);
+        sim.run_with_observer(&mut writer);
+
+        let gz = writer.finish().unwrap();
+        let mut csv = String::new();
+        flate2::read::GzDecoder::new(&gz[..])
+            .read_to_string(&mut csv)
+            .unwrap();
+        assert_eq!(csv.lines().count(), 1 + 10 * 3 * 2);
+        let adopters = sim
+            .agents
+            .iter()
+            .filter(|agent| agent.state.region == 2 && agent.state.adopted_concepts.contains(&0))
+            .count();
+        let line = csv
+            .lines()
+            .find(|line| line.starts_with("9,2,") && line.split(',').nth(4) == Some("0"))
+            .unwrap();
+        assert_eq!(line.split(',').nth(5), Some(adopters.to_string().as_str()));
+    }
+}
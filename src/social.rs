@@ -0,0 +1,220 @@
+//! Word-of-mouth between agents along a social graph.
+//!
+//! Edges are directed and weighted: `graph[a]` lists whom `a` influences
+//! and is influenced by. The generators produce undirected graphs (an edge
+//! in each direction, weight 1).
+
+use crate::core::agent::Agent;
+use crate::core::id::{AgentId, ConceptId};
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// agent -> (neighbor, weight) edges
+pub type SocialGraph = BTreeMap<AgentId, Vec<(AgentId, f32)>>;
+
+/// How `generate_social_graph` connects agents.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum SocialGraphModel {
+    /// Watts-Strogatz: a ring where each agent knows its `k` nearest agents
+    /// (in id order), each edge rewired to a random agent with probability
+    /// `rewire`.
+    SmallWorld { k: usize, rewire: f32 },
+    /// Barabasi-Albert: agents join in id order and link to `m` earlier
+    /// agents chosen in proportion to their degree.
+    PreferentialAttachment { m: usize },
+}
+
+/// Graph over `agent_ids` under `model`, reproducible from `seed`.
+pub fn generate_social_graph(
+    agent_ids: &[AgentId],
+    model: SocialGraphModel,
+    seed: u64,
+) -> SocialGraph {
+    let mut ids = agent_ids.to_vec();
+    ids.sort_unstable();
+    ids.dedup();
+    let n = ids.len();
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    let mut adjacency: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); n];
+    fn link(adjacency: &mut [BTreeSet<usize>], a: usize, b: usize) {
+        adjacency[a].insert(b);
+        adjacency[b].insert(a);
+    }
+
+    match model {
+        SocialGraphModel::SmallWorld { k, rewire } => {
+            for i in 0..n {
+                for j in 1..=(k / 2).min(n.saturating_sub(1) / 2) {
+                    let mut target = (i + j) % n;
+                    if rng.gen::<f32>() < rewire {
+                        // Keep the lattice edge if no free agent turns up.
+                        for _ in 0..n {
+                            let candidate = rng.gen_range(0..n);
+                            if candidate != i && !adjacency[i].contains(&candidate) {
+                                target = candidate;
+                                break;
+                            }
+                        }
+                    }
+                    link(&mut adjacency, i, target);
+                }
+            }
+        }
+        SocialGraphModel::PreferentialAttachment { m } => {
+            // Fully connected seed of `m + 1` agents, then attachment by
+            // degree: `endpoints` lists each agent once per edge end.
+            let seed_size = (m + 1).min(n);
+            let mut endpoints = Vec::new();
+            for a in 0..seed_size {
+                for b in a + 1..seed_size {
+                    link(&mut adjacency, a, b);
+                    endpoints.extend([a, b]);
+                }
+            }
+            for i in seed_size..n {
+                let mut targets = BTreeSet::new();
+                while targets.len() < m.min(i) {
+                    let target = if endpoints.is_empty() {
+                        rng.gen_range(0..i)
+                    } else {
+                        endpoints[rng.gen_range(0..endpoints.len())]
+                    };
+                    targets.insert(target);
+                }
+                for target in targets {
+                    link(&mut adjacency, i, target);
+                    endpoints.extend([i, target]);
+                }
+            }
+        }
+    }
+
+    adjacency
+        .into_iter()
+        .enumerate()
+        .map(|(i, neighbors)| {
+            let edges = neighbors.into_iter().map(|j| (ids[j], 1.0)).collect();
+            (ids[i], edges)
+        })
+        .collect()
+}
+
+/// Social influence on each agent for one tick, computed before agents
+/// step: the weighted fraction of its neighbors that adopted each concept,
+/// plus exposure shared to it along edges.
+#[derive(Debug, Default)]
+pub struct SocialView {
+    influence: HashMap<AgentId, BTreeMap<ConceptId, f32>>,
+}
+
+impl SocialView {
+    pub fn new(
+        graph: Option<&SocialGraph>,
+        agents: &[Agent],
+        exposure: &BTreeMap<AgentId, BTreeMap<ConceptId, f32>>,
+    ) -> SocialView {
+        let Some(graph) = graph else {
+            return SocialView::default();
+        };
+        let adopted: HashMap<AgentId, &[ConceptId]> = agents
+            .iter()
+            .map(|agent| (agent.id, agent.state.adopted_concepts.as_slice()))
+            .collect();
+
+        let mut influence: HashMap<AgentId, BTreeMap<ConceptId, f32>> = HashMap::new();
+        for agent in agents {
+            let edges = graph.get(&agent.id).map_or(&[][..], Vec::as_slice);
+            let total: f32 = edges.iter().map(|(_, w)| w.max(0.0)).sum();
+            let mut by_concept: BTreeMap<ConceptId, f32> = BTreeMap::new();
+            if total > 0.0 {
+                for (neighbor, weight) in edges {
+                    for concept_id in adopted.get(neighbor).copied().unwrap_or_default() {
+                        *by_concept.entry(*concept_id).or_insert(0.0) += weight.max(0.0) / total;
+                    }
+                }
+            }
+            for (concept_id, received) in exposure.get(&agent.id).into_iter().flatten() {
+                *by_concept.entry(*concept_id).or_insert(0.0) += received;
+            }
+            by_concept.retain(|_, v| *v > 0.0);
+            if !by_concept.is_empty() {
+                influence.insert(agent.id, by_concept);
+            }
+        }
+        SocialView { influence }
+    }
+
+    /// Added to the regional exposure given to `AdoptionModel::probability`.
+    pub fn influence(&self, agent_id: AgentId, concept_id: ConceptId) -> f32 {
+        self.influence
+            .get(&agent_id)
+            .and_then(|m| m.get(&concept_id).copied())
+            .unwrap_or(0.0)
+    }
+
+    /// Concepts `agent_id` hears about socially, in id order.
+    pub fn concepts(&self, agent_id: AgentId) -> impl Iterator<Item = ConceptId> + '_ {
+        self.influence
+            .get(&agent_id)
+            .into_iter()
+            .flat_map(|m| m.keys().copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::tests::sim;
+
+    fn ids() -> Vec<AgentId> {
+        (0..50).collect()
+    }
+
+    #[test]
+    fn graphs_are_undirected_and_deterministic() {
+        let model = SocialGraphModel::PreferentialAttachment { m: 2 };
+        let graph = generate_social_graph(&ids(), model, 1);
+        for (agent, edges) in &graph {
+            assert!(edges.len() >= 2);
+            for (neighbor, _) in edges {
+                assert_ne!(agent, neighbor);
+                assert!(graph[neighbor].iter().any(|(back, _)| back == agent));
+            }
+        }
+        assert_eq!(graph, generate_social_graph(&ids(), model, 1));
+
+        let ring = generate_social_graph(
+            &ids(),
+            SocialGraphModel::SmallWorld { k: 4, rewire: 0.0 },
+            1,
+        );
+        assert!(ring.values().all(|edges| edges.len() == 4));
+    }
+
+    #[test]
+    fn concepts_spread_along_edges_within_one_region() {
+        let mut plain = sim(100, 2);
+        for agent in &mut plain.agents {
+            agent.state.region = 0;
+            agent.attrs.mobility_score = 0.0;
+            agent.state.fear_level = 0.0;
+        }
+        plain.world.exposure_field.clear();
+        plain.config.max_ticks = 30;
+        plain.config.stop_conditions = Vec::new();
+        plain.agents[0].state.adopted_concepts.push(0);
+        let mut connected = plain.clone();
+
+        // Without exposure or edges the seed adopter stays alone
+        let result = plain.run();
+        assert_eq!(result.adoption_curves[&0].last(), Some(&1));
+
+        connected.generate_social_graph(SocialGraphModel::SmallWorld { k: 4, rewire: 0.1 });
+        let result = connected.run();
+        let adopters = *result.adoption_curves[&0].last().unwrap();
+        assert!(adopters > 10, "{adopters}");
+        assert!(!connected.social_exposure.is_empty());
+    }
+}
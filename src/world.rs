@@ -1,58 +1,796 @@
-use crate::core::id::{RegionId, ConceptId};
+use crate::core::id::{AgentId, RegionId, ConceptId, Tick};
 use crate::concept::Concept;
-use std::collections::HashMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
 
-#[derive(Debug, Clone)]
+/// Exposure a concept needs in a region (or a neighbor) to be visible there.
+pub const VISIBILITY_THRESHOLD: f32 = 1e-3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Region {
     pub id: RegionId,
     pub name: String,
     pub population: u32,
     pub area_km2: f32,
     pub neighbors: Vec<RegionId>, // mobility topology
+    /// Optional per-edge weights aligned with `neighbors` (empty: all 1.0)
+    pub neighbor_weights: Vec<f32>,
+    /// Most agents the region admits by moving in; `None` is unbounded
+    pub capacity: Option<u32>,
     pub eco_vulnerability: f32,   // weight in ecological scoring
 }
 
-#[derive(Debug)]
+/// How a moving agent picks among its region's neighbors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MovementModel {
+    /// Every neighbor equally likely.
+    #[default]
+    Uniform,
+    /// Proportional to `Region::neighbor_weights`.
+    EdgeWeighted,
+    /// Gravity-style: proportional to the neighbor's population, times its
+    /// edge weight.
+    Gravity,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct World {
-    pub regions: HashMap<RegionId, Region>,
-    pub concepts: HashMap<ConceptId, Concept>,
-    /// region -> concept -> current exposure intensity
-    pub exposure_field: HashMap<RegionId, HashMap<ConceptId, f32>>,
+    // Ordered maps, so every pass over them (and every sum or random draw
+    // made along the way) runs in id order on every run.
+    pub regions: BTreeMap<RegionId, Region>,
+    pub concepts: BTreeMap<ConceptId, Concept>,
+    /// Current exposure intensity of each concept in each region
+    pub exposure_field: ExposureField,
+    /// region -> fear after the last tick: its residents' aggregated fear
+    /// plus what spilled over from neighbors (see
+    /// `FearDynamics::regional_contagion`)
+    #[serde(default)]
+    pub regional_fear: BTreeMap<RegionId, f32>,
+}
+
+/// Exposure intensity by region and concept, stored sparsely in one flat
+/// map: absent entries are 0, and entries lowered to 0 are dropped.
+/// Serialized as region -> concept -> intensity, in id order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExposureField {
+    values: HashMap<(RegionId, ConceptId), f32>,
+}
+
+impl ExposureField {
+    pub fn get(&self, region: RegionId, concept_id: ConceptId) -> f32 {
+        self.values.get(&(region, concept_id)).copied().unwrap_or(0.0)
+    }
+
+    pub fn set(&mut self, region: RegionId, concept_id: ConceptId, intensity: f32) {
+        if intensity > 0.0 {
+            self.values.insert((region, concept_id), intensity);
+        } else {
+            self.values.remove(&(region, concept_id));
+        }
+    }
+
+    /// Add `amount`, never going below 0.
+    pub fn add(&mut self, region: RegionId, concept_id: ConceptId, amount: f32) {
+        let intensity = self.get(region, concept_id) + amount;
+        self.set(region, concept_id, intensity.max(0.0));
+    }
+
+    /// Nonzero entries as `(region, concept, intensity)`, in id order.
+    pub fn entries(&self) -> Vec<(RegionId, ConceptId, f32)> {
+        let mut entries: Vec<_> = self
+            .values
+            .iter()
+            .map(|(&(region, concept_id), &intensity)| (region, concept_id, intensity))
+            .collect();
+        entries.sort_unstable_by_key(|&(region, concept_id, _)| (region, concept_id));
+        entries
+    }
+
+    /// Keep only the entries for which `keep(region, concept, intensity)`.
+    pub fn retain(&mut self, mut keep: impl FnMut(RegionId, ConceptId, f32) -> bool) {
+        self.values
+            .retain(|&(region, concept_id), intensity| keep(region, concept_id, *intensity));
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+}
+
+impl FromIterator<(RegionId, ConceptId, f32)> for ExposureField {
+    fn from_iter<I: IntoIterator<Item = (RegionId, ConceptId, f32)>>(iter: I) -> Self {
+        let mut field = ExposureField::default();
+        for (region, concept_id, intensity) in iter {
+            field.set(region, concept_id, intensity);
+        }
+        field
+    }
+}
+
+impl Serialize for ExposureField {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut nested: BTreeMap<RegionId, BTreeMap<ConceptId, f32>> = BTreeMap::new();
+        for (region, concept_id, intensity) in self.entries() {
+            nested.entry(region).or_default().insert(concept_id, intensity);
+        }
+        nested.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ExposureField {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let nested = BTreeMap::<RegionId, BTreeMap<ConceptId, f32>>::deserialize(deserializer)?;
+        Ok(nested
+            .into_iter()
+            .flat_map(|(region, by_concept)| {
+                by_concept
+                    .into_iter()
+                    .map(move |(concept_id, intensity)| (region, concept_id, intensity))
+            })
+            .collect())
+    }
 }
 
+/// A world (or its agents) a run cannot start from; see `World::validate`
+/// and `Simulation::validate`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorldValidationError {
+    UnknownNeighbor { region: RegionId, neighbor: RegionId },
+    /// `exposure_field` has an entry for a concept not in `concepts`.
+    UnknownExposureConcept { region: RegionId, concept_id: ConceptId },
+    /// `exposure_field` has an entry for a region not in `regions`.
+    UnknownExposureRegion { region: RegionId, concept_id: ConceptId },
+    EcoVulnerabilityOutOfRange { region: RegionId, value: f32 },
+    /// A region of population 0 that agents live in.
+    EmptyRegionHasAgents { region: RegionId, agents: Vec<AgentId> },
+    UnknownAgentRegion { agent: AgentId, region: RegionId },
+    RegionValueNan { region: RegionId, field: &'static str },
+    ConceptValueNan { concept_id: ConceptId, field: &'static str },
+    AgentValueNan { agent: AgentId, field: &'static str },
+}
+
+impl fmt::Display for WorldValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorldValidationError::UnknownNeighbor { region, neighbor } => {
+                write!(f, "region {region} lists unknown neighbor {neighbor}")
+            }
+            WorldValidationError::UnknownExposureConcept { region, concept_id } => {
+                write!(f, "exposure of unknown concept {concept_id} in region {region}")
+            }
+            WorldValidationError::UnknownExposureRegion { region, concept_id } => {
+                write!(f, "exposure of concept {concept_id} in unknown region {region}")
+            }
+            WorldValidationError::EcoVulnerabilityOutOfRange { region, value } => {
+                write!(f, "region {region} eco_vulnerability {value} is outside 0..1")
+            }
+            WorldValidationError::EmptyRegionHasAgents { region, agents } => {
+                write!(f, "region {region} has population 0 but agents {agents:?}")
+            }
+            WorldValidationError::UnknownAgentRegion { agent, region } => {
+                write!(f, "agent {agent} lives in unknown region {region}")
+            }
+            WorldValidationError::RegionValueNan { region, field } => {
+                write!(f, "region {region} {field} is NaN")
+            }
+            WorldValidationError::ConceptValueNan { concept_id, field } => {
+                write!(f, "concept {concept_id} {field} is NaN")
+            }
+            WorldValidationError::AgentValueNan { agent, field } => {
+                write!(f, "agent {agent} {field} is NaN")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WorldValidationError {}
+
 pub struct WorldView<'a> {
     world: &'a World,
+    tick: Tick,
+    /// region -> visible concepts in id order, computed once per view
+    visible: HashMap<RegionId, Vec<&'a Concept>>,
+}
+
+impl Region {
+    /// Weight of the edge to `neighbors[index]`; 1.0 when no weights are set.
+    pub fn neighbor_weight(&self, index: usize) -> f32 {
+        self.neighbor_weights.get(index).copied().unwrap_or(1.0)
+    }
 }
 
 impl World {
-    pub fn view(&self) -> WorldView<'_> {
-        WorldView { world: self }
+    /// Snapshot for agent decisions at `tick`. Each region's visible
+    /// concepts (available ones only) are precomputed here so agents only
+    /// borrow them.
+    pub fn view(&self, tick: Tick) -> WorldView<'_> {
+        let visible = self
+            .regions
+            .values()
+            .map(|region| {
+                let concepts = self
+                    .concepts
+                    .values()
+                    .filter(|concept| concept.is_available(tick))
+                    .filter(|concept| {
+                        std::iter::once(&region.id)
+                            .chain(&region.neighbors)
+                            .any(|r| self.exposure(concept.id, *r) > VISIBILITY_THRESHOLD)
+                    })
+                    .collect();
+                (region.id, concepts)
+            })
+            .collect();
+        WorldView {
+            world: self,
+            tick,
+            visible,
+        }
+    }
+
+    /// Every inconsistency that would make a run misbehave: unknown
+    /// neighbors, exposure of unknown concepts or in unknown regions,
+    /// `eco_vulnerability` outside 0..1 and NaN region or concept values.
+    /// Agents are checked by `Simulation::validate`.
+    pub fn validate(&self) -> Result<(), Vec<WorldValidationError>> {
+        let mut errors = Vec::new();
+        for region in self.regions.values() {
+            for neighbor in &region.neighbors {
+                if !self.regions.contains_key(neighbor) {
+                    errors.push(WorldValidationError::UnknownNeighbor {
+                        region: region.id,
+                        neighbor: *neighbor,
+                    });
+                }
+            }
+            if !(0.0..=1.0).contains(&region.eco_vulnerability) {
+                errors.push(WorldValidationError::EcoVulnerabilityOutOfRange {
+                    region: region.id,
+                    value: region.eco_vulnerability,
+                });
+            }
+            let values = [("area_km2", region.area_km2)]
+                .into_iter()
+                .chain(region.neighbor_weights.iter().map(|w| ("neighbor_weights", *w)));
+            for (field, value) in values {
+                if value.is_nan() {
+                    errors.push(WorldValidationError::RegionValueNan {
+                        region: region.id,
+                        field,
+                    });
+                }
+            }
+        }
+        for concept in self.concepts.values() {
+            let (attrs, risk) = (&concept.attrs, &concept.risk_profile);
+            let values = [
+                ("attractiveness", attrs.attractiveness),
+                ("controversy", attrs.controversy),
+                ("resource_cost", attrs.resource_cost),
+                ("virality", attrs.virality),
+                ("expected_fear", risk.expected_fear),
+                ("eco_harm_score", risk.eco_harm_score),
+                ("data_abuse_risk", risk.data_abuse_risk),
+                ("irreversible_bio_risk", risk.irreversible_bio_risk),
+                ("sunset_abandon_rate", concept.sunset_abandon_rate),
+            ];
+            for (field, value) in values {
+                if value.is_nan() {
+                    errors.push(WorldValidationError::ConceptValueNan {
+                        concept_id: concept.id,
+                        field,
+                    });
+                }
+            }
+        }
+        for (region, concept_id, _) in self.exposure_field.entries() {
+            if !self.regions.contains_key(&region) {
+                errors.push(WorldValidationError::UnknownExposureRegion { region, concept_id });
+            }
+            if !self.concepts.contains_key(&concept_id) {
+                errors.push(WorldValidationError::UnknownExposureConcept { region, concept_id });
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Each region's fear, `local` (its residents' aggregated fear) plus
+    /// `rate` times its neighbors' `regional_fear` as of the previous tick,
+    /// averaged by neighbor population. Replaces `regional_fear`; reading
+    /// only the previous values makes the result independent of order.
+    pub fn spread_regional_fear(&mut self, local: &HashMap<RegionId, f32>, rate: f32) {
+        let previous = &self.regional_fear;
+        let next = self
+            .regions
+            .values()
+            .map(|region| {
+                let (weighted, population) = region
+                    .neighbors
+                    .iter()
+                    .filter_map(|id| self.regions.get(id))
+                    .fold((0.0, 0.0), |(weighted, population), neighbor| {
+                        let pop = neighbor.population as f32;
+                        let fear = previous.get(&neighbor.id).copied().unwrap_or(0.0);
+                        (weighted + pop * fear, population + pop)
+                    });
+                let spill = if population > 0.0 { weighted / population } else { 0.0 };
+                let own = local.get(&region.id).copied().unwrap_or(0.0);
+                (region.id, (own + rate * spill).clamp(0.0, 1.0))
+            })
+            .collect();
+        self.regional_fear = next;
+    }
+
+    fn exposure(&self, concept_id: ConceptId, region: RegionId) -> f32 {
+        self.exposure_field.get(region, concept_id)
+    }
+
+    /// GraphViz digraph of the regions, labeled with name and population,
+    /// and their `neighbors`: a mutual pair is one undirected edge, while a
+    /// neighbor declared on one side only is a dashed red "one-way" edge
+    /// and a region listing itself a red "self-loop".
+    pub fn to_dot(&self) -> String {
+        let mut lines = self.dot_nodes();
+        for region in self.regions.values() {
+            let neighbors: BTreeSet<RegionId> = region.neighbors.iter().copied().collect();
+            for to in neighbors {
+                let mutual = self
+                    .regions
+                    .get(&to)
+                    .is_some_and(|other| other.neighbors.contains(&region.id));
+                let attrs = if to == region.id {
+                    "color=red, label=\"self-loop\""
+                } else if !mutual {
+                    "color=red, style=dashed, label=\"one-way\""
+                } else if region.id < to {
+                    "dir=none"
+                } else {
+                    continue;
+                };
+                lines.push(format!("  {} -> {to} [{attrs}];", region.id));
+            }
+        }
+        lines.push("}".to_string());
+        lines.join("\n") + "\n"
+    }
+
+    /// GraphViz digraph of the regions and the net migration between each
+    /// pair, as recorded by `MigrationFlowRecorder`: one edge in the
+    /// dominant direction (none where they cancel out), labeled with the net
+    /// count and `penwidth` 1 to 8 in proportion to the largest. Moves from a region to itself are
+    /// drawn as a red "self-loop".
+    pub fn flows_to_dot(&self, flows: &BTreeMap<(RegionId, RegionId), u32>) -> String {
+        let count = |from, to| flows.get(&(from, to)).copied().unwrap_or(0);
+        let mut net: Vec<(RegionId, RegionId, u32)> = Vec::new();
+        for &(from, to) in flows.keys() {
+            let (forward, back) = (count(from, to), count(to, from));
+            if from == to || forward > back {
+                net.push((from, to, if from == to { forward } else { forward - back }));
+            }
+        }
+        let max = net.iter().map(|&(_, _, n)| n).max().unwrap_or(0).max(1) as f32;
+
+        let mut lines = self.dot_nodes();
+        for (from, to, n) in net {
+            let width = 1.0 + 7.0 * n as f32 / max;
+            let flag = if from == to { ", color=red" } else { "" };
+            let label = if from == to { format!("self-loop {n}") } else { n.to_string() };
+            lines.push(format!(
+                "  {from} -> {to} [penwidth={width:.2}, label=\"{label}\"{flag}];"
+            ));
+        }
+        lines.push("}".to_string());
+        lines.join("\n") + "\n"
+    }
+
+    fn dot_nodes(&self) -> Vec<String> {
+        let mut lines = vec!["digraph world {".to_string()];
+        for region in self.regions.values() {
+            let name = region.name.replace('\\', "\\\\").replace('"', "\\\"");
+            lines.push(format!(
+                "  {} [label=\"{name}\\npop {}\"];",
+                region.id, region.population
+            ));
+        }
+        lines
     }
 }
 
 impl<'a> WorldView<'a> {
-    pub fn visible_concepts(&self, region: RegionId) -> Vec<&Concept> {
-        self.world.concepts.values().collect()
+    /// Concepts exposed in `region` or one of its neighbors, in id order so
+    /// an agent's random draws line up with the same concepts on every run.
+    pub fn visible_concepts(&self, region: RegionId) -> &[&'a Concept] {
+        self.visible.get(&region).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn tick(&self) -> Tick {
+        self.tick
+    }
+
+    /// Any concept, available or not.
+    pub fn concept(&self, concept_id: ConceptId) -> Option<&Concept> {
+        self.world.concepts.get(&concept_id)
     }
 
     pub fn local_exposure_intensity(&self, concept_id: ConceptId, region: RegionId) -> f32 {
-        self.world
-            .exposure_field
-            .get(&region)
-            .and_then(|m| m.get(&concept_id).copied())
-            .unwrap_or(0.0)
+        self.world.exposure(concept_id, region)
     }
 
+    /// Neighbor to move to under `model`, never one in `closed`. Weighted
+    /// models fall back to uniform when no open neighbor has a positive,
+    /// finite weight.
     pub fn sample_neighbor_region(
         &self,
         region: RegionId,
+        model: MovementModel,
+        closed: &BTreeSet<RegionId>,
         rng: &mut impl rand::Rng,
     ) -> Option<RegionId> {
         let reg = self.world.regions.get(&region)?;
-        if reg.neighbors.is_empty() {
-            return None;
+        let open: Vec<(RegionId, f32)> = reg
+            .neighbors
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| !closed.contains(n))
+            .map(|(i, n)| {
+                let weight = match model {
+                    MovementModel::Uniform => 1.0,
+                    MovementModel::EdgeWeighted => reg.neighbor_weight(i),
+                    MovementModel::Gravity => {
+                        let population = self.world.regions.get(n).map_or(0, |r| r.population);
+                        population as f32 * reg.neighbor_weight(i)
+                    }
+                };
+                (*n, weight)
+            })
+            .collect();
+        match open.as_slice() {
+            [] => return None,
+            [(only, _)] => return Some(*only),
+            _ => {}
+        }
+
+        let usable = |w: f32| w.is_finite() && w > 0.0;
+        let total: f32 = open.iter().map(|(_, w)| *w).filter(|w| usable(*w)).sum();
+        if model == MovementModel::Uniform || total <= 0.0 || !total.is_finite() {
+            let idx = rng.gen_range(0..open.len());
+            return Some(open[idx].0);
+        }
+
+        let mut pick = rng.gen::<f32>() * total;
+        for (neighbor, w) in &open {
+            if !usable(*w) {
+                continue;
+            }
+            if pick < *w {
+                return Some(*neighbor);
+            }
+            pick -= w;
+        }
+        // Rounding left `pick` past the last usable neighbor.
+        open.iter()
+            .rev()
+            .find(|(_, w)| usable(*w))
+            .map(|(n, _)| *n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::tests::sim;
+
+    /// Three regions in a line, 0 - 1 - 2.
+    fn line_world() -> World {
+        let mut world = sim(0, 1).world;
+        world.regions.get_mut(&0).unwrap().neighbors = vec![1];
+        world.regions.get_mut(&1).unwrap().neighbors = vec![0, 2];
+        world.regions.get_mut(&2).unwrap().neighbors = vec![1];
+        world
+    }
+
+    #[test]
+    fn far_regions_do_not_see_local_concepts() {
+        let mut world = line_world();
+        world.exposure_field.clear();
+        world.exposure_field.set(0, 0, 0.5);
+        let view = world.view(0);
+        let ids = |region| {
+            view.visible_concepts(region)
+                .iter()
+                .map(|c| c.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(0), [0]);
+        assert_eq!(ids(1), [0]);
+        assert!(ids(2).is_empty());
+    }
+
+    #[test]
+    fn unavailable_concepts_are_not_visible() {
+        let mut world = line_world();
+        world.concepts.get_mut(&1).unwrap().available_from = 5;
+        assert_eq!(world.view(4).visible_concepts(0).len(), 1);
+        assert_eq!(world.view(5).visible_concepts(0).len(), 2);
+    }
+
+    /// Share of 100 000 moves out of region 0 that pick region 1.
+    fn share_to_region_1(world: &World, model: MovementModel, closed: &BTreeSet<RegionId>) -> f64 {
+        use rand::SeedableRng;
+        let view = world.view(0);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let draws = 100_000;
+        let hits = (0..draws)
+            .filter(|_| view.sample_neighbor_region(0, model, closed, &mut rng) == Some(1))
+            .count();
+        hits as f64 / draws as f64
+    }
+
+    #[test]
+    fn gravity_sampling_follows_population() {
+        let mut world = sim(0, 1).world;
+        world.regions.get_mut(&0).unwrap().neighbors = vec![1, 2];
+        world.regions.get_mut(&1).unwrap().population = 900;
+        world.regions.get_mut(&2).unwrap().population = 100;
+        let share = share_to_region_1(&world, MovementModel::Gravity, &BTreeSet::new());
+        assert!((share - 0.9).abs() < 0.01, "{share}");
+        let share = share_to_region_1(&world, MovementModel::Uniform, &BTreeSet::new());
+        assert!((share - 0.5).abs() < 0.01, "{share}");
+    }
+
+    #[test]
+    fn edge_weights_scale_sampling() {
+        let mut world = sim(0, 1).world;
+        let region = world.regions.get_mut(&0).unwrap();
+        region.neighbors = vec![1, 2];
+        region.neighbor_weights = vec![3.0, 1.0];
+        let share = share_to_region_1(&world, MovementModel::EdgeWeighted, &BTreeSet::new());
+        assert!((share - 0.75).abs() < 0.01, "{share}");
+    }
+
+    #[test]
+    fn degenerate_weights_fall_back_to_uniform() {
+        let mut world = sim(0, 1).world;
+        let region = world.regions.get_mut(&0).unwrap();
+        region.neighbors = vec![1, 2];
+        region.neighbor_weights = vec![0.0, f32::NAN];
+        let share = share_to_region_1(&world, MovementModel::EdgeWeighted, &BTreeSet::new());
+        assert!((share - 0.5).abs() < 0.01, "{share}");
+
+        // A single open neighbor is always picked, whatever its weight
+        let closed = BTreeSet::from([2]);
+        assert_eq!(
+            share_to_region_1(&world, MovementModel::EdgeWeighted, &closed),
+            1.0
+        );
+    }
+
+    #[test]
+    fn exposure_field_matches_a_nested_map() {
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let mut field = ExposureField::default();
+        let mut nested: BTreeMap<RegionId, BTreeMap<ConceptId, f32>> = BTreeMap::new();
+        for _ in 0..2_000 {
+            let (region, concept_id) = (rng.gen_range(0..20), rng.gen_range(0..10));
+            let amount = rng.gen_range(-0.3..0.5);
+            field.add(region, concept_id, amount);
+            let by_concept = nested.entry(region).or_default();
+            let intensity = (by_concept.get(&concept_id).copied().unwrap_or(0.0) + amount).max(0.0);
+            if intensity > 0.0 {
+                by_concept.insert(concept_id, intensity);
+            } else {
+                by_concept.remove(&concept_id);
+            }
+        }
+        nested.retain(|_, by_concept| !by_concept.is_empty());
+
+        assert_eq!(
+            serde_json::to_string(&field).unwrap(),
+            serde_json::to_string(&nested).unwrap()
+        );
+        let back: ExposureField =
+            serde_json::from_str(&serde_json::to_string(&nested).unwrap()).unwrap();
+        assert_eq!(back, field);
+
+        field.retain(|region, _, _| region % 2 == 0);
+        assert!(field.entries().iter().all(|(region, _, _)| region % 2 == 0));
+    }
+
+    /// Lookups and updates of the flat field against a nested map of the same
+    /// entries; `cargo test -- --ignored exposure_field_lookups` to run.
+    #[test]
+    #[ignore]
+    fn exposure_field_lookups_against_a_nested_map() {
+        use std::time::Instant;
+
+        let (regions, concepts) = (50_000u32, 2_000u32);
+        let mut nested: HashMap<RegionId, HashMap<ConceptId, f32>> = HashMap::new();
+        let mut flat = ExposureField::default();
+        for region in 0..regions {
+            for k in 0..20 {
+                let concept_id = (region * 7 + k * 97) % concepts;
+                nested.entry(region).or_default().insert(concept_id, 0.5);
+                flat.set(region, concept_id, 0.5);
+            }
         }
-        let idx = rng.gen_range(0..reg.neighbors.len());
-        Some(reg.neighbors[idx])
+        let key = |i: u32, step: u32| (i % regions, i.wrapping_mul(step) % concepts);
+
+        let mut sum = 0.0;
+        let start = Instant::now();
+        for i in 0..5_000_000 {
+            let (region, concept_id) = key(i, 31);
+            sum += nested
+                .get(&region)
+                .and_then(|by_concept| by_concept.get(&concept_id))
+                .copied()
+                .unwrap_or(0.0);
+        }
+        let nested_get = start.elapsed();
+        let start = Instant::now();
+        for i in 0..5_000_000 {
+            let (region, concept_id) = key(i, 31);
+            sum += flat.get(region, concept_id);
+        }
+        let flat_get = start.elapsed();
+
+        let start = Instant::now();
+        for i in 0..2_000_000 {
+            let (region, concept_id) = key(i, 13);
+            *nested
+                .entry(region)
+                .or_default()
+                .entry(concept_id)
+                .or_insert(0.0) += 0.01;
+        }
+        let nested_add = start.elapsed();
+        let start = Instant::now();
+        for i in 0..2_000_000 {
+            let (region, concept_id) = key(i, 13);
+            flat.add(region, concept_id, 0.01);
+        }
+        let flat_add = start.elapsed();
+        println!(
+"get: nested {nested_get:?}, flat {flat_get:?}; add: nested {nested_add:?}, flat {flat_add:?} ({sum})"
+);
+    }
+
+    #[test]
+    fn dot_marks_one_way_and_self_loop_edges() {
+        let mut world = sim(0, 1).world;
+        world.regions.get_mut(&0).unwrap().neighbors = vec![1, 0];
+        world.regions.get_mut(&1).unwrap().neighbors = vec![0, 2];
+        world.regions.get_mut(&1).unwrap().name = "a\"b".into();
+        let dot = world.to_dot();
+
+        assert!(dot.contains("  0 [label=\"r0\\npop 100\"];"), "{dot}");
+        assert!(dot.contains("  1 [label=\"a\\\"b\\npop 100\"];"), "{dot}");
+        assert!(dot.contains("  0 -> 1 [dir=none];"));
+        assert!(!dot.contains("  1 -> 0"));
+        assert!(dot.contains("  0 -> 0 [color=red, label=\"self-loop\"];"));
+        assert!(dot.contains("  1 -> 2 [color=red, style=dashed, label=\"one-way\"];"));
+        assert!(dot.contains("  2 -> 0 [color=red, style=dashed, label=\"one-way\"];"));
+    }
+
+    #[test]
+    fn flow_dot_draws_net_flows() {
+        let world = sim(0, 1).world;
+        let flows = BTreeMap::from([
+            ((0, 1), 5),
+            ((1, 0), 1),
+            ((1, 2), 2),
+            ((2, 1), 2),
+            ((2, 2), 1),
+        ]);
+        let dot = world.flows_to_dot(&flows);
+
+        assert!(
+            dot.contains("  0 -> 1 [penwidth=8.00, label=\"4\"];"),
+            "{dot}"
+        );
+        assert!(!dot.contains("1 -> 2") && !dot.contains("2 -> 1"), "{dot}");
+        assert!(
+            dot.contains("  2 -> 2 [penwidth=2.75, label=\"self-loop 1\", color=red];"),
+            "{dot}"
+        );
+    }
+
+    #[test]
+    fn validation_reports_each_error_class() {
+        use WorldValidationError::*;
+
+        let sim = sim(30, 1);
+        assert_eq!(sim.validate(), Ok(()));
+
+        let mut world = sim.world.clone();
+        world.regions.get_mut(&0).unwrap().neighbors.push(9);
+        assert_eq!(
+            world.validate(),
+            Err(vec![UnknownNeighbor {
+                region: 0,
+                neighbor: 9
+            }])
+        );
+
+        let mut world = sim.world.clone();
+        world.exposure_field.set(0, 7, 0.5);
+        world.exposure_field.set(8, 0, 0.5);
+        assert_eq!(
+            world.validate(),
+            Err(vec![
+                UnknownExposureConcept {
+                    region: 0,
+                    concept_id: 7
+                },
+                UnknownExposureRegion {
+                    region: 8,
+                    concept_id: 0
+                },
+            ])
+        );
+
+        let mut world = sim.world.clone();
+        world.regions.get_mut(&1).unwrap().eco_vulnerability = 1.5;
+        assert_eq!(
+            world.validate(),
+            Err(vec![EcoVulnerabilityOutOfRange {
+                region: 1,
+                value: 1.5
+            }])
+        );
+
+        let mut world = sim.world.clone();
+        world.regions.get_mut(&0).unwrap().area_km2 = f32::NAN;
+        world.concepts.get_mut(&1).unwrap().attrs.virality = f32::NAN;
+        assert_eq!(
+            world.validate(),
+            Err(vec![
+                RegionValueNan {
+                    region: 0,
+                    field: "area_km2"
+                },
+                ConceptValueNan {
+                    concept_id: 1,
+                    field: "virality"
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn validation_checks_agents_against_the_world() {
+        use WorldValidationError::*;
+
+        let mut sim = sim(30, 1);
+        sim.world.regions.get_mut(&2).unwrap().population = 0;
+        let errors = sim.validate().unwrap_err();
+        assert!(
+            matches!(&errors[..], [EmptyRegionHasAgents { region: 2, agents }] if agents.len() == 10 && agents[0] == 2),
+            "{errors:?}"
+        );
+        sim.world.regions.get_mut(&2).unwrap().population = 100;
+
+        sim.agents[3].state.region = 5;
+        sim.agents[4].beliefs.tech_skepticism = f32::NAN;
+        assert_eq!(
+            sim.validate(),
+            Err(vec![
+                UnknownAgentRegion {
+                    agent: 3,
+                    region: 5
+                },
+                AgentValueNan {
+                    agent: 4,
+                    field: "tech_skepticism"
+                },
+            ])
+        );
     }
 }